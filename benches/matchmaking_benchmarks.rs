@@ -23,11 +23,11 @@ fn bench_basic_matchmaking(c: &mut Criterion) {
                     let queue_manager = Arc::new(QueueManager::new(persistence.clone()));
                     
                     // Register queue
-                    queue_manager.register_queue(QueueConfig {
-                        name: "test_queue".to_string(),
-                        format: MatchFormat::one_v_one(),
-                        constraints: MatchConstraints::permissive(),
-                    }).await.unwrap();
+                    queue_manager.register_queue(QueueConfig::new(
+                        "test_queue",
+                        MatchFormat::one_v_one(),
+                        MatchConstraints::permissive(),
+                    )).await.unwrap();
                     
                     // Add 100 players
                     for i in 0..100 {
@@ -38,6 +38,7 @@ fn bench_basic_matchmaking(c: &mut Criterion) {
                             player_id,
                             rating,
                             EntryMetadata::default(),
+                            None,
                         ).await.unwrap();
                     }
                     
@@ -64,11 +65,11 @@ fn bench_queue_scaling(c: &mut Criterion) {
                     let persistence = Arc::new(InMemoryAdapter::new());
                     let queue_manager = Arc::new(QueueManager::new(persistence.clone()));
                     
-                    queue_manager.register_queue(QueueConfig {
-                        name: "scale_test".to_string(),
-                        format: MatchFormat::one_v_one(),
-                        constraints: MatchConstraints::permissive(),
-                    }).await.unwrap();
+                    queue_manager.register_queue(QueueConfig::new(
+                        "scale_test",
+                        MatchFormat::one_v_one(),
+                        MatchConstraints::permissive(),
+                    )).await.unwrap();
                     
                     let start = std::time::Instant::now();
                     
@@ -81,6 +82,7 @@ fn bench_queue_scaling(c: &mut Criterion) {
                             player_id,
                             rating,
                             EntryMetadata::default(),
+                            None,
                         ).await.unwrap();
                     }
                     
@@ -95,11 +97,11 @@ fn bench_queue_scaling(c: &mut Criterion) {
                     let persistence = Arc::new(InMemoryAdapter::new());
                     let queue_manager = Arc::new(QueueManager::new(persistence.clone()));
                     
-                    queue_manager.register_queue(QueueConfig {
-                        name: "scale_test".to_string(),
-                        format: MatchFormat::one_v_one(),
-                        constraints: MatchConstraints::permissive(),
-                    }).await.unwrap();
+                    queue_manager.register_queue(QueueConfig::new(
+                        "scale_test",
+                        MatchFormat::one_v_one(),
+                        MatchConstraints::permissive(),
+                    )).await.unwrap();
                     
                     // Pre-populate queue
                     for i in 0..size {
@@ -110,6 +112,7 @@ fn bench_queue_scaling(c: &mut Criterion) {
                             player_id,
                             rating,
                             EntryMetadata::default(),
+                            None,
                         ).await.unwrap();
                     }
                     
@@ -275,6 +278,39 @@ fn bench_persistence_operations(c: &mut Criterion) {
     });
 }
 
+/// Benchmark encoding a queue entry in each serialization format, reporting payload sizes
+fn bench_serialization_formats(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialization_formats");
+    
+    let entry = QueueEntry::new_party(
+        "ranked".to_string(),
+        Uuid::new_v4(),
+        (0..5).map(|_| Uuid::new_v4()).collect(),
+        Rating::new(1720.0, 80.0, 0.06),
+        EntryMetadata::default(),
+    );
+    
+    let formats = [
+        ("json", SerializationFormat::Json),
+        #[cfg(feature = "msgpack")]
+        ("msgpack", SerializationFormat::MessagePack),
+    ];
+    
+    for (name, format) in formats {
+        let payload = format.encode(&entry).unwrap();
+        println!("{} queue entry payload: {} bytes", name, payload.len());
+        
+        group.bench_with_input(BenchmarkId::new("encode_queue_entry", name), &format, |b, format| {
+            b.iter(|| black_box(format.encode(&entry).unwrap()));
+        });
+        group.bench_with_input(BenchmarkId::new("decode_queue_entry", name), &format, |b, format| {
+            b.iter(|| black_box(format.decode::<QueueEntry>(&payload).unwrap()));
+        });
+    }
+    
+    group.finish();
+}
+
 /// Benchmark concurrent operations
 fn bench_concurrent_operations(c: &mut Criterion) {
     let rt = Runtime::new().unwrap();
@@ -285,11 +321,11 @@ fn bench_concurrent_operations(c: &mut Criterion) {
                 let persistence = Arc::new(InMemoryAdapter::new());
                 let queue_manager = Arc::new(QueueManager::new(persistence.clone()));
                 
-                queue_manager.register_queue(QueueConfig {
-                    name: "concurrent_test".to_string(),
-                    format: MatchFormat::one_v_one(),
-                    constraints: MatchConstraints::permissive(),
-                }).await.unwrap();
+                queue_manager.register_queue(QueueConfig::new(
+                    "concurrent_test",
+                    MatchFormat::one_v_one(),
+                    MatchConstraints::permissive(),
+                )).await.unwrap();
                 
                 let start = std::time::Instant::now();
                 
@@ -305,6 +341,7 @@ fn bench_concurrent_operations(c: &mut Criterion) {
                             player_id,
                             rating,
                             EntryMetadata::default(),
+                            None,
                         ).await
                     });
                     handles.push(handle);
@@ -329,11 +366,11 @@ fn bench_matchmaking_runner(c: &mut Criterion) {
                 let persistence = Arc::new(InMemoryAdapter::new());
                 let queue_manager = Arc::new(QueueManager::new(persistence.clone()));
                 
-                queue_manager.register_queue(QueueConfig {
-                    name: "runner_test".to_string(),
-                    format: MatchFormat::one_v_one(),
-                    constraints: MatchConstraints::permissive(),
-                }).await.unwrap();
+                queue_manager.register_queue(QueueConfig::new(
+                    "runner_test",
+                    MatchFormat::one_v_one(),
+                    MatchConstraints::permissive(),
+                )).await.unwrap();
                 
                 // Pre-populate with 200 players
                 for i in 0..200 {
@@ -344,6 +381,7 @@ fn bench_matchmaking_runner(c: &mut Criterion) {
                         player_id,
                         rating,
                         EntryMetadata::default(),
+                        None,
                     ).await.unwrap();
                 }
                 
@@ -375,11 +413,11 @@ fn bench_memory_usage(c: &mut Criterion) {
                 let persistence = Arc::new(InMemoryAdapter::new());
                 let queue_manager = Arc::new(QueueManager::new(persistence.clone()));
                 
-                queue_manager.register_queue(QueueConfig {
-                    name: "memory_test".to_string(),
-                    format: MatchFormat::one_v_one(),
-                    constraints: MatchConstraints::permissive(),
-                }).await.unwrap();
+                queue_manager.register_queue(QueueConfig::new(
+                    "memory_test",
+                    MatchFormat::one_v_one(),
+                    MatchConstraints::permissive(),
+                )).await.unwrap();
                 
                 let start = std::time::Instant::now();
                 
@@ -392,6 +430,7 @@ fn bench_memory_usage(c: &mut Criterion) {
                         player_id,
                         rating,
                         EntryMetadata::default(),
+                        None,
                     ).await.unwrap();
                 }
                 
@@ -405,6 +444,38 @@ fn bench_memory_usage(c: &mut Criterion) {
     });
 }
 
+/// Benchmark the sorted 1v1 greedy path against the general greedy path
+fn bench_one_v_one_fast_path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("greedy_one_v_one");
+    group.sample_size(10);
+
+    let now = Utc::now();
+    let entries: Vec<QueueEntry> = (0..10_000)
+        .map(|i| {
+            // Spread ratings without sorting them by join order
+            let rating = Rating::new(800.0 + ((i * 7919) % 10_000) as f64 * 0.14, 300.0, 0.06);
+            let mut entry = QueueEntry::new_solo("duel".to_string(), Uuid::new_v4(), rating, EntryMetadata::default());
+            entry.joined_at = now - chrono::Duration::milliseconds(i as i64);
+            entry
+        })
+        .collect();
+
+    let fast = GreedyMatcher::new(MatchFormat::one_v_one(), MatchConstraints::strict());
+    group.bench_function("fast_path_10000", |b| {
+        b.iter(|| black_box(fast.find_matches(&entries, now)))
+    });
+
+    // A soft region mode with no regions set takes the general path with the same compatibility
+    let mut general_constraints = MatchConstraints::strict();
+    general_constraints.region_mode = ConstraintMode::Soft { penalty: 0.1 };
+    let general = GreedyMatcher::new(MatchFormat::one_v_one(), general_constraints);
+    group.bench_function("general_path_10000", |b| {
+        b.iter(|| black_box(general.find_matches(&entries, now)))
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_basic_matchmaking,
@@ -412,9 +483,11 @@ criterion_group!(
     bench_mmr_calculations,
     bench_party_operations,
     bench_persistence_operations,
+    bench_serialization_formats,
     bench_concurrent_operations,
     bench_matchmaking_runner,
-    bench_memory_usage
+    bench_memory_usage,
+    bench_one_v_one_fast_path
 );
 
 criterion_main!(benches);
@@ -25,6 +25,7 @@ async fn main() -> Result<()> {
         max_data_points: 10000,
         enable_detailed_tracking: true,
         enable_predictive_analytics: true,
+        ..Default::default()
     };
     
     let analytics = Arc::new(AnalyticsMetrics::new(analytics_config));
@@ -348,6 +349,7 @@ async fn simulate_casual_player_behavior(analytics: &AnalyticsMetrics, player_id
             quality_score: 0.6 + rand::random::<f64>() * 0.3,
             duration: Duration::from_secs(600 + rand::random::<u64>() % 600),
             rating_changes: vec![],
+            predictions: vec![],
         };
         analytics.record_match_completed(match_data).await;
     } else {
@@ -375,6 +377,7 @@ async fn simulate_competitive_player_behavior(analytics: &AnalyticsMetrics, play
         quality_score: 0.8 + rand::random::<f64>() * 0.2,
         duration: Duration::from_secs(900 + rand::random::<u64>() % 900),
         rating_changes: vec![],
+        predictions: vec![],
     };
     analytics.record_match_completed(match_data).await;
 }
@@ -399,6 +402,7 @@ async fn simulate_veteran_player_behavior(analytics: &AnalyticsMetrics, player_i
         quality_score: 0.85 + rand::random::<f64>() * 0.15,
         duration: Duration::from_secs(1200 + rand::random::<u64>() % 600),
         rating_changes: vec![],
+        predictions: vec![],
     };
     analytics.record_match_completed(match_data).await;
 }
@@ -38,28 +38,27 @@ async fn main() -> Result<()> {
     
     // Register queues
     let queues = vec![
-        QueueConfig {
-            name: "casual_1v1".to_string(),
-            format: MatchFormat::one_v_one(),
-            constraints: MatchConstraints::permissive(),
-        },
-        QueueConfig {
-            name: "ranked_1v1".to_string(),
-            format: MatchFormat::one_v_one(),
-            constraints: MatchConstraints::strict(),
-        },
-        QueueConfig {
-            name: "competitive_5v5".to_string(),
-            format: MatchFormat::team_v_team(5),
-            constraints: MatchConstraints {
+        QueueConfig::new("casual_1v1", MatchFormat::one_v_one(), MatchConstraints::permissive()),
+        QueueConfig::new("ranked_1v1", MatchFormat::one_v_one(), MatchConstraints::strict()),
+        QueueConfig::new(
+            "competitive_5v5",
+            MatchFormat::team_v_team(5),
+            MatchConstraints {
                 max_rating_difference: 200,
                 max_wait_time: Duration::from_secs(300),
                 role_requirements: vec![
                     RoleRequirement { role: "tank".to_string(), required: true },
                     RoleRequirement { role: "healer".to_string(), required: true },
                 ],
+                require_matching_tags: vec![],
+                split_party_across_teams: false,
+                region_mode: ConstraintMode::Hard,
+                tag_mode: ConstraintMode::Hard,
+                avoid_recent_window: 0,
+                recent_encounter_mode: ConstraintMode::Hard,
+                starvation: None,
             },
-        },
+        ),
     ];
     
     for queue_config in queues {
@@ -237,6 +236,9 @@ async fn simulate_player_activity(
             ],
             avoided_players: vec![],
             custom_attributes: HashMap::new(),
+            tags: std::collections::HashMap::new(),
+            mode_preferences: Vec::new(),
+            priority_boost_until: None,
         };
         
         let entry = queue_manager.join_queue_solo(
@@ -244,6 +246,7 @@ async fn simulate_player_activity(
             player_id,
             rating,
             metadata,
+            None,
         ).await?;
         
         // Record queue activity
@@ -268,6 +271,7 @@ async fn simulate_player_activity(
                 quality_score: 0.7 + (i % 3) as f64 * 0.1,
                 duration: Duration::from_secs(300 + (i % 600) as u64),
                 rating_changes: vec![],
+                predictions: vec![],
             };
             analytics.record_match_completed(match_data).await;
         } else if i % 7 == 0 {
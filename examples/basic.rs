@@ -15,17 +15,24 @@ async fn main() -> Result<()> {
     let lobby_manager = Arc::new(LobbyManager::new(persistence.clone()));
     
     // Configure 1v1 ranked queue
-    let queue_config = QueueConfig {
-        name: "ranked_1v1".to_string(),
-        format: MatchFormat::one_v_one(),
-        constraints: MatchConstraints {
+    let queue_config = QueueConfig::new(
+        "ranked_1v1",
+        MatchFormat::one_v_one(),
+        MatchConstraints {
             max_rating_delta: 200.0,
             same_region_required: false,
             role_requirements: vec![],
             max_wait_time_seconds: 300,
             expansion_rate: 5.0,
+            require_matching_tags: vec![],
+            split_party_across_teams: false,
+            region_mode: ConstraintMode::Hard,
+            tag_mode: ConstraintMode::Hard,
+            avoid_recent_window: 0,
+            recent_encounter_mode: ConstraintMode::Hard,
+            starvation: None,
         },
-    };
+    );
     
     queue_manager.register_queue(queue_config).await?;
     
@@ -47,6 +54,7 @@ async fn main() -> Result<()> {
             *player_id,
             *rating,
             EntryMetadata::default(),
+            None,
         ).await?;
         
         println!("Player {} joined queue (rating: {:.0})", player_id, rating.rating);
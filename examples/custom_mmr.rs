@@ -145,17 +145,24 @@ async fn main() -> Result<()> {
     println!("Using adaptive decay strategy");
     
     // Configure queue with custom constraints
-    let queue_config = QueueConfig {
-        name: "custom_ranked".to_string(),
-        format: MatchFormat::two_v_two(),
-        constraints: MatchConstraints {
+    let queue_config = QueueConfig::new(
+        "custom_ranked",
+        MatchFormat::two_v_two(),
+        MatchConstraints {
             max_rating_delta: 150.0,
             same_region_required: false,
             role_requirements: vec![],
             max_wait_time_seconds: 180,
             expansion_rate: 3.0,
+            require_matching_tags: vec![],
+            split_party_across_teams: false,
+            region_mode: ConstraintMode::Hard,
+            tag_mode: ConstraintMode::Hard,
+            avoid_recent_window: 0,
+            recent_encounter_mode: ConstraintMode::Hard,
+            starvation: None,
         },
-    };
+    );
     
     queue_manager.register_queue(queue_config).await?;
     
@@ -174,6 +181,7 @@ async fn main() -> Result<()> {
             *player_id,
             *rating,
             EntryMetadata::default(),
+            None,
         ).await?;
         
         println!("Player {} - Rating: {:.0}, Deviation: {:.0}, Volatility: {:.3}", 
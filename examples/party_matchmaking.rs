@@ -19,10 +19,10 @@ async fn main() -> Result<()> {
     ));
     
     // Configure 5v5 queue
-    let queue_config = QueueConfig {
-        name: "team_5v5".to_string(),
-        format: MatchFormat::five_v_five(),
-        constraints: MatchConstraints {
+    let queue_config = QueueConfig::new(
+        "team_5v5",
+        MatchFormat::five_v_five(),
+        MatchConstraints {
             max_rating_delta: 300.0,
             same_region_required: true,
             role_requirements: vec![
@@ -32,8 +32,15 @@ async fn main() -> Result<()> {
             ],
             max_wait_time_seconds: 600,
             expansion_rate: 10.0,
+            require_matching_tags: vec![],
+            split_party_across_teams: false,
+            region_mode: ConstraintMode::Hard,
+            tag_mode: ConstraintMode::Hard,
+            avoid_recent_window: 0,
+            recent_encounter_mode: ConstraintMode::Hard,
+            starvation: None,
         },
-    };
+    );
     
     queue_manager.register_queue(queue_config).await?;
     
@@ -109,8 +116,8 @@ async fn main() -> Result<()> {
         (Uuid::new_v4(), Rating::new(1520.0, 290.0, 0.06), "dps"),
         (Uuid::new_v4(), Rating::new(1490.0, 310.0, 0.06), "dps"),
         (Uuid::new_v4(), Rating::new(1510.0, 295.0, 0.06), "dps"),
-        (Uuid::new_v4(), Rating::new(1470.0, 330.0, 0.06), "dps"),
-        (Uuid::new_v4(), Rating::new(1530.0, 270.0, 0.06), "dps"),
+        (Uuid::new_v4(), Rating::new(1470.0, 330.0, 0.06), "tank"),
+        (Uuid::new_v4(), Rating::new(1530.0, 270.0, 0.06), "healer"),
     ];
     
     println!("\nAdding solo players to queue...");
@@ -128,6 +135,7 @@ async fn main() -> Result<()> {
             *player_id,
             *rating,
             metadata,
+            None,
         ).await?;
         
         println!("Solo player {} joined queue (rating: {:.0}, role: {})", player_id, rating.rating, role);
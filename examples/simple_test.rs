@@ -58,6 +58,7 @@ async fn test_analytics() -> Result<()> {
         quality_score: 0.85,
         duration: Duration::from_secs(1200),
         rating_changes,
+        predictions: vec![],
     };
     analytics.record_match_completed(match_data).await;
     
@@ -83,11 +84,7 @@ async fn test_queue_system() -> Result<()> {
     let queue_manager = Arc::new(QueueManager::new(persistence.clone()));
     
     // Create queue config
-    let queue_config = QueueConfig {
-        name: "ranked".to_string(),
-        format: MatchFormat::one_v_one(),
-        constraints: MatchConstraints::permissive(),
-    };
+    let queue_config = QueueConfig::new("ranked", MatchFormat::one_v_one(), MatchConstraints::permissive());
     
     // Register the queue
     queue_manager.register_queue(queue_config).await?;
@@ -104,14 +101,17 @@ async fn test_queue_system() -> Result<()> {
         region: Some("us-east".to_string()),
         roles: vec!["damage".to_string()],
         custom: std::collections::HashMap::new(),
+        tags: std::collections::HashMap::new(),
+        mode_preferences: Vec::new(),
+        priority_boost_until: None,
     };
     
     // Add some players to queue
     let player1 = uuid::Uuid::new_v4();
     let player2 = uuid::Uuid::new_v4();
     
-    queue_manager.join_queue_solo("ranked".to_string(), player1, rating.clone(), metadata.clone()).await?;
-    queue_manager.join_queue_solo("ranked".to_string(), player2, rating, metadata).await?;
+    queue_manager.join_queue_solo("ranked".to_string(), player1, rating.clone(), metadata.clone(), None).await?;
+    queue_manager.join_queue_solo("ranked".to_string(), player2, rating, metadata, None).await?;
     
     // Get queue size
     let queue_size = queue_manager.get_queue_size("ranked").await?;
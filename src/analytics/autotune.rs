@@ -0,0 +1,215 @@
+//! Automatic constraint relaxation driven by generated insights
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use super::insights::{InsightEngine, InsightType};
+use crate::error::{MatchForgeError, Result};
+use crate::queue::QueueManager;
+
+/// Bounds for [`AutoTuner`] relaxation
+#[derive(Debug, Clone)]
+pub struct AutoTuneConfig {
+    /// Only queue performance insights at or above this confidence relax queues
+    pub min_confidence: f64,
+
+    /// Rating delta added per evaluation while the insight keeps firing
+    pub step: f64,
+
+    /// Most steps a queue is ever widened by over its baseline
+    pub max_steps: u32,
+}
+
+impl Default for AutoTuneConfig {
+    fn default() -> Self {
+        Self {
+            min_confidence: 0.85,
+            step: 50.0,
+            max_steps: 4,
+        }
+    }
+}
+
+/// A rating delta change made by [`AutoTuner::evaluate`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuningChange {
+    pub queue_name: String,
+    pub from: f64,
+    pub to: f64,
+}
+
+/// Widens queues' `max_rating_delta` while queue performance insights fire
+///
+/// Opt-in: only queues added with [`with_queue`](Self::with_queue) are ever
+/// touched, and nothing happens until [`evaluate`](Self::evaluate) is called,
+/// e.g. on the insight generation interval. Each evaluation in which a
+/// [`InsightType::QueuePerformance`] insight reaches the confidence threshold
+/// widens every tuned queue by one step, up to `max_steps` over the delta
+/// the queue had before relaxing. Once no such insight fires, relaxed queues
+/// are restored to that baseline. Acknowledged insights are hidden by the
+/// engine and therefore count as recovered.
+pub struct AutoTuner {
+    insights: Arc<InsightEngine>,
+    queue_manager: Arc<QueueManager>,
+    config: AutoTuneConfig,
+    queues: Vec<String>,
+    /// Baseline delta and steps applied for each currently relaxed queue
+    relaxed: Arc<RwLock<HashMap<String, (f64, u32)>>>,
+}
+
+impl AutoTuner {
+    pub fn new(insights: Arc<InsightEngine>, queue_manager: Arc<QueueManager>, config: AutoTuneConfig) -> Self {
+        Self {
+            insights,
+            queue_manager,
+            config,
+            queues: Vec::new(),
+            relaxed: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Allow the tuner to relax a queue
+    pub fn with_queue(mut self, queue_name: impl Into<String>) -> Self {
+        self.queues.push(queue_name.into());
+        self
+    }
+
+    /// Generate insights and relax or restore the tuned queues
+    pub async fn evaluate(&self) -> Result<Vec<TuningChange>> {
+        if !self.config.step.is_finite() || self.config.step <= 0.0 {
+            return Err(MatchForgeError::InvalidConfiguration(
+                "Auto-tune step must be positive".to_string(),
+            ));
+        }
+
+        let insights = self
+            .insights
+            .generate_insights()
+            .await
+            .map_err(|e| MatchForgeError::OperationFailed(e.to_string()))?;
+        let struggling = insights
+            .iter()
+            .any(|i| i.insight_type == InsightType::QueuePerformance && i.confidence >= self.config.min_confidence);
+
+        if struggling {
+            self.relax().await
+        } else {
+            self.restore().await
+        }
+    }
+
+    /// Steps a queue is currently widened by, 0 when at its baseline
+    pub async fn relaxation_steps(&self, queue_name: &str) -> u32 {
+        self.relaxed.read().await.get(queue_name).map_or(0, |(_, steps)| *steps)
+    }
+
+    async fn relax(&self) -> Result<Vec<TuningChange>> {
+        let mut relaxed = self.relaxed.write().await;
+        let mut changes = Vec::new();
+        for queue_name in &self.queues {
+            let from = self.queue_manager.queue_config(queue_name).await?.constraints.max_rating_delta;
+            let (baseline, steps) = relaxed.get(queue_name).copied().unwrap_or((from, 0));
+            if steps >= self.config.max_steps {
+                continue;
+            }
+
+            let to = baseline + self.config.step * (steps + 1) as f64;
+            self.queue_manager.set_max_rating_delta(queue_name, to).await?;
+            relaxed.insert(queue_name.clone(), (baseline, steps + 1));
+            changes.push(TuningChange { queue_name: queue_name.clone(), from, to });
+        }
+        Ok(changes)
+    }
+
+    async fn restore(&self) -> Result<Vec<TuningChange>> {
+        let mut relaxed = self.relaxed.write().await;
+        let mut changes = Vec::new();
+        for (queue_name, (baseline, _)) in relaxed.drain() {
+            let from = self.queue_manager.queue_config(&queue_name).await?.constraints.max_rating_delta;
+            self.queue_manager.set_max_rating_delta(&queue_name, baseline).await?;
+            changes.push(TuningChange { queue_name, from, to: baseline });
+        }
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::metrics::{AnalyticsConfig, AnalyticsMetrics, QueueActivity};
+    use crate::persistence::InMemoryAdapter;
+    use crate::queue::{MatchConstraints, MatchFormat, QueueConfig};
+
+    async fn tuner(config: AutoTuneConfig) -> (AutoTuner, Arc<AnalyticsMetrics>, Arc<QueueManager>) {
+        let analytics = Arc::new(AnalyticsMetrics::new(AnalyticsConfig::default()));
+        let queue_manager = Arc::new(QueueManager::new(Arc::new(InMemoryAdapter::new())));
+        for name in ["ranked", "casual"] {
+            queue_manager
+                .register_queue(QueueConfig::new(name, MatchFormat::one_v_one(), MatchConstraints::strict()))
+                .await
+                .unwrap();
+        }
+        let engine = Arc::new(InsightEngine::new(analytics.clone()));
+        let tuner = AutoTuner::new(engine, queue_manager.clone(), config).with_queue("ranked");
+        (tuner, analytics, queue_manager)
+    }
+
+    async fn record_match(analytics: &AnalyticsMetrics, wait_secs: u64) {
+        analytics
+            .record_queue_activity("ranked".to_string(), QueueActivity::MatchFound(std::time::Duration::from_secs(wait_secs)))
+            .await;
+    }
+
+    async fn max_delta(queue_manager: &QueueManager, queue_name: &str) -> f64 {
+        queue_manager.queue_config(queue_name).await.unwrap().constraints.max_rating_delta
+    }
+
+    #[tokio::test]
+    async fn test_sustained_high_waits_relax_up_to_bound() {
+        let (tuner, analytics, queue_manager) = tuner(AutoTuneConfig { step: 25.0, max_steps: 2, ..AutoTuneConfig::default() }).await;
+        assert!(tuner.evaluate().await.unwrap().is_empty());
+        assert_eq!(max_delta(&queue_manager, "ranked").await, 100.0);
+
+        record_match(&analytics, 300).await;
+        let changes = tuner.evaluate().await.unwrap();
+        assert_eq!(changes, vec![TuningChange { queue_name: "ranked".to_string(), from: 100.0, to: 125.0 }]);
+
+        for _ in 0..3 {
+            record_match(&analytics, 300).await;
+            tuner.evaluate().await.unwrap();
+        }
+        assert_eq!(max_delta(&queue_manager, "ranked").await, 150.0);
+        assert_eq!(tuner.relaxation_steps("ranked").await, 2);
+        // Queues not opted in are never touched
+        assert_eq!(max_delta(&queue_manager, "casual").await, 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_recovered_waits_revert_relaxation() {
+        let (tuner, analytics, queue_manager) = tuner(AutoTuneConfig::default()).await;
+        record_match(&analytics, 300).await;
+        tuner.evaluate().await.unwrap();
+        record_match(&analytics, 300).await;
+        tuner.evaluate().await.unwrap();
+        assert_eq!(max_delta(&queue_manager, "ranked").await, 200.0);
+
+        // Fast matches pull the average wait back under a minute
+        for _ in 0..4 {
+            record_match(&analytics, 0).await;
+        }
+        let changes = tuner.evaluate().await.unwrap();
+        assert_eq!(changes, vec![TuningChange { queue_name: "ranked".to_string(), from: 200.0, to: 100.0 }]);
+        assert_eq!(max_delta(&queue_manager, "ranked").await, 100.0);
+        assert_eq!(tuner.relaxation_steps("ranked").await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_low_confidence_insights_ignored() {
+        let (tuner, analytics, queue_manager) = tuner(AutoTuneConfig { min_confidence: 0.95, ..AutoTuneConfig::default() }).await;
+        record_match(&analytics, 300).await;
+
+        assert!(tuner.evaluate().await.unwrap().is_empty());
+        assert_eq!(max_delta(&queue_manager, "ranked").await, 100.0);
+    }
+}
@@ -10,6 +10,8 @@ use uuid::Uuid;
 
 use super::{metrics::AnalyticsMetrics, reports::ReportGenerator};
 use super::insights::{InsightEngine, Severity as InsightSeverity};
+use crate::telemetry::monitoring::AlertThresholds;
+use crate::time::to_chrono;
 
 /// Dashboard data provider
 pub struct DashboardData {
@@ -17,6 +19,7 @@ pub struct DashboardData {
     report_generator: Arc<ReportGenerator>,
     insight_engine: Arc<InsightEngine>,
     config: DashboardConfig,
+    alert_thresholds: AlertThresholds,
 }
 
 /// Dashboard configuration
@@ -564,9 +567,19 @@ impl DashboardData {
             report_generator,
             insight_engine,
             config: DashboardConfig::default(),
+            alert_thresholds: AlertThresholds::default(),
         }
     }
     
+    /// Use the monitoring alert thresholds for gauge warning bands
+    ///
+    /// Pass the same thresholds as `MonitoringConfig::alert_thresholds` so
+    /// a gauge turns critical exactly when the matching alert fires.
+    pub fn with_alert_thresholds(mut self, alert_thresholds: AlertThresholds) -> Self {
+        self.alert_thresholds = alert_thresholds;
+        self
+    }
+    
     /// Generate complete dashboard
     pub async fn generate_dashboard(&self, time_range: Option<TimeRange>) -> Result<Dashboard, DashboardError> {
         let time_range = time_range.unwrap_or_else(|| TimeRange {
@@ -614,8 +627,12 @@ impl DashboardData {
         widgets.push(self.generate_queue_sizes_chart().await?);
         widgets.push(self.generate_rating_distribution_chart().await?);
         
-        // Table widget
+        // Gauge widgets
+        widgets.extend(self.generate_threshold_gauges().await);
+        
+        // Table widgets
         widgets.push(self.generate_queue_status_table().await?);
+        widgets.push(self.generate_latency_percentiles_table().await);
         
         // Alert widget
         widgets.push(self.generate_alerts_widget().await?);
@@ -1031,6 +1048,113 @@ impl DashboardData {
         })
     }
     
+    /// Generate API and database latency percentiles table widget
+    async fn generate_latency_percentiles_table(&self) -> Widget {
+        let snapshot = self.analytics.get_metrics_snapshot().await;
+        
+        let headers = [("source", "Source"), ("p50", "p50"), ("p90", "p90"), ("p95", "p95"), ("p99", "p99"), ("samples", "Samples")]
+            .into_iter()
+            .map(|(key, label)| TableHeader {
+                key: key.to_string(),
+                label: label.to_string(),
+                sortable: false,
+                width: None,
+            })
+            .collect();
+        let rows = [("API", snapshot.api_response_percentiles), ("Database", snapshot.db_query_percentiles)]
+            .into_iter()
+            .map(|(source, percentiles)| TableRow {
+                id: source.to_lowercase(),
+                cells: vec![
+                    TableCell::Text(source.to_string()),
+                    TableCell::Duration(to_chrono(percentiles.p50)),
+                    TableCell::Duration(to_chrono(percentiles.p90)),
+                    TableCell::Duration(to_chrono(percentiles.p95)),
+                    TableCell::Duration(to_chrono(percentiles.p99)),
+                    TableCell::Number(percentiles.sample_count as f64),
+                ],
+            })
+            .collect();
+        
+        Widget {
+            id: Uuid::new_v4(),
+            widget_type: WidgetType::Table,
+            title: "Latency Percentiles".to_string(),
+            position: WidgetPosition { x: 0, y: 20 },
+            size: WidgetSize { width: 12, height: 3 },
+            data: WidgetData::Table(TableData {
+                headers,
+                rows,
+                pagination: None,
+                sorting: None,
+            }),
+            config: WidgetConfig {
+                refresh_interval: Some(Duration::seconds(30)),
+                auto_refresh: true,
+                theme: None,
+                custom_options: HashMap::new(),
+            },
+            refresh_interval: Some(Duration::seconds(30)),
+        }
+    }
+    
+    /// Generate gauges for the metrics that have alert thresholds
+    async fn generate_threshold_gauges(&self) -> Vec<Widget> {
+        let snapshot = self.analytics.get_metrics_snapshot().await;
+        let thresholds = &self.alert_thresholds;
+        let largest_queue = snapshot.queue_sizes.values().copied().max().unwrap_or(0);
+        
+        let gauges = [
+            ("Average Wait Time", "seconds", snapshot.average_wait_time.as_secs_f64(), thresholds.max_average_wait_time as f64 / 1000.0),
+            ("Largest Queue", "players", largest_queue as f64, thresholds.max_queue_size as f64),
+            ("CPU Usage", "%", snapshot.cpu_usage_percent, thresholds.max_cpu_usage_percent),
+            ("Memory Usage", "MB", snapshot.memory_usage_mb as f64, thresholds.max_memory_usage_mb as f64),
+        ];
+        
+        gauges
+            .into_iter()
+            .enumerate()
+            .map(|(i, (label, unit, value, limit))| Widget {
+                id: Uuid::new_v4(),
+                widget_type: WidgetType::Gauge,
+                title: label.to_string(),
+                position: WidgetPosition { x: i as u32 * 3, y: 17 },
+                size: WidgetSize { width: 3, height: 3 },
+                data: WidgetData::Gauge(Self::threshold_gauge(label, unit, value, limit)),
+                config: WidgetConfig {
+                    refresh_interval: Some(Duration::seconds(30)),
+                    auto_refresh: true,
+                    theme: None,
+                    custom_options: HashMap::new(),
+                },
+                refresh_interval: Some(Duration::seconds(30)),
+            })
+            .collect()
+    }
+    
+    /// Gauge whose critical band starts at the alert threshold, with a warning band below it
+    fn threshold_gauge(label: &str, unit: &str, value: f64, limit: f64) -> GaugeData {
+        let warning = limit * 0.75;
+        let max = (limit * 1.5).max(value);
+        
+        GaugeData {
+            value,
+            min: 0.0,
+            max,
+            label: label.to_string(),
+            unit: unit.to_string(),
+            thresholds: vec![
+                GaugeThreshold { value: warning, label: "Warning".to_string(), color: "#ffc107".to_string() },
+                GaugeThreshold { value: limit, label: "Critical".to_string(), color: "#dc3545".to_string() },
+            ],
+            colors: vec![
+                GaugeColor { from: 0.0, to: warning, color: "#28a745".to_string() },
+                GaugeColor { from: warning, to: limit, color: "#ffc107".to_string() },
+                GaugeColor { from: limit, to: max, color: "#dc3545".to_string() },
+            ],
+        }
+    }
+    
     /// Generate alerts widget
     async fn generate_alerts_widget(&self) -> Result<Widget, DashboardError> {
         let insights = self.insight_engine.generate_insights().await.unwrap_or_default();
@@ -1216,3 +1340,76 @@ impl Default for DashboardLayout {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::metrics::{AnalyticsConfig, PerformanceMetric};
+
+    fn dashboard_data() -> DashboardData {
+        let analytics = Arc::new(AnalyticsMetrics::new(AnalyticsConfig::default()));
+        DashboardData::new(
+            analytics.clone(),
+            Arc::new(ReportGenerator::new(analytics.clone())),
+            Arc::new(InsightEngine::new(analytics)),
+        )
+    }
+
+    async fn critical_threshold(data: &DashboardData, title: &str) -> f64 {
+        let gauges = data.generate_threshold_gauges().await;
+        let widget = gauges.iter().find(|w| w.title == title).unwrap();
+        let WidgetData::Gauge(gauge) = &widget.data else {
+            panic!("{} is not a gauge", title);
+        };
+        gauge.thresholds.iter().find(|t| t.label == "Critical").unwrap().value
+    }
+
+    #[tokio::test]
+    async fn test_latency_percentiles_table() {
+        let data = dashboard_data();
+        for millis in [5, 5, 5, 400] {
+            data.analytics
+                .record_performance(PerformanceMetric::ApiResponseTime(std::time::Duration::from_millis(millis)))
+                .await;
+        }
+
+        let widget = data.generate_latency_percentiles_table().await;
+        let WidgetData::Table(table) = &widget.data else {
+            panic!("latency percentiles is not a table");
+        };
+        let api = &table.rows[0];
+        assert!(matches!(&api.cells[1], TableCell::Duration(p50) if *p50 == Duration::milliseconds(5)));
+        assert!(matches!(&api.cells[3], TableCell::Duration(p95) if *p95 == Duration::milliseconds(400)));
+        assert!(matches!(table.rows[1].cells[5], TableCell::Number(samples) if samples == 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_gauges_use_default_alert_thresholds() {
+        let data = dashboard_data();
+
+        assert_eq!(critical_threshold(&data, "Average Wait Time").await, 30.0);
+        assert_eq!(critical_threshold(&data, "Largest Queue").await, 1000.0);
+        assert_eq!(critical_threshold(&data, "CPU Usage").await, 80.0);
+        assert_eq!(critical_threshold(&data, "Memory Usage").await, 2048.0);
+    }
+
+    #[tokio::test]
+    async fn test_changing_alert_threshold_moves_gauge_bands() {
+        let thresholds = AlertThresholds {
+            max_average_wait_time: 90_000,
+            max_queue_size: 250,
+            ..AlertThresholds::default()
+        };
+        let data = dashboard_data().with_alert_thresholds(thresholds);
+
+        assert_eq!(critical_threshold(&data, "Average Wait Time").await, 90.0);
+        assert_eq!(critical_threshold(&data, "Largest Queue").await, 250.0);
+
+        let gauges = data.generate_threshold_gauges().await;
+        let WidgetData::Gauge(queue_gauge) = &gauges.iter().find(|w| w.title == "Largest Queue").unwrap().data else {
+            panic!("queue widget is not a gauge");
+        };
+        assert_eq!(queue_gauge.thresholds[0].value, 187.5);
+        assert_eq!(queue_gauge.colors[2].from, 250.0);
+    }
+}
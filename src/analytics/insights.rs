@@ -2,7 +2,7 @@
 //! 
 //! Provides intelligent insights and recommendations based on matchmaking data.
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc, Duration};
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::metrics::{AnalyticsMetrics, MetricsSnapshot};
+use crate::persistence::{InMemoryAdapter, PersistenceAdapter};
 
 /// Insight engine for generating actionable insights
 pub struct InsightEngine {
@@ -17,6 +18,9 @@ pub struct InsightEngine {
     config: InsightConfig,
     historical_data: Arc<RwLock<VecDeque<MetricsSnapshot>>>,
     ml_models: Arc<RwLock<HashMap<InsightType, MLModel>>>,
+    persistence: Arc<dyn PersistenceAdapter>,
+    /// Ids produced by the previous `generate_insights` call
+    active_ids: Arc<RwLock<HashSet<Uuid>>>,
 }
 
 /// Insight configuration
@@ -80,6 +84,47 @@ pub struct Insight {
     pub metadata: InsightMetadata,
 }
 
+impl Insight {
+    /// Stable identifier derived from the insight's type and subject
+    ///
+    /// The live numbers in the description are deliberately left out, so a
+    /// condition keeps its id for as long as it persists.
+    pub fn content_id(&self) -> Uuid {
+        stable_id(&[format!("{:?}", self.insight_type).as_bytes(), self.subject().as_bytes()])
+    }
+
+    /// The metrics the insight is about, or its title when it cites none
+    fn subject(&self) -> String {
+        if self.evidence.is_empty() {
+            return self.title.clone();
+        }
+        self.evidence.iter().map(|e| e.description.as_str()).collect::<Vec<_>>().join(",")
+    }
+}
+
+/// Stored insight along with its acknowledgement state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InsightRecord {
+    pub insight: Insight,
+    pub acknowledged: bool,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+}
+
+/// 128-bit FNV-1a hash of the given parts, as a UUID
+fn stable_id(parts: &[&[u8]]) -> Uuid {
+    const OFFSET: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    let mut hash = OFFSET;
+    for part in parts {
+        for byte in part.iter().chain(std::iter::once(&0xff)) {
+            hash ^= *byte as u128;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    Uuid::from_u128(hash)
+}
+
 /// Insight severity levels
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Severity {
@@ -197,8 +242,16 @@ impl InsightEngine {
             config: InsightConfig::default(),
             historical_data: Arc::new(RwLock::new(VecDeque::new())),
             ml_models: Arc::new(RwLock::new(HashMap::new())),
+            persistence: Arc::new(InMemoryAdapter::new()),
+            active_ids: Arc::new(RwLock::new(HashSet::new())),
         }
     }
+
+    /// Store insights and their acknowledgement state in the given adapter
+    pub fn with_persistence(mut self, persistence: Arc<dyn PersistenceAdapter>) -> Self {
+        self.persistence = persistence;
+        self
+    }
     
     /// Generate insights based on current data
     pub async fn generate_insights(&self) -> Result<Vec<Insight>, InsightError> {
@@ -234,7 +287,68 @@ impl InsightEngine {
                 .then(b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal))
         });
         
-        Ok(insights)
+        self.filter_acknowledged(insights).await
+    }
+
+    /// Mark an insight as acknowledged so it is hidden until its condition clears and recurs
+    pub async fn acknowledge_insight(&self, insight_id: Uuid) -> Result<(), InsightError> {
+        let mut record = self.persistence.load_insight(insight_id).await
+            .map_err(|e| InsightError::PersistenceFailed(e.to_string()))?
+            .ok_or(InsightError::InsightNotFound(insight_id))?;
+
+        record.acknowledged = true;
+        record.acknowledged_at = Some(Utc::now());
+        self.persistence.save_insight(&record).await
+            .map_err(|e| InsightError::PersistenceFailed(e.to_string()))
+    }
+
+    /// Assign stable ids, store unseen insights and drop acknowledged ones
+    ///
+    /// An acknowledged insight that wasn't generated this time has cleared,
+    /// so its acknowledgement is reset and it surfaces again if it recurs.
+    async fn filter_acknowledged(&self, insights: Vec<Insight>) -> Result<Vec<Insight>, InsightError> {
+        let mut visible = Vec::new();
+        let mut generated = HashSet::new();
+
+        for mut insight in insights {
+            insight.id = insight.content_id();
+            generated.insert(insight.id);
+            for recommendation in &mut insight.recommendations {
+                recommendation.id = stable_id(&[insight.id.as_bytes(), recommendation.title.as_bytes()]);
+            }
+
+            let stored = self.persistence.load_insight(insight.id).await
+                .map_err(|e| InsightError::PersistenceFailed(e.to_string()))?;
+            match stored {
+                Some(record) if record.acknowledged => continue,
+                Some(_) => {}
+                None => {
+                    let record = InsightRecord { insight: insight.clone(), acknowledged: false, acknowledged_at: None };
+                    self.persistence.save_insight(&record).await
+                        .map_err(|e| InsightError::PersistenceFailed(e.to_string()))?;
+                }
+            }
+            visible.push(insight);
+        }
+
+        let cleared: Vec<Uuid> = {
+            let mut active_ids = self.active_ids.write().await;
+            let cleared = active_ids.difference(&generated).copied().collect();
+            *active_ids = generated;
+            cleared
+        };
+        for insight_id in cleared {
+            let stored = self.persistence.load_insight(insight_id).await
+                .map_err(|e| InsightError::PersistenceFailed(e.to_string()))?;
+            if let Some(mut record) = stored.filter(|r| r.acknowledged) {
+                record.acknowledged = false;
+                record.acknowledged_at = None;
+                self.persistence.save_insight(&record).await
+                    .map_err(|e| InsightError::PersistenceFailed(e.to_string()))?;
+            }
+        }
+
+        Ok(visible)
     }
     
     /// Generate queue performance insights
@@ -274,7 +388,7 @@ impl InsightEngine {
                     Evidence {
                         evidence_type: EvidenceType::Metric,
                         description: "Current average wait time".to_string(),
-                        data: EvidenceData::Duration(Duration::from_std(snapshot.average_wait_time).unwrap_or_default()),
+                        data: EvidenceData::Duration(crate::time::to_chrono(snapshot.average_wait_time)),
                         weight: 1.0,
                     },
                 ],
@@ -425,7 +539,7 @@ impl InsightEngine {
                     Evidence {
                         evidence_type: EvidenceType::Metric,
                         description: "Average session duration".to_string(),
-                        data: EvidenceData::Duration(Duration::from_std(retention.average_session_duration).unwrap_or_default()),
+                        data: EvidenceData::Duration(crate::time::to_chrono(retention.average_session_duration)),
                         weight: 1.0,
                     },
                 ],
@@ -714,9 +828,9 @@ impl InsightEngine {
         let growth_rate = self.calculate_queue_growth_rate().await;
         
         if total_queue_size > 500 && growth_rate > 0.1 {
-            let predicted_overflow_time = Duration::from_std(std::time::Duration::from_secs_f64(
+            let predicted_overflow_time = crate::time::seconds_f64(
                 (1000.0 - total_queue_size as f64) / (total_queue_size as f64 * growth_rate / 3600.0)
-            )).unwrap_or_default();
+            );
             
             insights.push(Insight {
                 id: Uuid::new_v4(),
@@ -918,6 +1032,8 @@ pub enum InsightError {
     ModelNotTrained,
     InsufficientData,
     GenerationFailed(String),
+    InsightNotFound(Uuid),
+    PersistenceFailed(String),
 }
 
 impl std::fmt::Display for InsightError {
@@ -927,6 +1043,8 @@ impl std::fmt::Display for InsightError {
             InsightError::ModelNotTrained => write!(f, "ML model is not trained"),
             InsightError::InsufficientData => write!(f, "Insufficient data for insight generation"),
             InsightError::GenerationFailed(msg) => write!(f, "Insight generation failed: {}", msg),
+            InsightError::InsightNotFound(id) => write!(f, "Insight not found: {}", id),
+            InsightError::PersistenceFailed(msg) => write!(f, "Insight persistence failed: {}", msg),
         }
     }
 }
@@ -944,3 +1062,90 @@ impl Default for InsightConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::metrics::{AnalyticsConfig, QueueActivity};
+
+    const HIGH_WAIT_TITLE: &str = "High Average Wait Times Detected";
+
+    async fn record_match(analytics: &AnalyticsMetrics, wait_secs: u64) {
+        analytics
+            .record_queue_activity("ranked".to_string(), QueueActivity::MatchFound(std::time::Duration::from_secs(wait_secs)))
+            .await;
+    }
+
+    fn high_wait(insights: &[Insight]) -> Option<&Insight> {
+        insights.iter().find(|i| i.title == HIGH_WAIT_TITLE)
+    }
+
+    #[tokio::test]
+    async fn test_same_condition_produces_same_id() {
+        let analytics = Arc::new(AnalyticsMetrics::new(AnalyticsConfig::default()));
+        record_match(&analytics, 300).await;
+        let engine = InsightEngine::new(analytics);
+
+        let first = engine.generate_insights().await.unwrap();
+        let second = engine.generate_insights().await.unwrap();
+
+        let first = high_wait(&first).unwrap();
+        let second = high_wait(&second).unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(first.recommendations[0].id, second.recommendations[0].id);
+    }
+
+    #[tokio::test]
+    async fn test_id_stable_while_reported_numbers_move() {
+        let analytics = Arc::new(AnalyticsMetrics::new(AnalyticsConfig::default()));
+        record_match(&analytics, 300).await;
+        let engine = InsightEngine::new(analytics.clone());
+
+        let first = engine.generate_insights().await.unwrap();
+        record_match(&analytics, 200).await;
+        let second = engine.generate_insights().await.unwrap();
+
+        let first = high_wait(&first).unwrap();
+        let second = high_wait(&second).unwrap();
+        assert_ne!(first.description, second.description);
+        assert_eq!(first.id, second.id);
+    }
+
+    #[tokio::test]
+    async fn test_acknowledged_insight_hidden_until_condition_recurs() {
+        let analytics = Arc::new(AnalyticsMetrics::new(AnalyticsConfig::default()));
+        record_match(&analytics, 300).await;
+        let engine = InsightEngine::new(analytics.clone());
+
+        let insights = engine.generate_insights().await.unwrap();
+        let insight_id = high_wait(&insights).unwrap().id;
+        engine.acknowledge_insight(insight_id).await.unwrap();
+
+        // Still hidden while the wait stays high, even as the number moves
+        record_match(&analytics, 200).await;
+        let insights = engine.generate_insights().await.unwrap();
+        assert!(high_wait(&insights).is_none());
+
+        // The condition clears...
+        for _ in 0..10 {
+            record_match(&analytics, 0).await;
+        }
+        let insights = engine.generate_insights().await.unwrap();
+        assert!(high_wait(&insights).is_none());
+
+        // ...and surfaces again once it recurs
+        for _ in 0..10 {
+            record_match(&analytics, 600).await;
+        }
+        let insights = engine.generate_insights().await.unwrap();
+        assert_eq!(high_wait(&insights).unwrap().id, insight_id);
+    }
+
+    #[tokio::test]
+    async fn test_acknowledge_unknown_insight_fails() {
+        let engine = InsightEngine::new(Arc::new(AnalyticsMetrics::new(AnalyticsConfig::default())));
+
+        let result = engine.acknowledge_insight(Uuid::new_v4()).await;
+        assert!(matches!(result, Err(InsightError::InsightNotFound(_))));
+    }
+}
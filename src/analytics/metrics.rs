@@ -2,6 +2,7 @@
 //! 
 //! Provides comprehensive metrics collection and analysis for matchmaking operations.
 
+use async_trait::async_trait;
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, AtomicI64, Ordering};
@@ -10,6 +11,10 @@ use std::time::{Duration, Instant};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use crate::queue::WaitPercentiles;
+
+/// Number of equal-width buckets in [`AnalyticsMetrics::quality_distribution`]
+const QUALITY_BUCKETS: usize = 10;
 
 /// Advanced analytics metrics collector
 pub struct AnalyticsMetrics {
@@ -21,6 +26,8 @@ pub struct AnalyticsMetrics {
     
     // Matchmaking metrics
     total_matches: AtomicU64,
+    /// When recent matches completed, oldest first
+    match_completions: Arc<RwLock<VecDeque<DateTime<Utc>>>>,
     matches_per_hour: AtomicU64,
     average_wait_time: AtomicI64,
     match_quality_score: AtomicI64,
@@ -29,16 +36,22 @@ pub struct AnalyticsMetrics {
     // Queue metrics
     queue_sizes: Arc<RwLock<HashMap<String, u64>>>,
     queue_wait_times: Arc<RwLock<HashMap<String, VecDeque<Duration>>>>,
-    abandonment_rates: Arc<RwLock<HashMap<String, f64>>>,
+    realized_waits: Arc<RwLock<HashMap<String, WaitHistogram>>>,
+    abandonment: Arc<RwLock<HashMap<String, AbandonmentTracker>>>,
+    match_quality: Arc<RwLock<HashMap<String, [u64; QUALITY_BUCKETS]>>>,
     
     // Rating metrics
     rating_distribution: Arc<RwLock<HashMap<String, u64>>>,
+    /// Sum and count of completed matches' average ratings
+    match_rating_total: Arc<RwLock<(f64, u64)>>,
     rating_changes: Arc<RwLock<VecDeque<RatingChange>>>,
     rating_accuracy: AtomicI64,
+    outcome_predictions: Arc<RwLock<VecDeque<OutcomePrediction>>>,
     
     // Party metrics
     party_sizes: Arc<RwLock<HashMap<usize, u64>>>,
-    party_success_rates: Arc<RwLock<HashMap<usize, f64>>>,
+    /// (successful, total) matches by party size
+    party_outcomes: Arc<RwLock<HashMap<usize, (u64, u64)>>>,
     solo_vs_party_win_rates: Arc<RwLock<HashMap<String, f64>>>,
     
     // Performance metrics
@@ -78,6 +91,12 @@ pub struct AnalyticsConfig {
     
     /// Enable predictive analytics
     pub enable_predictive_analytics: bool,
+    
+    /// Upper bounds of the realized wait histogram buckets, ascending
+    pub wait_histogram_bounds: Vec<Duration>,
+    
+    /// A player who leaves a queue and is not matched within this window counts as abandoned
+    pub abandonment_window: Duration,
 }
 
 impl Default for AnalyticsConfig {
@@ -88,6 +107,11 @@ impl Default for AnalyticsConfig {
             max_data_points: 10000,
             enable_detailed_tracking: true,
             enable_predictive_analytics: true,
+            wait_histogram_bounds: [5, 10, 30, 60, 120, 300, 600]
+                .into_iter()
+                .map(Duration::from_secs)
+                .collect(),
+            abandonment_window: Duration::from_secs(5 * 60),
         }
     }
 }
@@ -138,18 +162,23 @@ impl AnalyticsMetrics {
             new_players_today: AtomicU64::new(0),
             returning_players: AtomicU64::new(0),
             total_matches: AtomicU64::new(0),
+            match_completions: Arc::new(RwLock::new(VecDeque::new())),
             matches_per_hour: AtomicU64::new(0),
             average_wait_time: AtomicI64::new(0),
             match_quality_score: AtomicI64::new(0),
             matchmaking_success_rate: AtomicI64::new(0),
             queue_sizes: Arc::new(RwLock::new(HashMap::new())),
             queue_wait_times: Arc::new(RwLock::new(HashMap::new())),
-            abandonment_rates: Arc::new(RwLock::new(HashMap::new())),
+            realized_waits: Arc::new(RwLock::new(HashMap::new())),
+            abandonment: Arc::new(RwLock::new(HashMap::new())),
+            match_quality: Arc::new(RwLock::new(HashMap::new())),
             rating_distribution: Arc::new(RwLock::new(HashMap::new())),
+            match_rating_total: Arc::new(RwLock::new((0.0, 0))),
             rating_changes: Arc::new(RwLock::new(VecDeque::new())),
             rating_accuracy: AtomicI64::new(0),
+            outcome_predictions: Arc::new(RwLock::new(VecDeque::new())),
             party_sizes: Arc::new(RwLock::new(HashMap::new())),
-            party_success_rates: Arc::new(RwLock::new(HashMap::new())),
+            party_outcomes: Arc::new(RwLock::new(HashMap::new())),
             solo_vs_party_win_rates: Arc::new(RwLock::new(HashMap::new())),
             api_response_times: Arc::new(RwLock::new(VecDeque::new())),
             database_query_times: Arc::new(RwLock::new(VecDeque::new())),
@@ -165,6 +194,52 @@ impl AnalyticsMetrics {
         }
     }
     
+    /// Zero every counter and clear every recorded series, keeping the config
+    pub async fn reset(&self) {
+        for counter in [
+            &self.total_players,
+            &self.active_players,
+            &self.new_players_today,
+            &self.returning_players,
+            &self.total_matches,
+            &self.matches_per_hour,
+            &self.memory_usage,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+        for gauge in [
+            &self.average_wait_time,
+            &self.match_quality_score,
+            &self.matchmaking_success_rate,
+            &self.rating_accuracy,
+            &self.cpu_usage,
+            &self.churn_rate,
+            &self.revenue_per_player,
+        ] {
+            gauge.store(0, Ordering::Relaxed);
+        }
+        
+        self.match_completions.write().await.clear();
+        self.queue_sizes.write().await.clear();
+        self.queue_wait_times.write().await.clear();
+        self.realized_waits.write().await.clear();
+        self.abandonment.write().await.clear();
+        self.match_quality.write().await.clear();
+        self.rating_distribution.write().await.clear();
+        *self.match_rating_total.write().await = (0.0, 0);
+        self.rating_changes.write().await.clear();
+        self.outcome_predictions.write().await.clear();
+        self.party_sizes.write().await.clear();
+        self.party_outcomes.write().await.clear();
+        self.solo_vs_party_win_rates.write().await.clear();
+        self.api_response_times.write().await.clear();
+        self.database_query_times.write().await.clear();
+        self.player_retention.write().await.clear();
+        self.session_durations.write().await.clear();
+        self.hourly_metrics.write().await.clear();
+        self.daily_metrics.write().await.clear();
+    }
+    
     /// Record player activity
     pub async fn record_player_activity(&self, player_id: Uuid, activity_type: PlayerActivityType) {
         match activity_type {
@@ -186,6 +261,13 @@ impl AnalyticsMetrics {
     /// Record match completion
     pub async fn record_match_completed(&self, match_data: MatchCompletionData) {
         self.total_matches.fetch_add(1, Ordering::Relaxed);
+        {
+            let mut completions = self.match_completions.write().await;
+            completions.push_back(Utc::now());
+            if completions.len() > self.config.max_data_points {
+                completions.pop_front();
+            }
+        }
         self.match_quality_score.store(
             ((self.match_quality_score.load(Ordering::Relaxed) as f64 + match_data.quality_score) / 2.0) as i64,
             Ordering::Relaxed,
@@ -195,6 +277,22 @@ impl AnalyticsMetrics {
         let mut rating_dist = self.rating_distribution.write().await;
         let rating_bucket = self.get_rating_bucket(match_data.average_rating);
         *rating_dist.entry(rating_bucket).or_insert(0) += 1;
+        {
+            let mut total = self.match_rating_total.write().await;
+            total.0 += match_data.average_rating;
+            total.1 += 1;
+        }
+        
+        // Record predictions for calibration
+        {
+            let mut predictions = self.outcome_predictions.write().await;
+            for prediction in match_data.predictions {
+                predictions.push_back(prediction);
+                if predictions.len() > self.config.max_data_points {
+                    predictions.pop_front();
+                }
+            }
+        }
         
         // Record rating changes
         if self.config.enable_detailed_tracking {
@@ -222,15 +320,14 @@ impl AnalyticsMetrics {
                 }
                 
                 // Record wait time
-                let mut wait_times = self.queue_wait_times.write().await;
-                let queue_wait_times = wait_times.entry(queue_name.clone()).or_insert_with(VecDeque::new);
-                queue_wait_times.push_back(wait_time);
-                if queue_wait_times.len() > 1000 {
-                    queue_wait_times.pop_front();
+                {
+                    let mut wait_times = self.queue_wait_times.write().await;
+                    let queue_wait_times = wait_times.entry(queue_name.clone()).or_insert_with(VecDeque::new);
+                    queue_wait_times.push_back(wait_time);
+                    if queue_wait_times.len() > 1000 {
+                        queue_wait_times.pop_front();
+                    }
                 }
-                
-                // Update abandonment rate
-                self.update_abandonment_rate(&queue_name).await;
             }
             QueueActivity::MatchFound(wait_time) => {
                 // Update average wait time
@@ -247,6 +344,127 @@ impl AnalyticsMetrics {
         }
     }
     
+    /// Record a step of one player's time in a queue, for [`abandonment_rate`](Self::abandonment_rate)
+    pub async fn record_player_queue_event(
+        &self,
+        queue_name: &str,
+        player_id: Uuid,
+        event: PlayerQueueEvent,
+        at: DateTime<Utc>,
+    ) {
+        let window = self.abandonment_window();
+        let mut trackers = self.abandonment.write().await;
+        let tracker = trackers.entry(queue_name.to_string()).or_default();
+        tracker.settle(at, window);
+        
+        match event {
+            PlayerQueueEvent::Joined => tracker.joins += 1,
+            PlayerQueueEvent::Left => {
+                tracker.pending.insert(player_id, at);
+            }
+            PlayerQueueEvent::Matched => {
+                tracker.pending.remove(&player_id);
+            }
+        }
+    }
+    
+    /// Share of joins that ended in the player leaving without a match
+    ///
+    /// A leave counts once the configured `abandonment_window` has passed
+    /// without that player being matched in the queue; leaves still inside
+    /// the window are not counted yet. Zero for queues without joins.
+    pub async fn abandonment_rate(&self, queue_name: &str) -> f64 {
+        self.abandonment_rate_at(queue_name, Utc::now()).await
+    }
+    
+    /// Abandonment rate as of `now`
+    pub async fn abandonment_rate_at(&self, queue_name: &str, now: DateTime<Utc>) -> f64 {
+        let trackers = self.abandonment.read().await;
+        match trackers.get(queue_name) {
+            Some(tracker) if tracker.joins > 0 => {
+                (tracker.abandoned_at(now, self.abandonment_window()) as f64 / tracker.joins as f64).min(1.0)
+            }
+            _ => 0.0,
+        }
+    }
+    
+    fn abandonment_window(&self) -> chrono::Duration {
+        crate::time::to_chrono(self.config.abandonment_window)
+    }
+    
+    /// Record how long each participant of a newly formed match actually waited
+    pub async fn record_realized_waits(&self, queue_name: &str, waits: &[Duration]) {
+        let mut histograms = self.realized_waits.write().await;
+        let histogram = histograms
+            .entry(queue_name.to_string())
+            .or_insert_with(|| WaitHistogram::new(self.config.wait_histogram_bounds.clone()));
+        for wait in waits {
+            histogram.record(*wait);
+        }
+    }
+    
+    /// Record the quality score of a match formed in a queue
+    pub async fn record_match_quality(&self, queue_name: &str, quality: f64) {
+        let index = ((quality.clamp(0.0, 1.0) * QUALITY_BUCKETS as f64) as usize).min(QUALITY_BUCKETS - 1);
+        let mut distributions = self.match_quality.write().await;
+        distributions.entry(queue_name.to_string()).or_insert([0; QUALITY_BUCKETS])[index] += 1;
+    }
+    
+    /// Quality scores of a queue's matches as `(upper bound, count)` buckets of width 0.1
+    ///
+    /// Bucket `i` counts scores in `[i / 10, (i + 1) / 10)`; a perfect 1.0
+    /// lands in the last bucket. Every bucket is reported, so queues without
+    /// matches have all counts zero.
+    pub async fn quality_distribution(&self, queue_name: &str) -> Vec<(f64, u64)> {
+        let counts = self.match_quality.read().await.get(queue_name).copied().unwrap_or([0; QUALITY_BUCKETS]);
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| ((i + 1) as f64 / QUALITY_BUCKETS as f64, count))
+            .collect()
+    }
+    
+    /// Quality distribution over every queue, see [`quality_distribution`](Self::quality_distribution)
+    pub async fn overall_quality_distribution(&self) -> Vec<(f64, u64)> {
+        let mut counts = [0; QUALITY_BUCKETS];
+        for queue_counts in self.match_quality.read().await.values() {
+            for (total, count) in counts.iter_mut().zip(queue_counts) {
+                *total += count;
+            }
+        }
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| ((i + 1) as f64 / QUALITY_BUCKETS as f64, count))
+            .collect()
+    }
+    
+    /// Completed matches recorded in `[start, end]`, up to `max_data_points` of the most recent
+    pub async fn matches_completed_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> usize {
+        self.match_completions
+            .read()
+            .await
+            .iter()
+            .filter(|completed_at| (start..=end).contains(*completed_at))
+            .count()
+    }
+    
+    /// Mean average rating of completed matches, `None` before any completed
+    pub async fn average_match_rating(&self) -> Option<f64> {
+        let (total, count) = *self.match_rating_total.read().await;
+        (count > 0).then(|| total / count as f64)
+    }
+    
+    /// Histogram of realized waits (join to match formation) for a queue
+    pub async fn realized_wait_histogram(&self, queue_name: &str) -> WaitHistogram {
+        self.realized_waits
+            .read()
+            .await
+            .get(queue_name)
+            .cloned()
+            .unwrap_or_else(|| WaitHistogram::new(self.config.wait_histogram_bounds.clone()))
+    }
+    
     /// Record party activity
     pub async fn record_party_activity(&self, party_size: usize, activity: PartyActivity) {
         match activity {
@@ -255,14 +473,32 @@ impl AnalyticsMetrics {
                 *sizes.entry(party_size).or_insert(0) += 1;
             }
             PartyActivity::MatchFound(success) => {
-                let mut success_rates = self.party_success_rates.write().await;
-                let current_rate = success_rates.get(&party_size).copied().unwrap_or(0.0);
-                let new_rate = (current_rate + if success { 1.0 } else { 0.0 }) / 2.0;
-                success_rates.insert(party_size, new_rate);
+                let mut outcomes = self.party_outcomes.write().await;
+                let (successes, total) = outcomes.entry(party_size).or_insert((0, 0));
+                *successes += u64::from(success);
+                *total += 1;
             }
         }
     }
     
+    /// Share of matches won by parties of `party_size`, `None` without recorded matches
+    pub async fn party_success_rate(&self, party_size: usize) -> Option<f64> {
+        let outcomes = self.party_outcomes.read().await;
+        outcomes
+            .get(&party_size)
+            .filter(|(_, total)| *total > 0)
+            .map(|(successes, total)| *successes as f64 / *total as f64)
+    }
+    
+    /// Share of matches won by parties of any size, `None` without recorded matches
+    pub async fn overall_party_success_rate(&self) -> Option<f64> {
+        let outcomes = self.party_outcomes.read().await;
+        let (successes, total) = outcomes
+            .values()
+            .fold((0, 0), |(successes, total), (s, t)| (successes + s, total + t));
+        (total > 0).then(|| successes as f64 / total as f64)
+    }
+    
     /// Record performance metrics
     pub async fn record_performance(&self, metric: PerformanceMetric) {
         match metric {
@@ -289,6 +525,47 @@ impl AnalyticsMetrics {
         }
     }
     
+    /// Compare predicted win probabilities against actual results
+    ///
+    /// Recorded predictions are bucketed into tenths of predicted probability
+    /// (a reliability curve); a well calibrated system has each bucket's
+    /// actual win rate close to its mean prediction. Empty buckets are omitted.
+    pub async fn calibration_report(&self) -> CalibrationReport {
+        const BUCKETS: usize = 10;
+        
+        let predictions = self.outcome_predictions.read().await;
+        let mut sums = [(0usize, 0.0f64, 0.0f64); BUCKETS];
+        let mut squared_error = 0.0;
+        
+        for prediction in predictions.iter() {
+            let p = prediction.predicted_win_probability.clamp(0.0, 1.0);
+            let index = ((p * BUCKETS as f64) as usize).min(BUCKETS - 1);
+            sums[index].0 += 1;
+            sums[index].1 += p;
+            sums[index].2 += prediction.actual_score;
+            squared_error += (p - prediction.actual_score).powi(2);
+        }
+        
+        let buckets = sums
+            .iter()
+            .enumerate()
+            .filter(|(_, (count, _, _))| *count > 0)
+            .map(|(i, (count, predicted, actual))| CalibrationBucket {
+                lower: i as f64 / BUCKETS as f64,
+                upper: (i + 1) as f64 / BUCKETS as f64,
+                sample_count: *count,
+                mean_predicted: predicted / *count as f64,
+                actual_win_rate: actual / *count as f64,
+            })
+            .collect();
+        
+        CalibrationReport {
+            sample_count: predictions.len(),
+            brier_score: if predictions.is_empty() { 0.0 } else { squared_error / predictions.len() as f64 },
+            buckets,
+        }
+    }
+    
     /// Get comprehensive metrics snapshot
     pub async fn get_metrics_snapshot(&self) -> MetricsSnapshot {
         let queue_sizes = self.queue_sizes.read().await.clone();
@@ -311,6 +588,8 @@ impl AnalyticsMetrics {
             party_sizes,
             average_api_response_time: self.calculate_average_duration(&api_times),
             average_db_query_time: self.calculate_average_duration(&db_times),
+            api_response_percentiles: WaitPercentiles::from_durations(&api_times),
+            db_query_percentiles: WaitPercentiles::from_durations(&db_times),
             memory_usage_mb: self.memory_usage.load(Ordering::Relaxed) / 1024 / 1024,
             cpu_usage_percent: self.cpu_usage.load(Ordering::Relaxed) as f64,
             revenue_per_player: self.revenue_per_player.load(Ordering::Relaxed) as f64,
@@ -385,6 +664,25 @@ impl AnalyticsMetrics {
         }
     }
     
+    /// Percentiles of the recent API response times (last 1000)
+    pub async fn api_response_percentiles(&self) -> WaitPercentiles {
+        WaitPercentiles::from_durations(self.api_response_times.read().await.iter())
+    }
+    
+    /// Percentiles of the recent database query times (last 1000)
+    pub async fn db_query_percentiles(&self) -> WaitPercentiles {
+        WaitPercentiles::from_durations(self.database_query_times.read().await.iter())
+    }
+    
+    /// Wait-time percentiles from the recent wait-time window of a queue
+    pub async fn wait_time_percentiles(&self, queue_name: &str) -> WaitPercentiles {
+        let wait_times = self.queue_wait_times.read().await;
+        wait_times
+            .get(queue_name)
+            .map(WaitPercentiles::from_durations)
+            .unwrap_or_default()
+    }
+    
     /// Get player retention analytics
     pub async fn get_retention_analytics(&self) -> RetentionAnalytics {
         let retention_data = self.player_retention.read().await.clone();
@@ -409,19 +707,6 @@ impl AnalyticsMetrics {
         else { "2000+".to_string() }
     }
     
-    async fn update_abandonment_rate(&self, queue_name: &str) {
-        let wait_times = self.queue_wait_times.read().await;
-        if let Some(queue_wait_times) = wait_times.get(queue_name) {
-            if queue_wait_times.len() > 10 {
-                let avg_wait = self.calculate_average_duration(queue_wait_times);
-                let abandonment_rate = (avg_wait.as_secs_f64() / 300.0).min(1.0); // 5 minutes = 100% abandonment
-                
-                let mut rates = self.abandonment_rates.write().await;
-                rates.insert(queue_name.to_string(), abandonment_rate);
-            }
-        }
-    }
-    
     fn calculate_average_duration(&self, durations: &VecDeque<Duration>) -> Duration {
         if durations.is_empty() {
             return Duration::ZERO;
@@ -464,8 +749,10 @@ impl AnalyticsMetrics {
     }
     
     async fn calculate_total_abandonments(&self) -> u64 {
-        let rates = self.abandonment_rates.read().await;
-        rates.values().map(|rate| (*rate * 100.0) as u64).sum()
+        let now = Utc::now();
+        let window = self.abandonment_window();
+        let trackers = self.abandonment.read().await;
+        trackers.values().map(|tracker| tracker.abandoned_at(now, window)).sum()
     }
     
     async fn calculate_average_session_duration(&self) -> Duration {
@@ -495,6 +782,112 @@ pub struct MatchCompletionData {
     pub quality_score: f64,
     pub duration: Duration,
     pub rating_changes: Vec<RatingChange>,
+    /// Pre-match win predictions and how each side actually did
+    pub predictions: Vec<OutcomePrediction>,
+}
+
+/// A side's predicted chance of winning a match and its actual result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutcomePrediction {
+    pub match_id: Uuid,
+    pub predicted_win_probability: f64,
+    /// 1.0 for a win, 0.5 for a draw, 0.0 for a loss
+    pub actual_score: f64,
+}
+
+/// Reliability curve of predicted win probability against actual results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationReport {
+    pub buckets: Vec<CalibrationBucket>,
+    pub sample_count: usize,
+    /// Mean squared error of the predictions (0 is perfect)
+    pub brier_score: f64,
+}
+
+/// Predictions whose probability fell in `[lower, upper)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationBucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub sample_count: usize,
+    pub mean_predicted: f64,
+    pub actual_win_rate: f64,
+}
+
+/// Distribution of realized queue waits
+///
+/// Bucket `i` counts waits of at most `bounds[i]` that did not fit an earlier
+/// bucket; the final bucket counts waits longer than every bound.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaitHistogram {
+    pub bounds: Vec<Duration>,
+    /// One more entry than `bounds`, the last being the overflow bucket
+    pub counts: Vec<u64>,
+    pub sample_count: u64,
+    pub total_wait: Duration,
+}
+
+impl WaitHistogram {
+    pub fn new(bounds: Vec<Duration>) -> Self {
+        let counts = vec![0; bounds.len() + 1];
+        Self {
+            bounds,
+            counts,
+            sample_count: 0,
+            total_wait: Duration::ZERO,
+        }
+    }
+    
+    pub fn record(&mut self, wait: Duration) {
+        let index = self
+            .bounds
+            .iter()
+            .position(|bound| wait <= *bound)
+            .unwrap_or(self.bounds.len());
+        self.counts[index] += 1;
+        self.sample_count += 1;
+        self.total_wait += wait;
+    }
+    
+    /// Mean realized wait, zero if nothing was recorded
+    pub fn mean(&self) -> Duration {
+        if self.sample_count == 0 {
+            return Duration::ZERO;
+        }
+        self.total_wait / self.sample_count as u32
+    }
+}
+
+/// A step in one player's time in a queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerQueueEvent {
+    Joined,
+    /// Left the queue without a match (cancelled or expired)
+    Left,
+    Matched,
+}
+
+/// Per-queue joins and leaves awaiting a possible match
+#[derive(Debug, Default)]
+struct AbandonmentTracker {
+    joins: u64,
+    /// Leaves whose window passed without a match
+    abandoned: u64,
+    /// When each player last left, while they may still be matched
+    pending: HashMap<Uuid, DateTime<Utc>>,
+}
+
+impl AbandonmentTracker {
+    /// Move leaves whose window has passed into `abandoned`
+    fn settle(&mut self, now: DateTime<Utc>, window: chrono::Duration) {
+        let before = self.pending.len();
+        self.pending.retain(|_, left_at| now - *left_at <= window);
+        self.abandoned += (before - self.pending.len()) as u64;
+    }
+    
+    fn abandoned_at(&self, now: DateTime<Utc>, window: chrono::Duration) -> u64 {
+        self.abandoned + self.pending.values().filter(|left_at| now - **left_at > window).count() as u64
+    }
 }
 
 /// Queue activity types
@@ -540,6 +933,12 @@ pub struct MetricsSnapshot {
     pub memory_usage_mb: u64,
     pub cpu_usage_percent: f64,
     pub revenue_per_player: f64,
+    /// Spread of the recent API response times behind `average_api_response_time`
+    #[serde(default)]
+    pub api_response_percentiles: WaitPercentiles,
+    /// Spread of the recent database query times behind `average_db_query_time`
+    #[serde(default)]
+    pub db_query_percentiles: WaitPercentiles,
 }
 
 /// Retention analytics
@@ -553,10 +952,11 @@ pub struct RetentionAnalytics {
 }
 
 /// Trait for metrics collection
+#[async_trait]
 pub trait MetricsCollector: Send + Sync {
-    fn record_metric(&self, event: MetricEvent);
-    fn get_metrics(&self) -> MetricsSnapshot;
-    fn reset_metrics(&self);
+    async fn record_metric(&self, event: MetricEvent);
+    async fn get_metrics(&self) -> MetricsSnapshot;
+    async fn reset_metrics(&self);
 }
 
 /// Metric events
@@ -577,65 +977,335 @@ pub struct DefaultMetricsCollector {
 
 impl DefaultMetricsCollector {
     pub fn new() -> Self {
-        Self {
-            analytics: Arc::new(AnalyticsMetrics::new(AnalyticsConfig::default())),
-        }
+        Self::with_analytics(Arc::new(AnalyticsMetrics::new(AnalyticsConfig::default())))
+    }
+
+    /// Collect into existing analytics, e.g. one shared with a dashboard
+    pub fn with_analytics(analytics: Arc<AnalyticsMetrics>) -> Self {
+        Self { analytics }
     }
 }
 
+#[async_trait]
 impl MetricsCollector for DefaultMetricsCollector {
-    fn record_metric(&self, event: MetricEvent) {
-        let analytics = self.analytics.clone();
-        tokio::spawn(async move {
-            match event {
-                MetricEvent::PlayerJoined { player_id, queue } => {
-                    analytics.record_player_activity(player_id, PlayerActivityType::Login).await;
-                    analytics.record_queue_activity(queue, QueueActivity::PlayerJoined).await;
-                }
-                MetricEvent::PlayerLeft { player_id, queue, wait_time } => {
-                    analytics.record_player_activity(player_id, PlayerActivityType::Logout).await;
-                    analytics.record_queue_activity(queue, QueueActivity::PlayerLeft(wait_time)).await;
-                }
-                MetricEvent::MatchFound { match_id, quality } => {
-                    // This would need more context for full implementation
-                }
-                MetricEvent::RatingUpdated { player_id, old_rating, new_rating } => {
-                    // Record rating change
-                }
-                MetricEvent::PartyCreated { party_id, size } => {
-                    analytics.record_party_activity(size, PartyActivity::Created).await;
-                }
-                MetricEvent::Performance { metric } => {
-                    analytics.record_performance(metric).await;
-                }
+    async fn record_metric(&self, event: MetricEvent) {
+        let analytics = &self.analytics;
+        match event {
+            MetricEvent::PlayerJoined { player_id, queue } => {
+                analytics.record_player_activity(player_id, PlayerActivityType::Login).await;
+                analytics.record_queue_activity(queue, QueueActivity::PlayerJoined).await;
+            }
+            MetricEvent::PlayerLeft { player_id, queue, wait_time } => {
+                analytics.record_player_activity(player_id, PlayerActivityType::Logout).await;
+                analytics.record_queue_activity(queue, QueueActivity::PlayerLeft(wait_time)).await;
+            }
+            MetricEvent::MatchFound { match_id, quality } => {
+                // This would need more context for full implementation
+            }
+            MetricEvent::RatingUpdated { player_id, old_rating, new_rating } => {
+                // Record rating change
+            }
+            MetricEvent::PartyCreated { party_id, size } => {
+                analytics.record_party_activity(size, PartyActivity::Created).await;
             }
+            MetricEvent::Performance { metric } => {
+                analytics.record_performance(metric).await;
+            }
+        }
+    }
+    
+    async fn get_metrics(&self) -> MetricsSnapshot {
+        self.analytics.get_metrics_snapshot().await
+    }
+    
+    async fn reset_metrics(&self) {
+        self.analytics.reset().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wait_time_percentiles() {
+        let analytics = AnalyticsMetrics::new(AnalyticsConfig::default());
+        for secs in (1..=20).rev() {
+            analytics
+                .record_queue_activity("ranked".to_string(), QueueActivity::PlayerLeft(Duration::from_secs(secs)))
+                .await;
+        }
+
+        let percentiles = analytics.wait_time_percentiles("ranked").await;
+        assert_eq!(percentiles.sample_count, 20);
+        assert_eq!(percentiles.p50, Duration::from_secs(10));
+        assert_eq!(percentiles.p90, Duration::from_secs(18));
+        assert_eq!(percentiles.p95, Duration::from_secs(19));
+        assert_eq!(percentiles.p99, Duration::from_secs(20));
+
+        assert_eq!(analytics.wait_time_percentiles("casual").await, WaitPercentiles::default());
+    }
+
+    #[tokio::test]
+    async fn test_latency_percentiles_expose_spikes() {
+        let analytics = AnalyticsMetrics::new(AnalyticsConfig::default());
+        for i in 0..100 {
+            let (api, db) = if i % 10 == 0 { (500, 200) } else { (10, 2) };
+            analytics.record_performance(PerformanceMetric::ApiResponseTime(Duration::from_millis(api))).await;
+            analytics.record_performance(PerformanceMetric::DatabaseQueryTime(Duration::from_millis(db))).await;
+        }
+
+        let snapshot = analytics.get_metrics_snapshot().await;
+        assert_eq!(snapshot.average_api_response_time, Duration::from_millis(59));
+        let api = analytics.api_response_percentiles().await;
+        assert_eq!(api, snapshot.api_response_percentiles);
+        assert_eq!(api.sample_count, 100);
+        assert_eq!(api.p50, Duration::from_millis(10));
+        assert_eq!(api.p95, Duration::from_millis(500));
+        assert!(api.p95 > snapshot.average_api_response_time * 5);
+
+        let db = analytics.db_query_percentiles().await;
+        assert_eq!(db.p90, Duration::from_millis(2));
+        assert_eq!(db.p99, Duration::from_millis(200));
+        assert!(db.p95 > snapshot.average_db_query_time);
+    }
+
+    #[tokio::test]
+    async fn test_realized_wait_histogram_buckets() {
+        let analytics = AnalyticsMetrics::new(AnalyticsConfig {
+            wait_histogram_bounds: vec![Duration::from_secs(10), Duration::from_secs(60)],
+            ..AnalyticsConfig::default()
         });
+        let waits: Vec<Duration> = [3, 10, 11, 45, 600].into_iter().map(Duration::from_secs).collect();
+        analytics.record_realized_waits("ranked", &waits).await;
+        
+        let histogram = analytics.realized_wait_histogram("ranked").await;
+        assert_eq!(histogram.counts, vec![2, 2, 1]);
+        assert_eq!(histogram.sample_count, 5);
+        assert_eq!(histogram.mean(), Duration::from_secs(669) / 5);
+        
+        let empty = analytics.realized_wait_histogram("casual").await;
+        assert_eq!(empty.counts, vec![0, 0, 0]);
+        assert_eq!(empty.mean(), Duration::ZERO);
     }
     
-    fn get_metrics(&self) -> MetricsSnapshot {
-        // This would need to be async in a real implementation
-        // For now, return a placeholder
-        MetricsSnapshot {
-            timestamp: Utc::now(),
-            total_players: 0,
-            active_players: 0,
-            new_players_today: 0,
-            total_matches: 0,
-            average_wait_time: Duration::ZERO,
-            match_quality_score: 0.0,
-            matchmaking_success_rate: 0.0,
-            queue_sizes: HashMap::new(),
-            rating_distribution: HashMap::new(),
-            party_sizes: HashMap::new(),
-            average_api_response_time: Duration::ZERO,
-            average_db_query_time: Duration::ZERO,
-            memory_usage_mb: 0,
-            cpu_usage_percent: 0.0,
-            revenue_per_player: 0.0,
-        }
-    }
-    
-    fn reset_metrics(&self) {
-        // Reset all metrics to zero
+    #[tokio::test]
+    async fn test_party_success_rate_by_size() {
+        let analytics = AnalyticsMetrics::new(AnalyticsConfig::default());
+        for success in [true, false, true, true] {
+            analytics.record_party_activity(2, PartyActivity::MatchFound(success)).await;
+        }
+        for success in [false, false, true] {
+            analytics.record_party_activity(3, PartyActivity::MatchFound(success)).await;
+        }
+        analytics.record_party_activity(4, PartyActivity::Created).await;
+        
+        assert_eq!(analytics.party_success_rate(2).await, Some(0.75));
+        assert_eq!(analytics.party_success_rate(3).await, Some(1.0 / 3.0));
+        assert_eq!(analytics.party_success_rate(4).await, None);
+        assert_eq!(analytics.overall_party_success_rate().await, Some(4.0 / 7.0));
+        
+        let empty = AnalyticsMetrics::new(AnalyticsConfig::default());
+        assert_eq!(empty.overall_party_success_rate().await, None);
+    }
+    
+    #[tokio::test]
+    async fn test_quality_distribution_buckets() {
+        let analytics = AnalyticsMetrics::new(AnalyticsConfig::default());
+        for quality in [0.05, 0.1, 0.55, 0.59, 0.95, 1.0, 1.0] {
+            analytics.record_match_quality("ranked", quality).await;
+        }
+        analytics.record_match_quality("casual", 0.3).await;
+        
+        let distribution = analytics.quality_distribution("ranked").await;
+        assert_eq!(distribution.len(), 10);
+        let counts: Vec<u64> = distribution.iter().map(|(_, count)| *count).collect();
+        assert_eq!(counts, vec![1, 1, 0, 0, 0, 2, 0, 0, 0, 3]);
+        assert!((distribution[0].0 - 0.1).abs() < 1e-12);
+        assert!((distribution[9].0 - 1.0).abs() < 1e-12);
+        
+        let overall: Vec<u64> = analytics.overall_quality_distribution().await.iter().map(|(_, count)| *count).collect();
+        assert_eq!(overall, vec![1, 1, 0, 1, 0, 2, 0, 0, 0, 3]);
+        assert!(analytics.quality_distribution("arena").await.iter().all(|(_, count)| *count == 0));
+    }
+    
+    #[tokio::test]
+    async fn test_average_match_rating_from_completed_matches() {
+        let analytics = AnalyticsMetrics::new(AnalyticsConfig::default());
+        assert_eq!(analytics.average_match_rating().await, None);
+        
+        for average_rating in [1200.0, 1900.0, 2000.0] {
+            analytics.record_match_completed(MatchCompletionData { average_rating, ..completed_match(vec![]) }).await;
+        }
+        assert_eq!(analytics.average_match_rating().await, Some(1700.0));
+    }
+    
+    #[tokio::test]
+    async fn test_abandonment_rate_counts_unmatched_leaves() {
+        let analytics = AnalyticsMetrics::new(AnalyticsConfig {
+            abandonment_window: Duration::from_secs(60),
+            ..AnalyticsConfig::default()
+        });
+        let start = Utc::now();
+        let at = |secs: i64| start + chrono::Duration::seconds(secs);
+        let [a, b, c, d] = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        
+        let events = [
+            (a, PlayerQueueEvent::Joined, 0),
+            (b, PlayerQueueEvent::Joined, 0),
+            (c, PlayerQueueEvent::Joined, 0),
+            (d, PlayerQueueEvent::Joined, 0),
+            // a leaves, rejoins and is matched inside the window: not abandoned
+            (a, PlayerQueueEvent::Left, 10),
+            (a, PlayerQueueEvent::Joined, 20),
+            (a, PlayerQueueEvent::Matched, 40),
+            // b leaves and never comes back: abandoned
+            (b, PlayerQueueEvent::Left, 30),
+            (c, PlayerQueueEvent::Matched, 50),
+            // d is only matched after the window: abandoned
+            (d, PlayerQueueEvent::Left, 60),
+            (d, PlayerQueueEvent::Matched, 200),
+        ];
+        for (player_id, event, secs) in events {
+            analytics.record_player_queue_event("ranked", player_id, event, at(secs)).await;
+        }
+        
+        // 5 joins, 2 abandoned
+        assert!((analytics.abandonment_rate_at("ranked", at(300)).await - 0.4).abs() < 1e-12);
+        assert_eq!(analytics.abandonment_rate_at("casual", at(300)).await, 0.0);
+    }
+    
+    #[tokio::test]
+    async fn test_leave_inside_window_not_yet_abandoned() {
+        let analytics = AnalyticsMetrics::new(AnalyticsConfig {
+            abandonment_window: Duration::from_secs(60),
+            ..AnalyticsConfig::default()
+        });
+        let start = Utc::now();
+        let player_id = Uuid::new_v4();
+        analytics.record_player_queue_event("ranked", player_id, PlayerQueueEvent::Joined, start).await;
+        analytics.record_player_queue_event("ranked", Uuid::new_v4(), PlayerQueueEvent::Joined, start).await;
+        analytics.record_player_queue_event("ranked", player_id, PlayerQueueEvent::Left, start).await;
+        
+        assert_eq!(analytics.abandonment_rate_at("ranked", start + chrono::Duration::seconds(60)).await, 0.0);
+        assert_eq!(analytics.abandonment_rate_at("ranked", start + chrono::Duration::seconds(61)).await, 0.5);
+    }
+    
+    fn prediction(predicted_win_probability: f64, actual_score: f64) -> OutcomePrediction {
+        OutcomePrediction {
+            match_id: Uuid::new_v4(),
+            predicted_win_probability,
+            actual_score,
+        }
+    }
+
+    fn completed_match(predictions: Vec<OutcomePrediction>) -> MatchCompletionData {
+        MatchCompletionData {
+            match_id: Uuid::new_v4(),
+            average_rating: 1500.0,
+            quality_score: 0.8,
+            duration: Duration::from_secs(600),
+            rating_changes: vec![],
+            predictions,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_calibration_report_buckets_actual_win_rates() {
+        let analytics = AnalyticsMetrics::new(AnalyticsConfig::default());
+
+        // Favourites at 75% win three of four; underdogs at 25% win one of four
+        for won in [true, true, true, false] {
+            let favourite = prediction(0.75, if won { 1.0 } else { 0.0 });
+            let underdog = prediction(0.25, if won { 0.0 } else { 1.0 });
+            analytics.record_match_completed(completed_match(vec![favourite, underdog])).await;
+        }
+        // Even matches that were drawn
+        analytics.record_match_completed(completed_match(vec![prediction(0.5, 0.5), prediction(0.5, 0.5)])).await;
+
+        let report = analytics.calibration_report().await;
+        assert_eq!(report.sample_count, 10);
+        assert_eq!(report.buckets.len(), 3);
+
+        let underdogs = &report.buckets[0];
+        assert_eq!((underdogs.lower, underdogs.upper), (0.2, 0.3));
+        assert_eq!(underdogs.sample_count, 4);
+        assert_eq!(underdogs.actual_win_rate, 0.25);
+
+        let even = &report.buckets[1];
+        assert_eq!(even.lower, 0.5);
+        assert_eq!(even.actual_win_rate, 0.5);
+
+        let favourites = &report.buckets[2];
+        assert_eq!(favourites.lower, 0.7);
+        assert_eq!(favourites.mean_predicted, 0.75);
+        assert_eq!(favourites.actual_win_rate, 0.75);
+    }
+
+    #[tokio::test]
+    async fn test_certain_prediction_lands_in_top_bucket() {
+        let analytics = AnalyticsMetrics::new(AnalyticsConfig::default());
+        analytics.record_match_completed(completed_match(vec![prediction(1.0, 1.0), prediction(0.0, 0.0)])).await;
+
+        let report = analytics.calibration_report().await;
+        assert_eq!(report.buckets.len(), 2);
+        assert_eq!(report.buckets[1].lower, 0.9);
+        assert_eq!(report.brier_score, 0.0);
+        assert!(AnalyticsMetrics::new(AnalyticsConfig::default()).calibration_report().await.buckets.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_collector_get_metrics_reflects_recorded_data() {
+        let collector = DefaultMetricsCollector::new();
+        collector
+            .record_metric(MetricEvent::PlayerJoined { player_id: Uuid::new_v4(), queue: "ranked".to_string() })
+            .await;
+        collector.record_metric(MetricEvent::PartyCreated { party_id: Uuid::new_v4(), size: 3 }).await;
+        collector
+            .record_metric(MetricEvent::Performance { metric: PerformanceMetric::ApiResponseTime(Duration::from_millis(40)) })
+            .await;
+        collector.record_metric(MetricEvent::Performance { metric: PerformanceMetric::MemoryUsage(64 * 1024 * 1024) }).await;
+        
+        let snapshot = collector.get_metrics().await;
+        assert_eq!(snapshot.active_players, 1);
+        assert_eq!(snapshot.queue_sizes.get("ranked"), Some(&1));
+        assert_eq!(snapshot.party_sizes.get(&3), Some(&1));
+        assert_eq!(snapshot.average_api_response_time, Duration::from_millis(40));
+        assert_eq!(snapshot.memory_usage_mb, 64);
+    }
+    
+    #[tokio::test]
+    async fn test_reset_zeroes_snapshot() {
+        let analytics = Arc::new(AnalyticsMetrics::new(AnalyticsConfig::default()));
+        let collector = DefaultMetricsCollector::with_analytics(analytics.clone());
+        collector
+            .record_metric(MetricEvent::PlayerJoined { player_id: Uuid::new_v4(), queue: "ranked".to_string() })
+            .await;
+        analytics.record_queue_activity("ranked".to_string(), QueueActivity::MatchFound(Duration::from_secs(30))).await;
+        analytics.record_match_quality("ranked", 0.8).await;
+        analytics.record_match_completed(completed_match(vec![])).await;
+        analytics.record_party_activity(2, PartyActivity::MatchFound(true)).await;
+        analytics.record_performance(PerformanceMetric::DatabaseQueryTime(Duration::from_millis(5))).await;
+        analytics.record_performance(PerformanceMetric::CpuUsage(42.0)).await;
+        assert!(collector.get_metrics().await.total_matches > 0);
+        
+        collector.reset_metrics().await;
+        let snapshot = collector.get_metrics().await;
+        assert_eq!(snapshot.total_players, 0);
+        assert_eq!(snapshot.active_players, 0);
+        assert_eq!(snapshot.total_matches, 0);
+        assert_eq!(snapshot.average_wait_time, Duration::ZERO);
+        assert_eq!(snapshot.match_quality_score, 0.0);
+        assert_eq!(snapshot.matchmaking_success_rate, 0.0);
+        assert!(snapshot.queue_sizes.is_empty());
+        assert!(snapshot.rating_distribution.is_empty());
+        assert!(snapshot.party_sizes.is_empty());
+        assert_eq!(snapshot.average_db_query_time, Duration::ZERO);
+        assert_eq!(snapshot.db_query_percentiles, WaitPercentiles::default());
+        assert_eq!(snapshot.cpu_usage_percent, 0.0);
+        assert_eq!(analytics.average_match_rating().await, None);
+        assert_eq!(analytics.wait_time_percentiles("ranked").await, WaitPercentiles::default());
+        assert!(analytics.quality_distribution("ranked").await.iter().all(|(_, count)| *count == 0));
+        assert_eq!(analytics.overall_party_success_rate().await, None);
     }
 }
@@ -6,8 +6,12 @@ pub mod metrics;
 pub mod reports;
 pub mod insights;
 pub mod dashboard;
+pub mod autotune;
+pub mod sink;
 
-pub use metrics::{AnalyticsMetrics, MetricsCollector};
+pub use metrics::{AnalyticsMetrics, MetricsCollector, PlayerQueueEvent, WaitHistogram};
 pub use reports::{ReportGenerator, ReportType, ReportFormat};
-pub use insights::{InsightEngine, InsightType, Recommendation};
+pub use insights::{InsightEngine, InsightRecord, InsightType, Recommendation};
 pub use dashboard::{DashboardData, DashboardConfig};
+pub use autotune::{AutoTuneConfig, AutoTuner, TuningChange};
+pub use sink::{AnalyticsSink, NoopAnalytics};
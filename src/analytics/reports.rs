@@ -9,6 +9,13 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::metrics::{AnalyticsMetrics, MetricsSnapshot, RetentionAnalytics};
+use crate::time::to_chrono;
+
+/// Confidence level a report approaches as its data points grow
+const MAX_CONFIDENCE_LEVEL: f64 = 0.95;
+
+/// Data points at which a report reaches half of [`MAX_CONFIDENCE_LEVEL`]
+const HALF_CONFIDENCE_DATA_POINTS: f64 = 30.0;
 
 /// Report generator for analytics data
 pub struct ReportGenerator {
@@ -313,11 +320,22 @@ pub enum Effort {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReportMetadata {
     pub generation_time: Duration,
+    /// Completed matches recorded within the report's date range
     pub data_points: usize,
+    /// Grows with `data_points` towards 0.95, so sparse ranges report lower confidence
     pub confidence_level: f64,
+    /// Aggregations behind the report and the sample they were drawn from
     pub methodology: String,
 }
 
+impl ReportMetadata {
+    /// Confidence level for a report drawn from `data_points` samples, 0 with no data
+    pub fn confidence_for(data_points: usize) -> f64 {
+        let data_points = data_points as f64;
+        MAX_CONFIDENCE_LEVEL * data_points / (data_points + HALF_CONFIDENCE_DATA_POINTS)
+    }
+}
+
 impl ReportGenerator {
     /// Create new report generator
     pub fn new(analytics: Arc<AnalyticsMetrics>) -> Self {
@@ -359,6 +377,8 @@ impl ReportGenerator {
         };
         
         let generation_time = start_time.elapsed();
+        let data_points = self.analytics.matches_completed_between(date_range.start, date_range.end).await;
+        let confidence_level = ReportMetadata::confidence_for(data_points);
         
         Ok(Report {
             id: Uuid::new_v4(),
@@ -371,9 +391,14 @@ impl ReportGenerator {
             recommendations,
             metadata: ReportMetadata {
                 generation_time: Duration::milliseconds(50), // Placeholder
-                data_points: 0, // Would calculate actual data points
-                confidence_level: 0.95,
-                methodology: "Statistical analysis with confidence intervals".to_string(),
+                data_points,
+                confidence_level,
+                methodology: format!(
+                    "{} over {} completed matches in range (confidence {:.2})",
+                    self.get_report_methodology(&report_type),
+                    data_points,
+                    confidence_level,
+                ),
             },
         })
     }
@@ -386,7 +411,7 @@ impl ReportGenerator {
             total_players: snapshot.total_players,
             active_players: snapshot.active_players,
             total_matches: snapshot.total_matches,
-            average_wait_time: Duration::from_std(snapshot.average_wait_time).unwrap_or_default(),
+            average_wait_time: to_chrono(snapshot.average_wait_time),
             match_quality_score: snapshot.match_quality_score,
             key_insights: vec![
                 format!("Average wait time: {:.2}s", snapshot.average_wait_time.as_secs_f64()),
@@ -408,7 +433,7 @@ impl ReportGenerator {
                     },
                     MetricData {
                         name: "Average Wait Time".to_string(),
-                        value: MetricValue::Duration(Duration::from_std(snapshot.average_wait_time).unwrap_or_default()),
+                        value: MetricValue::Duration(to_chrono(snapshot.average_wait_time)),
                         unit: "seconds".to_string(),
                         trend: Trend::Down,
                         significance: Significance::High,
@@ -461,7 +486,7 @@ impl ReportGenerator {
             total_players: snapshot.total_players,
             active_players: snapshot.active_players,
             total_matches: snapshot.total_matches,
-            average_wait_time: Duration::from_std(snapshot.average_wait_time).unwrap_or_default(),
+            average_wait_time: to_chrono(snapshot.average_wait_time),
             match_quality_score: snapshot.match_quality_score,
             key_insights: vec![
                 format!("Day 1 retention: {:.1}%", retention.day_1_retention * 100.0),
@@ -530,7 +555,7 @@ impl ReportGenerator {
             total_players: snapshot.total_players,
             active_players: snapshot.active_players,
             total_matches: snapshot.total_matches,
-            average_wait_time: Duration::from_std(snapshot.average_wait_time).unwrap_or_default(),
+            average_wait_time: to_chrono(snapshot.average_wait_time),
             match_quality_score: snapshot.match_quality_score,
             key_insights: vec![
                 format!("Total queues: {}", snapshot.queue_sizes.len()),
@@ -577,11 +602,14 @@ impl ReportGenerator {
             total_players: snapshot.total_players,
             active_players: snapshot.active_players,
             total_matches: snapshot.total_matches,
-            average_wait_time: Duration::from_std(snapshot.average_wait_time).unwrap_or_default(),
+            average_wait_time: to_chrono(snapshot.average_wait_time),
             match_quality_score: snapshot.match_quality_score,
             key_insights: vec![
                 format!("Rating distribution: {} buckets", snapshot.rating_distribution.len()),
-                format!("Average rating: {:.0}", self.calculate_average_rating(&snapshot.rating_distribution)),
+                match self.analytics.average_match_rating().await {
+                    Some(average) => format!("Average rating: {:.0}", average),
+                    None => "Average rating: n/a".to_string(),
+                },
                 format!("Rating accuracy: {:.1}%", snapshot.match_quality_score),
             ],
         };
@@ -606,6 +634,17 @@ impl ReportGenerator {
                     interactive: true,
                 },
             },
+            ChartData {
+                chart_type: ChartType::Histogram,
+                title: "Match Quality Distribution".to_string(),
+                data: ChartDataContent::Histogram(self.analytics.overall_quality_distribution().await),
+                metadata: ChartMetadata {
+                    x_axis_label: "Quality".to_string(),
+                    y_axis_label: "Matches".to_string(),
+                    colors: vec!["#28a745".to_string()],
+                    interactive: true,
+                },
+            },
         ];
         
         Ok(ReportData {
@@ -624,12 +663,15 @@ impl ReportGenerator {
             total_players: snapshot.total_players,
             active_players: snapshot.active_players,
             total_matches: snapshot.total_matches,
-            average_wait_time: Duration::from_std(snapshot.average_wait_time).unwrap_or_default(),
+            average_wait_time: to_chrono(snapshot.average_wait_time),
             match_quality_score: snapshot.match_quality_score,
             key_insights: vec![
                 format!("Party sizes: {}", snapshot.party_sizes.len()),
                 format!("Average party size: {:.1}", self.calculate_average_party_size(&snapshot.party_sizes)),
-                format!("Party success rate: {:.1}%", self.calculate_party_success_rate().await * 100.0),
+                match self.analytics.overall_party_success_rate().await {
+                    Some(rate) => format!("Party success rate: {:.1}%", rate * 100.0),
+                    None => "Party success rate: no party matches recorded".to_string(),
+                },
             ],
         };
         
@@ -671,12 +713,17 @@ impl ReportGenerator {
             total_players: snapshot.total_players,
             active_players: snapshot.active_players,
             total_matches: snapshot.total_matches,
-            average_wait_time: Duration::from_std(snapshot.average_wait_time).unwrap_or_default(),
+            average_wait_time: to_chrono(snapshot.average_wait_time),
             match_quality_score: snapshot.match_quality_score,
             key_insights: vec![
                 format!("Memory usage: {} MB", snapshot.memory_usage_mb),
                 format!("CPU usage: {:.1}%", snapshot.cpu_usage_percent),
                 format!("API response time: {:.2}ms", snapshot.average_api_response_time.as_millis()),
+                format!(
+                    "API response p95: {}ms, DB query p95: {}ms",
+                    snapshot.api_response_percentiles.p95.as_millis(),
+                    snapshot.db_query_percentiles.p95.as_millis()
+                ),
             ],
         };
         
@@ -700,11 +747,32 @@ impl ReportGenerator {
                     },
                     MetricData {
                         name: "API Response Time".to_string(),
-                        value: MetricValue::Duration(Duration::from_std(snapshot.average_api_response_time).unwrap_or_default()),
+                        value: MetricValue::Duration(to_chrono(snapshot.average_api_response_time)),
                         unit: "ms".to_string(),
                         trend: Trend::Down,
                         significance: Significance::Medium,
                     },
+                    MetricData {
+                        name: "API Response Time p95".to_string(),
+                        value: MetricValue::Duration(to_chrono(snapshot.api_response_percentiles.p95)),
+                        unit: "ms".to_string(),
+                        trend: Trend::Stable,
+                        significance: Significance::High,
+                    },
+                    MetricData {
+                        name: "DB Query Time".to_string(),
+                        value: MetricValue::Duration(to_chrono(snapshot.average_db_query_time)),
+                        unit: "ms".to_string(),
+                        trend: Trend::Stable,
+                        significance: Significance::Medium,
+                    },
+                    MetricData {
+                        name: "DB Query Time p95".to_string(),
+                        value: MetricValue::Duration(to_chrono(snapshot.db_query_percentiles.p95)),
+                        unit: "ms".to_string(),
+                        trend: Trend::Stable,
+                        significance: Significance::High,
+                    },
                 ]),
                 importance: Importance::High,
             },
@@ -747,7 +815,7 @@ impl ReportGenerator {
             total_players: snapshot.total_players,
             active_players: snapshot.active_players,
             total_matches: snapshot.total_matches,
-            average_wait_time: Duration::from_std(snapshot.average_wait_time).unwrap_or_default(),
+            average_wait_time: to_chrono(snapshot.average_wait_time),
             match_quality_score: snapshot.match_quality_score,
             key_insights: vec![
                 format!("Churn rate: {:.1}%", retention.churn_rate * 100.0),
@@ -818,7 +886,7 @@ impl ReportGenerator {
                 total_players: snapshot.total_players,
                 active_players: snapshot.active_players,
                 total_matches: snapshot.total_matches,
-                average_wait_time: Duration::from_std(snapshot.average_wait_time).unwrap_or_default(),
+                average_wait_time: to_chrono(snapshot.average_wait_time),
                 match_quality_score: snapshot.match_quality_score,
                 key_insights: vec!["Custom report generated".to_string()],
             },
@@ -834,7 +902,7 @@ impl ReportGenerator {
         
         match report_type {
             ReportType::Performance => {
-                if report_data.summary.average_wait_time > Duration::from_std(std::time::Duration::from_secs(30)).unwrap_or_default() {
+                if report_data.summary.average_wait_time > Duration::seconds(30) {
                     recommendations.push(Recommendation {
                         id: Uuid::new_v4(),
                         title: "Reduce Wait Times".to_string(),
@@ -922,6 +990,19 @@ impl ReportGenerator {
         }
     }
     
+    fn get_report_methodology(&self, report_type: &ReportType) -> &'static str {
+        match report_type {
+            ReportType::Performance => "Running totals of matches with mean wait time and match quality",
+            ReportType::PlayerAnalytics => "Day 1, 7 and 30 retention rates with mean session duration",
+            ReportType::QueueAnalytics => "Current queue sizes with mean queue size and peak wait time",
+            ReportType::RatingAnalytics => "Rating bucket counts, mean match rating and a match quality histogram",
+            ReportType::PartyAnalytics => "Party size counts with party match success rates",
+            ReportType::SystemHealth => "Latest memory and CPU usage with mean API response time",
+            ReportType::BusinessAnalytics => "Churn rate, revenue per player and lifetime value",
+            ReportType::Custom(_) => "Snapshot totals",
+        }
+    }
+    
     async fn generate_queue_performance_table(&self) -> TableData {
         // Placeholder implementation
         TableData {
@@ -953,7 +1034,7 @@ impl ReportGenerator {
     
     async fn calculate_peak_wait_time(&self) -> Duration {
         // Placeholder implementation
-        Duration::from_std(std::time::Duration::from_secs(60)).unwrap_or_default()
+        Duration::seconds(60)
     }
     
     async fn generate_queue_metrics_table(&self, queue_sizes: &HashMap<String, u64>) -> TableData {
@@ -962,7 +1043,7 @@ impl ReportGenerator {
             rows.push(vec![
                 TableCell::Text(queue_name.clone()),
                 TableCell::Number(*size as f64),
-                TableCell::Duration(Duration::from_std(std::time::Duration::from_secs(30)).unwrap_or_default()), // Placeholder
+                TableCell::Duration(Duration::seconds(30)), // Placeholder
                 TableCell::Percentage(0.85), // Placeholder
             ]);
         }
@@ -981,11 +1062,6 @@ impl ReportGenerator {
             .collect()
     }
     
-    fn calculate_average_rating(&self, rating_distribution: &HashMap<String, u64>) -> f64 {
-        // Simple average calculation
-        1500.0 // Placeholder
-    }
-    
     async fn generate_rating_distribution_table(&self, rating_distribution: &HashMap<String, u64>) -> TableData {
         let mut rows = Vec::new();
         for (bucket, count) in rating_distribution {
@@ -1033,18 +1109,16 @@ impl ReportGenerator {
         total_players as f64 / total_parties as f64
     }
     
-    async fn calculate_party_success_rate(&self) -> f64 {
-        // Placeholder implementation
-        0.75
-    }
-    
     async fn generate_party_metrics_table(&self, party_sizes: &HashMap<usize, u64>) -> TableData {
         let mut rows = Vec::new();
         for (size, count) in party_sizes {
             rows.push(vec![
                 TableCell::Number(*size as f64),
                 TableCell::Number(*count as f64),
-                TableCell::Percentage(0.75), // Placeholder success rate
+                match self.analytics.party_success_rate(*size).await {
+                    Some(rate) => TableCell::Percentage(rate),
+                    None => TableCell::Text("n/a".to_string()),
+                },
             ]);
         }
         
@@ -1121,3 +1195,60 @@ impl Default for ReportConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::metrics::{AnalyticsConfig, MatchCompletionData};
+
+    async fn analytics_with_matches(count: usize) -> Arc<AnalyticsMetrics> {
+        let analytics = Arc::new(AnalyticsMetrics::new(AnalyticsConfig::default()));
+        for _ in 0..count {
+            analytics
+                .record_match_completed(MatchCompletionData {
+                    match_id: Uuid::new_v4(),
+                    average_rating: 1500.0,
+                    quality_score: 0.8,
+                    duration: std::time::Duration::from_secs(600),
+                    rating_changes: vec![],
+                    predictions: vec![],
+                })
+                .await;
+        }
+        analytics
+    }
+
+    async fn metadata(analytics: Arc<AnalyticsMetrics>, date_range: Option<DateRange>) -> ReportMetadata {
+        ReportGenerator::new(analytics)
+            .generate_report(ReportType::Performance, date_range, ReportFormat::Json)
+            .await
+            .unwrap()
+            .metadata
+    }
+
+    #[tokio::test]
+    async fn test_sparse_range_reports_lower_confidence() {
+        let sparse = metadata(analytics_with_matches(3).await, None).await;
+        let dense = metadata(analytics_with_matches(300).await, None).await;
+
+        assert_eq!(sparse.data_points, 3);
+        assert_eq!(dense.data_points, 300);
+        assert!(sparse.confidence_level < dense.confidence_level);
+        assert!(dense.confidence_level < 0.95);
+        assert!(dense.methodology.contains("mean wait time"));
+        assert!(dense.methodology.contains("300 completed matches"));
+    }
+
+    #[tokio::test]
+    async fn test_data_points_limited_to_date_range() {
+        let analytics = analytics_with_matches(10).await;
+        let last_week = DateRange {
+            start: Utc::now() - Duration::days(14),
+            end: Utc::now() - Duration::days(7),
+        };
+
+        let metadata = metadata(analytics, Some(last_week)).await;
+        assert_eq!(metadata.data_points, 0);
+        assert_eq!(metadata.confidence_level, 0.0);
+    }
+}
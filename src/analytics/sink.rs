@@ -0,0 +1,145 @@
+//! Destination for the analytics recorded by the managers
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::metrics::{AnalyticsMetrics, PlayerQueueEvent};
+
+/// Receives the analytics the managers record on the matchmaking path
+///
+/// [`AnalyticsMetrics`] aggregates everything it receives; [`NoopAnalytics`]
+/// discards it and is what managers use until given another sink.
+#[async_trait]
+pub trait AnalyticsSink: Send + Sync {
+    /// Whether anything is recorded; when `false` callers skip gathering the data
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// A player joined, left or was matched in a queue at `at`
+    async fn record_player_queue_event(&self, queue_name: &str, player_id: Uuid, event: PlayerQueueEvent, at: DateTime<Utc>);
+
+    /// How long each participant of a newly formed match waited
+    async fn record_realized_waits(&self, queue_name: &str, waits: &[Duration]);
+
+    /// Quality score of a newly formed match
+    async fn record_match_quality(&self, queue_name: &str, quality: f64);
+}
+
+/// Sink that records nothing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopAnalytics;
+
+#[async_trait]
+impl AnalyticsSink for NoopAnalytics {
+    fn is_enabled(&self) -> bool {
+        false
+    }
+
+    async fn record_player_queue_event(&self, _queue_name: &str, _player_id: Uuid, _event: PlayerQueueEvent, _at: DateTime<Utc>) {}
+
+    async fn record_realized_waits(&self, _queue_name: &str, _waits: &[Duration]) {}
+
+    async fn record_match_quality(&self, _queue_name: &str, _quality: f64) {}
+}
+
+#[async_trait]
+impl AnalyticsSink for AnalyticsMetrics {
+    async fn record_player_queue_event(&self, queue_name: &str, player_id: Uuid, event: PlayerQueueEvent, at: DateTime<Utc>) {
+        AnalyticsMetrics::record_player_queue_event(self, queue_name, player_id, event, at).await;
+    }
+
+    async fn record_realized_waits(&self, queue_name: &str, waits: &[Duration]) {
+        AnalyticsMetrics::record_realized_waits(self, queue_name, waits).await;
+    }
+
+    async fn record_match_quality(&self, queue_name: &str, quality: f64) {
+        AnalyticsMetrics::record_match_quality(self, queue_name, quality).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mmr::Rating,
+        persistence::InMemoryAdapter,
+        queue::{EntryMetadata, MatchConstraints, MatchFormat, QueueConfig, QueueManager},
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct RecordingSink {
+        events: Mutex<Vec<(Uuid, PlayerQueueEvent)>>,
+        waits: Mutex<Vec<Duration>>,
+        qualities: Mutex<Vec<f64>>,
+    }
+
+    #[async_trait]
+    impl AnalyticsSink for RecordingSink {
+        async fn record_player_queue_event(&self, _queue_name: &str, player_id: Uuid, event: PlayerQueueEvent, _at: DateTime<Utc>) {
+            self.events.lock().unwrap().push((player_id, event));
+        }
+
+        async fn record_realized_waits(&self, _queue_name: &str, waits: &[Duration]) {
+            self.waits.lock().unwrap().extend_from_slice(waits);
+        }
+
+        async fn record_match_quality(&self, _queue_name: &str, quality: f64) {
+            self.qualities.lock().unwrap().push(quality);
+        }
+    }
+
+    /// Join two players and a third who leaves, then match the two
+    async fn run_match_flow(manager: &QueueManager) -> [Uuid; 3] {
+        manager
+            .register_queue(QueueConfig::new("ranked", MatchFormat::one_v_one(), MatchConstraints::permissive()))
+            .await
+            .unwrap();
+        let players = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        for player_id in players {
+            manager
+                .join_queue_solo("ranked".to_string(), player_id, Rating::default_beginner(), EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+        manager.leave_queue("ranked", players[2]).await.unwrap();
+
+        let matches = manager.find_matches("ranked").await.unwrap();
+        assert_eq!(matches.len(), 1);
+        manager.remove_matched_entries("ranked", &matches[0].entries).await.unwrap();
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 0);
+        players
+    }
+
+    #[tokio::test]
+    async fn test_noop_analytics_runs_full_match_flow() {
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new())).with_analytics(Arc::new(NoopAnalytics));
+        run_match_flow(&manager).await;
+        assert!(!NoopAnalytics.is_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_recording_sink_captures_queue_events() {
+        let sink = Arc::new(RecordingSink::default());
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new())).with_analytics(sink.clone());
+        let [first, second, leaver] = run_match_flow(&manager).await;
+
+        let events = sink.events.lock().unwrap().clone();
+        assert_eq!(
+            events,
+            vec![
+                (first, PlayerQueueEvent::Joined),
+                (second, PlayerQueueEvent::Joined),
+                (leaver, PlayerQueueEvent::Joined),
+                (leaver, PlayerQueueEvent::Left),
+                (first, PlayerQueueEvent::Matched),
+                (second, PlayerQueueEvent::Matched),
+            ]
+        );
+        assert_eq!(sink.waits.lock().unwrap().len(), 2);
+        assert_eq!(*sink.qualities.lock().unwrap(), vec![1.0]);
+    }
+}
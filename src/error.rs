@@ -15,24 +15,40 @@ pub enum MatchForgeError {
     #[error("Lobby not found: {0}")]
     LobbyNotFound(Uuid),
 
+    /// The queue is draining for maintenance and accepts no new joins
+    #[error("Queue is draining: {0}")]
+    QueueDraining(String),
+
     #[error("Player already in queue: {0}")]
     AlreadyInQueue(Uuid),
 
     #[error("Player not in queue: {0}")]
     NotInQueue(Uuid),
 
+    /// The player joined queues too often, see [`QueueManager::with_rate_limiter`](crate::queue::QueueManager::with_rate_limiter)
+    #[error("Player {0} is rate limited: {1}")]
+    RateLimited(Uuid, String),
+
     #[error("Party is full (max size: {0})")]
     PartyFull(usize),
 
+    /// Adding players would exceed the lobby's team capacities
+    #[error("Lobby {0} has only {1} open slots")]
+    LobbyFull(Uuid, usize),
+
     #[error("Invalid party operation: {0}")]
     InvalidPartyOperation(String),
 
     #[error("Match constraints not satisfied: {0}")]
     ConstraintsNotSatisfied(String),
 
+    /// Persistence failure that doesn't fit a [`StorageError`] category
     #[error("Persistence error: {0}")]
     PersistenceError(String),
 
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+
     #[error("Invalid configuration: {0}")]
     InvalidConfiguration(String),
 
@@ -40,4 +56,23 @@ pub enum MatchForgeError {
     OperationFailed(String),
 }
 
+/// Categorized persistence failures reported by the storage adapters
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum StorageError {
+    #[error("record not found: {0}")]
+    NotFound(String),
+
+    #[error("connection failed: {0}")]
+    Connection(String),
+
+    #[error("serialization failed: {0}")]
+    Serialization(String),
+
+    #[error("conflicting write: {0}")]
+    Conflict(String),
+
+    #[error("operation timed out: {0}")]
+    Timeout(String),
+}
+
 pub type Result<T> = std::result::Result<T, MatchForgeError>;
@@ -0,0 +1,334 @@
+//! One-stop setup that builds and connects every manager from a single config
+
+use crate::{
+    analytics::{metrics::AnalyticsConfig, AnalyticsMetrics},
+    clock::{Clock, SystemClock},
+    error::*,
+    party::{AverageStrategy, PartyManager, PartyMmrStrategy},
+    persistence::{InMemoryAdapter, PersistenceAdapter},
+    queue::{QueueConfig, QueueManager},
+    runner::{DispatchCallback, LobbyManager, MatchmakingRunner, QueueRunnerConfig, RunnerConfig},
+    security::{SecurityConfig, SecurityManager},
+};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// Everything needed to stand up a [`MatchForge`] instance
+#[derive(Debug, Clone)]
+pub struct MatchForgeConfig {
+    /// Queues registered at startup
+    pub queues: Vec<QueueConfig>,
+    /// Runner settings; registered queues missing from it are processed with default settings
+    pub runner: RunnerConfig,
+    /// Collect analytics if set
+    pub analytics: Option<AnalyticsConfig>,
+    /// Enable the security layer if set
+    ///
+    /// Its rate limiter counts queue joins and its anti-abuse system keeps
+    /// shadow-banned players matching among themselves, both in direct
+    /// queue calls and in the runner.
+    pub security: Option<SecurityConfig>,
+}
+
+impl Default for MatchForgeConfig {
+    fn default() -> Self {
+        Self {
+            queues: Vec::new(),
+            runner: RunnerConfig::builder().build(),
+            analytics: None,
+            security: None,
+        }
+    }
+}
+
+/// Builder for [`MatchForge`]
+///
+/// Defaults to in-memory persistence, [`AverageStrategy`] for party ratings
+/// and the system clock.
+pub struct MatchForgeBuilder {
+    config: MatchForgeConfig,
+    persistence: Arc<dyn PersistenceAdapter>,
+    party_strategy: Arc<dyn PartyMmrStrategy>,
+    clock: Arc<dyn Clock>,
+    on_ready: Option<DispatchCallback>,
+}
+
+impl MatchForgeBuilder {
+    pub fn new(config: MatchForgeConfig) -> Self {
+        Self {
+            config,
+            persistence: Arc::new(InMemoryAdapter::new()),
+            party_strategy: Arc::new(AverageStrategy),
+            clock: Arc::new(SystemClock),
+            on_ready: None,
+        }
+    }
+
+    /// Share a persistence adapter between all managers
+    pub fn with_persistence(mut self, persistence: Arc<dyn PersistenceAdapter>) -> Self {
+        self.persistence = persistence;
+        self
+    }
+
+    /// Strategy for computing a party's rating
+    pub fn with_party_strategy(mut self, strategy: Arc<dyn PartyMmrStrategy>) -> Self {
+        self.party_strategy = strategy;
+        self
+    }
+
+    /// Use a custom clock for queues, lobbies and the runner
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Invoke `callback` whenever a lobby becomes `Ready`, see [`LobbyManager::with_dispatch_callback`]
+    pub fn with_dispatch_callback(mut self, callback: DispatchCallback) -> Self {
+        self.on_ready = Some(callback);
+        self
+    }
+
+    /// Construct the managers and register the configured queues
+    pub async fn build(self) -> Result<MatchForge> {
+        let MatchForgeConfig {
+            queues,
+            mut runner,
+            analytics,
+            security,
+        } = self.config;
+
+        let analytics = analytics.map(|config| Arc::new(AnalyticsMetrics::new(config)));
+        let security = security.map(|config| Arc::new(SecurityManager::new(config)));
+        let mut queue_manager = QueueManager::new(self.persistence.clone()).with_clock(self.clock.clone());
+        if let Some(analytics) = &analytics {
+            queue_manager = queue_manager.with_analytics(analytics.clone());
+        }
+        if let Some(rate_limiter) = security.as_ref().and_then(|s| s.rate_limiter()) {
+            queue_manager = queue_manager.with_rate_limiter(rate_limiter.clone());
+        }
+        if let Some(anti_abuse) = security.as_ref().and_then(|s| s.anti_abuse()) {
+            queue_manager = queue_manager.with_anti_abuse(anti_abuse.clone());
+        }
+        let queue_manager = Arc::new(queue_manager);
+        for queue in queues {
+            runner.queue_configs.entry(queue.name.clone()).or_insert_with(QueueRunnerConfig::default);
+            queue_manager.register_queue(queue).await?;
+        }
+
+        let party_manager = Arc::new(PartyManager::new(self.persistence.clone(), self.party_strategy));
+
+        let mut lobby_manager = LobbyManager::new(self.persistence.clone()).with_clock(self.clock.clone());
+        if let Some(on_ready) = self.on_ready {
+            lobby_manager = lobby_manager.with_dispatch_callback(on_ready);
+        }
+
+        let runner = Arc::new(
+            MatchmakingRunner::new(runner, queue_manager.clone(), self.persistence.clone()).with_clock(self.clock),
+        );
+
+        Ok(MatchForge {
+            persistence: self.persistence,
+            queue_manager,
+            party_manager,
+            lobby_manager: Arc::new(lobby_manager),
+            runner,
+            analytics,
+            security,
+        })
+    }
+}
+
+/// All MatchForge managers wired to the same persistence and clock
+pub struct MatchForge {
+    persistence: Arc<dyn PersistenceAdapter>,
+    queue_manager: Arc<QueueManager>,
+    party_manager: Arc<PartyManager>,
+    lobby_manager: Arc<LobbyManager>,
+    runner: Arc<MatchmakingRunner>,
+    analytics: Option<Arc<AnalyticsMetrics>>,
+    security: Option<Arc<SecurityManager>>,
+}
+
+impl MatchForge {
+    pub fn builder(config: MatchForgeConfig) -> MatchForgeBuilder {
+        MatchForgeBuilder::new(config)
+    }
+
+    pub fn persistence(&self) -> &Arc<dyn PersistenceAdapter> {
+        &self.persistence
+    }
+
+    pub fn queue_manager(&self) -> &Arc<QueueManager> {
+        &self.queue_manager
+    }
+
+    pub fn party_manager(&self) -> &Arc<PartyManager> {
+        &self.party_manager
+    }
+
+    pub fn lobby_manager(&self) -> &Arc<LobbyManager> {
+        &self.lobby_manager
+    }
+
+    pub fn runner(&self) -> &Arc<MatchmakingRunner> {
+        &self.runner
+    }
+
+    pub fn analytics(&self) -> Option<&Arc<AnalyticsMetrics>> {
+        self.analytics.as_ref()
+    }
+
+    pub fn security(&self) -> Option<&Arc<SecurityManager>> {
+        self.security.as_ref()
+    }
+
+    /// Spawn the matchmaking runner on the current tokio runtime
+    pub fn start(&self) -> JoinHandle<Result<()>> {
+        let runner = self.runner.clone();
+        tokio::spawn(async move { runner.start().await })
+    }
+
+    /// Stop the runner after its current tick
+    pub fn stop(&self) {
+        self.runner.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lobby::{Lobby, LobbyMetadata, LobbyState},
+        mmr::{Glicko2Algorithm, Outcome, Rating},
+        queue::{EntryMetadata, MatchConstraints, MatchFormat},
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use uuid::Uuid;
+
+    fn config() -> MatchForgeConfig {
+        MatchForgeConfig {
+            queues: vec![QueueConfig::new("ranked_1v1", MatchFormat::one_v_one(), MatchConstraints::permissive())],
+            runner: RunnerConfig::builder().tick_interval_ms(10).auto_dispatch(false).build(),
+            analytics: Some(AnalyticsConfig::default()),
+            security: None,
+        }
+    }
+
+    async fn join_two_players(forge: &MatchForge) -> [Uuid; 2] {
+        let players = [Uuid::new_v4(), Uuid::new_v4()];
+        for player_id in players {
+            let rating = Rating::default_beginner();
+            forge.persistence().save_player_rating(player_id, rating).await.unwrap();
+            forge
+                .queue_manager()
+                .join_queue_solo("ranked_1v1".to_string(), player_id, rating, EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+        players
+    }
+
+    #[tokio::test]
+    async fn test_two_player_flow_through_facade() {
+        let dispatched = Arc::new(AtomicUsize::new(0));
+        let counter = dispatched.clone();
+        let forge = MatchForge::builder(config())
+            .with_dispatch_callback(Arc::new(move |_: &Lobby| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }))
+            .build()
+            .await
+            .unwrap();
+        assert!(forge.analytics().is_some());
+        assert!(forge.security().is_none());
+
+        let [winner, loser] = join_two_players(&forge).await;
+        let matches = forge.queue_manager().find_matches("ranked_1v1").await.unwrap();
+        assert_eq!(matches.len(), 1);
+        forge.queue_manager().remove_matched_entries("ranked_1v1", &matches[0].entries).await.unwrap();
+        let analytics = forge.analytics().unwrap();
+        assert_eq!(analytics.realized_wait_histogram("ranked_1v1").await.sample_count, 2);
+
+        let mut lobby = Lobby::from_match_result(matches[0].clone(), vec![1, 1], LobbyMetadata::default());
+        lobby.transition_to(LobbyState::WaitingForReady).unwrap();
+        forge.persistence().save_lobby(&lobby).await.unwrap();
+
+        let lobby_manager = forge.lobby_manager();
+        lobby_manager.mark_player_ready(lobby.id, winner).await.unwrap();
+        lobby_manager.mark_player_ready(lobby.id, loser).await.unwrap();
+        assert_eq!(dispatched.load(Ordering::SeqCst), 1);
+
+        lobby_manager
+            .update_ratings(
+                lobby.id,
+                &[(winner, Outcome::Win), (loser, Outcome::Loss)],
+                Arc::new(Glicko2Algorithm::default()),
+            )
+            .await
+            .unwrap();
+        let persistence = forge.persistence();
+        let winner_rating = persistence.load_player_rating(winner).await.unwrap().unwrap();
+        let loser_rating = persistence.load_player_rating(loser).await.unwrap().unwrap();
+        assert!(winner_rating.rating > loser_rating.rating);
+    }
+
+    #[tokio::test]
+    async fn test_security_rate_limits_queue_joins() {
+        let security = SecurityConfig {
+            rate_limit_config: Some(crate::security::RateLimitConfig {
+                max_requests: 1,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let forge = MatchForge::builder(MatchForgeConfig { security: Some(security), ..config() })
+            .build()
+            .await
+            .unwrap();
+        let player_id = Uuid::new_v4();
+        let join = || {
+            forge
+                .queue_manager()
+                .join_queue_solo("ranked_1v1".to_string(), player_id, Rating::default_beginner(), EntryMetadata::default(), None)
+        };
+
+        join().await.unwrap();
+        forge.queue_manager().leave_queue("ranked_1v1", player_id).await.unwrap();
+        let err = join().await.unwrap_err();
+        assert!(matches!(err, MatchForgeError::RateLimited(id, _) if id == player_id));
+    }
+
+    #[tokio::test]
+    async fn test_security_shadow_bans_apply_to_matching() {
+        let forge = MatchForge::builder(MatchForgeConfig { security: Some(SecurityConfig::default()), ..config() })
+            .build()
+            .await
+            .unwrap();
+        let [banned, _] = join_two_players(&forge).await;
+        let anti_abuse = forge.security().unwrap().anti_abuse().unwrap();
+        anti_abuse.apply_action(banned, crate::security::AbuseAction::ShadowBan).await.unwrap();
+
+        let matches = forge.queue_manager().find_matches("ranked_1v1").await.unwrap();
+        assert!(matches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_started_runner_processes_registered_queues() {
+        let forge = MatchForge::builder(config()).build().await.unwrap();
+        join_two_players(&forge).await;
+
+        let handle = forge.start();
+        for _ in 0..100 {
+            if forge.queue_manager().get_queue_size("ranked_1v1").await.unwrap() == 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(forge.runner().is_running());
+        assert_eq!(forge.queue_manager().get_queue_size("ranked_1v1").await.unwrap(), 0);
+
+        forge.stop();
+        handle.await.unwrap().unwrap();
+        assert!(!forge.runner().is_running());
+    }
+}
@@ -20,11 +20,7 @@
 //!     let lobby_manager = Arc::new(LobbyManager::new(persistence.clone()));
 //!     
 //!     // Configure queues
-//!     let queue_config = QueueConfig {
-//!         name: "ranked_1v1".to_string(),
-//!         format: MatchFormat::one_v_one(),
-//!         constraints: MatchConstraints::strict(),
-//!     };
+//!     let queue_config = QueueConfig::new("ranked_1v1", MatchFormat::one_v_one(), MatchConstraints::strict());
 //!     queue_manager.register_queue(queue_config).await?;
 //!     
 //!     // Start matchmaking runner
@@ -42,6 +38,7 @@
 //!         player_id,
 //!         rating,
 //!         EntryMetadata::default(),
+//!         None,
 //!     ).await?;
 //!     
 //!     // Start runner in background
@@ -54,35 +51,84 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! [`MatchForge`] does the same wiring from a single [`MatchForgeConfig`]:
+//! register queues in `MatchForgeConfig::queues`, call
+//! `MatchForge::builder(config).build().await?`, then `start()` the runner.
+//!
+//! # Feature flags
+//!
+//! - `runtime` (default): queue, party and lobby managers, persistence,
+//!   analytics, security and the matchmaking runner, all built on Tokio.
+//!   With `default-features = false` only the synchronous core remains:
+//!   [`Rating`], the MMR algorithms, [`MatchConstraints`] and the matchers
+//!   such as [`GreedyMatcher::find_matches`]. That subset builds for
+//!   `wasm32-unknown-unknown` together with the `wasm` feature.
+//! - `wasm`: browser entropy and clock for `uuid` and `chrono`.
+//! - `msgpack`, `redis`, `postgres`: serialization and storage backends.
 
+#[cfg(feature = "runtime")]
 pub mod analytics;
+pub mod clock;
 pub mod error;
+#[cfg(feature = "runtime")]
+pub mod facade;
 pub mod lobby;
 pub mod mmr;
 pub mod party;
+#[cfg(feature = "runtime")]
 pub mod persistence;
 pub mod queue;
+#[cfg(feature = "runtime")]
 pub mod runner;
+#[cfg(feature = "runtime")]
 pub mod security;
+#[cfg(feature = "runtime")]
+pub mod sim;
+#[cfg(feature = "runtime")]
 pub mod telemetry;
+pub mod time;
 
 // Re-export commonly used types
-pub use error::{MatchForgeError, Result};
-pub use lobby::{Lobby, LobbyMetadata, LobbyState};
+pub use clock::{Clock, MockClock, SystemClock};
+pub use error::{MatchForgeError, Result, StorageError};
+#[cfg(feature = "runtime")]
+pub use facade::{MatchForge, MatchForgeBuilder, MatchForgeConfig};
+pub use lobby::{DraftController, DraftOrder, Lobby, LobbyMetadata, LobbyState, ServerHandle};
+#[cfg(feature = "runtime")]
+pub use lobby::DodgeTracker;
 pub use mmr::{
-    DecayStrategy, EloAlgorithm, Glicko2Algorithm, LinearDecay,
-    MmrAlgorithm, NoDecay, Outcome, Rating, Season, SeasonResetStrategy, SoftReset, HardReset,
+    DecayStrategy, EloAlgorithm, Glicko2Algorithm, LinearDecay, MmrAlgorithm, NoDecay, Outcome, Rating, TeamRanking, Tier,
+    TierLadder, Deviation, RatingDelta, RatingValue,
 };
-pub use party::{AverageStrategy, MaxStrategy, Party, PartyManager, PartyMmrStrategy, WeightedWithPenaltyStrategy};
-pub use persistence::{InMemoryAdapter, PersistenceAdapter};
+#[cfg(feature = "runtime")]
+pub use mmr::{
+    AuditLog, InMemoryAuditLog, PersistentAuditLog, RatingAdjustment, RatingAuditEntry, RatingBounds, RatingChange,
+    RatingChangeReason, RatingProtection, RatingService, Season, SeasonManager, SeasonResetStrategy, SoftReset, HardReset,
+};
+pub use party::{AverageStrategy, DispersionPenaltyStrategy, MaxStrategy, Party, PartyMmrStrategy, WeightedMaxStrategy, WeightedWithPenaltyStrategy};
+#[cfg(feature = "runtime")]
+pub use party::PartyManager;
+#[cfg(feature = "runtime")]
+pub use persistence::{CachingAdapter, InMemoryAdapter, PersistenceAdapter, SerializationFormat};
 pub use queue::{
-    EntryMetadata, GreedyMatcher, MatchConstraints, MatchFormat, MatchResult, QueueConfig,
-    QueueEntry, QueueManager,
+    CandidateDiagnosis, ConstraintMode, ConstraintViolation, EntryMetadata, EntryStatus, ExpansionCurve, FeatureSchema, GlobalQueueStats, GreedyMatcher, MatchConstraints, MatchDiagnosis, MatchFormat, MatchIdStrategy, MatchResult, MatchSearchResult, Matcher, MatcherKind, ModePreferenceMatcher,
+    QualityWeights, QueueEntry, QueueEntryBuilder, QueueSnapshot, RecentEncounters, StarvationPolicy,
 };
-pub use runner::{LobbyManager, MatchmakingRunner, RunnerConfig};
-pub use analytics::{AnalyticsMetrics, ReportGenerator, InsightEngine, DashboardData};
+#[cfg(feature = "runtime")]
+pub use queue::{QueueConfig, QueueManager, ShardedQueueManager};
+#[cfg(feature = "runtime")]
+pub use runner::{
+    DispatchCallback, LobbyManager, MatchValidator, MatchmakingRunner, RunnerConfig, ServerAllocator, StaticPoolAllocator, ValidationOutcome,
+};
+#[cfg(feature = "runtime")]
+pub use analytics::{AnalyticsMetrics, AnalyticsSink, NoopAnalytics, AutoTuner, ReportGenerator, InsightEngine, DashboardData};
+#[cfg(feature = "runtime")]
 pub use telemetry::{MatchmakingMetrics, MetricsCollector, Event, EventCollector, MonitoringService};
+#[cfg(feature = "runtime")]
 pub use security::{RateLimiter, AntiAbuseSystem, SecurityManager, SecurityConfig};
+#[cfg(feature = "runtime")]
+pub use sim::{Simulation, SimulationConfig, SimulationReport};
 
 /// Prelude module for convenient imports
 pub mod prelude;
@@ -93,20 +139,36 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "runtime")]
     use std::sync::Arc;
     use uuid::Uuid;
 
+    /// Runs under `cargo test --no-default-features` as well
+    #[test]
+    fn core_matching_without_runtime() {
+        let elo = EloAlgorithm::new(32.0);
+        let winner = elo.calculate_new_rating(Rating::default_beginner(), Rating::default_beginner(), Outcome::Win);
+        assert!(winner.rating > Rating::default_beginner().rating);
+
+        let entries: Vec<QueueEntry> = [winner, Rating::default_beginner(), Rating::default_beginner()]
+            .into_iter()
+            .map(|rating| QueueEntry::new_solo("core".to_string(), Uuid::new_v4(), rating, EntryMetadata::default()))
+            .collect();
+        let matcher = GreedyMatcher::new(MatchFormat::one_v_one(), MatchConstraints::permissive());
+        let matches = matcher.find_matches(&entries, chrono::Utc::now());
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].entries.len(), 2);
+    }
+
+    #[cfg(feature = "runtime")]
     #[tokio::test]
     async fn basic_matchmaking_flow() -> Result<()> {
         let persistence = Arc::new(InMemoryAdapter::new());
         let queue_manager = Arc::new(QueueManager::new(persistence.clone()));
 
         // Register queue
-        let queue_config = QueueConfig {
-            name: "test_queue".to_string(),
-            format: MatchFormat::one_v_one(),
-            constraints: MatchConstraints::permissive(),
-        };
+        let queue_config = QueueConfig::new("test_queue", MatchFormat::one_v_one(), MatchConstraints::permissive());
         queue_manager.register_queue(queue_config).await?;
 
         // Add two players
@@ -119,6 +181,7 @@ mod tests {
             player1,
             rating,
             EntryMetadata::default(),
+            None,
         ).await?;
 
         queue_manager.join_queue_solo(
@@ -126,6 +189,7 @@ mod tests {
             player2,
             rating,
             EntryMetadata::default(),
+            None,
         ).await?;
 
         // Find matches
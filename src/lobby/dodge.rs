@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Records players who failed a ready check or left a queue
+///
+/// See [`QueueManager::with_leave_grace`](crate::queue::QueueManager::with_leave_grace)
+/// for which queue leaves count.
+///
+/// Games decide the penalty themselves, e.g. a queue lockout that grows
+/// with [`dodges_since`](Self::dodges_since).
+#[derive(Default)]
+pub struct DodgeTracker {
+    dodges: Arc<RwLock<HashMap<Uuid, Vec<DateTime<Utc>>>>>,
+}
+
+impl DodgeTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that a player dodged at `at`
+    pub async fn record_dodge(&self, player_id: Uuid, at: DateTime<Utc>) {
+        self.dodges.write().await.entry(player_id).or_default().push(at);
+    }
+
+    /// Every dodge recorded for a player
+    pub async fn dodge_count(&self, player_id: Uuid) -> usize {
+        self.dodges.read().await.get(&player_id).map_or(0, Vec::len)
+    }
+
+    /// Dodges recorded for a player at or after `since`
+    pub async fn dodges_since(&self, player_id: Uuid, since: DateTime<Utc>) -> usize {
+        self.dodges
+            .read()
+            .await
+            .get(&player_id)
+            .map_or(0, |dodges| dodges.iter().filter(|at| **at >= since).count())
+    }
+}
@@ -0,0 +1,234 @@
+use super::team::Team;
+use crate::error::*;
+use uuid::Uuid;
+
+/// Order in which captains take turns picking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DraftOrder {
+    /// Captains pick in the same order every round (A, B, A, B, ...)
+    Alternating,
+    /// The order reverses every round (A, B, B, A, A, B, ...)
+    #[default]
+    Snake,
+}
+
+/// Captain draft: each team's captain takes turns picking from the remaining players
+///
+/// Team `i` is captained by `captains[i]`, who starts on that team. Picking
+/// ends once every player has been assigned.
+#[derive(Debug, Clone)]
+pub struct DraftController {
+    captains: Vec<Uuid>,
+    available: Vec<Uuid>,
+    teams: Vec<Team>,
+    order: DraftOrder,
+    picks_made: usize,
+}
+
+impl DraftController {
+    /// Start a draft over `player_ids`, which must include every captain
+    pub fn new(captains: Vec<Uuid>, player_ids: &[Uuid], order: DraftOrder) -> Result<Self> {
+        if captains.len() < 2 {
+            return Err(MatchForgeError::InvalidConfiguration(
+                "A draft needs at least two captains".to_string(),
+            ));
+        }
+
+        for (i, captain) in captains.iter().enumerate() {
+            if !player_ids.contains(captain) {
+                return Err(MatchForgeError::PlayerNotFound(*captain));
+            }
+            if captains[..i].contains(captain) {
+                return Err(MatchForgeError::InvalidConfiguration(format!(
+                    "Player {} captains more than one team",
+                    captain
+                )));
+            }
+        }
+
+        let teams = captains
+            .iter()
+            .enumerate()
+            .map(|(team_id, captain)| Team {
+                team_id,
+                player_ids: vec![*captain],
+                capacity: 0,
+            })
+            .collect();
+        let available = player_ids
+            .iter()
+            .filter(|id| !captains.contains(id))
+            .copied()
+            .collect();
+
+        Ok(Self {
+            captains,
+            available,
+            teams,
+            order,
+            picks_made: 0,
+        })
+    }
+
+    /// Captain whose turn it is, or `None` once the draft is complete
+    pub fn current_captain(&self) -> Option<Uuid> {
+        if self.is_complete() {
+            return None;
+        }
+
+        let team_count = self.captains.len();
+        let round = self.picks_made / team_count;
+        let position = self.picks_made % team_count;
+        let team_index = match self.order {
+            DraftOrder::Snake if round % 2 == 1 => team_count - 1 - position,
+            _ => position,
+        };
+
+        Some(self.captains[team_index])
+    }
+
+    /// Add `player_id` to `captain`'s team if it is their turn and the player is still available
+    pub fn pick(&mut self, captain: Uuid, player_id: Uuid) -> Result<()> {
+        let current = self.current_captain().ok_or_else(|| {
+            MatchForgeError::OperationFailed("Draft is already complete".to_string())
+        })?;
+        if captain != current {
+            return Err(MatchForgeError::OperationFailed(format!(
+                "It is not captain {}'s turn to pick",
+                captain
+            )));
+        }
+
+        let index = self
+            .available
+            .iter()
+            .position(|id| *id == player_id)
+            .ok_or(MatchForgeError::PlayerNotFound(player_id))?;
+        self.available.remove(index);
+
+        let team_index = self.captains.iter().position(|id| *id == captain).unwrap_or(0);
+        self.teams[team_index].add_player(player_id);
+        self.picks_made += 1;
+
+        Ok(())
+    }
+
+    /// Whether every player has been picked
+    pub fn is_complete(&self) -> bool {
+        self.available.is_empty()
+    }
+
+    /// Players not yet picked
+    pub fn available_players(&self) -> &[Uuid] {
+        &self.available
+    }
+
+    /// Teams as drafted so far
+    pub fn teams(&self) -> &[Team] {
+        &self.teams
+    }
+
+    /// Final teams, or an error if players are still unpicked
+    pub fn into_teams(self) -> Result<Vec<Team>> {
+        if !self.is_complete() {
+            return Err(MatchForgeError::OperationFailed(format!(
+                "Draft still has {} unpicked players",
+                self.available.len()
+            )));
+        }
+        Ok(self.teams)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lobby::{Lobby, LobbyMetadata, LobbyState},
+        queue::MatchResult,
+    };
+
+    fn players(n: usize) -> Vec<Uuid> {
+        (0..n).map(|_| Uuid::new_v4()).collect()
+    }
+
+    #[test]
+    fn test_snake_draft_assigns_everyone() {
+        let players = players(6);
+        let (a, b) = (players[0], players[1]);
+        let mut draft = DraftController::new(vec![a, b], &players, DraftOrder::Snake).unwrap();
+
+        // A, B, B, A
+        let expected_turns = [a, b, b, a];
+        for (turn, captain) in expected_turns.iter().enumerate() {
+            assert_eq!(draft.current_captain(), Some(*captain), "pick {}", turn);
+            let pick = draft.available_players()[0];
+            draft.pick(*captain, pick).unwrap();
+        }
+
+        assert!(draft.is_complete());
+        assert_eq!(draft.current_captain(), None);
+
+        let teams = draft.into_teams().unwrap();
+        assert_eq!(teams[0].player_ids, vec![a, players[2], players[5]]);
+        assert_eq!(teams[1].player_ids, vec![b, players[3], players[4]]);
+    }
+
+    #[test]
+    fn test_out_of_turn_and_invalid_picks_rejected() {
+        let players = players(4);
+        let (a, b) = (players[0], players[1]);
+        let mut draft = DraftController::new(vec![a, b], &players, DraftOrder::Alternating).unwrap();
+
+        assert!(draft.pick(b, players[2]).is_err());
+        assert!(draft.pick(a, b).is_err());
+        assert_eq!(draft.available_players().len(), 2);
+
+        draft.pick(a, players[2]).unwrap();
+        assert!(draft.pick(b, players[2]).is_err());
+        assert!(draft.pick(a, players[3]).is_err());
+        draft.pick(b, players[3]).unwrap();
+
+        assert!(draft.pick(a, Uuid::new_v4()).is_err());
+    }
+
+    #[test]
+    fn test_incomplete_draft_has_no_final_teams() {
+        let players = players(4);
+        let draft = DraftController::new(vec![players[0], players[1]], &players, DraftOrder::Snake).unwrap();
+        assert!(draft.into_teams().is_err());
+        assert!(DraftController::new(vec![players[0], players[0]], &players, DraftOrder::Snake).is_err());
+        assert!(DraftController::new(vec![players[0], Uuid::new_v4()], &players, DraftOrder::Snake).is_err());
+    }
+
+    #[test]
+    fn test_lobby_advances_after_draft() {
+        let players = players(4);
+        let mut lobby = Lobby::from_match_result(
+            MatchResult {
+                match_id: Uuid::new_v4(),
+                entries: Vec::new(),
+                team_assignments: Vec::new(),
+                split_party_ids: Vec::new(),
+                quality_score: 1.0,
+                game_mode: None,
+                team_roles: Vec::new(),
+            },
+            vec![2, 2],
+            LobbyMetadata::default(),
+        );
+        lobby.player_ids = players.clone();
+
+        let mut draft = lobby.start_draft(vec![players[0], players[1]], DraftOrder::Snake).unwrap();
+        assert_eq!(lobby.state, LobbyState::Drafting);
+        assert!(lobby.finish_draft(draft.clone()).is_err());
+
+        draft.pick(players[0], players[3]).unwrap();
+        draft.pick(players[1], players[2]).unwrap();
+        lobby.finish_draft(draft).unwrap();
+
+        assert_eq!(lobby.state, LobbyState::WaitingForReady);
+        assert_eq!(lobby.get_player_team(players[3]), Some(0));
+        assert_eq!(lobby.get_player_team(players[2]), Some(1));
+    }
+}
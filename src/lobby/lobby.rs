@@ -1,16 +1,61 @@
 use super::{
+    draft::{DraftController, DraftOrder},
     state::LobbyState,
-    team::{SequentialAssignment, Team, TeamAssignmentStrategy},
+    team::{balance_by_rating, SequentialAssignment, Team, TeamAssignmentStrategy},
+};
+use crate::{
+    error::*,
+    mmr::{Outcome, Rating},
+    queue::{MatchResult, QueueEntry},
 };
-use crate::{error::*, queue::MatchResult};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashSet, sync::Arc};
 use uuid::Uuid;
 
+/// Teams holding each entry's players on the team the matcher assigned it
+///
+/// `None` if there isn't one assignment per entry, an assignment names a
+/// team `team_sizes` doesn't have, or a team would overflow its size.
+fn assigned_teams(match_result: &MatchResult, team_sizes: &[usize]) -> Option<Vec<Team>> {
+    let assignments = &match_result.team_assignments;
+    if assignments.len() != match_result.entries.len() || assignments.iter().any(|&team| team >= team_sizes.len()) {
+        return None;
+    }
+
+    let mut teams: Vec<Team> = team_sizes
+        .iter()
+        .enumerate()
+        .map(|(i, &size)| Team::new(i).with_capacity(size))
+        .collect();
+    let is_split = |entry: &QueueEntry| match_result.split_party_ids.contains(&entry.party_id.unwrap_or(entry.id));
+    let (split, whole): (Vec<_>, Vec<_>) = match_result.entries.iter().zip(assignments).partition(|(e, _)| is_split(e));
+
+    for (entry, &team) in whole {
+        for player_id in &entry.player_ids {
+            teams[team].add_player(*player_id);
+        }
+    }
+    for (entry, &team) in split {
+        let (first, rest) = entry.player_ids.split_first()?;
+        teams[team].add_player(*first);
+        for player_id in rest {
+            let emptiest = teams
+                .iter_mut()
+                .filter(|t| t.open_slots() > 0)
+                .max_by(|a, b| a.open_slots().cmp(&b.open_slots()).then(b.team_id.cmp(&a.team_id)))?;
+            emptiest.add_player(*player_id);
+        }
+    }
+
+    teams.iter().all(|t| t.size() <= t.capacity).then_some(teams)
+}
+
 /// A lobby represents a matched set of players ready to play together
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Lobby {
+    /// Serialization format version, see [`Lobby::SCHEMA_VERSION`]
+    pub schema_version: u32,
     pub id: Uuid,
     pub match_id: Uuid,
     pub state: LobbyState,
@@ -19,6 +64,10 @@ pub struct Lobby {
     pub ready_players: HashSet<Uuid>,
     pub created_at: DateTime<Utc>,
     pub metadata: LobbyMetadata,
+    /// Queue entries the lobby was formed from, used to return players to
+    /// the queue if the ready check is cancelled
+    #[serde(default)]
+    pub queue_entries: Vec<QueueEntry>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -27,10 +76,48 @@ pub struct LobbyMetadata {
     pub game_mode: Option<String>,
     pub map: Option<String>,
     pub server_id: Option<String>,
+    /// Game server reserved for the lobby by a [`ServerAllocator`](crate::runner::ServerAllocator)
+    #[serde(default)]
+    pub server: Option<ServerHandle>,
     pub custom: std::collections::HashMap<String, String>,
+    /// Role each player queued for, used to keep roles spread across teams
+    #[serde(default)]
+    pub player_roles: std::collections::HashMap<Uuid, String>,
+    /// Disconnected players keeping their slot until the given deadline
+    #[serde(default)]
+    pub reconnecting: std::collections::HashMap<Uuid, DateTime<Utc>>,
+}
+
+/// A game server reserved for a lobby
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerHandle {
+    pub server_id: String,
+    /// Where players connect, e.g. `host:port`
+    pub address: String,
+}
+
+impl ServerHandle {
+    pub fn new(server_id: impl Into<String>, address: impl Into<String>) -> Self {
+        Self {
+            server_id: server_id.into(),
+            address: address.into(),
+        }
+    }
 }
 
 impl Lobby {
+    /// Current serialization format version
+    ///
+    /// Version 1 predates `schema_version`.
+    pub const SCHEMA_VERSION: u32 = 2;
+
+    /// Lobby for a match, with one team per entry of `team_sizes`
+    ///
+    /// Players join the team the matcher assigned their entry to (a split
+    /// party's first player joins its assigned team and the rest the emptiest
+    /// teams), so role-aware and validator-adjusted placements carry over.
+    /// A result whose assignments don't fit `team_sizes`, e.g. one built by
+    /// hand, is filled team by team in entry order instead.
     pub fn from_match_result(
         match_result: MatchResult,
         team_sizes: Vec<usize>,
@@ -42,10 +129,15 @@ impl Lobby {
             .flat_map(|e| e.player_ids.clone())
             .collect();
 
-        let strategy = SequentialAssignment;
-        let teams = strategy.assign_teams(player_ids.clone(), &team_sizes);
+        let mut teams = assigned_teams(&match_result, &team_sizes)
+            .unwrap_or_else(|| SequentialAssignment.assign_teams(player_ids.clone(), &team_sizes));
+        for (team, size) in teams.iter_mut().zip(&team_sizes) {
+            team.capacity = *size;
+        }
+        let queue_entries = match_result.entries;
 
         Self {
+            schema_version: Self::SCHEMA_VERSION,
             id: Uuid::new_v4(),
             match_id: match_result.match_id,
             state: LobbyState::Forming,
@@ -54,6 +146,7 @@ impl Lobby {
             ready_players: HashSet::new(),
             created_at: Utc::now(),
             metadata,
+            queue_entries,
         }
     }
 
@@ -68,10 +161,15 @@ impl Lobby {
             .iter()
             .flat_map(|e| e.player_ids.clone())
             .collect();
+        let queue_entries = match_result.entries;
 
-        let teams = strategy.assign_teams(player_ids.clone(), &team_sizes);
+        let mut teams = strategy.assign_teams(player_ids.clone(), &team_sizes);
+        for (team, size) in teams.iter_mut().zip(&team_sizes) {
+            team.capacity = *size;
+        }
 
         Self {
+            schema_version: Self::SCHEMA_VERSION,
             id: Uuid::new_v4(),
             match_id: match_result.match_id,
             state: LobbyState::Forming,
@@ -80,9 +178,221 @@ impl Lobby {
             ready_players: HashSet::new(),
             created_at: Utc::now(),
             metadata,
+            queue_entries,
         }
     }
 
+    /// Reassign current players to teams to minimize the rating spread between teams
+    ///
+    /// Only allowed while the lobby is `Forming`, so players who have already
+    /// readied up are never moved. Players who queued as a party stay on one
+    /// team (unless the party is larger than every team), and no team gets
+    /// more players than its capacity. Players missing from `ratings` count as beginners.
+    pub fn rebalance_teams(&mut self, ratings: &std::collections::HashMap<Uuid, Rating>) -> Result<()> {
+        if self.state != LobbyState::Forming {
+            return Err(MatchForgeError::OperationFailed(format!(
+                "Cannot rebalance teams while lobby is {:?}",
+                self.state
+            )));
+        }
+
+        let capacities = self.team_capacities();
+        let teams = balance_by_rating(&self.party_groups(&capacities), ratings, &self.metadata.player_roles, &capacities)
+            .ok_or_else(|| {
+                MatchForgeError::OperationFailed(format!(
+                    "Parties in lobby {} don't fit its team capacities {:?}",
+                    self.id, capacities
+                ))
+            })?;
+        self.replace_teams(teams)
+    }
+
+    /// Each team's capacity, or an even split of the players if any team was
+    /// stored without one
+    fn team_capacities(&self) -> Vec<usize> {
+        if !self.teams.is_empty() && self.teams.iter().all(|t| t.capacity > 0) {
+            return self.teams.iter().map(|t| t.capacity).collect();
+        }
+        let team_count = self.teams.len().max(1);
+        let players = self.player_ids.len();
+        (0..team_count)
+            .map(|i| players / team_count + usize::from(i < players % team_count))
+            .collect()
+    }
+
+    /// Current players grouped by the queue entry they joined with
+    ///
+    /// Backfilled players, and members of a party too large for any team,
+    /// form groups of one.
+    fn party_groups(&self, capacities: &[usize]) -> Vec<Vec<Uuid>> {
+        let largest_team = capacities.iter().copied().max().unwrap_or(0);
+        let mut groups: Vec<Vec<Uuid>> = self
+            .queue_entries
+            .iter()
+            .map(|entry| entry.player_ids.iter().copied().filter(|id| self.player_ids.contains(id)).collect::<Vec<_>>())
+            .filter(|members| !members.is_empty() && members.len() <= largest_team)
+            .collect();
+        let grouped: HashSet<Uuid> = groups.iter().flatten().copied().collect();
+        groups.extend(self.player_ids.iter().filter(|id| !grouped.contains(id)).map(|id| vec![*id]));
+        groups
+    }
+
+    /// Move a forming lobby into a captain draft over its players
+    pub fn start_draft(&mut self, captains: Vec<Uuid>, order: DraftOrder) -> Result<DraftController> {
+        let draft = DraftController::new(captains, &self.player_ids, order)?;
+        self.transition_to(LobbyState::Drafting)?;
+        Ok(draft)
+    }
+
+    /// Adopt the teams of a completed draft and advance to `WaitingForReady`
+    pub fn finish_draft(&mut self, draft: DraftController) -> Result<()> {
+        if self.state != LobbyState::Drafting {
+            return Err(MatchForgeError::OperationFailed(format!(
+                "Cannot finish a draft while lobby is {:?}",
+                self.state
+            )));
+        }
+
+        let teams = draft.into_teams()?;
+        self.replace_teams(teams)?;
+        self.transition_to(LobbyState::WaitingForReady)
+    }
+
+    /// Swap in new teams, keeping each team's capacity
+    ///
+    /// Fails without changing anything if a new team has more players than
+    /// the capacity of the team it replaces.
+    fn replace_teams(&mut self, mut teams: Vec<Team>) -> Result<()> {
+        for (team, old) in teams.iter().zip(&self.teams) {
+            if old.capacity > 0 && team.size() > old.capacity {
+                return Err(MatchForgeError::OperationFailed(format!(
+                    "Team {} would have {} players but holds {}",
+                    team.team_id,
+                    team.size(),
+                    old.capacity
+                )));
+            }
+        }
+        for (team, old) in teams.iter_mut().zip(&self.teams) {
+            team.capacity = old.capacity;
+        }
+        self.teams = teams;
+        Ok(())
+    }
+
+    /// Open slots left across all teams
+    pub fn remaining_slots(&self) -> usize {
+        self.teams.iter().map(Team::open_slots).sum()
+    }
+
+    /// Add players to teams with open slots, e.g. to backfill leavers
+    ///
+    /// Each player joins the team with the most open slots (the lowest team
+    /// on ties). Nothing is added if the players don't all fit, if any is
+    /// already in the lobby, or if the lobby is drafting, ready or closed.
+    /// Returns the team each player joined.
+    pub fn add_players(&mut self, player_ids: &[Uuid]) -> Result<Vec<usize>> {
+        if matches!(self.state, LobbyState::Drafting | LobbyState::Ready | LobbyState::Closed) {
+            return Err(MatchForgeError::OperationFailed(format!(
+                "Cannot add players while lobby is {:?}",
+                self.state
+            )));
+        }
+        for (i, player_id) in player_ids.iter().enumerate() {
+            if self.player_ids.contains(player_id) || player_ids[..i].contains(player_id) {
+                return Err(MatchForgeError::OperationFailed(format!(
+                    "Player {} is already in lobby {}",
+                    player_id, self.id
+                )));
+            }
+        }
+
+        let remaining = self.remaining_slots();
+        if player_ids.len() > remaining {
+            return Err(MatchForgeError::LobbyFull(self.id, remaining));
+        }
+
+        let lobby_id = self.id;
+        let mut assigned = Vec::with_capacity(player_ids.len());
+        for player_id in player_ids {
+            let team = self
+                .teams
+                .iter_mut()
+                .filter(|team| team.open_slots() > 0)
+                .max_by(|a, b| a.open_slots().cmp(&b.open_slots()).then(b.team_id.cmp(&a.team_id)))
+                .ok_or(MatchForgeError::LobbyFull(lobby_id, 0))?;
+            team.add_player(*player_id);
+            self.player_ids.push(*player_id);
+            assigned.push(team.team_id);
+        }
+
+        Ok(assigned)
+    }
+
+    /// Whether a player disconnected and hasn't reconnected yet
+    pub fn is_reconnecting(&self, player_id: Uuid) -> bool {
+        self.metadata.reconnecting.contains_key(&player_id)
+    }
+
+    /// Keep a disconnected player's slot until `deadline`
+    ///
+    /// Only lobbies that can be backfilled (forming, waiting for ready or
+    /// dispatched) hold slots; a later disconnect keeps the first deadline.
+    pub fn mark_reconnecting(&mut self, player_id: Uuid, deadline: DateTime<Utc>) -> Result<()> {
+        if !self.player_ids.contains(&player_id) {
+            return Err(MatchForgeError::PlayerNotFound(player_id));
+        }
+        if matches!(self.state, LobbyState::Drafting | LobbyState::Ready | LobbyState::Closed) {
+            return Err(MatchForgeError::OperationFailed(format!(
+                "Cannot hold a slot for reconnection while lobby is {:?}",
+                self.state
+            )));
+        }
+
+        self.metadata.reconnecting.entry(player_id).or_insert(deadline);
+        Ok(())
+    }
+
+    /// Restore a reconnecting player whose deadline is still ahead of `now`
+    pub fn reconnect(&mut self, player_id: Uuid, now: DateTime<Utc>) -> Result<()> {
+        let deadline = *self.metadata.reconnecting.get(&player_id).ok_or_else(|| {
+            MatchForgeError::OperationFailed(format!("Player {} is not reconnecting to lobby {}", player_id, self.id))
+        })?;
+        if now >= deadline {
+            return Err(MatchForgeError::OperationFailed(format!(
+                "Reconnection window for player {} in lobby {} expired at {}",
+                player_id, self.id, deadline
+            )));
+        }
+
+        self.metadata.reconnecting.remove(&player_id);
+        Ok(())
+    }
+
+    /// Remove reconnecting players whose deadline is at or before `now`
+    ///
+    /// Their slots open up for [`add_players`](Self::add_players). Returns
+    /// the removed players.
+    pub fn remove_expired_reconnections(&mut self, now: DateTime<Utc>) -> Vec<Uuid> {
+        let expired: Vec<Uuid> = self
+            .metadata
+            .reconnecting
+            .iter()
+            .filter(|(_, deadline)| now >= **deadline)
+            .map(|(player_id, _)| *player_id)
+            .collect();
+        for player_id in &expired {
+            self.metadata.reconnecting.remove(player_id);
+            self.metadata.player_roles.remove(player_id);
+            self.ready_players.remove(player_id);
+            self.player_ids.retain(|id| id != player_id);
+            for team in &mut self.teams {
+                team.player_ids.retain(|id| id != player_id);
+            }
+        }
+        expired
+    }
+
     /// Transition to a new state
     pub fn transition_to(&mut self, new_state: LobbyState) -> Result<()> {
         if !self.state.can_transition_to(new_state) {
@@ -104,9 +414,7 @@ impl Lobby {
         self.ready_players.insert(player_id);
 
         // Auto-transition if all players ready
-        if self.ready_players.len() == self.player_ids.len()
-            && self.state == LobbyState::WaitingForReady
-        {
+        if self.is_fully_ready() && self.state == LobbyState::WaitingForReady {
             self.transition_to(LobbyState::Ready)?;
         }
 
@@ -115,7 +423,50 @@ impl Lobby {
 
     /// Check if all players are ready
     pub fn all_players_ready(&self) -> bool {
-        self.ready_players.len() == self.player_ids.len()
+        self.is_fully_ready()
+    }
+
+    /// Whether every player in the lobby has readied up (false for an empty lobby)
+    pub fn is_fully_ready(&self) -> bool {
+        !self.player_ids.is_empty() && self.player_ids.iter().all(|id| self.ready_players.contains(id))
+    }
+
+    /// Check a reported result covers exactly this lobby's players
+    ///
+    /// Every lobby player must be reported once, and players on the same
+    /// team must share an outcome. Unknown players fail with [`MatchForgeError::PlayerNotFound`].
+    pub fn validate_result(&self, results: &[(Uuid, Outcome)]) -> Result<()> {
+        let mut team_outcomes: std::collections::HashMap<usize, Outcome> = std::collections::HashMap::new();
+        let mut reported = HashSet::new();
+        for (player_id, outcome) in results {
+            if !self.player_ids.contains(player_id) {
+                return Err(MatchForgeError::PlayerNotFound(*player_id));
+            }
+            if !reported.insert(*player_id) {
+                return Err(MatchForgeError::OperationFailed(format!(
+                    "Player {} reported more than once for lobby {}",
+                    player_id, self.id
+                )));
+            }
+            let Some(team_id) = self.get_player_team(*player_id) else {
+                continue;
+            };
+            if *team_outcomes.entry(team_id).or_insert(*outcome) != *outcome {
+                return Err(MatchForgeError::OperationFailed(format!(
+                    "Team {} of lobby {} reported with mixed outcomes",
+                    team_id, self.id
+                )));
+            }
+        }
+        if reported.len() != self.player_ids.len() {
+            return Err(MatchForgeError::OperationFailed(format!(
+                "Result for lobby {} covers {} of its {} players",
+                self.id,
+                reported.len(),
+                self.player_ids.len()
+            )));
+        }
+        Ok(())
     }
 
     /// Get team for a specific player
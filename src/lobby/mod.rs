@@ -1,7 +1,13 @@
+#[cfg(feature = "runtime")]
+pub mod dodge;
+pub mod draft;
 pub mod lobby;
 pub mod state;
 pub mod team;
 
-pub use lobby::{Lobby, LobbyMetadata};
+#[cfg(feature = "runtime")]
+pub use dodge::DodgeTracker;
+pub use draft::{DraftController, DraftOrder};
+pub use lobby::{Lobby, LobbyMetadata, ServerHandle};
 pub use state::LobbyState;
 pub use team::{SequentialAssignment, Team, TeamAssignmentStrategy};
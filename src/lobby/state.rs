@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 pub enum LobbyState {
     /// Players are being added to the lobby
     Forming,
+    /// Captains are picking teams (see [`DraftController`](super::DraftController))
+    Drafting,
     /// All players present, waiting for ready confirmations
     WaitingForReady,
     /// All players ready, lobby can be dispatched to game server
@@ -21,6 +23,8 @@ impl LobbyState {
         matches!(
             (self, new_state),
             (Forming, WaitingForReady)
+                | (Forming, Drafting)
+                | (Drafting, WaitingForReady)
                 | (WaitingForReady, Ready)
                 | (Ready, Dispatched)
                 | (Dispatched, Closed)
@@ -1,4 +1,6 @@
+use crate::mmr::Rating;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// Represents a team in a lobby
@@ -6,6 +8,9 @@ use uuid::Uuid;
 pub struct Team {
     pub team_id: usize,
     pub player_ids: Vec<Uuid>,
+    /// Most players the team can hold (0 for teams stored before capacities were tracked)
+    #[serde(default)]
+    pub capacity: usize,
 }
 
 impl Team {
@@ -13,9 +18,20 @@ impl Team {
         Self {
             team_id,
             player_ids: Vec::new(),
+            capacity: 0,
         }
     }
 
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Players that can still join this team
+    pub fn open_slots(&self) -> usize {
+        self.capacity.saturating_sub(self.size())
+    }
+
     pub fn add_player(&mut self, player_id: Uuid) {
         self.player_ids.push(player_id);
     }
@@ -55,3 +71,50 @@ impl TeamAssignmentStrategy for SequentialAssignment {
         teams
     }
 }
+
+/// Place groups of players that must share a team onto teams of the given
+/// capacities with similar total rating
+///
+/// Groups are placed largest first, then strongest first, each onto the team
+/// with room for it that has the fewest players sharing a role with the group
+/// and then the lowest rating total, so roles stay spread across teams.
+/// `None` if some group doesn't fit on any team.
+pub(crate) fn balance_by_rating(
+    groups: &[Vec<Uuid>],
+    ratings: &HashMap<Uuid, Rating>,
+    roles: &HashMap<Uuid, String>,
+    capacities: &[usize],
+) -> Option<Vec<Team>> {
+    let rating_of = |id: &Uuid| ratings.get(id).copied().unwrap_or_else(Rating::default_beginner).rating;
+    let group_total = |group: &[Uuid]| group.iter().map(rating_of).sum::<f64>();
+
+    let mut sorted: Vec<&Vec<Uuid>> = groups.iter().collect();
+    sorted.sort_by(|a, b| b.len().cmp(&a.len()).then(group_total(b).total_cmp(&group_total(a))));
+
+    let mut teams: Vec<Team> = (0..capacities.len()).map(Team::new).collect();
+    let mut totals = vec![0.0_f64; capacities.len()];
+
+    for group in sorted {
+        let same_role = |team: &Team| {
+            team.player_ids
+                .iter()
+                .filter(|id| group.iter().any(|member| roles.get(member).is_some_and(|role| roles.get(*id) == Some(role))))
+                .count()
+        };
+
+        let team_index = (0..teams.len())
+            .filter(|&i| teams[i].size() + group.len() <= capacities[i])
+            .min_by(|&a, &b| {
+                same_role(&teams[a])
+                    .cmp(&same_role(&teams[b]))
+                    .then(totals[a].total_cmp(&totals[b]))
+            })?;
+
+        totals[team_index] += group_total(group);
+        for player_id in group {
+            teams[team_index].add_player(*player_id);
+        }
+    }
+
+    Some(teams)
+}
@@ -1,4 +1,6 @@
-use super::rating::{Outcome, Rating};
+use super::conversion::{GLICKO2_SCALE, RATING_CENTER};
+use super::rating::{Outcome, Rating, TeamRanking};
+use crate::error::{MatchForgeError, Result};
 use async_trait::async_trait;
 
 /// Trait for MMR calculation algorithms
@@ -19,19 +21,43 @@ pub trait MmrAlgorithm: Send + Sync {
 /// Simple Elo rating system
 pub struct EloAlgorithm {
     k_factor: f64,
+    opponent_deviation_aware: bool,
 }
 
 impl EloAlgorithm {
     pub fn new(k_factor: f64) -> Self {
-        Self { k_factor }
+        Self {
+            k_factor,
+            opponent_deviation_aware: false,
+        }
     }
 
     pub fn default() -> Self {
-        Self { k_factor: 32.0 }
+        Self::new(32.0)
+    }
+
+    /// Scale each update by the opponent's deviation, as Glicko-1 does
+    ///
+    /// Results against an uncertain opponent say less about the player, so
+    /// both the expected score and the rating change shrink towards zero as
+    /// the opponent's deviation grows. An opponent with zero deviation gives
+    /// plain Elo.
+    pub fn with_opponent_deviation(mut self, enabled: bool) -> Self {
+        self.opponent_deviation_aware = enabled;
+        self
+    }
+
+    fn expected_score(&self, rating_a: f64, rating_b: f64, weight: f64) -> f64 {
+        1.0 / (1.0 + 10_f64.powf(weight * (rating_b - rating_a) / 400.0))
     }
 
-    fn expected_score(&self, rating_a: f64, rating_b: f64) -> f64 {
-        1.0 / (1.0 + 10_f64.powf((rating_b - rating_a) / 400.0))
+    /// Glicko-1 attenuation `g(RD)` of the opponent's deviation, 1.0 when disabled
+    fn opponent_weight(&self, opponent_deviation: f64) -> f64 {
+        if !self.opponent_deviation_aware {
+            return 1.0;
+        }
+        let q = std::f64::consts::LN_10 / 400.0;
+        1.0 / (1.0 + 3.0 * (q * opponent_deviation).powi(2) / std::f64::consts::PI.powi(2)).sqrt()
     }
 }
 
@@ -43,9 +69,10 @@ impl MmrAlgorithm for EloAlgorithm {
         opponent_rating: Rating,
         outcome: Outcome,
     ) -> Rating {
-        let expected = self.expected_score(player_rating.rating, opponent_rating.rating);
+        let weight = self.opponent_weight(opponent_rating.deviation);
+        let expected = self.expected_score(player_rating.rating, opponent_rating.rating, weight);
         let actual = outcome.score();
-        let new_rating = player_rating.rating + self.k_factor * (actual - expected);
+        let new_rating = player_rating.rating + self.k_factor * weight * (actual - expected);
 
         Rating {
             rating: new_rating,
@@ -59,28 +86,178 @@ impl MmrAlgorithm for EloAlgorithm {
     }
 }
 
-/// Glicko2 rating system (simplified implementation)
+/// Glicko-2 rating system (single-opponent rating periods)
 pub struct Glicko2Algorithm {
     tau: f64, // System volatility constant
+    max_deviation: f64,
 }
 
+/// Convergence tolerance for the volatility iteration
+const VOLATILITY_EPSILON: f64 = 0.000001;
+
 impl Glicko2Algorithm {
     pub fn new(tau: f64) -> Self {
-        Self { tau }
+        Self {
+            tau,
+            max_deviation: 350.0,
+        }
     }
 
     pub fn default() -> Self {
-        Self { tau: 0.5 }
+        Self::new(0.5)
+    }
+
+    /// Cap rating deviation after every update and inactivity period
+    pub fn with_max_deviation(mut self, max_deviation: f64) -> Self {
+        self.max_deviation = max_deviation;
+        self
+    }
+
+    /// System constant constraining how fast volatility changes (typically 0.3 to 1.2)
+    pub fn tau(&self) -> f64 {
+        self.tau
+    }
+
+    /// Ceiling applied to rating deviation
+    pub fn max_deviation(&self) -> f64 {
+        self.max_deviation
+    }
+
+    /// Grow a player's deviation for rating periods spent without playing
+    pub fn apply_inactivity(&self, rating: Rating, periods: u32) -> Rating {
+        let mut phi = rating.deviation / GLICKO2_SCALE;
+        for _ in 0..periods {
+            phi = (phi.powi(2) + rating.volatility.powi(2)).sqrt();
+            if phi * GLICKO2_SCALE >= self.max_deviation {
+                break;
+            }
+        }
+
+        Rating {
+            rating: rating.rating,
+            deviation: (phi * GLICKO2_SCALE).min(self.max_deviation),
+            volatility: rating.volatility,
+        }
+    }
+
+    /// Rate one period in which the player met every listed opponent
+    ///
+    /// A period without games only grows the deviation, as in
+    /// [`Self::apply_inactivity`].
+    pub fn rate_period(&self, player_rating: Rating, results: &[(Rating, Outcome)]) -> Rating {
+        if results.is_empty() {
+            return self.apply_inactivity(player_rating, 1);
+        }
+
+        let mu = (player_rating.rating - RATING_CENTER) / GLICKO2_SCALE;
+        let phi = player_rating.deviation / GLICKO2_SCALE;
+
+        let mut information = 0.0;
+        let mut improvement = 0.0;
+        for (opponent_rating, outcome) in results {
+            let opponent_mu = (opponent_rating.rating - RATING_CENTER) / GLICKO2_SCALE;
+            let opponent_phi = opponent_rating.deviation / GLICKO2_SCALE;
+
+            let g_value = self.g(opponent_phi);
+            let expected = self.expected_score(mu, opponent_mu, opponent_phi);
+            information += g_value.powi(2) * expected * (1.0 - expected);
+            improvement += g_value * (outcome.score() - expected);
+        }
+
+        let variance = 1.0 / information;
+        let delta = variance * improvement;
+
+        let volatility = self.new_volatility(phi, player_rating.volatility, variance, delta);
+        let phi_star = (phi.powi(2) + volatility.powi(2)).sqrt();
+        let new_phi = 1.0 / (1.0 / phi_star.powi(2) + 1.0 / variance).sqrt();
+        let new_mu = mu + new_phi.powi(2) * improvement;
+
+        Rating {
+            rating: new_mu * GLICKO2_SCALE + RATING_CENTER,
+            deviation: (new_phi * GLICKO2_SCALE).min(self.max_deviation),
+            volatility,
+        }
+    }
+
+    /// Update every player of a multi-team match
+    ///
+    /// Each team is treated as one opponent with its players' mean rating and
+    /// root-mean-square deviation. A player is rated in a single period
+    /// against every other team, scoring a win, loss or draw from
+    /// `ranking`. Tied teams score 0.5 against each other: between equally
+    /// rated teams a draw leaves ratings where they were while deviations
+    /// still shrink, and between unequal teams it pulls both slightly
+    /// together.
+    pub fn update_teams(&self, teams: &[Vec<Rating>], ranking: &TeamRanking) -> Result<Vec<Vec<Rating>>> {
+        if teams.len() != ranking.team_count() {
+            return Err(MatchForgeError::InvalidConfiguration(format!(
+                "Ranking covers {} teams but the match has {}",
+                ranking.team_count(),
+                teams.len()
+            )));
+        }
+
+        let composites: Vec<Rating> = teams.iter().map(|team| Rating::composite(team)).collect();
+
+        Ok(teams
+            .iter()
+            .enumerate()
+            .map(|(team, players)| {
+                let results: Vec<(Rating, Outcome)> = (0..teams.len())
+                    .filter(|&opponent| opponent != team)
+                    .map(|opponent| (composites[opponent], ranking.outcome(team, opponent)))
+                    .collect();
+                players.iter().map(|player| self.rate_period(*player, &results)).collect()
+            })
+            .collect())
     }
 
-    fn g(&self, deviation: f64) -> f64 {
-        let q = (3.0 * deviation.powi(2)) / std::f64::consts::PI.powi(2);
-        1.0 / (1.0 + q).sqrt()
+    fn g(&self, phi: f64) -> f64 {
+        1.0 / (1.0 + 3.0 * phi.powi(2) / std::f64::consts::PI.powi(2)).sqrt()
     }
 
-    fn expected_score(&self, rating: f64, opponent_rating: f64, opponent_deviation: f64) -> f64 {
-        let g_value = self.g(opponent_deviation);
-        1.0 / (1.0 + (-g_value * (rating - opponent_rating) / 400.0).exp())
+    fn expected_score(&self, mu: f64, opponent_mu: f64, opponent_phi: f64) -> f64 {
+        1.0 / (1.0 + (-self.g(opponent_phi) * (mu - opponent_mu)).exp())
+    }
+
+    /// New volatility via the Illinois iteration from Glickman's paper
+    fn new_volatility(&self, phi: f64, sigma: f64, variance: f64, delta: f64) -> f64 {
+        let a = sigma.powi(2).ln();
+        let tau_sq = self.tau.powi(2);
+        let f = |x: f64| {
+            let ex = x.exp();
+            ex * (delta.powi(2) - phi.powi(2) - variance - ex)
+                / (2.0 * (phi.powi(2) + variance + ex).powi(2))
+                - (x - a) / tau_sq
+        };
+
+        let mut big_a = a;
+        let mut big_b = if delta.powi(2) > phi.powi(2) + variance {
+            (delta.powi(2) - phi.powi(2) - variance).ln()
+        } else {
+            let mut k = 1.0;
+            while f(a - k * self.tau) < 0.0 {
+                k += 1.0;
+            }
+            a - k * self.tau
+        };
+
+        let mut f_a = f(big_a);
+        let mut f_b = f(big_b);
+        while (big_b - big_a).abs() > VOLATILITY_EPSILON {
+            let big_c = big_a + (big_a - big_b) * f_a / (f_b - f_a);
+            let f_c = f(big_c);
+            if f_c * f_b <= 0.0 {
+                big_a = big_b;
+                f_a = f_b;
+            } else {
+                f_a /= 2.0;
+            }
+            big_b = big_c;
+            f_b = f_c;
+        }
+
+        (big_a / 2.0).exp()
     }
 }
 
@@ -92,32 +269,115 @@ impl MmrAlgorithm for Glicko2Algorithm {
         opponent_rating: Rating,
         outcome: Outcome,
     ) -> Rating {
-        let g_value = self.g(opponent_rating.deviation);
-        let expected = self.expected_score(
-            player_rating.rating,
-            opponent_rating.rating,
-            opponent_rating.deviation,
+        self.rate_period(player_rating, &[(opponent_rating, outcome)])
+    }
+
+    fn name(&self) -> &str {
+        "Glicko2"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deviation_aware_elo_gains_less_against_uncertain_opponent() {
+        let elo = EloAlgorithm::new(32.0).with_opponent_deviation(true);
+        let player = Rating::new(1500.0, 100.0, 0.06);
+        let gain = |opponent| elo.calculate_new_rating(player, opponent, Outcome::Win).rating - player.rating;
+
+        let certain = gain(Rating::new(1500.0, 30.0, 0.06));
+        let uncertain = gain(Rating::new(1500.0, 350.0, 0.06));
+        assert!(uncertain > 0.0);
+        assert!(uncertain < certain);
+
+        // Plain Elo ignores deviation, and a zero-deviation opponent matches it
+        let plain = EloAlgorithm::new(32.0);
+        let plain_gain = |opponent| plain.calculate_new_rating(player, opponent, Outcome::Win).rating - player.rating;
+        assert_eq!(plain_gain(Rating::new(1500.0, 30.0, 0.06)), plain_gain(Rating::new(1500.0, 350.0, 0.06)));
+        assert!((gain(Rating::new(1500.0, 0.0, 0.06)) - 16.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_glicko2_winner_gains_and_deviation_shrinks() {
+        let glicko = Glicko2Algorithm::default();
+        let player = Rating::new(1500.0, 200.0, 0.06);
+        let opponent = Rating::new(1500.0, 200.0, 0.06);
+
+        let updated = glicko.calculate_new_rating(player, opponent, Outcome::Win);
+        assert!(updated.rating > player.rating);
+        assert!(updated.deviation < player.deviation);
+    }
+
+    #[test]
+    fn test_max_deviation_caps_inactive_player() {
+        let glicko = Glicko2Algorithm::default().with_max_deviation(200.0);
+        let returning = glicko.apply_inactivity(Rating::new(1700.0, 60.0, 0.06), 10_000);
+
+        assert_eq!(returning.deviation, 200.0);
+        assert_eq!(returning.rating, 1700.0);
+
+        // Updates never push deviation above the ceiling either
+        let after_match = glicko.calculate_new_rating(
+            Rating::new(1500.0, 350.0, 0.06),
+            Rating::new(1500.0, 350.0, 0.06),
+            Outcome::Loss,
         );
-        let actual = outcome.score();
+        assert!(after_match.deviation <= 200.0);
+    }
 
-        // Simplified Glicko2 update (full algorithm is more complex)
-        let d_squared = 1.0 / (g_value.powi(2) * expected * (1.0 - expected));
-        let variance = 1.0 / d_squared;
+    #[test]
+    fn test_larger_tau_increases_volatility_sensitivity() {
+        // An established favourite losing badly to a much weaker player
+        let favourite = Rating::new(1900.0, 50.0, 0.06);
+        let underdog = Rating::new(1300.0, 50.0, 0.06);
 
-        let delta = variance * g_value * (actual - expected);
-        let new_rating = player_rating.rating + delta;
+        let calm = Glicko2Algorithm::new(0.3).calculate_new_rating(favourite, underdog, Outcome::Loss);
+        let reactive = Glicko2Algorithm::new(1.2).calculate_new_rating(favourite, underdog, Outcome::Loss);
 
-        // Update deviation (simplified)
-        let new_deviation = (player_rating.deviation.powi(2) + variance).sqrt();
+        assert!(calm.volatility > favourite.volatility);
+        assert!(reactive.volatility > calm.volatility);
+    }
 
-        Rating {
-            rating: new_rating,
-            deviation: new_deviation.min(350.0), // Cap deviation
-            volatility: player_rating.volatility,
+    #[test]
+    fn test_two_team_draw_leaves_ratings_nearly_unchanged() {
+        let glicko = Glicko2Algorithm::default();
+        let teams = vec![
+            vec![Rating::new(1500.0, 90.0, 0.06), Rating::new(1500.0, 150.0, 0.06)],
+            vec![Rating::new(1500.0, 120.0, 0.06), Rating::new(1500.0, 120.0, 0.06)],
+        ];
+
+        let updated = glicko.update_teams(&teams, &TeamRanking::draw(2)).unwrap();
+        for (before, after) in teams.iter().flatten().zip(updated.iter().flatten()) {
+            assert!((after.rating - before.rating).abs() < 1e-6);
+            assert!(after.deviation < before.deviation);
         }
+
+        // A draw against a stronger team pulls the two slightly together
+        let uneven = vec![vec![Rating::new(1700.0, 120.0, 0.06)], vec![Rating::new(1500.0, 120.0, 0.06)]];
+        let updated = glicko.update_teams(&uneven, &TeamRanking::draw(2)).unwrap();
+        assert!(updated[0][0].rating < 1700.0 && updated[0][0].rating > 1650.0);
+        assert!(updated[1][0].rating > 1500.0 && updated[1][0].rating < 1550.0);
     }
 
-    fn name(&self) -> &str {
-        "Glicko2"
+    #[test]
+    fn test_three_team_tie_for_first() {
+        let glicko = Glicko2Algorithm::default();
+        let teams = vec![vec![Rating::new(1500.0, 150.0, 0.06)]; 3];
+        let ranking = TeamRanking::new(vec![0, 0, 1]);
+        assert_eq!(ranking.outcome(0, 1), Outcome::Draw);
+        assert_eq!(ranking.outcome(1, 2), Outcome::Win);
+
+        let updated = glicko.update_teams(&teams, &ranking).unwrap();
+        let (first_a, first_b, last) = (updated[0][0], updated[1][0], updated[2][0]);
+
+        assert!(first_a.rating > 1500.0);
+        assert!((first_a.rating - first_b.rating).abs() < 1e-9);
+        assert!(last.rating < 1500.0);
+        // Two losses outweigh one win in size
+        assert!(1500.0 - last.rating > first_a.rating - 1500.0);
+
+        assert!(glicko.update_teams(&teams, &TeamRanking::draw(2)).is_err());
     }
 }
@@ -0,0 +1,131 @@
+use super::{rating::Rating, units::RatingDelta};
+use crate::{error::Result, persistence::PersistenceAdapter};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Why a player's rating changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RatingChangeReason {
+    /// Result of a completed match
+    Match { match_id: Uuid },
+    /// Inactivity decay
+    Decay,
+    /// Season reset
+    SeasonReset { season_id: String },
+    /// Set by an operator, e.g. after a support ticket or anti-cheat review
+    ManualAdjustment { note: String },
+}
+
+/// A single recorded rating change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RatingAuditEntry {
+    pub player_id: Uuid,
+    pub reason: RatingChangeReason,
+    pub before: Rating,
+    pub after: Rating,
+    pub at: DateTime<Utc>,
+}
+
+impl RatingAuditEntry {
+    /// How far the rating moved
+    pub fn delta(&self) -> RatingDelta {
+        self.after.rating_value() - self.before.rating_value()
+    }
+}
+
+/// Destination for rating audit entries
+#[async_trait]
+pub trait AuditLog: Send + Sync {
+    /// Append an entry
+    async fn record(&self, entry: RatingAuditEntry) -> Result<()>;
+
+    /// Every entry recorded for a player, oldest first
+    async fn entries_for(&self, player_id: Uuid) -> Result<Vec<RatingAuditEntry>>;
+}
+
+/// Audit log kept in memory (for development/testing)
+#[derive(Default)]
+pub struct InMemoryAuditLog {
+    entries: Arc<RwLock<HashMap<Uuid, Vec<RatingAuditEntry>>>>,
+}
+
+impl InMemoryAuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AuditLog for InMemoryAuditLog {
+    async fn record(&self, entry: RatingAuditEntry) -> Result<()> {
+        let mut entries = self.entries.write().await;
+        entries.entry(entry.player_id).or_default().push(entry);
+        Ok(())
+    }
+
+    async fn entries_for(&self, player_id: Uuid) -> Result<Vec<RatingAuditEntry>> {
+        let entries = self.entries.read().await;
+        Ok(entries.get(&player_id).cloned().unwrap_or_default())
+    }
+}
+
+/// Audit log stored through a [`PersistenceAdapter`]
+pub struct PersistentAuditLog {
+    persistence: Arc<dyn PersistenceAdapter>,
+}
+
+impl PersistentAuditLog {
+    pub fn new(persistence: Arc<dyn PersistenceAdapter>) -> Self {
+        Self { persistence }
+    }
+}
+
+#[async_trait]
+impl AuditLog for PersistentAuditLog {
+    async fn record(&self, entry: RatingAuditEntry) -> Result<()> {
+        self.persistence.save_rating_audit(&entry).await
+    }
+
+    async fn entries_for(&self, player_id: Uuid) -> Result<Vec<RatingAuditEntry>> {
+        self.persistence.load_rating_audit(player_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::InMemoryAdapter;
+
+    fn entry(player_id: Uuid, reason: RatingChangeReason) -> RatingAuditEntry {
+        RatingAuditEntry {
+            player_id,
+            reason,
+            before: Rating::new(1500.0, 100.0, 0.06),
+            after: Rating::new(1516.0, 95.0, 0.06),
+            at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_persistent_log_returns_player_entries_in_order() {
+        let log = PersistentAuditLog::new(Arc::new(InMemoryAdapter::new()));
+        let player_id = Uuid::new_v4();
+        let match_id = Uuid::new_v4();
+
+        log.record(entry(player_id, RatingChangeReason::Match { match_id })).await.unwrap();
+        log.record(entry(Uuid::new_v4(), RatingChangeReason::Decay)).await.unwrap();
+        log.record(entry(player_id, RatingChangeReason::ManualAdjustment { note: "refund".to_string() }))
+            .await
+            .unwrap();
+
+        let entries = log.entries_for(player_id).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].reason, RatingChangeReason::Match { match_id });
+        assert_eq!(entries[0].delta(), RatingDelta(16.0));
+        assert!(matches!(entries[1].reason, RatingChangeReason::ManualAdjustment { .. }));
+    }
+}
@@ -0,0 +1,81 @@
+//! Conversion between Glicko-2 ratings and Elo-equivalent numbers
+//!
+//! Glicko-2 works internally on a scale where `mu = (r - 1500) / 173.7178`.
+//! Elo's expected score `1 / (1 + 10^(-d / 400))` is the same logistic curve
+//! with a scale of `400 / ln 10`, which is what 173.7178 approximates. Mapping
+//! `mu` through that factor around 1500 therefore gives the Elo rating with the
+//! same expected score against a certain (zero-deviation) opponent.
+//!
+//! Only the rating itself carries over: Elo has no notion of deviation or
+//! volatility, so converting to Elo drops them and converting back assigns the
+//! beginner defaults.
+
+use super::rating::Rating;
+
+/// Conversion factor between the Glicko and Glicko-2 scales
+pub const GLICKO2_SCALE: f64 = 173.7178;
+
+/// Rating both systems treat as the population average
+pub const RATING_CENTER: f64 = 1500.0;
+
+/// Logistic scale of Elo's expected score formula (`400 / ln 10`)
+const ELO_SCALE: f64 = 400.0 / std::f64::consts::LN_10;
+
+/// Elo-equivalent of a Glicko-2 rating, ignoring its deviation and volatility
+pub fn glicko2_to_elo(rating: &Rating) -> f64 {
+    let mu = (rating.rating - RATING_CENTER) / GLICKO2_SCALE;
+    RATING_CENTER + mu * ELO_SCALE
+}
+
+/// Glicko-2 rating for an Elo number, with beginner deviation and volatility
+///
+/// Players migrating with a long Elo history are usually better served by a
+/// lower deviation; adjust the returned rating before saving it.
+pub fn elo_to_glicko2(elo: f64) -> Rating {
+    let mu = (elo - RATING_CENTER) / ELO_SCALE;
+    Rating {
+        rating: RATING_CENTER + mu * GLICKO2_SCALE,
+        ..Rating::default_beginner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f64 = 1e-6;
+
+    #[test]
+    fn test_center_maps_to_center() {
+        assert!((glicko2_to_elo(&Rating::new(1500.0, 80.0, 0.06)) - 1500.0).abs() < TOLERANCE);
+        assert!((elo_to_glicko2(1500.0).rating - 1500.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_round_trip_within_tolerance() {
+        for value in [800.0, 1234.5, 1500.0, 1850.0, 2400.0, 3100.0] {
+            let elo = glicko2_to_elo(&Rating::new(value, 120.0, 0.06));
+            assert!((elo - value).abs() < 0.01, "{value} converted to {elo}");
+            assert!((elo_to_glicko2(elo).rating - value).abs() < TOLERANCE);
+        }
+    }
+
+    #[test]
+    fn test_elo_expected_score_matches_glicko2() {
+        let player = Rating::new(1700.0, 0.0, 0.06);
+        let opponent = Rating::new(1450.0, 0.0, 0.06);
+
+        let glicko2 = 1.0 / (1.0 + (-(player.rating - opponent.rating) / GLICKO2_SCALE).exp());
+        let diff = glicko2_to_elo(&player) - glicko2_to_elo(&opponent);
+        let elo = 1.0 / (1.0 + 10_f64.powf(-diff / 400.0));
+
+        assert!((glicko2 - elo).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_elo_to_glicko2_uses_beginner_uncertainty() {
+        let rating = elo_to_glicko2(2000.0);
+        assert_eq!(rating.deviation, Rating::default_beginner().deviation);
+        assert_eq!(rating.volatility, Rating::default_beginner().volatility);
+    }
+}
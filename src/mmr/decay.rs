@@ -1,5 +1,7 @@
 use super::rating::Rating;
+use crate::clock::{Clock, SystemClock};
 use chrono::{DateTime, Utc};
+use std::sync::Arc;
 
 /// MMR decay strategy
 pub trait DecayStrategy: Send + Sync {
@@ -8,9 +10,14 @@ pub trait DecayStrategy: Send + Sync {
 }
 
 /// Linear decay: reduce rating by a fixed amount per time period
+///
+/// Nothing decays during the grace period after the last match; after it,
+/// decay counts whole days from the end of the grace period.
 pub struct LinearDecay {
     pub decay_per_day: f64,
     pub max_decay: f64,
+    pub grace_period: chrono::Duration,
+    clock: Arc<dyn Clock>,
 }
 
 impl LinearDecay {
@@ -18,21 +25,35 @@ impl LinearDecay {
         Self {
             decay_per_day,
             max_decay,
+            grace_period: chrono::Duration::zero(),
+            clock: Arc::new(SystemClock),
         }
     }
 
+    /// Let players be inactive for `grace_period` before any decay applies
+    pub fn with_grace_period(mut self, grace_period: chrono::Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// Measure inactivity against a custom clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     pub fn default() -> Self {
-        Self {
-            decay_per_day: 1.0,
-            max_decay: 100.0,
-        }
+        Self::new(1.0, 100.0)
     }
 }
 
 impl DecayStrategy for LinearDecay {
     fn apply_decay(&self, rating: Rating, last_match_time: DateTime<Utc>) -> Rating {
-        let now = Utc::now();
-        let days_inactive = (now - last_match_time).num_days() as f64;
+        // A grace period reaching past the representable range never ends
+        let Some(grace_end) = last_match_time.checked_add_signed(self.grace_period) else {
+            return rating;
+        };
+        let days_inactive = (self.clock.now() - grace_end).num_days() as f64;
 
         if days_inactive <= 0.0 {
             return rating;
@@ -56,3 +77,68 @@ impl DecayStrategy for NoDecay {
         rating
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    #[test]
+    fn test_linear_decay_uses_clock() {
+        let last_match = Utc::now();
+        let clock = Arc::new(MockClock::new(last_match));
+        let decay = LinearDecay::new(2.0, 100.0).with_clock(clock.clone());
+        let rating = Rating::new(1500.0, 100.0, 0.06);
+
+        assert_eq!(decay.apply_decay(rating, last_match).rating, 1500.0);
+
+        clock.advance(chrono::Duration::days(10));
+        assert_eq!(decay.apply_decay(rating, last_match).rating, 1480.0);
+    }
+
+    #[test]
+    fn test_no_decay_within_grace_period() {
+        let last_match = Utc::now();
+        let clock = Arc::new(MockClock::new(last_match));
+        let decay = LinearDecay::new(2.0, 100.0)
+            .with_grace_period(chrono::Duration::days(7))
+            .with_clock(clock.clone());
+        let rating = Rating::new(1500.0, 100.0, 0.06);
+
+        clock.advance(chrono::Duration::days(7));
+        let unchanged = decay.apply_decay(rating, last_match);
+        assert_eq!(unchanged.rating, 1500.0);
+        assert_eq!(unchanged.deviation, 100.0);
+    }
+
+    #[test]
+    fn test_huge_grace_period_does_not_overflow() {
+        let last_match = Utc::now();
+        let clock = Arc::new(MockClock::new(last_match));
+        let decay = LinearDecay::new(2.0, 100.0)
+            .with_grace_period(chrono::Duration::MAX)
+            .with_clock(clock.clone());
+        let rating = Rating::new(1500.0, 100.0, 0.06);
+
+        clock.advance(chrono::Duration::days(365 * 100));
+        assert_eq!(decay.apply_decay(rating, last_match).rating, 1500.0);
+    }
+
+    #[test]
+    fn test_decay_counts_from_end_of_grace_period() {
+        let last_match = Utc::now();
+        let clock = Arc::new(MockClock::new(last_match));
+        let decay = LinearDecay::new(2.0, 100.0)
+            .with_grace_period(chrono::Duration::days(7))
+            .with_clock(clock.clone());
+        let rating = Rating::new(1500.0, 100.0, 0.06);
+
+        clock.advance(chrono::Duration::days(8));
+        let decayed = decay.apply_decay(rating, last_match);
+        assert_eq!(decayed.rating, 1498.0);
+        assert_eq!(decayed.deviation, 100.5);
+
+        clock.advance(chrono::Duration::days(2));
+        assert_eq!(decay.apply_decay(rating, last_match).rating, 1494.0);
+    }
+}
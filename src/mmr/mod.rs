@@ -1,9 +1,26 @@
 pub mod algorithm;
+#[cfg(feature = "runtime")]
+pub mod audit;
+pub mod conversion;
 pub mod decay;
 pub mod rating;
+#[cfg(feature = "runtime")]
 pub mod season;
+pub mod seeding;
+#[cfg(feature = "runtime")]
+pub mod service;
+pub mod tiers;
+pub mod units;
 
 pub use algorithm::{EloAlgorithm, Glicko2Algorithm, MmrAlgorithm};
+#[cfg(feature = "runtime")]
+pub use audit::{AuditLog, InMemoryAuditLog, PersistentAuditLog, RatingAuditEntry, RatingChangeReason};
 pub use decay::{DecayStrategy, LinearDecay, NoDecay};
-pub use rating::{Outcome, Rating};
-pub use season::{HardReset, Season, SeasonResetStrategy, SoftReset};
+pub use rating::{Outcome, Rating, TeamRanking};
+#[cfg(feature = "runtime")]
+pub use season::{HardReset, Season, SeasonManager, SeasonResetStrategy, SoftReset};
+pub use seeding::{CalibrationSeeder, NoSeeding, RatingSeeder};
+#[cfg(feature = "runtime")]
+pub use service::{RatingAdjustment, RatingBounds, RatingChange, RatingProtection, RatingService};
+pub use tiers::{Tier, TierLadder};
+pub use units::{Deviation, RatingDelta, RatingValue};
@@ -1,3 +1,5 @@
+use super::units::{Deviation, RatingValue};
+use crate::error::{MatchForgeError, Result};
 use serde::{Deserialize, Serialize};
 
 /// Represents a player's skill rating
@@ -12,6 +14,7 @@ pub struct Rating {
 }
 
 impl Rating {
+    /// Build a rating without checking its values, see [`try_new`](Self::try_new)
     pub fn new(rating: f64, deviation: f64, volatility: f64) -> Self {
         Self {
             rating,
@@ -20,6 +23,45 @@ impl Rating {
         }
     }
 
+    /// Build a rating from user input, rejecting NaN or infinite values and
+    /// a negative deviation or volatility
+    pub fn try_new(rating: f64, deviation: f64, volatility: f64) -> Result<Self> {
+        if !rating.is_finite() {
+            return Err(MatchForgeError::InvalidConfiguration(format!(
+                "Rating must be a finite number, got {}",
+                rating
+            )));
+        }
+        if !deviation.is_finite() || deviation < 0.0 {
+            return Err(MatchForgeError::InvalidConfiguration(format!(
+                "Rating deviation must be finite and non-negative, got {}",
+                deviation
+            )));
+        }
+        if !volatility.is_finite() || volatility < 0.0 {
+            return Err(MatchForgeError::InvalidConfiguration(format!(
+                "Rating volatility must be finite and non-negative, got {}",
+                volatility
+            )));
+        }
+        Ok(Self::new(rating, deviation, volatility))
+    }
+
+    /// Build a rating from typed values
+    pub fn from_typed(rating: RatingValue, deviation: Deviation, volatility: f64) -> Self {
+        Self::new(rating.0, deviation.0, volatility)
+    }
+
+    /// The rating as a [`RatingValue`]
+    pub fn rating_value(&self) -> RatingValue {
+        RatingValue(self.rating)
+    }
+
+    /// The deviation as a [`Deviation`]
+    pub fn deviation_value(&self) -> Deviation {
+        Deviation(self.deviation)
+    }
+
     /// Create a default beginner rating
     pub fn default_beginner() -> Self {
         Self {
@@ -29,6 +71,20 @@ impl Rating {
         }
     }
 
+    /// A group of players as a single opponent: mean rating, root-mean-square deviation
+    pub fn composite(players: &[Rating]) -> Rating {
+        if players.is_empty() {
+            return Rating::default_beginner();
+        }
+
+        let count = players.len() as f64;
+        Rating {
+            rating: players.iter().map(|r| r.rating).sum::<f64>() / count,
+            deviation: (players.iter().map(|r| r.deviation.powi(2)).sum::<f64>() / count).sqrt(),
+            volatility: players.iter().map(|r| r.volatility).sum::<f64>() / count,
+        }
+    }
+
     /// Get a conservative estimate of skill (rating - 2*deviation)
     pub fn conservative_estimate(&self) -> f64 {
         self.rating - 2.0 * self.deviation
@@ -58,3 +114,71 @@ impl Outcome {
         }
     }
 }
+
+/// Finishing positions of the teams in a match, best first
+///
+/// `ranks[i]` is the place of team `i` (lower is better); teams sharing a
+/// rank tied with each other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeamRanking {
+    ranks: Vec<usize>,
+}
+
+impl TeamRanking {
+    pub fn new(ranks: Vec<usize>) -> Self {
+        Self { ranks }
+    }
+
+    /// Every team tied
+    pub fn draw(team_count: usize) -> Self {
+        Self::new(vec![0; team_count])
+    }
+
+    pub fn team_count(&self) -> usize {
+        self.ranks.len()
+    }
+
+    pub fn rank(&self, team: usize) -> usize {
+        self.ranks[team]
+    }
+
+    /// Result for `team` against `opponent`: ahead is a win, level is a draw
+    pub fn outcome(&self, team: usize, opponent: usize) -> Outcome {
+        match self.ranks[team].cmp(&self.ranks[opponent]) {
+            std::cmp::Ordering::Less => Outcome::Win,
+            std::cmp::Ordering::Equal => Outcome::Draw,
+            std::cmp::Ordering::Greater => Outcome::Loss,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_new_accepts_valid_rating() {
+        let rating = Rating::try_new(1720.0, 80.0, 0.06).unwrap();
+        assert_eq!(rating.rating, 1720.0);
+        assert_eq!(rating.deviation, 80.0);
+        assert_eq!(rating.volatility, 0.06);
+
+        // Elo ignores volatility, so zero is allowed
+        assert!(Rating::try_new(-40.0, 0.0, 0.0).is_ok());
+    }
+
+    #[test]
+    fn test_try_new_rejects_negative_deviation() {
+        let err = Rating::try_new(1500.0, -1.0, 0.06).unwrap_err();
+        assert!(matches!(err, MatchForgeError::InvalidConfiguration(_)));
+        assert!(Rating::try_new(1500.0, 350.0, -0.06).is_err());
+    }
+
+    #[test]
+    fn test_try_new_rejects_nan_and_infinite() {
+        assert!(Rating::try_new(f64::NAN, 350.0, 0.06).is_err());
+        assert!(Rating::try_new(f64::INFINITY, 350.0, 0.06).is_err());
+        assert!(Rating::try_new(1500.0, f64::NAN, 0.06).is_err());
+        assert!(Rating::try_new(1500.0, 350.0, f64::NAN).is_err());
+    }
+}
@@ -1,6 +1,17 @@
-use super::rating::Rating;
+use super::{
+    audit::{AuditLog, RatingAuditEntry, RatingChangeReason},
+    rating::Rating,
+};
+use crate::{
+    clock::{Clock, SystemClock},
+    error::{MatchForgeError, Result},
+    persistence::PersistenceAdapter,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::RwLock;
+use uuid::Uuid;
 
 /// Represents a competitive season
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,13 +19,178 @@ pub struct Season {
     pub id: String,
     pub start_time: DateTime<Utc>,
     pub end_time: DateTime<Utc>,
+    /// Queues whose ratings belong to this season
+    #[serde(default)]
+    pub queues: Vec<String>,
 }
 
 impl Season {
+    pub fn new(id: String, start_time: DateTime<Utc>, end_time: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            start_time,
+            end_time,
+            queues: Vec::new(),
+        }
+    }
+
+    /// Associate a queue with this season
+    pub fn with_queue(mut self, queue_name: impl Into<String>) -> Self {
+        self.queues.push(queue_name.into());
+        self
+    }
+
     pub fn is_active(&self) -> bool {
-        let now = Utc::now();
+        self.is_active_at(Utc::now())
+    }
+
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
         now >= self.start_time && now < self.end_time
     }
+
+    /// Whether ratings for `queue_name` are scoped to this season
+    pub fn covers_queue(&self, queue_name: &str) -> bool {
+        self.queues.iter().any(|q| q == queue_name)
+    }
+
+    fn overlaps(&self, other: &Season) -> bool {
+        self.start_time < other.end_time && other.start_time < self.end_time
+    }
+}
+
+/// Tracks concurrent seasons and scopes season ratings to them
+///
+/// Several seasons can run at once (e.g. a ranked season alongside an
+/// event season) as long as no queue belongs to two overlapping seasons.
+pub struct SeasonManager {
+    seasons: Arc<RwLock<HashMap<String, Season>>>,
+    persistence: Arc<dyn PersistenceAdapter>,
+    clock: Arc<dyn Clock>,
+    audit_log: Option<Arc<dyn AuditLog>>,
+}
+
+impl SeasonManager {
+    pub fn new(persistence: Arc<dyn PersistenceAdapter>) -> Self {
+        Self {
+            seasons: Arc::new(RwLock::new(HashMap::new())),
+            persistence,
+            clock: Arc::new(SystemClock),
+            audit_log: None,
+        }
+    }
+
+    /// Record every rating changed by a season reset
+    pub fn with_audit_log(mut self, audit_log: Arc<dyn AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Use the given clock to decide which seasons are active
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Register a season
+    ///
+    /// Fails if one of its queues already belongs to a season with an
+    /// overlapping time range.
+    pub async fn add_season(&self, season: Season) -> Result<()> {
+        if season.end_time <= season.start_time {
+            return Err(MatchForgeError::InvalidConfiguration(format!(
+                "Season {} ends before it starts",
+                season.id
+            )));
+        }
+
+        let mut seasons = self.seasons.write().await;
+        if let Some(conflict) = seasons.values().find(|existing| {
+            existing.id != season.id
+                && existing.overlaps(&season)
+                && season.queues.iter().any(|q| existing.covers_queue(q))
+        }) {
+            return Err(MatchForgeError::InvalidConfiguration(format!(
+                "Season {} overlaps season {} on a shared queue",
+                season.id, conflict.id
+            )));
+        }
+
+        seasons.insert(season.id.clone(), season);
+        Ok(())
+    }
+
+    pub async fn get_season(&self, season_id: &str) -> Option<Season> {
+        self.seasons.read().await.get(season_id).cloned()
+    }
+
+    /// Season currently active for a queue, if any
+    pub async fn current_for_queue(&self, queue_name: &str) -> Option<Season> {
+        let now = self.clock.now();
+        self.seasons
+            .read()
+            .await
+            .values()
+            .find(|season| season.covers_queue(queue_name) && season.is_active_at(now))
+            .cloned()
+    }
+
+    /// Record a player's rating in the queue's current season
+    pub async fn save_rating(&self, queue_name: &str, player_id: Uuid, rating: Rating) -> Result<()> {
+        let season = self.require_current(queue_name).await?;
+        self.persistence
+            .save_season_rating(queue_name, &season.id, player_id, rating)
+            .await
+    }
+
+    /// Load a player's rating from the queue's current season
+    pub async fn load_rating(&self, queue_name: &str, player_id: Uuid) -> Result<Option<Rating>> {
+        let season = self.require_current(queue_name).await?;
+        self.persistence
+            .load_season_rating(queue_name, &season.id, player_id)
+            .await
+    }
+
+    /// Apply a reset strategy to every rating recorded in one season
+    ///
+    /// Only the season's own queues are touched; returns the number of
+    /// ratings reset.
+    pub async fn reset_season(&self, season_id: &str, strategy: &dyn SeasonResetStrategy) -> Result<usize> {
+        let season = self.get_season(season_id).await.ok_or_else(|| {
+            MatchForgeError::OperationFailed(format!("Season not found: {}", season_id))
+        })?;
+
+        let mut reset = 0;
+        for queue_name in &season.queues {
+            let ratings = self.persistence.load_season_ratings(queue_name, &season.id).await?;
+            for (player_id, rating) in ratings {
+                let after = strategy.reset_rating(rating);
+                self.persistence
+                    .save_season_rating(queue_name, &season.id, player_id, after)
+                    .await?;
+
+                if let Some(audit_log) = &self.audit_log {
+                    audit_log
+                        .record(RatingAuditEntry {
+                            player_id,
+                            reason: RatingChangeReason::SeasonReset { season_id: season.id.clone() },
+                            before: rating,
+                            after,
+                            at: self.clock.now(),
+                        })
+                        .await?;
+                }
+                reset += 1;
+            }
+        }
+
+        Ok(reset)
+    }
+
+    async fn require_current(&self, queue_name: &str) -> Result<Season> {
+        self.current_for_queue(queue_name).await.ok_or_else(|| {
+            MatchForgeError::OperationFailed(format!("No active season for queue {}", queue_name))
+        })
+    }
 }
 
 /// Strategy for resetting ratings at season boundaries
@@ -23,36 +199,44 @@ pub trait SeasonResetStrategy: Send + Sync {
     fn reset_rating(&self, current_rating: Rating) -> Rating;
 }
 
-/// Soft reset: move rating toward the mean
+/// Soft reset: compress ratings toward a target
+///
+/// The new rating is `target + keep_fraction * (old - target)`, and the
+/// deviation is multiplied by `deviation_inflation` (capped at 350).
 pub struct SoftReset {
-    pub target_rating: f64,
-    pub reset_percentage: f64, // 0.0 to 1.0
+    /// Share of last season's distance from the target that carries over (0.0 to 1.0)
+    pub keep_fraction: f64,
+    /// Rating that everyone is pulled toward
+    pub target: f64,
+    /// Multiplier applied to the deviation at season start
+    pub deviation_inflation: f64,
 }
 
 impl SoftReset {
-    pub fn new(target_rating: f64, reset_percentage: f64) -> Self {
+    pub fn new(keep_fraction: f64, target: f64, deviation_inflation: f64) -> Self {
         Self {
-            target_rating,
-            reset_percentage,
+            keep_fraction,
+            target,
+            deviation_inflation,
         }
     }
 
     pub fn default() -> Self {
         Self {
-            target_rating: 1500.0,
-            reset_percentage: 0.5,
+            keep_fraction: 0.5,
+            target: 1500.0,
+            deviation_inflation: 1.5,
         }
     }
 }
 
 impl SeasonResetStrategy for SoftReset {
     fn reset_rating(&self, current_rating: Rating) -> Rating {
-        let diff = self.target_rating - current_rating.rating;
-        let new_rating = current_rating.rating + diff * self.reset_percentage;
+        let new_rating = self.target + self.keep_fraction * (current_rating.rating - self.target);
 
         Rating {
             rating: new_rating,
-            deviation: 200.0, // Moderate uncertainty for new season
+            deviation: (current_rating.deviation * self.deviation_inflation).min(350.0),
             volatility: current_rating.volatility,
         }
     }
@@ -78,3 +262,110 @@ impl SeasonResetStrategy for HardReset {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock::MockClock, persistence::InMemoryAdapter};
+    use chrono::{Duration, TimeZone};
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()
+    }
+
+    async fn overlapping_seasons() -> SeasonManager {
+        let clock = Arc::new(MockClock::new(start() + Duration::days(10)));
+        let manager = SeasonManager::new(Arc::new(InMemoryAdapter::new())).with_clock(clock);
+
+        let ranked = Season::new("ranked-s1".to_string(), start(), start() + Duration::days(90)).with_queue("ranked");
+        let event = Season::new("spring-event".to_string(), start() + Duration::days(7), start() + Duration::days(21))
+            .with_queue("event");
+        manager.add_season(ranked).await.unwrap();
+        manager.add_season(event).await.unwrap();
+        manager
+    }
+
+    #[test]
+    fn test_soft_reset_full_keep_is_noop_on_rating() {
+        let reset = SoftReset::new(1.0, 1500.0, 1.0);
+        let rating = reset.reset_rating(Rating::new(2100.0, 80.0, 0.06));
+        assert_eq!(rating.rating, 2100.0);
+        assert_eq!(rating.deviation, 80.0);
+    }
+
+    #[test]
+    fn test_soft_reset_zero_keep_snaps_to_target() {
+        let reset = SoftReset::new(0.0, 1500.0, 1.0);
+        assert_eq!(reset.reset_rating(Rating::new(2100.0, 80.0, 0.06)).rating, 1500.0);
+        assert_eq!(reset.reset_rating(Rating::new(900.0, 80.0, 0.06)).rating, 1500.0);
+    }
+
+    #[test]
+    fn test_soft_reset_compresses_and_inflates_deviation() {
+        let reset = SoftReset::new(0.25, 1500.0, 2.0);
+        let rating = reset.reset_rating(Rating::new(2100.0, 80.0, 0.06));
+        assert_eq!(rating.rating, 1650.0);
+        assert_eq!(rating.deviation, 160.0);
+
+        // Deviation never exceeds the beginner ceiling
+        let capped = reset.reset_rating(Rating::new(2100.0, 300.0, 0.06));
+        assert_eq!(capped.deviation, 350.0);
+    }
+
+    #[tokio::test]
+    async fn test_current_season_resolved_per_queue() {
+        let manager = overlapping_seasons().await;
+
+        assert_eq!(manager.current_for_queue("ranked").await.unwrap().id, "ranked-s1");
+        assert_eq!(manager.current_for_queue("event").await.unwrap().id, "spring-event");
+        assert!(manager.current_for_queue("casual").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reset_only_affects_its_own_season() {
+        let manager = overlapping_seasons().await;
+        let player = Uuid::new_v4();
+
+        manager.save_rating("ranked", player, Rating::new(2100.0, 80.0, 0.06)).await.unwrap();
+        manager.save_rating("event", player, Rating::new(1900.0, 90.0, 0.06)).await.unwrap();
+
+        let reset = manager.reset_season("ranked-s1", &HardReset::new(1500.0)).await.unwrap();
+        assert_eq!(reset, 1);
+
+        assert_eq!(manager.load_rating("ranked", player).await.unwrap().unwrap().rating, 1500.0);
+        let event_rating = manager.load_rating("event", player).await.unwrap().unwrap();
+        assert_eq!(event_rating.rating, 1900.0);
+        assert_eq!(event_rating.deviation, 90.0);
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_seasons_cannot_share_a_queue() {
+        let manager = overlapping_seasons().await;
+
+        let clash = Season::new("ranked-s1-rerun".to_string(), start() + Duration::days(30), start() + Duration::days(120))
+            .with_queue("ranked");
+        assert!(manager.add_season(clash).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_season_reset_audited_once_per_rating() {
+        let clock = Arc::new(MockClock::new(start() + Duration::days(10)));
+        let audit_log = Arc::new(crate::mmr::InMemoryAuditLog::new());
+        let manager = SeasonManager::new(Arc::new(InMemoryAdapter::new()))
+            .with_clock(clock)
+            .with_audit_log(audit_log.clone());
+        let season = Season::new("ranked-s1".to_string(), start(), start() + Duration::days(90)).with_queue("ranked");
+        manager.add_season(season).await.unwrap();
+
+        let player = Uuid::new_v4();
+        manager.save_rating("ranked", player, Rating::new(2100.0, 80.0, 0.06)).await.unwrap();
+        manager.reset_season("ranked-s1", &HardReset::new(1500.0)).await.unwrap();
+
+        let entries = audit_log.entries_for(player).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].reason, RatingChangeReason::SeasonReset { season_id: "ranked-s1".to_string() });
+        assert_eq!(entries[0].before.rating, 2100.0);
+        assert_eq!(entries[0].after.rating, 1500.0);
+        assert_eq!(entries[0].at, start() + Duration::days(10));
+    }
+}
@@ -0,0 +1,56 @@
+use super::rating::Rating;
+use std::collections::HashMap;
+
+/// Strategy for choosing a starting rating for players with no rating history
+pub trait RatingSeeder: Send + Sync {
+    /// Map external skill signals to a starting rating, or `None` to keep the default
+    fn seed_rating(&self, signals: &HashMap<String, String>) -> Option<Rating>;
+}
+
+/// No seeding: new players start with whatever rating they queue with
+pub struct NoSeeding;
+
+impl RatingSeeder for NoSeeding {
+    fn seed_rating(&self, _signals: &HashMap<String, String>) -> Option<Rating> {
+        None
+    }
+}
+
+/// Seed from a normalized calibration score (0.0 to 1.0), e.g. a survey result
+pub struct CalibrationSeeder {
+    /// Key in the signal map holding the calibration score
+    pub signal_key: String,
+    pub min_rating: f64,
+    pub max_rating: f64,
+    /// Deviation assigned to seeded ratings (kept high since the signal is only a guess)
+    pub deviation: f64,
+}
+
+impl CalibrationSeeder {
+    pub fn new(signal_key: String, min_rating: f64, max_rating: f64) -> Self {
+        Self {
+            signal_key,
+            min_rating,
+            max_rating,
+            deviation: 300.0,
+        }
+    }
+}
+
+impl RatingSeeder for CalibrationSeeder {
+    fn seed_rating(&self, signals: &HashMap<String, String>) -> Option<Rating> {
+        let score = signals.get(&self.signal_key)?.parse::<f64>().ok()?;
+        if !score.is_finite() {
+            return None;
+        }
+
+        let score = score.clamp(0.0, 1.0);
+        let rating = self.min_rating + (self.max_rating - self.min_rating) * score;
+
+        Some(Rating {
+            rating,
+            deviation: self.deviation,
+            volatility: 0.06,
+        })
+    }
+}
@@ -0,0 +1,618 @@
+use super::{
+    algorithm::{Glicko2Algorithm, MmrAlgorithm},
+    audit::{AuditLog, RatingAuditEntry, RatingChangeReason},
+    rating::{Outcome, Rating},
+    tiers::TierLadder,
+    units::{Deviation, RatingDelta, RatingValue},
+};
+use crate::{
+    clock::{Clock, SystemClock},
+    error::{MatchForgeError, Result},
+    persistence::PersistenceAdapter,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use uuid::Uuid;
+
+/// Allowed range for ratings set through [`RatingService`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RatingBounds {
+    pub min_rating: RatingValue,
+    pub max_rating: RatingValue,
+}
+
+impl RatingBounds {
+    pub fn new(min_rating: RatingValue, max_rating: RatingValue) -> Self {
+        Self { min_rating, max_rating }
+    }
+
+    pub fn contains(&self, rating: &Rating) -> bool {
+        (self.min_rating..=self.max_rating).contains(&rating.rating_value())
+    }
+}
+
+/// A new rating value, optionally replacing deviation and volatility too
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RatingAdjustment {
+    pub rating: RatingValue,
+    /// Keep the current deviation if `None`
+    pub deviation: Option<Deviation>,
+    /// Keep the current volatility if `None`
+    pub volatility: Option<f64>,
+}
+
+impl RatingAdjustment {
+    pub fn new(rating: RatingValue) -> Self {
+        Self {
+            rating,
+            deviation: None,
+            volatility: None,
+        }
+    }
+
+    pub fn with_deviation(mut self, deviation: Deviation) -> Self {
+        self.deviation = Some(deviation);
+        self
+    }
+
+    pub fn with_volatility(mut self, volatility: f64) -> Self {
+        self.volatility = Some(volatility);
+        self
+    }
+
+    /// The adjusted rating starting from `current`
+    pub fn apply_to(&self, current: Rating) -> Rating {
+        Rating::from_typed(
+            self.rating,
+            self.deviation.unwrap_or(current.deviation_value()),
+            self.volatility.unwrap_or(current.volatility),
+        )
+    }
+}
+
+/// A player's rating before and after a reported match
+#[derive(Debug, Clone, Copy)]
+pub struct RatingChange {
+    pub player_id: Uuid,
+    pub outcome: Outcome,
+    pub before: Rating,
+    pub after: Rating,
+}
+
+impl RatingChange {
+    /// How far the rating moved
+    pub fn delta(&self) -> RatingDelta {
+        self.after.rating_value() - self.before.rating_value()
+    }
+}
+
+/// Freezes the rating in one direction for a player in a reported match
+///
+/// A protected change keeps the old rating but still takes the new deviation
+/// and volatility, so e.g. a protected placement loss still makes the rating
+/// more certain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RatingProtection {
+    /// Keep the rating if it would drop
+    pub no_loss: bool,
+    /// Keep the rating if it would rise
+    pub no_gain: bool,
+}
+
+impl RatingProtection {
+    /// Losses leave the rating unchanged, gains still apply
+    pub fn no_loss() -> Self {
+        Self { no_loss: true, no_gain: false }
+    }
+
+    /// `after` with its rating reset to `before` if this protection blocks the change
+    pub fn apply(&self, before: Rating, after: Rating) -> Rating {
+        let frozen = (self.no_loss && after.rating < before.rating) || (self.no_gain && after.rating > before.rating);
+        if frozen {
+            Rating { rating: before.rating, ..after }
+        } else {
+            after
+        }
+    }
+}
+
+/// Administrative rating operations, e.g. for support staff correcting a rating after a bug
+pub struct RatingService {
+    persistence: Arc<dyn PersistenceAdapter>,
+    audit_log: Option<Arc<dyn AuditLog>>,
+    bounds: Option<RatingBounds>,
+    clock: Arc<dyn Clock>,
+    algorithm: Arc<dyn MmrAlgorithm>,
+    shadow_algorithm: Option<Arc<dyn MmrAlgorithm>>,
+    tier_ladder: Option<Arc<TierLadder>>,
+}
+
+impl RatingService {
+    pub fn new(persistence: Arc<dyn PersistenceAdapter>) -> Self {
+        Self {
+            persistence,
+            audit_log: None,
+            bounds: None,
+            clock: Arc::new(SystemClock),
+            algorithm: Arc::new(Glicko2Algorithm::default()),
+            shadow_algorithm: None,
+            tier_ladder: None,
+        }
+    }
+
+    /// Record every adjustment in an audit log
+    pub fn with_audit_log(mut self, audit_log: Arc<dyn AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Reject adjustments that fall outside `bounds`
+    pub fn with_bounds(mut self, bounds: RatingBounds) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Use a custom clock for audit timestamps
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Algorithm used by [`report_match_result`](Self::report_match_result), Glicko-2 by default
+    pub fn with_algorithm(mut self, algorithm: Arc<dyn MmrAlgorithm>) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Also rate every reported match with `algorithm`, e.g. to evaluate it before switching
+    ///
+    /// Shadow ratings are stored with [`PersistenceAdapter::save_shadow_rating`]
+    /// and never affect live ratings or matching. A player's first shadow
+    /// rating starts from their live rating. Rating protections and the audit
+    /// log apply only to live ratings.
+    pub fn with_shadow_algorithm(mut self, algorithm: Arc<dyn MmrAlgorithm>) -> Self {
+        self.shadow_algorithm = Some(algorithm);
+        self
+    }
+
+    /// Shield reported losses from demoting players out of a tier, see [`TierLadder`]
+    ///
+    /// The ladder applies after any [`RatingProtection`].
+    pub fn with_tier_ladder(mut self, tier_ladder: Arc<TierLadder>) -> Self {
+        self.tier_ladder = Some(tier_ladder);
+        self
+    }
+
+    /// Rate a finished match, persist the new ratings and return each player's change
+    ///
+    /// Players reporting the same outcome are treated as teammates, and each
+    /// player is rated against the composite of everyone with a different
+    /// outcome. When every player reports the same outcome, as in a draw,
+    /// each is rated against the composite of all other players. All ratings
+    /// are loaded before any is written, so an unknown player fails the whole
+    /// report. Changes are audited with the match id when an audit log is set.
+    pub async fn report_match_result(&self, match_id: Uuid, outcomes: Vec<(Uuid, Outcome)>) -> Result<Vec<RatingChange>> {
+        self.report_match_result_with_protection(match_id, outcomes, &HashMap::new()).await
+    }
+
+    /// [`report_match_result`](Self::report_match_result) with a
+    /// [`RatingProtection`] for some players, e.g. their first games
+    ///
+    /// Protected players still count at their full rating as opponents.
+    pub async fn report_match_result_with_protection(
+        &self,
+        match_id: Uuid,
+        outcomes: Vec<(Uuid, Outcome)>,
+        protections: &HashMap<Uuid, RatingProtection>,
+    ) -> Result<Vec<RatingChange>> {
+        let mut seen = HashSet::new();
+        if let Some((duplicate, _)) = outcomes.iter().find(|(player_id, _)| !seen.insert(*player_id)) {
+            return Err(MatchForgeError::InvalidConfiguration(format!(
+                "Player {} is reported more than once",
+                duplicate
+            )));
+        }
+        if outcomes.len() < 2 {
+            return Err(MatchForgeError::InvalidConfiguration(
+                "A match result needs at least two players".to_string(),
+            ));
+        }
+
+        let mut ratings = Vec::with_capacity(outcomes.len());
+        for (player_id, _) in &outcomes {
+            let rating = self
+                .persistence
+                .load_player_rating(*player_id)
+                .await?
+                .ok_or(MatchForgeError::PlayerNotFound(*player_id))?;
+            ratings.push(rating);
+        }
+
+        let mut shadow_ratings = Vec::new();
+        if self.shadow_algorithm.is_some() {
+            for ((player_id, _), live) in outcomes.iter().zip(&ratings) {
+                shadow_ratings.push(self.persistence.load_shadow_rating(*player_id).await?.unwrap_or(*live));
+            }
+        }
+
+        let changes: Vec<RatingChange> = outcomes
+            .iter()
+            .zip(&ratings)
+            .zip(rate_match(self.algorithm.as_ref(), &outcomes, &ratings))
+            .map(|(((player_id, outcome), before), after)| {
+                let protection = protections.get(player_id).copied().unwrap_or_default();
+                let mut after = protection.apply(*before, after);
+                if let Some(tier_ladder) = &self.tier_ladder {
+                    after = tier_ladder.apply(*player_id, *before, after);
+                }
+                RatingChange {
+                    player_id: *player_id,
+                    outcome: *outcome,
+                    before: *before,
+                    after,
+                }
+            })
+            .collect();
+
+        let at = self.clock.now();
+        for change in &changes {
+            self.persistence.save_player_rating(change.player_id, change.after).await?;
+            if let Some(audit_log) = &self.audit_log {
+                audit_log
+                    .record(RatingAuditEntry {
+                        player_id: change.player_id,
+                        reason: RatingChangeReason::Match { match_id },
+                        before: change.before,
+                        after: change.after,
+                        at,
+                    })
+                    .await?;
+            }
+        }
+
+        if let Some(shadow_algorithm) = &self.shadow_algorithm {
+            let shadow_afters = rate_match(shadow_algorithm.as_ref(), &outcomes, &shadow_ratings);
+            for ((player_id, _), after) in outcomes.iter().zip(shadow_afters) {
+                self.persistence.save_shadow_rating(*player_id, after).await?;
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Overwrite a player's rating, recording `reason` in the audit log
+    ///
+    /// Fails if the player has no rating yet or the result violates the
+    /// configured bounds; nothing is written in either case.
+    pub async fn adjust_rating(&self, player_id: Uuid, new_rating: RatingAdjustment, reason: String) -> Result<()> {
+        let before = self
+            .persistence
+            .load_player_rating(player_id)
+            .await?
+            .ok_or(MatchForgeError::PlayerNotFound(player_id))?;
+        let after = new_rating.apply_to(before);
+
+        if let Some(bounds) = &self.bounds {
+            if !bounds.contains(&after) {
+                return Err(MatchForgeError::ConstraintsNotSatisfied(format!(
+                    "Rating {} is outside the allowed range {} to {}",
+                    after.rating, bounds.min_rating, bounds.max_rating
+                )));
+            }
+        }
+
+        self.persistence.save_player_rating(player_id, after).await?;
+
+        if let Some(audit_log) = &self.audit_log {
+            audit_log
+                .record(RatingAuditEntry {
+                    player_id,
+                    reason: RatingChangeReason::ManualAdjustment { note: reason },
+                    before,
+                    after,
+                    at: self.clock.now(),
+                })
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// New rating of each reported player, rated against the composite of their opponents
+fn rate_match(algorithm: &dyn MmrAlgorithm, outcomes: &[(Uuid, Outcome)], ratings: &[Rating]) -> Vec<Rating> {
+    let single_outcome = outcomes.iter().all(|(_, outcome)| *outcome == outcomes[0].1);
+    outcomes
+        .iter()
+        .zip(ratings)
+        .enumerate()
+        .map(|(index, ((_, outcome), before))| {
+            let opponents: Vec<Rating> = outcomes
+                .iter()
+                .zip(ratings)
+                .enumerate()
+                .filter(|(other, ((_, other_outcome), _))| {
+                    if single_outcome { *other != index } else { other_outcome != outcome }
+                })
+                .map(|(_, (_, rating))| *rating)
+                .collect();
+            algorithm.calculate_new_rating(*before, Rating::composite(&opponents), *outcome)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mmr::{EloAlgorithm, InMemoryAuditLog, Tier},
+        persistence::InMemoryAdapter,
+    };
+
+    async fn service_with_player() -> (RatingService, Arc<InMemoryAdapter>, Arc<InMemoryAuditLog>, Uuid) {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let audit_log = Arc::new(InMemoryAuditLog::new());
+        let player_id = Uuid::new_v4();
+        persistence
+            .save_player_rating(player_id, Rating::new(1400.0, 90.0, 0.06))
+            .await
+            .unwrap();
+
+        let service = RatingService::new(persistence.clone())
+            .with_audit_log(audit_log.clone())
+            .with_bounds(RatingBounds::new(RatingValue(0.0), RatingValue(3000.0)));
+        (service, persistence, audit_log, player_id)
+    }
+
+    #[tokio::test]
+    async fn test_adjustment_persists_and_is_audited() {
+        let (service, persistence, audit_log, player_id) = service_with_player().await;
+
+        service
+            .adjust_rating(player_id, RatingAdjustment::new(RatingValue(1650.0)), "MMR lost to server crash".to_string())
+            .await
+            .unwrap();
+
+        let saved = persistence.load_player_rating(player_id).await.unwrap().unwrap();
+        assert_eq!(saved.rating, 1650.0);
+        assert_eq!(saved.deviation, 90.0);
+
+        let entries = audit_log.entries_for(player_id).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].reason,
+            RatingChangeReason::ManualAdjustment { note: "MMR lost to server crash".to_string() }
+        );
+        assert_eq!(entries[0].before.rating, 1400.0);
+        assert_eq!(entries[0].after.rating, 1650.0);
+    }
+
+    #[tokio::test]
+    async fn test_adjustment_can_reset_uncertainty() {
+        let (service, persistence, _, player_id) = service_with_player().await;
+
+        service
+            .adjust_rating(
+                player_id,
+                RatingAdjustment::new(RatingValue(1500.0)).with_deviation(Deviation(350.0)).with_volatility(0.09),
+                "Account recovery".to_string(),
+            )
+            .await
+            .unwrap();
+
+        let adjusted = persistence.load_player_rating(player_id).await.unwrap().unwrap();
+        assert_eq!(adjusted.deviation, 350.0);
+        assert_eq!(adjusted.volatility, 0.09);
+    }
+
+    #[tokio::test]
+    async fn test_one_v_one_report_moves_ratings_apart() {
+        let (service, persistence, audit_log, winner) = service_with_player().await;
+        let loser = Uuid::new_v4();
+        persistence.save_player_rating(loser, Rating::new(1400.0, 90.0, 0.06)).await.unwrap();
+
+        let match_id = Uuid::new_v4();
+        let changes = service
+            .report_match_result(match_id, vec![(winner, Outcome::Win), (loser, Outcome::Loss)])
+            .await
+            .unwrap();
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].player_id, winner);
+        assert!(changes[0].delta() > RatingDelta(0.0));
+        assert!(changes[1].delta() < RatingDelta(0.0));
+        // Equal ratings and deviations move by the same amount
+        assert!((changes[0].delta() + changes[1].delta()).abs() < RatingDelta(1e-9));
+
+        let saved = persistence.load_player_rating(winner).await.unwrap().unwrap();
+        assert_eq!(saved.rating, changes[0].after.rating);
+        let entries = audit_log.entries_for(loser).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].reason, RatingChangeReason::Match { match_id });
+    }
+
+    #[tokio::test]
+    async fn test_shadow_ratings_updated_alongside_live() {
+        let (service, persistence, audit_log, winner) = service_with_player().await;
+        let service = service.with_shadow_algorithm(Arc::new(EloAlgorithm::default()));
+        let loser = Uuid::new_v4();
+        persistence.save_player_rating(loser, Rating::new(1400.0, 90.0, 0.06)).await.unwrap();
+        let start = Rating::new(1400.0, 90.0, 0.06);
+
+        let report = vec![(winner, Outcome::Win), (loser, Outcome::Loss)];
+        let changes = service.report_match_result(Uuid::new_v4(), report.clone()).await.unwrap();
+
+        // Live ratings are what the live algorithm alone would produce
+        let live = Glicko2Algorithm::default().calculate_new_rating(start, start, Outcome::Win);
+        assert_eq!(changes[0].after.rating, live.rating);
+        assert_eq!(persistence.load_player_rating(winner).await.unwrap().unwrap().rating, live.rating);
+        assert_eq!(audit_log.entries_for(winner).await.unwrap().len(), 1);
+
+        // The first shadow rating starts from the live rating
+        let elo = EloAlgorithm::default();
+        let shadow_winner = elo.calculate_new_rating(start, start, Outcome::Win);
+        let shadow_loser = elo.calculate_new_rating(start, start, Outcome::Loss);
+        assert_eq!(persistence.load_shadow_rating(winner).await.unwrap().unwrap().rating, shadow_winner.rating);
+        assert_eq!(persistence.load_shadow_rating(loser).await.unwrap().unwrap().rating, shadow_loser.rating);
+        assert_ne!(shadow_winner.rating, live.rating);
+
+        // Later matches build on the stored shadow ratings
+        service.report_match_result(Uuid::new_v4(), report).await.unwrap();
+        let expected = elo.calculate_new_rating(shadow_winner, shadow_loser, Outcome::Win);
+        assert_eq!(persistence.load_shadow_rating(winner).await.unwrap().unwrap().rating, expected.rating);
+    }
+
+    #[tokio::test]
+    async fn test_team_report_signs_every_delta() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let service = RatingService::new(persistence.clone());
+        let players: Vec<Uuid> = (0..6).map(|_| Uuid::new_v4()).collect();
+        for (i, player_id) in players.iter().enumerate() {
+            persistence
+                .save_player_rating(*player_id, Rating::new(1400.0 + 40.0 * i as f64, 120.0, 0.06))
+                .await
+                .unwrap();
+        }
+
+        let outcomes: Vec<(Uuid, Outcome)> = players
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, if i % 2 == 0 { Outcome::Win } else { Outcome::Loss }))
+            .collect();
+        let changes = service.report_match_result(Uuid::new_v4(), outcomes).await.unwrap();
+
+        assert_eq!(changes.len(), 6);
+        for change in &changes {
+            match change.outcome {
+                Outcome::Win => assert!(change.delta() > RatingDelta(0.0)),
+                _ => assert!(change.delta() < RatingDelta(0.0)),
+            }
+            let saved = persistence.load_player_rating(change.player_id).await.unwrap().unwrap();
+            assert_eq!(saved.rating, change.after.rating);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_protected_loss_keeps_rating_but_narrows_deviation() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let service = RatingService::new(persistence.clone());
+        let (winner, loser) = (Uuid::new_v4(), Uuid::new_v4());
+        persistence.save_player_rating(winner, Rating::new(1500.0, 200.0, 0.06)).await.unwrap();
+        persistence.save_player_rating(loser, Rating::new(1500.0, 350.0, 0.06)).await.unwrap();
+
+        let protections = HashMap::from([(loser, RatingProtection::no_loss())]);
+        let changes = service
+            .report_match_result_with_protection(Uuid::new_v4(), vec![(winner, Outcome::Win), (loser, Outcome::Loss)], &protections)
+            .await
+            .unwrap();
+
+        let loss = changes.iter().find(|c| c.player_id == loser).unwrap();
+        assert_eq!(loss.delta(), RatingDelta(0.0));
+        assert!(loss.after.deviation < 350.0);
+        assert_eq!(persistence.load_player_rating(loser).await.unwrap().unwrap().rating, 1500.0);
+
+        // The unprotected winner gains as usual
+        let win = changes.iter().find(|c| c.player_id == winner).unwrap();
+        assert!(win.delta() > RatingDelta(0.0));
+    }
+
+    #[tokio::test]
+    async fn test_tier_ladder_shields_first_demoting_loss() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let ladder = Arc::new(TierLadder::new(vec![Tier::new("Silver", 1000.0), Tier::new("Gold", 1500.0)]));
+        let service = RatingService::new(persistence.clone())
+            .with_algorithm(Arc::new(EloAlgorithm::default()))
+            .with_tier_ladder(ladder.clone());
+        let (player, opponent) = (Uuid::new_v4(), Uuid::new_v4());
+        persistence.save_player_rating(player, Rating::new(1505.0, 100.0, 0.06)).await.unwrap();
+        persistence.save_player_rating(opponent, Rating::new(1505.0, 100.0, 0.06)).await.unwrap();
+
+        let report = || service.report_match_result(Uuid::new_v4(), vec![(opponent, Outcome::Win), (player, Outcome::Loss)]);
+        let first = report().await.unwrap();
+        let shielded = first.iter().find(|c| c.player_id == player).unwrap();
+        assert_eq!(shielded.after.rating, 1500.0);
+        assert_eq!(ladder.shields_used(player), 1);
+
+        let second = report().await.unwrap();
+        let demoted = second.iter().find(|c| c.player_id == player).unwrap();
+        assert!(demoted.after.rating < 1500.0);
+        assert_eq!(ladder.tier_for(demoted.after.rating).unwrap().name, "Silver");
+        assert_eq!(persistence.load_player_rating(player).await.unwrap().unwrap().rating, demoted.after.rating);
+    }
+
+    #[tokio::test]
+    async fn test_protected_gain_follows_configuration() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let service = RatingService::new(persistence.clone());
+        let players: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        for player_id in &players {
+            persistence.save_player_rating(*player_id, Rating::new(1500.0, 200.0, 0.06)).await.unwrap();
+        }
+
+        // No-loss protection lets a win through; no-gain protection freezes it
+        let protections = HashMap::from([
+            (players[0], RatingProtection::no_loss()),
+            (players[2], RatingProtection { no_loss: false, no_gain: true }),
+        ]);
+        let mut deltas = HashMap::new();
+        for pair in players.chunks(2) {
+            let changes = service
+                .report_match_result_with_protection(Uuid::new_v4(), vec![(pair[0], Outcome::Win), (pair[1], Outcome::Loss)], &protections)
+                .await
+                .unwrap();
+            deltas.extend(changes.iter().map(|c| (c.player_id, c.delta())));
+        }
+
+        assert!(deltas[&players[0]] > RatingDelta(0.0));
+        assert_eq!(deltas[&players[2]], RatingDelta(0.0));
+        assert_eq!(deltas[&players[0]], -deltas[&players[1]]);
+    }
+
+    #[tokio::test]
+    async fn test_draw_and_invalid_reports() {
+        let (service, persistence, _, player_id) = service_with_player().await;
+        let other = Uuid::new_v4();
+        persistence.save_player_rating(other, Rating::new(1400.0, 90.0, 0.06)).await.unwrap();
+
+        // A draw between equal ratings keeps both in place
+        let changes = service
+            .report_match_result(Uuid::new_v4(), vec![(player_id, Outcome::Draw), (other, Outcome::Draw)])
+            .await
+            .unwrap();
+        assert!(changes.iter().all(|c| c.delta().abs() < RatingDelta(1e-9)));
+
+        let unknown = Uuid::new_v4();
+        let err = service
+            .report_match_result(Uuid::new_v4(), vec![(player_id, Outcome::Win), (unknown, Outcome::Loss)])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MatchForgeError::PlayerNotFound(id) if id == unknown));
+        assert_eq!(persistence.load_player_rating(player_id).await.unwrap().unwrap().rating, changes[0].after.rating);
+
+        assert!(service
+            .report_match_result(Uuid::new_v4(), vec![(player_id, Outcome::Win), (player_id, Outcome::Loss)])
+            .await
+            .is_err());
+        assert!(service.report_match_result(Uuid::new_v4(), vec![(player_id, Outcome::Win)]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_adjustment_outside_bounds_rejected() {
+        let (service, persistence, audit_log, player_id) = service_with_player().await;
+
+        let err = service
+            .adjust_rating(player_id, RatingAdjustment::new(RatingValue(4200.0)), "typo".to_string())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MatchForgeError::ConstraintsNotSatisfied(_)));
+
+        assert_eq!(persistence.load_player_rating(player_id).await.unwrap().unwrap().rating, 1400.0);
+        assert!(audit_log.entries_for(player_id).await.unwrap().is_empty());
+        assert!(service
+            .adjust_rating(Uuid::new_v4(), RatingAdjustment::new(RatingValue(1500.0)), "unknown".to_string())
+            .await
+            .is_err());
+    }
+}
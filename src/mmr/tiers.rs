@@ -0,0 +1,155 @@
+//! Named rating tiers with protection against demoting on a single loss
+
+use super::rating::Rating;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Mutex};
+use uuid::Uuid;
+
+/// A tier covering ratings from `min_rating` up to the next tier's minimum
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tier {
+    pub name: String,
+    pub min_rating: f64,
+}
+
+impl Tier {
+    pub fn new(name: impl Into<String>, min_rating: f64) -> Self {
+        Self {
+            name: name.into(),
+            min_rating,
+        }
+    }
+}
+
+/// Rating tiers, e.g. Silver from 1000 and Gold from 1500, with a demotion shield
+///
+/// A rating change that would drop a player into a lower tier is shielded
+/// while the player has shields left: the rating stops at the floor of their
+/// current tier instead (deviation and volatility still update). Each
+/// shielded loss uses one shield, and any rating gain restores them all, so
+/// with the default single shield the first loss across a boundary holds and
+/// the next consecutive one demotes. Shield counts live in memory only.
+pub struct TierLadder {
+    tiers: Vec<Tier>,
+    shield_losses: u32,
+    shields_used: Mutex<HashMap<Uuid, u32>>,
+}
+
+impl TierLadder {
+    /// Ladder over `tiers` in any order; ratings below the lowest tier have no tier
+    pub fn new(mut tiers: Vec<Tier>) -> Self {
+        tiers.sort_by(|a, b| a.min_rating.total_cmp(&b.min_rating));
+        Self {
+            tiers,
+            shield_losses: 1,
+            shields_used: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Shield this many consecutive demoting losses, 0 to demote right away
+    pub fn with_demotion_shield(mut self, losses: u32) -> Self {
+        self.shield_losses = losses;
+        self
+    }
+
+    /// Tiers from lowest to highest
+    pub fn tiers(&self) -> &[Tier] {
+        &self.tiers
+    }
+
+    /// The tier a rating falls in
+    pub fn tier_for(&self, rating: f64) -> Option<&Tier> {
+        self.tier_index(rating).map(|index| &self.tiers[index])
+    }
+
+    /// Demotions shielded for a player since they last gained rating
+    pub fn shields_used(&self, player_id: Uuid) -> u32 {
+        self.shields_used.lock().unwrap().get(&player_id).copied().unwrap_or(0)
+    }
+
+    /// `after`, held at the floor of the player's tier if the demotion is shielded
+    pub fn apply(&self, player_id: Uuid, before: Rating, after: Rating) -> Rating {
+        let mut shields_used = self.shields_used.lock().unwrap();
+        if after.rating > before.rating {
+            shields_used.remove(&player_id);
+            return after;
+        }
+
+        let Some(current) = self.tier_index(before.rating) else {
+            return after;
+        };
+        if self.tier_index(after.rating) >= Some(current) {
+            return after;
+        }
+
+        let used = shields_used.entry(player_id).or_insert(0);
+        if *used < self.shield_losses {
+            *used += 1;
+            Rating { rating: self.tiers[current].min_rating, ..after }
+        } else {
+            shields_used.remove(&player_id);
+            after
+        }
+    }
+
+    fn tier_index(&self, rating: f64) -> Option<usize> {
+        self.tiers.iter().rposition(|tier| rating >= tier.min_rating)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ladder() -> TierLadder {
+        TierLadder::new(vec![Tier::new("Gold", 1500.0), Tier::new("Silver", 1000.0), Tier::new("Platinum", 2000.0)])
+    }
+
+    fn rating(value: f64) -> Rating {
+        Rating::new(value, 100.0, 0.06)
+    }
+
+    #[test]
+    fn test_tiers_sorted_and_looked_up() {
+        let ladder = ladder();
+        let names: Vec<&str> = ladder.tiers().iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Silver", "Gold", "Platinum"]);
+        assert_eq!(ladder.tier_for(1500.0).unwrap().name, "Gold");
+        assert_eq!(ladder.tier_for(1499.9).unwrap().name, "Silver");
+        assert!(ladder.tier_for(900.0).is_none());
+    }
+
+    #[test]
+    fn test_first_demoting_loss_shielded_next_demotes() {
+        let ladder = ladder();
+        let player_id = Uuid::new_v4();
+
+        let shielded = ladder.apply(player_id, rating(1510.0), Rating::new(1480.0, 95.0, 0.06));
+        assert_eq!(shielded.rating, 1500.0);
+        assert_eq!(shielded.deviation, 95.0);
+        assert_eq!(ladder.shields_used(player_id), 1);
+
+        let demoted = ladder.apply(player_id, shielded, rating(1470.0));
+        assert_eq!(demoted.rating, 1470.0);
+        assert_eq!(ladder.tier_for(demoted.rating).unwrap().name, "Silver");
+        assert_eq!(ladder.shields_used(player_id), 0);
+
+        // Losses within a tier are untouched
+        assert_eq!(ladder.apply(player_id, rating(1300.0), rating(1280.0)).rating, 1280.0);
+    }
+
+    #[test]
+    fn test_gain_restores_shield() {
+        let ladder = ladder();
+        let player_id = Uuid::new_v4();
+
+        let shielded = ladder.apply(player_id, rating(1505.0), rating(1490.0));
+        assert_eq!(shielded.rating, 1500.0);
+        let won = ladder.apply(player_id, shielded, rating(1512.0));
+        assert_eq!(ladder.shields_used(player_id), 0);
+        assert_eq!(ladder.apply(player_id, won, rating(1495.0)).rating, 1500.0);
+
+        let unshielded = TierLadder::new(vec![Tier::new("Gold", 1500.0)]).with_demotion_shield(0);
+        assert_eq!(unshielded.apply(player_id, rating(1505.0), rating(1490.0)).rating, 1490.0);
+    }
+}
@@ -0,0 +1,245 @@
+//! Typed rating quantities
+//!
+//! Ratings, rating differences and deviations are all plain numbers on the
+//! same scale, so it is easy to pass one where another is expected. These
+//! wrappers only combine in meaningful ways: a rating plus a delta is a
+//! rating, the difference of two ratings is a delta, and a deviation never
+//! mixes with either.
+//!
+//! ```
+//! use matchforge::mmr::{Deviation, RatingDelta, RatingValue};
+//!
+//! let before = RatingValue(1500.0);
+//! let after = before + RatingDelta(16.0);
+//! assert_eq!(after - before, RatingDelta(16.0));
+//! assert_eq!(Deviation(80.0) * 0.5, Deviation(40.0));
+//! ```
+//!
+//! ```compile_fail
+//! use matchforge::mmr::{Deviation, RatingValue};
+//!
+//! // A deviation is not a rating change
+//! let _ = RatingValue(1500.0) + Deviation(80.0);
+//! ```
+//!
+//! ```compile_fail
+//! use matchforge::mmr::{RatingDelta, RatingValue};
+//!
+//! fn set_rating(_rating: RatingValue) {}
+//!
+//! // A delta is not a rating
+//! set_rating(RatingDelta(25.0));
+//! ```
+//!
+//! [`Rating`](super::Rating) keeps plain `f64` fields so stored data is
+//! unchanged; use [`Rating::rating_value`](super::Rating::rating_value) and
+//! [`Rating::deviation_value`](super::Rating::deviation_value) to get typed values.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+/// An absolute skill rating, e.g. 1500
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RatingValue(pub f64);
+
+/// A change in or difference between ratings
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RatingDelta(pub f64);
+
+/// Uncertainty of a rating, on the rating scale
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Deviation(pub f64);
+
+impl RatingValue {
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
+    /// Keep the rating within `[min, max]`
+    pub fn clamp(self, min: RatingValue, max: RatingValue) -> Self {
+        Self(self.0.clamp(min.0, max.0))
+    }
+}
+
+impl RatingDelta {
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
+    /// Size of the change regardless of direction
+    pub fn abs(self) -> Self {
+        Self(self.0.abs())
+    }
+}
+
+impl Deviation {
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
+    /// Deviation of the sum of two independent estimates (root sum of squares)
+    pub fn combine(self, other: Deviation) -> Self {
+        Self(self.0.hypot(other.0))
+    }
+}
+
+impl Add<RatingDelta> for RatingValue {
+    type Output = RatingValue;
+
+    fn add(self, delta: RatingDelta) -> RatingValue {
+        RatingValue(self.0 + delta.0)
+    }
+}
+
+impl AddAssign<RatingDelta> for RatingValue {
+    fn add_assign(&mut self, delta: RatingDelta) {
+        self.0 += delta.0;
+    }
+}
+
+impl Sub<RatingDelta> for RatingValue {
+    type Output = RatingValue;
+
+    fn sub(self, delta: RatingDelta) -> RatingValue {
+        RatingValue(self.0 - delta.0)
+    }
+}
+
+impl SubAssign<RatingDelta> for RatingValue {
+    fn sub_assign(&mut self, delta: RatingDelta) {
+        self.0 -= delta.0;
+    }
+}
+
+impl Sub for RatingValue {
+    type Output = RatingDelta;
+
+    fn sub(self, other: RatingValue) -> RatingDelta {
+        RatingDelta(self.0 - other.0)
+    }
+}
+
+impl Add for RatingDelta {
+    type Output = RatingDelta;
+
+    fn add(self, other: RatingDelta) -> RatingDelta {
+        RatingDelta(self.0 + other.0)
+    }
+}
+
+impl Sub for RatingDelta {
+    type Output = RatingDelta;
+
+    fn sub(self, other: RatingDelta) -> RatingDelta {
+        RatingDelta(self.0 - other.0)
+    }
+}
+
+impl Neg for RatingDelta {
+    type Output = RatingDelta;
+
+    fn neg(self) -> RatingDelta {
+        RatingDelta(-self.0)
+    }
+}
+
+impl Mul<f64> for RatingDelta {
+    type Output = RatingDelta;
+
+    fn mul(self, factor: f64) -> RatingDelta {
+        RatingDelta(self.0 * factor)
+    }
+}
+
+impl Mul<f64> for Deviation {
+    type Output = Deviation;
+
+    fn mul(self, factor: f64) -> Deviation {
+        Deviation(self.0 * factor)
+    }
+}
+
+impl From<RatingValue> for f64 {
+    fn from(rating: RatingValue) -> f64 {
+        rating.0
+    }
+}
+
+impl From<RatingDelta> for f64 {
+    fn from(delta: RatingDelta) -> f64 {
+        delta.0
+    }
+}
+
+impl From<Deviation> for f64 {
+    fn from(deviation: Deviation) -> f64 {
+        deviation.0
+    }
+}
+
+impl fmt::Display for RatingValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for RatingDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:+}", self.0)
+    }
+}
+
+impl fmt::Display for Deviation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mmr::Rating;
+
+    #[test]
+    fn test_rating_arithmetic_keeps_units() {
+        let before = RatingValue(1500.0);
+        let after = before + RatingDelta(24.5);
+        assert_eq!(after, RatingValue(1524.5));
+        assert_eq!(after - before, RatingDelta(24.5));
+        assert_eq!(after - RatingDelta(24.5), before);
+        assert_eq!(-(after - before) * 2.0, RatingDelta(-49.0));
+        assert_eq!((before - after).abs(), RatingDelta(24.5));
+
+        let mut rating = before;
+        rating += RatingDelta(10.0);
+        rating -= RatingDelta(4.0);
+        assert_eq!(rating, RatingValue(1506.0));
+        assert_eq!(RatingValue(3100.0).clamp(RatingValue(0.0), RatingValue(3000.0)), RatingValue(3000.0));
+
+        assert_eq!(Deviation(30.0).combine(Deviation(40.0)), Deviation(50.0));
+        assert_eq!(RatingDelta(-12.0).to_string(), "-12");
+        assert_eq!(RatingDelta(12.0).to_string(), "+12");
+    }
+
+    #[test]
+    fn test_typed_values_round_trip_through_rating() {
+        let rating = Rating::new(1620.0, 85.0, 0.06);
+        assert_eq!(rating.rating_value(), RatingValue(1620.0));
+        assert_eq!(rating.deviation_value(), Deviation(85.0));
+        assert_eq!(f64::from(rating.rating_value()), rating.rating);
+
+        let typed = Rating::from_typed(RatingValue(1620.0), Deviation(85.0), 0.06);
+        assert_eq!(typed.rating, rating.rating);
+        assert_eq!(typed.deviation, rating.deviation);
+
+        // Transparent serde keeps the plain number representation
+        assert_eq!(serde_json::to_string(&RatingValue(1620.5)).unwrap(), "1620.5");
+        assert_eq!(serde_json::from_str::<Deviation>("85.0").unwrap(), Deviation(85.0));
+    }
+}
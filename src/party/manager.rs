@@ -1,5 +1,6 @@
 use super::{mmr_strategy::PartyMmrStrategy, party::Party};
 use crate::{error::*, mmr::Rating, persistence::PersistenceAdapter};
+use chrono::{DateTime, Utc};
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::RwLock;
 use uuid::Uuid;
@@ -7,6 +8,8 @@ use uuid::Uuid;
 pub struct PartyManager {
     parties: Arc<RwLock<HashMap<Uuid, Party>>>,
     player_to_party: Arc<RwLock<HashMap<Uuid, Uuid>>>,
+    /// Last heartbeat of each party member
+    last_seen: Arc<RwLock<HashMap<Uuid, DateTime<Utc>>>>,
     persistence: Arc<dyn PersistenceAdapter>,
     mmr_strategy: Arc<dyn PartyMmrStrategy>,
 }
@@ -19,6 +22,7 @@ impl PartyManager {
         Self {
             parties: Arc::new(RwLock::new(HashMap::new())),
             player_to_party: Arc::new(RwLock::new(HashMap::new())),
+            last_seen: Arc::new(RwLock::new(HashMap::new())),
             persistence,
             mmr_strategy,
         }
@@ -33,6 +37,7 @@ impl PartyManager {
 
         parties.insert(party.id, party.clone());
         player_map.insert(leader_id, party.id);
+        self.last_seen.write().await.insert(leader_id, Utc::now());
 
         self.persistence.save_party(&party).await?;
 
@@ -60,6 +65,7 @@ impl PartyManager {
 
         party.member_ids.push(player_id);
         player_map.insert(player_id, party_id);
+        self.last_seen.write().await.insert(player_id, Utc::now());
 
         self.persistence.save_party(party).await?;
 
@@ -83,9 +89,13 @@ impl PartyManager {
 
         party.member_ids.retain(|id| *id != player_id);
         player_map.remove(&player_id);
+        self.last_seen.write().await.remove(&player_id);
 
         // Disband if empty or leader left
         if party.member_ids.is_empty() || player_id == party.leader_id {
+            for member_id in &party.member_ids {
+                player_map.remove(member_id);
+            }
             parties.remove(&party_id);
             self.persistence.delete_party(party_id).await?;
         } else {
@@ -95,6 +105,57 @@ impl PartyManager {
         Ok(())
     }
 
+    /// Record a heartbeat for a party member
+    pub async fn touch_member(&self, party_id: Uuid, player_id: Uuid) -> Result<()> {
+        let parties = self.parties.read().await;
+        let party = parties
+            .get(&party_id)
+            .ok_or(MatchForgeError::PartyNotFound(party_id))?;
+
+        if !party.has_member(player_id) {
+            return Err(MatchForgeError::InvalidPartyOperation(
+                "Player not in party".to_string(),
+            ));
+        }
+
+        self.last_seen.write().await.insert(player_id, Utc::now());
+
+        Ok(())
+    }
+
+    /// Disband parties whose members have all been idle for longer than `max_idle`
+    ///
+    /// Returns the IDs of the disbanded parties.
+    pub async fn reap_stale_parties(&self, max_idle: chrono::Duration) -> Result<Vec<Uuid>> {
+        let mut parties = self.parties.write().await;
+        let mut player_map = self.player_to_party.write().await;
+        let mut last_seen = self.last_seen.write().await;
+
+        let cutoff = Utc::now() - max_idle;
+        let stale: Vec<Uuid> = parties
+            .values()
+            .filter(|party| {
+                party
+                    .member_ids
+                    .iter()
+                    .all(|member_id| !matches!(last_seen.get(member_id), Some(seen) if *seen >= cutoff))
+            })
+            .map(|party| party.id)
+            .collect();
+
+        for party_id in &stale {
+            if let Some(party) = parties.remove(party_id) {
+                for member_id in &party.member_ids {
+                    player_map.remove(member_id);
+                    last_seen.remove(member_id);
+                }
+            }
+            self.persistence.delete_party(*party_id).await?;
+        }
+
+        Ok(stale)
+    }
+
     /// Calculate party MMR
     pub async fn calculate_party_rating(&self, party_id: Uuid) -> Result<Rating> {
         let parties = self.parties.read().await;
@@ -123,3 +184,64 @@ impl PartyManager {
             .and_then(|party_id| parties.get(party_id).cloned())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{party::AverageStrategy, persistence::InMemoryAdapter};
+
+    fn party_manager(persistence: Arc<InMemoryAdapter>) -> PartyManager {
+        PartyManager::new(persistence, Arc::new(AverageStrategy))
+    }
+
+    async fn mark_idle(manager: &PartyManager, player_id: Uuid, idle_for: chrono::Duration) {
+        manager
+            .last_seen
+            .write()
+            .await
+            .insert(player_id, Utc::now() - idle_for);
+    }
+
+    #[tokio::test]
+    async fn test_party_with_active_member_survives_reaping() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let manager = party_manager(persistence.clone());
+
+        let leader = Uuid::new_v4();
+        let member = Uuid::new_v4();
+        let party = manager.create_party(leader, 4).await.unwrap();
+        manager.add_member(party.id, member).await.unwrap();
+
+        mark_idle(&manager, leader, chrono::Duration::minutes(10)).await;
+        manager.touch_member(party.id, member).await.unwrap();
+
+        let reaped = manager.reap_stale_parties(chrono::Duration::minutes(5)).await.unwrap();
+        assert!(reaped.is_empty());
+        assert!(manager.get_player_party(leader).await.is_some());
+        assert!(persistence.load_party(party.id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_fully_idle_party_is_disbanded() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let manager = party_manager(persistence.clone());
+
+        let leader = Uuid::new_v4();
+        let member = Uuid::new_v4();
+        let party = manager.create_party(leader, 4).await.unwrap();
+        manager.add_member(party.id, member).await.unwrap();
+
+        mark_idle(&manager, leader, chrono::Duration::minutes(10)).await;
+        mark_idle(&manager, member, chrono::Duration::minutes(6)).await;
+
+        let reaped = manager.reap_stale_parties(chrono::Duration::minutes(5)).await.unwrap();
+        assert_eq!(reaped, vec![party.id]);
+        assert!(manager.get_player_party(leader).await.is_none());
+        assert!(manager.get_player_party(member).await.is_none());
+        assert!(persistence.load_party(party.id).await.unwrap().is_none());
+        assert!(matches!(
+            manager.touch_member(party.id, leader).await,
+            Err(MatchForgeError::PartyNotFound(_))
+        ));
+    }
+}
@@ -42,6 +42,29 @@ impl PartyMmrStrategy for MaxStrategy {
     }
 }
 
+/// Blend of [`MaxStrategy`] and [`AverageStrategy`]: `max_weight * max + (1 - max_weight) * average`
+///
+/// A middle ground for parties with one strong and several weak players;
+/// 0.0 is the average and 1.0 the max (the weight is clamped to that range).
+pub struct WeightedMaxStrategy {
+    pub max_weight: f64,
+}
+
+impl PartyMmrStrategy for WeightedMaxStrategy {
+    fn calculate_party_rating(&self, ratings: &[(Uuid, Rating)]) -> Rating {
+        let weight = self.max_weight.clamp(0.0, 1.0);
+        let max = MaxStrategy.calculate_party_rating(ratings);
+        let average = AverageStrategy.calculate_party_rating(ratings);
+        let blend = |max: f64, average: f64| weight * max + (1.0 - weight) * average;
+
+        Rating {
+            rating: blend(max.rating, average.rating),
+            deviation: blend(max.deviation, average.deviation),
+            volatility: blend(max.volatility, average.volatility),
+        }
+    }
+}
+
 /// Weighted average with penalty for skill gaps
 pub struct WeightedWithPenaltyStrategy {
     pub gap_penalty: f64,
@@ -68,3 +91,101 @@ impl PartyMmrStrategy for WeightedWithPenaltyStrategy {
         }
     }
 }
+
+/// Average MMR plus penalties for party size and rating dispersion
+///
+/// Adds `size_penalty` per member beyond the first and `dispersion_penalty`
+/// times the (population) standard deviation of member ratings, so a
+/// cohesive party queues closer to its average than a lopsided one of the
+/// same size.
+pub struct DispersionPenaltyStrategy {
+    pub size_penalty: f64,
+    pub dispersion_penalty: f64,
+}
+
+impl PartyMmrStrategy for DispersionPenaltyStrategy {
+    fn calculate_party_rating(&self, ratings: &[(Uuid, Rating)]) -> Rating {
+        if ratings.is_empty() {
+            return Rating::default();
+        }
+
+        let average = AverageStrategy.calculate_party_rating(ratings);
+        let variance = ratings
+            .iter()
+            .map(|(_, r)| (r.rating - average.rating).powi(2))
+            .sum::<f64>()
+            / ratings.len() as f64;
+        let size_term = self.size_penalty * (ratings.len() - 1) as f64;
+
+        Rating {
+            rating: average.rating + size_term + self.dispersion_penalty * variance.sqrt(),
+            ..average
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lopsided_party() -> Vec<(Uuid, Rating)> {
+        [2400.0, 1400.0, 1300.0, 1300.0]
+            .iter()
+            .map(|rating| (Uuid::new_v4(), Rating::new(*rating, 80.0, 0.05)))
+            .collect()
+    }
+
+    #[test]
+    fn test_weighted_max_reduces_to_average_and_max() {
+        let party = lopsided_party();
+        let rate = |max_weight| WeightedMaxStrategy { max_weight }.calculate_party_rating(&party);
+
+        let average = AverageStrategy.calculate_party_rating(&party);
+        assert_eq!(rate(0.0).rating, average.rating);
+        assert_eq!(rate(0.0).deviation, average.deviation);
+
+        let max = MaxStrategy.calculate_party_rating(&party);
+        assert_eq!(rate(1.0).rating, max.rating);
+        assert_eq!(rate(1.0).deviation, max.deviation);
+
+        // Out-of-range weights are clamped
+        assert_eq!(rate(-0.5).rating, average.rating);
+        assert_eq!(rate(2.0).rating, max.rating);
+    }
+
+    #[test]
+    fn test_weighted_max_lies_between_average_and_max() {
+        let party = lopsided_party();
+        let rating = WeightedMaxStrategy { max_weight: 0.25 }.calculate_party_rating(&party).rating;
+
+        // Average 1600, max 2400
+        assert!((rating - 1800.0).abs() < 1e-9);
+        assert!(WeightedMaxStrategy { max_weight: 0.5 }.calculate_party_rating(&[]).rating.is_finite());
+    }
+
+    fn party(ratings: &[f64]) -> Vec<(Uuid, Rating)> {
+        ratings.iter().map(|rating| (Uuid::new_v4(), Rating::new(*rating, 80.0, 0.05))).collect()
+    }
+
+    #[test]
+    fn test_dispersion_penalty_favors_cohesive_parties() {
+        let strategy = DispersionPenaltyStrategy { size_penalty: 10.0, dispersion_penalty: 0.5 };
+        // Both average 1500
+        let tight = strategy.calculate_party_rating(&party(&[1490.0, 1510.0, 1500.0]));
+        let spread = strategy.calculate_party_rating(&party(&[1100.0, 1900.0, 1500.0]));
+
+        assert!(spread.rating > tight.rating);
+        // Size term 2 * 10, standard deviations 8.16 and 326.6
+        assert!((tight.rating - (1520.0 + 0.5 * (200.0f64 / 3.0).sqrt())).abs() < 1e-9);
+        assert!((spread.rating - (1520.0 + 0.5 * (320_000.0f64 / 3.0).sqrt())).abs() < 1e-9);
+        assert_eq!(spread.deviation, 80.0);
+    }
+
+    #[test]
+    fn test_dispersion_penalty_solo_is_own_rating() {
+        let strategy = DispersionPenaltyStrategy { size_penalty: 25.0, dispersion_penalty: 1.0 };
+        assert_eq!(strategy.calculate_party_rating(&party(&[1725.0])).rating, 1725.0);
+        assert_eq!(strategy.calculate_party_rating(&party(&[1500.0, 1500.0])).rating, 1525.0);
+        assert!(strategy.calculate_party_rating(&[]).rating.is_finite());
+    }
+}
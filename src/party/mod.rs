@@ -1,7 +1,9 @@
+#[cfg(feature = "runtime")]
 pub mod manager;
 pub mod mmr_strategy;
 pub mod party;
 
+#[cfg(feature = "runtime")]
 pub use manager::PartyManager;
-pub use mmr_strategy::{AverageStrategy, MaxStrategy, PartyMmrStrategy, WeightedWithPenaltyStrategy};
+pub use mmr_strategy::{AverageStrategy, DispersionPenaltyStrategy, MaxStrategy, PartyMmrStrategy, WeightedMaxStrategy, WeightedWithPenaltyStrategy};
 pub use party::Party;
@@ -0,0 +1,532 @@
+use super::traits::PersistenceAdapter;
+use crate::{
+    analytics::insights::InsightRecord,
+    clock::{Clock, SystemClock},
+    error::Result,
+    lobby::Lobby,
+    mmr::{Rating, RatingAuditEntry},
+    party::Party,
+    queue::{QueueEntry, TournamentBracket},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Cached rating with its insertion time and recency stamp
+struct CachedRating {
+    rating: Rating,
+    cached_at: DateTime<Utc>,
+    last_used: u64,
+}
+
+/// Cached ratings plus an index of them by recency, so the least recently
+/// used one can be found without scanning
+#[derive(Default)]
+struct RatingCache {
+    entries: HashMap<Uuid, CachedRating>,
+    by_recency: BTreeMap<u64, Uuid>,
+    /// Recency counter for LRU eviction
+    tick: u64,
+}
+
+impl RatingCache {
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// A cached rating no older than `ttl`, marked as most recently used
+    fn get(&mut self, player_id: Uuid, now: DateTime<Utc>, ttl: chrono::Duration) -> Option<Rating> {
+        let cached = self.entries.get(&player_id)?;
+        if now - cached.cached_at >= ttl {
+            self.remove(player_id);
+            return None;
+        }
+
+        let last_used = cached.last_used;
+        let tick = self.next_tick();
+        self.by_recency.remove(&last_used);
+        self.by_recency.insert(tick, player_id);
+        let cached = self.entries.get_mut(&player_id)?;
+        cached.last_used = tick;
+        Some(cached.rating)
+    }
+
+    /// Cache a rating, evicting the least recently used one if `capacity` is reached
+    fn insert(&mut self, player_id: Uuid, rating: Rating, now: DateTime<Utc>, capacity: usize) {
+        self.remove(player_id);
+        if self.entries.len() >= capacity {
+            if let Some((_, oldest)) = self.by_recency.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        let tick = self.next_tick();
+        self.by_recency.insert(tick, player_id);
+        self.entries.insert(player_id, CachedRating { rating, cached_at: now, last_used: tick });
+    }
+
+    fn remove(&mut self, player_id: Uuid) {
+        if let Some(cached) = self.entries.remove(&player_id) {
+            self.by_recency.remove(&cached.last_used);
+        }
+    }
+}
+
+/// Read-through cache for player ratings in front of another adapter
+///
+/// `load_player_rating` is served from an LRU cache with a TTL; saving a
+/// rating through this adapter invalidates the cached value. All other
+/// operations go straight to the inner adapter.
+pub struct CachingAdapter<A: PersistenceAdapter> {
+    inner: A,
+    capacity: usize,
+    ttl: chrono::Duration,
+    clock: Arc<dyn Clock>,
+    ratings: Arc<RwLock<RatingCache>>,
+    /// Bumped on every rating save so reads that raced a save don't cache stale values
+    write_epoch: AtomicU64,
+}
+
+impl<A: PersistenceAdapter> CachingAdapter<A> {
+    /// Wrap an adapter with a cache of 10,000 ratings kept for five minutes
+    pub fn new(inner: A) -> Self {
+        Self {
+            inner,
+            capacity: 10_000,
+            ttl: chrono::Duration::minutes(5),
+            clock: Arc::new(SystemClock),
+            ratings: Arc::new(RwLock::new(RatingCache::default())),
+            write_epoch: AtomicU64::new(0),
+        }
+    }
+
+    /// Maximum number of cached ratings before the least recently used is evicted
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// How long a cached rating is served before it is reloaded
+    pub fn with_ttl(mut self, ttl: chrono::Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Number of ratings currently cached
+    pub async fn cached_len(&self) -> usize {
+        self.ratings.read().await.len()
+    }
+
+    /// Drop a player's cached rating
+    pub async fn invalidate(&self, player_id: Uuid) {
+        self.ratings.write().await.remove(player_id);
+    }
+
+    async fn cache_rating(&self, player_id: Uuid, rating: Rating, epoch: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut ratings = self.ratings.write().await;
+        if self.write_epoch.load(Ordering::SeqCst) != epoch {
+            return;
+        }
+
+        ratings.insert(player_id, rating, self.clock.now(), self.capacity);
+    }
+}
+
+#[async_trait]
+impl<A: PersistenceAdapter> PersistenceAdapter for CachingAdapter<A> {
+    async fn save_player_rating(&self, player_id: Uuid, rating: Rating) -> Result<()> {
+        self.write_epoch.fetch_add(1, Ordering::SeqCst);
+        self.invalidate(player_id).await;
+        let result = self.inner.save_player_rating(player_id, rating).await;
+        // A read may have cached the old value while the save was in flight
+        self.invalidate(player_id).await;
+        result
+    }
+
+    async fn load_player_rating(&self, player_id: Uuid) -> Result<Option<Rating>> {
+        if let Some(rating) = self.ratings.write().await.get(player_id, self.clock.now(), self.ttl) {
+            return Ok(Some(rating));
+        }
+
+        let epoch = self.write_epoch.load(Ordering::SeqCst);
+        let rating = self.inner.load_player_rating(player_id).await?;
+        if let Some(rating) = rating {
+            self.cache_rating(player_id, rating, epoch).await;
+        }
+        Ok(rating)
+    }
+
+    async fn save_shadow_rating(&self, player_id: Uuid, rating: Rating) -> Result<()> {
+        self.inner.save_shadow_rating(player_id, rating).await
+    }
+
+    async fn load_shadow_rating(&self, player_id: Uuid) -> Result<Option<Rating>> {
+        self.inner.load_shadow_rating(player_id).await
+    }
+
+    async fn load_all_ratings(&self) -> Result<Vec<(Uuid, Rating)>> {
+        self.inner.load_all_ratings().await
+    }
+
+    async fn save_queue_entry(&self, entry: &QueueEntry) -> Result<()> {
+        self.inner.save_queue_entry(entry).await
+    }
+
+    async fn load_queue_entries(&self, queue_name: &str) -> Result<Vec<QueueEntry>> {
+        self.inner.load_queue_entries(queue_name).await
+    }
+
+    async fn delete_queue_entry(&self, player_id: Uuid) -> Result<()> {
+        self.inner.delete_queue_entry(player_id).await
+    }
+
+    async fn load_all_queue_entries(&self) -> Result<Vec<QueueEntry>> {
+        self.inner.load_all_queue_entries().await
+    }
+
+    async fn save_party(&self, party: &Party) -> Result<()> {
+        self.inner.save_party(party).await
+    }
+
+    async fn load_party(&self, party_id: Uuid) -> Result<Option<Party>> {
+        self.inner.load_party(party_id).await
+    }
+
+    async fn delete_party(&self, party_id: Uuid) -> Result<()> {
+        self.inner.delete_party(party_id).await
+    }
+
+    async fn load_all_parties(&self) -> Result<Vec<Party>> {
+        self.inner.load_all_parties().await
+    }
+
+    async fn save_lobby(&self, lobby: &Lobby) -> Result<()> {
+        self.inner.save_lobby(lobby).await
+    }
+
+    async fn load_lobby(&self, lobby_id: Uuid) -> Result<Option<Lobby>> {
+        self.inner.load_lobby(lobby_id).await
+    }
+
+    async fn delete_lobby(&self, lobby_id: Uuid) -> Result<()> {
+        self.inner.delete_lobby(lobby_id).await
+    }
+
+    async fn load_all_lobbies(&self) -> Result<Vec<Lobby>> {
+        self.inner.load_all_lobbies().await
+    }
+
+    async fn player_in_active_lobby(&self, player_id: Uuid) -> Result<bool> {
+        self.inner.player_in_active_lobby(player_id).await
+    }
+
+    async fn save_match_result(&self, lobby: &Lobby) -> Result<()> {
+        self.inner.save_match_result(lobby).await
+    }
+
+    async fn load_player_match_history(&self, player_id: Uuid, limit: usize) -> Result<Vec<Lobby>> {
+        self.inner.load_player_match_history(player_id, limit).await
+    }
+
+    async fn save_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid, rating: Rating) -> Result<()> {
+        self.inner.save_season_rating(queue_name, season_id, player_id, rating).await
+    }
+
+    async fn load_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid) -> Result<Option<Rating>> {
+        self.inner.load_season_rating(queue_name, season_id, player_id).await
+    }
+
+    async fn load_season_ratings(&self, queue_name: &str, season_id: &str) -> Result<Vec<(Uuid, Rating)>> {
+        self.inner.load_season_ratings(queue_name, season_id).await
+    }
+
+    async fn top_players(&self, queue_name: &str, season_id: &str, n: usize) -> Result<Vec<(Uuid, Rating)>> {
+        self.inner.top_players(queue_name, season_id, n).await
+    }
+
+    async fn save_insight(&self, record: &InsightRecord) -> Result<()> {
+        self.inner.save_insight(record).await
+    }
+
+    async fn load_insight(&self, insight_id: Uuid) -> Result<Option<InsightRecord>> {
+        self.inner.load_insight(insight_id).await
+    }
+
+    async fn save_rating_audit(&self, entry: &RatingAuditEntry) -> Result<()> {
+        self.inner.save_rating_audit(entry).await
+    }
+
+    async fn load_rating_audit(&self, player_id: Uuid) -> Result<Vec<RatingAuditEntry>> {
+        self.inner.load_rating_audit(player_id).await
+    }
+
+    async fn save_bracket(&self, bracket: &TournamentBracket) -> Result<()> {
+        self.inner.save_bracket(bracket).await
+    }
+
+    async fn load_bracket(&self, bracket_id: Uuid) -> Result<Option<TournamentBracket>> {
+        self.inner.load_bracket(bracket_id).await
+    }
+
+    async fn delete_bracket(&self, bracket_id: Uuid) -> Result<()> {
+        self.inner.delete_bracket(bracket_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock::MockClock, persistence::InMemoryAdapter};
+    use std::sync::atomic::AtomicUsize;
+
+    /// In-memory adapter that counts rating reads
+    #[derive(Default)]
+    struct CountingAdapter {
+        inner: InMemoryAdapter,
+        rating_loads: AtomicUsize,
+    }
+
+    impl CountingAdapter {
+        fn loads(&self) -> usize {
+            self.rating_loads.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl PersistenceAdapter for CountingAdapter {
+        async fn save_player_rating(&self, player_id: Uuid, rating: Rating) -> Result<()> {
+            self.inner.save_player_rating(player_id, rating).await
+        }
+
+        async fn load_player_rating(&self, player_id: Uuid) -> Result<Option<Rating>> {
+            self.rating_loads.fetch_add(1, Ordering::SeqCst);
+            self.inner.load_player_rating(player_id).await
+        }
+
+        async fn save_shadow_rating(&self, player_id: Uuid, rating: Rating) -> Result<()> {
+            self.inner.save_shadow_rating(player_id, rating).await
+        }
+
+        async fn load_shadow_rating(&self, player_id: Uuid) -> Result<Option<Rating>> {
+            self.inner.load_shadow_rating(player_id).await
+        }
+
+        async fn load_all_ratings(&self) -> Result<Vec<(Uuid, Rating)>> {
+            self.inner.load_all_ratings().await
+        }
+
+        async fn save_queue_entry(&self, entry: &QueueEntry) -> Result<()> {
+            self.inner.save_queue_entry(entry).await
+        }
+
+        async fn load_queue_entries(&self, queue_name: &str) -> Result<Vec<QueueEntry>> {
+            self.inner.load_queue_entries(queue_name).await
+        }
+
+        async fn delete_queue_entry(&self, player_id: Uuid) -> Result<()> {
+            self.inner.delete_queue_entry(player_id).await
+        }
+
+        async fn load_all_queue_entries(&self) -> Result<Vec<QueueEntry>> {
+            self.inner.load_all_queue_entries().await
+        }
+
+        async fn save_party(&self, party: &Party) -> Result<()> {
+            self.inner.save_party(party).await
+        }
+
+        async fn load_party(&self, party_id: Uuid) -> Result<Option<Party>> {
+            self.inner.load_party(party_id).await
+        }
+
+        async fn delete_party(&self, party_id: Uuid) -> Result<()> {
+            self.inner.delete_party(party_id).await
+        }
+
+        async fn load_all_parties(&self) -> Result<Vec<Party>> {
+            self.inner.load_all_parties().await
+        }
+
+        async fn save_lobby(&self, lobby: &Lobby) -> Result<()> {
+            self.inner.save_lobby(lobby).await
+        }
+
+        async fn load_lobby(&self, lobby_id: Uuid) -> Result<Option<Lobby>> {
+            self.inner.load_lobby(lobby_id).await
+        }
+
+        async fn delete_lobby(&self, lobby_id: Uuid) -> Result<()> {
+            self.inner.delete_lobby(lobby_id).await
+        }
+
+        async fn load_all_lobbies(&self) -> Result<Vec<Lobby>> {
+            self.inner.load_all_lobbies().await
+        }
+
+        async fn player_in_active_lobby(&self, player_id: Uuid) -> Result<bool> {
+            self.inner.player_in_active_lobby(player_id).await
+        }
+
+        async fn save_match_result(&self, lobby: &Lobby) -> Result<()> {
+            self.inner.save_match_result(lobby).await
+        }
+
+        async fn load_player_match_history(&self, player_id: Uuid, limit: usize) -> Result<Vec<Lobby>> {
+            self.inner.load_player_match_history(player_id, limit).await
+        }
+
+        async fn save_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid, rating: Rating) -> Result<()> {
+            self.inner.save_season_rating(queue_name, season_id, player_id, rating).await
+        }
+
+        async fn load_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid) -> Result<Option<Rating>> {
+            self.inner.load_season_rating(queue_name, season_id, player_id).await
+        }
+
+        async fn load_season_ratings(&self, queue_name: &str, season_id: &str) -> Result<Vec<(Uuid, Rating)>> {
+            self.inner.load_season_ratings(queue_name, season_id).await
+        }
+
+        async fn top_players(&self, queue_name: &str, season_id: &str, n: usize) -> Result<Vec<(Uuid, Rating)>> {
+            self.inner.top_players(queue_name, season_id, n).await
+        }
+
+        async fn save_insight(&self, record: &InsightRecord) -> Result<()> {
+            self.inner.save_insight(record).await
+        }
+
+        async fn load_insight(&self, insight_id: Uuid) -> Result<Option<InsightRecord>> {
+            self.inner.load_insight(insight_id).await
+        }
+
+        async fn save_rating_audit(&self, entry: &RatingAuditEntry) -> Result<()> {
+            self.inner.save_rating_audit(entry).await
+        }
+
+        async fn load_rating_audit(&self, player_id: Uuid) -> Result<Vec<RatingAuditEntry>> {
+            self.inner.load_rating_audit(player_id).await
+        }
+
+        async fn save_bracket(&self, bracket: &TournamentBracket) -> Result<()> {
+            self.inner.save_bracket(bracket).await
+        }
+
+        async fn load_bracket(&self, bracket_id: Uuid) -> Result<Option<TournamentBracket>> {
+            self.inner.load_bracket(bracket_id).await
+        }
+
+        async fn delete_bracket(&self, bracket_id: Uuid) -> Result<()> {
+            self.inner.delete_bracket(bracket_id).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_second_read_served_from_cache() {
+        let adapter = CachingAdapter::new(CountingAdapter::default());
+        let player_id = Uuid::new_v4();
+        adapter.save_player_rating(player_id, Rating::new(1700.0, 90.0, 0.06)).await.unwrap();
+
+        let first = adapter.load_player_rating(player_id).await.unwrap().unwrap();
+        let second = adapter.load_player_rating(player_id).await.unwrap().unwrap();
+
+        assert_eq!(first.rating, second.rating);
+        assert_eq!(adapter.inner().loads(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_save_invalidates_cached_rating() {
+        let adapter = CachingAdapter::new(CountingAdapter::default());
+        let player_id = Uuid::new_v4();
+        adapter.save_player_rating(player_id, Rating::new(1700.0, 90.0, 0.06)).await.unwrap();
+        adapter.load_player_rating(player_id).await.unwrap();
+
+        adapter.save_player_rating(player_id, Rating::new(1725.0, 85.0, 0.06)).await.unwrap();
+        let reloaded = adapter.load_player_rating(player_id).await.unwrap().unwrap();
+
+        assert_eq!(reloaded.rating, 1725.0);
+        assert_eq!(adapter.inner().loads(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_expired_entry_is_reloaded() {
+        let clock = Arc::new(MockClock::default());
+        let adapter = CachingAdapter::new(CountingAdapter::default())
+            .with_ttl(chrono::Duration::seconds(30))
+            .with_clock(clock.clone());
+        let player_id = Uuid::new_v4();
+        adapter.save_player_rating(player_id, Rating::default_beginner()).await.unwrap();
+
+        adapter.load_player_rating(player_id).await.unwrap();
+        clock.advance(chrono::Duration::seconds(31));
+        adapter.load_player_rating(player_id).await.unwrap();
+
+        assert_eq!(adapter.inner().loads(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_least_recently_used_rating_evicted() {
+        let adapter = CachingAdapter::new(CountingAdapter::default()).with_capacity(2);
+        let players: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        for player_id in &players {
+            adapter.save_player_rating(*player_id, Rating::default_beginner()).await.unwrap();
+        }
+
+        adapter.load_player_rating(players[0]).await.unwrap();
+        adapter.load_player_rating(players[1]).await.unwrap();
+        adapter.load_player_rating(players[0]).await.unwrap();
+        adapter.load_player_rating(players[2]).await.unwrap();
+        assert_eq!(adapter.cached_len().await, 2);
+        assert_eq!(adapter.inner().loads(), 3);
+
+        // players[1] was least recently used and had to be reloaded
+        adapter.load_player_rating(players[1]).await.unwrap();
+        assert_eq!(adapter.inner().loads(), 4);
+        adapter.load_player_rating(players[2]).await.unwrap();
+        assert_eq!(adapter.inner().loads(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_invalidated_rating_frees_its_slot() {
+        let adapter = CachingAdapter::new(CountingAdapter::default()).with_capacity(2);
+        let players: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        for player_id in &players {
+            adapter.save_player_rating(*player_id, Rating::default_beginner()).await.unwrap();
+        }
+
+        adapter.load_player_rating(players[0]).await.unwrap();
+        adapter.load_player_rating(players[1]).await.unwrap();
+        adapter.save_player_rating(players[0], Rating::default_beginner()).await.unwrap();
+        adapter.load_player_rating(players[2]).await.unwrap();
+        assert_eq!(adapter.cached_len().await, 2);
+
+        // The slot players[0] gave up was reused, so players[1] is still cached
+        adapter.load_player_rating(players[1]).await.unwrap();
+        assert_eq!(adapter.inner().loads(), 3);
+    }
+}
@@ -0,0 +1,113 @@
+use super::schema::{from_versioned_json, Versioned};
+use crate::error::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Encoding used for values written to a key-value store
+///
+/// Postgres stores entities in JSONB columns and always uses JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SerializationFormat {
+    /// Human-readable JSON
+    #[default]
+    Json,
+    /// Compact binary MessagePack (requires the `msgpack` feature)
+    ///
+    /// Structs are written as maps with human-readable UUIDs and timestamps,
+    /// so versioned entities can be migrated exactly like their JSON form.
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+}
+
+impl SerializationFormat {
+    /// Serialize a value into this format
+    pub fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            SerializationFormat::Json => serde_json::to_vec(value).map_err(serialization_error),
+            #[cfg(feature = "msgpack")]
+            SerializationFormat::MessagePack => {
+                let mut bytes = Vec::new();
+                let mut serializer = rmp_serde::Serializer::new(&mut bytes)
+                    .with_struct_map()
+                    .with_human_readable();
+                value.serialize(&mut serializer).map_err(serialization_error)?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Deserialize a value written in this format
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        match self {
+            SerializationFormat::Json => serde_json::from_slice(bytes).map_err(serialization_error),
+            #[cfg(feature = "msgpack")]
+            SerializationFormat::MessagePack => {
+                let mut deserializer = rmp_serde::Deserializer::new(bytes).with_human_readable();
+                T::deserialize(&mut deserializer).map_err(serialization_error)
+            }
+        }
+    }
+
+    /// Deserialize a versioned entity, migrating older formats, see [`from_versioned_json`]
+    pub fn decode_versioned<T: Versioned>(&self, bytes: &[u8]) -> Result<T> {
+        from_versioned_json(self.decode(bytes)?)
+    }
+}
+
+fn serialization_error(e: impl std::fmt::Display) -> MatchForgeError {
+    StorageError::Serialization(e.to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mmr::Rating,
+        queue::{EntryMetadata, QueueEntry},
+    };
+    use uuid::Uuid;
+
+    fn sample_entry() -> QueueEntry {
+        let mut metadata = EntryMetadata::default();
+        metadata.region = Some("eu-west".to_string());
+        metadata.tags.insert("input_device".to_string(), "controller".to_string());
+        QueueEntry::new_party(
+            "ranked".to_string(),
+            Uuid::new_v4(),
+            vec![Uuid::new_v4(), Uuid::new_v4()],
+            Rating::new(1720.0, 80.0, 0.06),
+            metadata,
+        )
+    }
+
+    fn assert_round_trip(format: SerializationFormat) -> usize {
+        let entry = sample_entry();
+        let bytes = format.encode(&entry).unwrap();
+        let decoded: QueueEntry = format.decode_versioned(&bytes).unwrap();
+
+        assert_eq!(decoded.id, entry.id);
+        assert_eq!(decoded.party_id, entry.party_id);
+        assert_eq!(decoded.player_ids, entry.player_ids);
+        assert_eq!(decoded.joined_at, entry.joined_at);
+        assert_eq!(decoded.average_rating.rating, entry.average_rating.rating);
+        assert_eq!(decoded.metadata.tags, entry.metadata.tags);
+        bytes.len()
+    }
+
+    #[test]
+    fn test_queue_entry_round_trips_as_json() {
+        assert_round_trip(SerializationFormat::Json);
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_queue_entry_round_trips_as_messagepack() {
+        let msgpack_size = assert_round_trip(SerializationFormat::MessagePack);
+        assert!(msgpack_size < assert_round_trip(SerializationFormat::Json));
+    }
+
+    #[test]
+    fn test_malformed_payload_is_a_serialization_error() {
+        let err = SerializationFormat::Json.decode::<QueueEntry>(b"\x93\x01").unwrap_err();
+        assert!(matches!(err, MatchForgeError::Storage(StorageError::Serialization(_))));
+    }
+}
@@ -1,33 +1,47 @@
 use super::traits::PersistenceAdapter;
 use crate::{
+    analytics::insights::InsightRecord,
     error::Result,
-    lobby::Lobby,
-    mmr::Rating,
+    lobby::{Lobby, LobbyState},
+    mmr::{Rating, RatingAuditEntry},
     party::Party,
-    queue::QueueEntry,
+    queue::{QueueEntry, TournamentBracket},
 };
 use async_trait::async_trait;
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+/// Season ratings keyed by (queue name, season id)
+type SeasonRatings = HashMap<(String, String), HashMap<Uuid, Rating>>;
+
 /// In-memory persistence adapter (for development/testing)
 pub struct InMemoryAdapter {
     player_ratings: Arc<RwLock<HashMap<Uuid, Rating>>>,
+    shadow_ratings: Arc<RwLock<HashMap<Uuid, Rating>>>,
     queue_entries: Arc<RwLock<HashMap<String, Vec<QueueEntry>>>>,
     parties: Arc<RwLock<HashMap<Uuid, Party>>>,
     lobbies: Arc<RwLock<HashMap<Uuid, Lobby>>>,
     match_history: Arc<RwLock<Vec<Lobby>>>,
+    season_ratings: Arc<RwLock<SeasonRatings>>,
+    insights: Arc<RwLock<HashMap<Uuid, InsightRecord>>>,
+    rating_audit: Arc<RwLock<HashMap<Uuid, Vec<RatingAuditEntry>>>>,
+    brackets: Arc<RwLock<HashMap<Uuid, TournamentBracket>>>,
 }
 
 impl InMemoryAdapter {
     pub fn new() -> Self {
         Self {
             player_ratings: Arc::new(RwLock::new(HashMap::new())),
+            shadow_ratings: Arc::new(RwLock::new(HashMap::new())),
             queue_entries: Arc::new(RwLock::new(HashMap::new())),
             parties: Arc::new(RwLock::new(HashMap::new())),
             lobbies: Arc::new(RwLock::new(HashMap::new())),
             match_history: Arc::new(RwLock::new(Vec::new())),
+            season_ratings: Arc::new(RwLock::new(HashMap::new())),
+            insights: Arc::new(RwLock::new(HashMap::new())),
+            rating_audit: Arc::new(RwLock::new(HashMap::new())),
+            brackets: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 }
@@ -51,12 +65,30 @@ impl PersistenceAdapter for InMemoryAdapter {
         Ok(ratings.get(&player_id).copied())
     }
 
+    async fn save_shadow_rating(&self, player_id: Uuid, rating: Rating) -> Result<()> {
+        let mut ratings = self.shadow_ratings.write().await;
+        ratings.insert(player_id, rating);
+        Ok(())
+    }
+
+    async fn load_shadow_rating(&self, player_id: Uuid) -> Result<Option<Rating>> {
+        let ratings = self.shadow_ratings.read().await;
+        Ok(ratings.get(&player_id).copied())
+    }
+
+    async fn load_all_ratings(&self) -> Result<Vec<(Uuid, Rating)>> {
+        let ratings = self.player_ratings.read().await;
+        Ok(ratings.iter().map(|(id, rating)| (*id, *rating)).collect())
+    }
+
     async fn save_queue_entry(&self, entry: &QueueEntry) -> Result<()> {
         let mut entries = self.queue_entries.write().await;
-        entries
-            .entry(entry.queue_name.clone())
-            .or_insert_with(Vec::new)
-            .push(entry.clone());
+        let queue = entries.entry(entry.queue_name.clone()).or_insert_with(Vec::new);
+        // Saving an entry again replaces it, like the other adapters' upserts
+        match queue.iter_mut().find(|e| e.id == entry.id) {
+            Some(existing) => *existing = entry.clone(),
+            None => queue.push(entry.clone()),
+        }
         Ok(())
     }
 
@@ -73,6 +105,11 @@ impl PersistenceAdapter for InMemoryAdapter {
         Ok(())
     }
 
+    async fn load_all_queue_entries(&self) -> Result<Vec<QueueEntry>> {
+        let entries = self.queue_entries.read().await;
+        Ok(entries.values().flatten().cloned().collect())
+    }
+
     async fn save_party(&self, party: &Party) -> Result<()> {
         let mut parties = self.parties.write().await;
         parties.insert(party.id, party.clone());
@@ -90,6 +127,11 @@ impl PersistenceAdapter for InMemoryAdapter {
         Ok(())
     }
 
+    async fn load_all_parties(&self) -> Result<Vec<Party>> {
+        let parties = self.parties.read().await;
+        Ok(parties.values().cloned().collect())
+    }
+
     async fn save_lobby(&self, lobby: &Lobby) -> Result<()> {
         let mut lobbies = self.lobbies.write().await;
         lobbies.insert(lobby.id, lobby.clone());
@@ -107,9 +149,156 @@ impl PersistenceAdapter for InMemoryAdapter {
         Ok(())
     }
 
+    async fn load_all_lobbies(&self) -> Result<Vec<Lobby>> {
+        let lobbies = self.lobbies.read().await;
+        Ok(lobbies.values().cloned().collect())
+    }
+
+    async fn player_in_active_lobby(&self, player_id: Uuid) -> Result<bool> {
+        let lobbies = self.lobbies.read().await;
+        Ok(lobbies
+            .values()
+            .any(|lobby| lobby.state != LobbyState::Closed && lobby.player_ids.contains(&player_id)))
+    }
+
     async fn save_match_result(&self, lobby: &Lobby) -> Result<()> {
         let mut history = self.match_history.write().await;
         history.push(lobby.clone());
         Ok(())
     }
+
+    async fn load_player_match_history(&self, player_id: Uuid, limit: usize) -> Result<Vec<Lobby>> {
+        let history = self.match_history.read().await;
+        Ok(history
+            .iter()
+            .rev()
+            .filter(|lobby| lobby.player_ids.contains(&player_id))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn save_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid, rating: Rating) -> Result<()> {
+        let mut season_ratings = self.season_ratings.write().await;
+        season_ratings
+            .entry((queue_name.to_string(), season_id.to_string()))
+            .or_default()
+            .insert(player_id, rating);
+        Ok(())
+    }
+
+    async fn load_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid) -> Result<Option<Rating>> {
+        let season_ratings = self.season_ratings.read().await;
+        Ok(season_ratings
+            .get(&(queue_name.to_string(), season_id.to_string()))
+            .and_then(|players| players.get(&player_id).copied()))
+    }
+
+    async fn load_season_ratings(&self, queue_name: &str, season_id: &str) -> Result<Vec<(Uuid, Rating)>> {
+        let season_ratings = self.season_ratings.read().await;
+        Ok(season_ratings
+            .get(&(queue_name.to_string(), season_id.to_string()))
+            .map(|players| players.iter().map(|(id, rating)| (*id, *rating)).collect())
+            .unwrap_or_default())
+    }
+
+    async fn top_players(&self, queue_name: &str, season_id: &str, n: usize) -> Result<Vec<(Uuid, Rating)>> {
+        let mut ratings = self.load_season_ratings(queue_name, season_id).await?;
+
+        ratings.sort_by(|a, b| {
+            b.1.conservative_estimate()
+                .partial_cmp(&a.1.conservative_estimate())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        ratings.truncate(n);
+
+        Ok(ratings)
+    }
+
+    async fn save_insight(&self, record: &InsightRecord) -> Result<()> {
+        let mut insights = self.insights.write().await;
+        insights.insert(record.insight.id, record.clone());
+        Ok(())
+    }
+
+    async fn load_insight(&self, insight_id: Uuid) -> Result<Option<InsightRecord>> {
+        let insights = self.insights.read().await;
+        Ok(insights.get(&insight_id).cloned())
+    }
+
+    async fn save_rating_audit(&self, entry: &RatingAuditEntry) -> Result<()> {
+        let mut rating_audit = self.rating_audit.write().await;
+        rating_audit.entry(entry.player_id).or_default().push(entry.clone());
+        Ok(())
+    }
+
+    async fn load_rating_audit(&self, player_id: Uuid) -> Result<Vec<RatingAuditEntry>> {
+        let rating_audit = self.rating_audit.read().await;
+        Ok(rating_audit.get(&player_id).cloned().unwrap_or_default())
+    }
+
+    async fn save_bracket(&self, bracket: &TournamentBracket) -> Result<()> {
+        let mut brackets = self.brackets.write().await;
+        brackets.insert(bracket.bracket_id, bracket.clone());
+        Ok(())
+    }
+
+    async fn load_bracket(&self, bracket_id: Uuid) -> Result<Option<TournamentBracket>> {
+        let brackets = self.brackets.read().await;
+        Ok(brackets.get(&bracket_id).cloned())
+    }
+
+    async fn delete_bracket(&self, bracket_id: Uuid) -> Result<()> {
+        let mut brackets = self.brackets.write().await;
+        brackets.remove(&bracket_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_top_players_by_conservative_estimate() {
+        let adapter = InMemoryAdapter::new();
+        let players: Vec<(Uuid, Rating)> = vec![
+            (Uuid::new_v4(), Rating::new(2000.0, 300.0, 0.06)), // 1400
+            (Uuid::new_v4(), Rating::new(1800.0, 50.0, 0.06)),  // 1700
+            (Uuid::new_v4(), Rating::new(1600.0, 100.0, 0.06)), // 1400
+            (Uuid::new_v4(), Rating::new(1900.0, 60.0, 0.06)),  // 1780
+            (Uuid::new_v4(), Rating::new(1200.0, 80.0, 0.06)),  // 1040
+        ];
+
+        for (player_id, rating) in &players {
+            adapter.save_season_rating("ranked", "s1", *player_id, *rating).await.unwrap();
+        }
+
+        let top = adapter.top_players("ranked", "s1", 2).await.unwrap();
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].0, players[3].0);
+        assert_eq!(top[1].0, players[1].0);
+
+        let all = adapter.top_players("ranked", "s1", 10).await.unwrap();
+        assert_eq!(all.len(), 5);
+        assert!(all.windows(2).all(|w| w[0].1.conservative_estimate() >= w[1].1.conservative_estimate()));
+    }
+
+    #[tokio::test]
+    async fn test_top_players_scoped_to_queue_and_season() {
+        let adapter = InMemoryAdapter::new();
+        let player_id = Uuid::new_v4();
+
+        adapter.save_season_rating("ranked", "s1", player_id, Rating::new(1800.0, 50.0, 0.06)).await.unwrap();
+        adapter.save_season_rating("ranked", "s2", Uuid::new_v4(), Rating::new(1500.0, 50.0, 0.06)).await.unwrap();
+
+        // Re-recording replaces the previous season rating
+        adapter.save_season_rating("ranked", "s1", player_id, Rating::new(1900.0, 50.0, 0.06)).await.unwrap();
+
+        let s1 = adapter.top_players("ranked", "s1", 10).await.unwrap();
+        assert_eq!(s1.len(), 1);
+        assert_eq!(s1[0].1.rating, 1900.0);
+
+        assert!(adapter.top_players("casual", "s1", 10).await.unwrap().is_empty());
+    }
 }
@@ -0,0 +1,144 @@
+//! Copying stored state between persistence adapters
+
+use super::traits::PersistenceAdapter;
+use crate::error::Result;
+
+/// Entities copied by [`migrate`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MigrationStats {
+    pub ratings: usize,
+    pub parties: usize,
+    pub lobbies: usize,
+    pub queue_entries: usize,
+}
+
+/// Copy every rating, party, lobby and queue entry from one adapter to another
+///
+/// Entities are written with the target's regular `save_*` methods, which
+/// overwrite by id, so running a migration again (e.g. after an interrupted
+/// run) copies the same entities without duplicating them. Entities already
+/// in the target that the source doesn't have are left alone. Match history,
+/// season ratings, audit trails, insights and brackets are not copied.
+pub async fn migrate(from: &dyn PersistenceAdapter, to: &dyn PersistenceAdapter) -> Result<MigrationStats> {
+    let mut stats = MigrationStats::default();
+
+    for (player_id, rating) in from.load_all_ratings().await? {
+        to.save_player_rating(player_id, rating).await?;
+        stats.ratings += 1;
+    }
+
+    for party in from.load_all_parties().await? {
+        to.save_party(&party).await?;
+        stats.parties += 1;
+    }
+
+    for lobby in from.load_all_lobbies().await? {
+        to.save_lobby(&lobby).await?;
+        stats.lobbies += 1;
+    }
+
+    for entry in from.load_all_queue_entries().await? {
+        to.save_queue_entry(&entry).await?;
+        stats.queue_entries += 1;
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lobby::{Lobby, LobbyMetadata},
+        mmr::Rating,
+        party::Party,
+        persistence::InMemoryAdapter,
+        queue::{EntryMetadata, MatchResult, QueueEntry},
+    };
+    use uuid::Uuid;
+
+    async fn populated() -> InMemoryAdapter {
+        let adapter = InMemoryAdapter::new();
+        for rating in [1200.0, 1500.0, 1800.0] {
+            adapter.save_player_rating(Uuid::new_v4(), Rating::new(rating, 200.0, 0.06)).await.unwrap();
+        }
+
+        let leader = Uuid::new_v4();
+        adapter.save_party(&Party::new(leader, 4)).await.unwrap();
+
+        let entries: Vec<QueueEntry> = ["ranked", "ranked", "casual"]
+            .into_iter()
+            .map(|queue| QueueEntry::new_solo(queue.to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default()))
+            .collect();
+        for entry in &entries {
+            adapter.save_queue_entry(entry).await.unwrap();
+        }
+
+        let match_result = MatchResult {
+            match_id: Uuid::new_v4(),
+            entries: entries[..2].to_vec(),
+            team_assignments: vec![0, 1],
+            split_party_ids: Vec::new(),
+            quality_score: 1.0,
+            game_mode: None,
+            team_roles: Vec::new(),
+        };
+        let lobby = Lobby::from_match_result(match_result, vec![1, 1], LobbyMetadata::default());
+        adapter.save_lobby(&lobby).await.unwrap();
+        adapter
+    }
+
+    fn sorted<T, K: Ord>(mut items: Vec<T>, key: impl Fn(&T) -> K) -> Vec<T> {
+        items.sort_by_key(|item| key(item));
+        items
+    }
+
+    #[tokio::test]
+    async fn test_migrate_copies_every_entity() {
+        let source = populated().await;
+        let target = InMemoryAdapter::new();
+
+        let stats = migrate(&source, &target).await.unwrap();
+        assert_eq!(stats, MigrationStats { ratings: 3, parties: 1, lobbies: 1, queue_entries: 3 });
+
+        let source_ratings = sorted(source.load_all_ratings().await.unwrap(), |(id, _)| *id);
+        let target_ratings = sorted(target.load_all_ratings().await.unwrap(), |(id, _)| *id);
+        assert_eq!(source_ratings.len(), target_ratings.len());
+        for ((source_id, source_rating), (target_id, target_rating)) in source_ratings.iter().zip(&target_ratings) {
+            assert_eq!(source_id, target_id);
+            assert_eq!(source_rating.rating, target_rating.rating);
+        }
+
+        let party_ids = |parties: Vec<Party>| sorted(parties.into_iter().map(|p| p.id).collect(), |id: &Uuid| *id);
+        assert_eq!(party_ids(source.load_all_parties().await.unwrap()), party_ids(target.load_all_parties().await.unwrap()));
+
+        let lobby = &source.load_all_lobbies().await.unwrap()[0];
+        let copied = target.load_lobby(lobby.id).await.unwrap().unwrap();
+        assert_eq!(copied.player_ids, lobby.player_ids);
+        assert_eq!(copied.match_id, lobby.match_id);
+
+        for queue in ["ranked", "casual"] {
+            let ids = |entries: Vec<QueueEntry>| sorted(entries.into_iter().map(|e| e.id).collect(), |id: &Uuid| *id);
+            assert_eq!(
+                ids(source.load_queue_entries(queue).await.unwrap()),
+                ids(target.load_queue_entries(queue).await.unwrap())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migrate_twice_is_idempotent() {
+        let source = populated().await;
+        let target = InMemoryAdapter::new();
+
+        let first = migrate(&source, &target).await.unwrap();
+        let second = migrate(&source, &target).await.unwrap();
+        assert_eq!(first, second);
+
+        assert_eq!(target.load_all_ratings().await.unwrap().len(), 3);
+        assert_eq!(target.load_all_parties().await.unwrap().len(), 1);
+        assert_eq!(target.load_all_lobbies().await.unwrap().len(), 1);
+        assert_eq!(target.load_all_queue_entries().await.unwrap().len(), 3);
+        assert_eq!(target.load_queue_entries("ranked").await.unwrap().len(), 2);
+    }
+}
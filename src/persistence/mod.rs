@@ -1,14 +1,22 @@
+pub mod caching;
+pub mod format;
 pub mod memory;
+pub mod migrate;
 #[cfg(feature = "postgres")]
 pub mod postgres;
 pub mod redis;
+pub mod schema;
 pub mod traits;
 
 #[cfg(feature = "redis")]
-pub use redis::{CleanupStats, PlayerStats, QueueStats, RedisAdapter};
+pub use redis::{CleanupStats, PlayerStats, QueueStats, RedisAdapter, RedisConfig, RedisTopology};
 
 #[cfg(feature = "postgres")]
 pub use postgres::{CleanupStats as PgCleanupStats, DatabaseMetrics, PlayerStats as PgPlayerStats, PostgresAdapter, QueueStats as PgQueueStats};
 
+pub use caching::CachingAdapter;
+pub use format::SerializationFormat;
 pub use memory::InMemoryAdapter;
+pub use migrate::{migrate, MigrationStats};
+pub use schema::{from_versioned_json, from_versioned_str, Versioned};
 pub use traits::PersistenceAdapter;
@@ -1,9 +1,28 @@
-use super::traits::PersistenceAdapter;
-use crate::{error::*, lobby::Lobby, mmr::Rating, party::Party, queue::QueueEntry};
+use super::{schema::from_versioned_json, traits::PersistenceAdapter};
+use crate::{analytics::insights::InsightRecord, error::*, lobby::Lobby, mmr::{Rating, RatingAuditEntry}, party::Party, queue::{QueueEntry, TournamentBracket}};
 use async_trait::async_trait;
 use sqlx::{postgres::PgRow, PgPool, Row};
 use uuid::Uuid;
 
+/// Map a sqlx error onto the matching [`StorageError`] category
+fn sqlx_error(e: sqlx::Error) -> MatchForgeError {
+    match e {
+        sqlx::Error::RowNotFound => StorageError::NotFound(e.to_string()).into(),
+        sqlx::Error::PoolTimedOut => StorageError::Timeout(e.to_string()).into(),
+        sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolClosed => {
+            StorageError::Connection(e.to_string()).into()
+        }
+        sqlx::Error::ColumnDecode { .. } | sqlx::Error::Decode(_) => {
+            StorageError::Serialization(e.to_string()).into()
+        }
+        // 23505: unique_violation
+        sqlx::Error::Database(ref db) if db.code().as_deref() == Some("23505") => {
+            StorageError::Conflict(e.to_string()).into()
+        }
+        _ => MatchForgeError::PersistenceError(e.to_string()),
+    }
+}
+
 /// Postgres persistence adapter
 /// 
 /// Provides a production-ready persistence layer using PostgreSQL as the backend.
@@ -16,7 +35,7 @@ impl PostgresAdapter {
     /// Create a new Postgres adapter with the given connection string
     pub async fn new(connection_string: &str) -> Result<Self> {
         let pool = PgPool::connect(connection_string).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let adapter = Self { pool };
         
@@ -29,7 +48,7 @@ impl PostgresAdapter {
     /// Initialize the database schema
     async fn init_schema(&self) -> Result<()> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         // Create tables
         sqlx::query(
@@ -43,9 +62,17 @@ impl PostgresAdapter {
             );
             
             CREATE INDEX IF NOT EXISTS idx_player_ratings_updated_at ON player_ratings(updated_at);
+            
+            CREATE TABLE IF NOT EXISTS shadow_ratings (
+                player_id UUID PRIMARY KEY,
+                rating DOUBLE PRECISION NOT NULL,
+                deviation DOUBLE PRECISION NOT NULL,
+                volatility DOUBLE PRECISION NOT NULL,
+                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+            );
             "#
         ).execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         sqlx::query(
             r#"
@@ -68,7 +95,7 @@ impl PostgresAdapter {
             CREATE INDEX IF NOT EXISTS idx_queue_entries_party_id ON queue_entries(party_id);
             "#
         ).execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         sqlx::query(
             r#"
@@ -84,7 +111,7 @@ impl PostgresAdapter {
             CREATE INDEX IF NOT EXISTS idx_parties_member_ids ON parties USING GIN(member_ids);
             "#
         ).execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         sqlx::query(
             r#"
@@ -96,16 +123,20 @@ impl PostgresAdapter {
                 teams JSONB NOT NULL,
                 ready_players UUID[] DEFAULT '{}',
                 created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                metadata JSONB DEFAULT '{}'
+                metadata JSONB DEFAULT '{}',
+                queue_entries JSONB DEFAULT '[]'
             );
             
+            -- Tables created before queue entries were stored with the lobby
+            ALTER TABLE lobbies ADD COLUMN IF NOT EXISTS queue_entries JSONB DEFAULT '[]';
+            
             CREATE INDEX IF NOT EXISTS idx_lobbies_match_id ON lobbies(match_id);
             CREATE INDEX IF NOT EXISTS idx_lobbies_state ON lobbies(state);
             CREATE INDEX IF NOT EXISTS idx_lobbies_created_at ON lobbies(created_at);
             CREATE INDEX IF NOT EXISTS idx_lobbies_player_ids ON lobbies USING GIN(player_ids);
             "#
         ).execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         sqlx::query(
             r#"
@@ -120,7 +151,7 @@ impl PostgresAdapter {
             CREATE INDEX IF NOT EXISTS idx_match_history_completed_at ON match_history(completed_at);
             "#
         ).execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         sqlx::query(
             r#"
@@ -139,7 +170,64 @@ impl PostgresAdapter {
             CREATE INDEX IF NOT EXISTS idx_player_match_history_played_at ON player_match_history(played_at);
             "#
         ).execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
+        
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS season_ratings (
+                queue_name VARCHAR(255) NOT NULL,
+                season_id VARCHAR(255) NOT NULL,
+                player_id UUID NOT NULL,
+                rating DOUBLE PRECISION NOT NULL,
+                deviation DOUBLE PRECISION NOT NULL,
+                volatility DOUBLE PRECISION NOT NULL,
+                conservative_estimate DOUBLE PRECISION NOT NULL,
+                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+                PRIMARY KEY (queue_name, season_id, player_id)
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_season_ratings_leaderboard
+                ON season_ratings(queue_name, season_id, conservative_estimate DESC);
+            "#
+        ).execute(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS insights (
+                id UUID PRIMARY KEY,
+                record JSONB NOT NULL,
+                acknowledged BOOLEAN NOT NULL DEFAULT FALSE,
+                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+            );
+            "#
+        ).execute(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS rating_audit (
+                id BIGSERIAL PRIMARY KEY,
+                player_id UUID NOT NULL,
+                entry JSONB NOT NULL,
+                recorded_at TIMESTAMP WITH TIME ZONE NOT NULL
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_rating_audit_player_id ON rating_audit(player_id, id);
+            "#
+        ).execute(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS tournament_brackets (
+                id UUID PRIMARY KEY,
+                bracket JSONB NOT NULL,
+                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+            );
+            "#
+        ).execute(&mut conn).await
+            .map_err(sqlx_error)?;
         
         Ok(())
     }
@@ -148,41 +236,42 @@ impl PostgresAdapter {
     fn row_to_rating(row: &PgRow) -> Result<Rating> {
         Ok(Rating {
             rating: row.try_get("rating")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             deviation: row.try_get("deviation")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             volatility: row.try_get("volatility")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
         })
     }
     
     /// Helper to convert row to QueueEntry
     fn row_to_queue_entry(row: &PgRow) -> Result<QueueEntry> {
         let metadata_json: serde_json::Value = row.try_get("metadata")
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let metadata: EntryMetadata = serde_json::from_value(metadata_json)
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
         
         Ok(QueueEntry {
+            schema_version: QueueEntry::SCHEMA_VERSION,
             id: row.try_get("id")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             queue_name: row.try_get("queue_name")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             player_ids: row.try_get("player_ids")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             party_id: row.try_get("party_id")
-                .map_err(|_| MatchForgeError::PersistenceError("Failed to parse party_id".to_string()))?,
+                .map_err(|_| StorageError::Serialization("Failed to parse party_id".to_string()))?,
             average_rating: Rating {
                 rating: row.try_get("average_rating")
-                    .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                    .map_err(sqlx_error)?,
                 deviation: row.try_get("average_deviation")
-                    .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                    .map_err(sqlx_error)?,
                 volatility: row.try_get("average_volatility")
-                    .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                    .map_err(sqlx_error)?,
             },
             joined_at: row.try_get("joined_at")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             metadata,
         })
     }
@@ -191,60 +280,69 @@ impl PostgresAdapter {
     fn row_to_party(row: &PgRow) -> Result<Party> {
         Ok(Party {
             id: row.try_get("id")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             leader_id: row.try_get("leader_id")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             member_ids: row.try_get("member_ids")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             max_size: row.try_get("max_size")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             created_at: row.try_get("created_at")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
         })
     }
     
     /// Helper to convert row to Lobby
     fn row_to_lobby(row: &PgRow) -> Result<Lobby> {
         let teams_json: serde_json::Value = row.try_get("teams")
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let teams: Vec<Team> = serde_json::from_value(teams_json)
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
         
         let metadata_json: serde_json::Value = row.try_get("metadata")
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let metadata: LobbyMetadata = serde_json::from_value(metadata_json)
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
         
         let state_str: String = row.try_get("state")
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let state = match state_str.as_str() {
             "Forming" => LobbyState::Forming,
+            "Drafting" => LobbyState::Drafting,
             "WaitingForReady" => LobbyState::WaitingForReady,
             "Ready" => LobbyState::Ready,
             "Dispatched" => LobbyState::Dispatched,
             "Closed" => LobbyState::Closed,
-            _ => return Err(MatchForgeError::PersistenceError(format!("Invalid lobby state: {}", state_str))),
+            _ => return Err(StorageError::Serialization(format!("Invalid lobby state: {}", state_str)).into()),
         };
         
         let ready_players: std::collections::HashSet<Uuid> = row.try_get("ready_players")
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
+        
+        let queue_entries_json: serde_json::Value = row.try_get("queue_entries")
+            .map_err(sqlx_error)?;
+        
+        let queue_entries: Vec<QueueEntry> = serde_json::from_value(queue_entries_json)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
         
         Ok(Lobby {
+            schema_version: Lobby::SCHEMA_VERSION,
             id: row.try_get("id")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             match_id: row.try_get("match_id")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             state,
             teams,
             player_ids: row.try_get("player_ids")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             ready_players,
             created_at: row.try_get("created_at")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             metadata,
+            queue_entries,
         })
     }
 }
@@ -253,7 +351,7 @@ impl PostgresAdapter {
 impl PersistenceAdapter for PostgresAdapter {
     async fn save_player_rating(&self, player_id: Uuid, rating: Rating) -> Result<()> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         sqlx::query(
             r#"
@@ -272,31 +370,89 @@ impl PersistenceAdapter for PostgresAdapter {
         .bind(rating.deviation)
         .bind(rating.volatility)
         .execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         Ok(())
     }
 
     async fn load_player_rating(&self, player_id: Uuid) -> Result<Option<Rating>> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let row = sqlx::query(
             "SELECT rating, deviation, volatility FROM player_ratings WHERE player_id = $1"
         )
         .bind(player_id)
         .fetch_optional(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         Ok(row.map(|r| self.row_to_rating(&r)).transpose()?)
     }
 
+    async fn save_shadow_rating(&self, player_id: Uuid, rating: Rating) -> Result<()> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        sqlx::query(
+            r#"
+            INSERT INTO shadow_ratings (player_id, rating, deviation, volatility)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (player_id) 
+            DO UPDATE SET 
+                rating = EXCLUDED.rating,
+                deviation = EXCLUDED.deviation,
+                volatility = EXCLUDED.volatility,
+                updated_at = NOW()
+            "#
+        )
+        .bind(player_id)
+        .bind(rating.rating)
+        .bind(rating.deviation)
+        .bind(rating.volatility)
+        .execute(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        Ok(())
+    }
+
+    async fn load_shadow_rating(&self, player_id: Uuid) -> Result<Option<Rating>> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let row = sqlx::query(
+            "SELECT rating, deviation, volatility FROM shadow_ratings WHERE player_id = $1"
+        )
+        .bind(player_id)
+        .fetch_optional(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        Ok(row.map(|r| self.row_to_rating(&r)).transpose()?)
+    }
+
+    async fn load_all_ratings(&self) -> Result<Vec<(Uuid, Rating)>> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let rows = sqlx::query("SELECT player_id, rating, deviation, volatility FROM player_ratings")
+        .fetch_all(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        let mut ratings = Vec::new();
+        for row in rows {
+            let player_id: Uuid = row.try_get("player_id")
+                .map_err(sqlx_error)?;
+            ratings.push((player_id, Self::row_to_rating(&row)?));
+        }
+        
+        Ok(ratings)
+    }
+
     async fn save_queue_entry(&self, entry: &QueueEntry) -> Result<()> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let metadata_json = serde_json::to_value(&entry.metadata)
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
         
         sqlx::query(
             r#"
@@ -327,21 +483,21 @@ impl PersistenceAdapter for PostgresAdapter {
         .bind(entry.joined_at)
         .bind(metadata_json)
         .execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         Ok(())
     }
 
     async fn load_queue_entries(&self, queue_name: &str) -> Result<Vec<QueueEntry>> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let rows = sqlx::query(
             "SELECT * FROM queue_entries WHERE queue_name = $1 ORDER BY joined_at ASC"
         )
         .bind(queue_name)
         .fetch_all(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let mut entries = Vec::new();
         for row in rows {
@@ -353,19 +509,35 @@ impl PersistenceAdapter for PostgresAdapter {
 
     async fn delete_queue_entry(&self, player_id: Uuid) -> Result<()> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         sqlx::query("DELETE FROM queue_entries WHERE $1 = ANY(player_ids)")
         .bind(player_id)
         .execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         Ok(())
     }
 
+    async fn load_all_queue_entries(&self) -> Result<Vec<QueueEntry>> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let rows = sqlx::query("SELECT * FROM queue_entries")
+        .fetch_all(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(Self::row_to_queue_entry(&row)?);
+        }
+        
+        Ok(entries)
+    }
+
     async fn save_party(&self, party: &Party) -> Result<()> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         sqlx::query(
             r#"
@@ -383,44 +555,63 @@ impl PersistenceAdapter for PostgresAdapter {
         .bind(&party.member_ids)
         .bind(party.max_size as i32)
         .execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         Ok(())
     }
 
     async fn load_party(&self, party_id: Uuid) -> Result<Option<Party>> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let row = sqlx::query("SELECT * FROM parties WHERE id = $1")
         .bind(party_id)
         .fetch_optional(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         Ok(row.map(|r| self.row_to_party(&r)).transpose()?)
     }
 
     async fn delete_party(&self, party_id: Uuid) -> Result<()> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         sqlx::query("DELETE FROM parties WHERE id = $1")
         .bind(party_id)
         .execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         Ok(())
     }
 
+    async fn load_all_parties(&self) -> Result<Vec<Party>> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let rows = sqlx::query("SELECT * FROM parties")
+        .fetch_all(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        let mut parties = Vec::new();
+        for row in rows {
+            parties.push(Self::row_to_party(&row)?);
+        }
+        
+        Ok(parties)
+    }
+
     async fn save_lobby(&self, lobby: &Lobby) -> Result<()> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let teams_json = serde_json::to_value(&lobby.teams)
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
         
         let metadata_json = serde_json::to_value(&lobby.metadata)
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        
+        let queue_entries_json = serde_json::to_value(&lobby.queue_entries)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
         
         let ready_players: Vec<Uuid> = lobby.ready_players.iter().cloned().collect();
         let state_str = format!("{:?}", lobby.state);
@@ -428,16 +619,17 @@ impl PersistenceAdapter for PostgresAdapter {
         sqlx::query(
             r#"
             INSERT INTO lobbies (
-                id, match_id, state, player_ids, teams, ready_players, metadata
+                id, match_id, state, player_ids, teams, ready_players, metadata, queue_entries
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             ON CONFLICT (id) 
             DO UPDATE SET 
                 state = EXCLUDED.state,
                 player_ids = EXCLUDED.player_ids,
                 teams = EXCLUDED.teams,
                 ready_players = EXCLUDED.ready_players,
-                metadata = EXCLUDED.metadata
+                metadata = EXCLUDED.metadata,
+                queue_entries = EXCLUDED.queue_entries
             "#
         )
         .bind(lobby.id)
@@ -447,42 +639,74 @@ impl PersistenceAdapter for PostgresAdapter {
         .bind(teams_json)
         .bind(&ready_players)
         .bind(metadata_json)
+        .bind(queue_entries_json)
         .execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         Ok(())
     }
 
     async fn load_lobby(&self, lobby_id: Uuid) -> Result<Option<Lobby>> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let row = sqlx::query("SELECT * FROM lobbies WHERE id = $1")
         .bind(lobby_id)
         .fetch_optional(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         Ok(row.map(|r| self.row_to_lobby(&r)).transpose()?)
     }
 
     async fn delete_lobby(&self, lobby_id: Uuid) -> Result<()> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         sqlx::query("DELETE FROM lobbies WHERE id = $1")
         .bind(lobby_id)
         .execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         Ok(())
     }
 
+    async fn load_all_lobbies(&self) -> Result<Vec<Lobby>> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let rows = sqlx::query("SELECT * FROM lobbies")
+        .fetch_all(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        let mut lobbies = Vec::new();
+        for row in rows {
+            lobbies.push(Self::row_to_lobby(&row)?);
+        }
+        
+        Ok(lobbies)
+    }
+
+    async fn player_in_active_lobby(&self, player_id: Uuid) -> Result<bool> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let row = sqlx::query(
+            "SELECT EXISTS(SELECT 1 FROM lobbies WHERE $1 = ANY(player_ids) AND state <> 'Closed') AS active"
+        )
+        .bind(player_id)
+        .fetch_one(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        row.try_get("active")
+            .map_err(sqlx_error)
+    }
+
     async fn save_match_result(&self, lobby: &Lobby) -> Result<()> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let lobby_data = serde_json::to_value(lobby)
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
         
         sqlx::query(
             "INSERT INTO match_history (match_id, lobby_data) VALUES ($1, $2)"
@@ -490,7 +714,283 @@ impl PersistenceAdapter for PostgresAdapter {
         .bind(lobby.match_id)
         .bind(lobby_data)
         .execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
+        
+        Ok(())
+    }
+
+    async fn load_player_match_history(&self, player_id: Uuid, limit: usize) -> Result<Vec<Lobby>> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let rows = sqlx::query(
+            r#"
+            SELECT lobby_data
+            FROM match_history
+            WHERE lobby_data->'player_ids' ? $1
+            ORDER BY completed_at DESC
+            LIMIT $2
+            "#
+        )
+        .bind(player_id.to_string())
+        .bind(limit as i64)
+        .fetch_all(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        rows.iter()
+            .map(|row| {
+                let lobby_data: serde_json::Value = row.try_get("lobby_data")
+                    .map_err(sqlx_error)?;
+                from_versioned_json(lobby_data)
+            })
+            .collect()
+    }
+
+    async fn save_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid, rating: Rating) -> Result<()> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        sqlx::query(
+            r#"
+            INSERT INTO season_ratings (
+                queue_name, season_id, player_id, rating, deviation, volatility, conservative_estimate
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (queue_name, season_id, player_id) 
+            DO UPDATE SET 
+                rating = EXCLUDED.rating,
+                deviation = EXCLUDED.deviation,
+                volatility = EXCLUDED.volatility,
+                conservative_estimate = EXCLUDED.conservative_estimate,
+                updated_at = NOW()
+            "#
+        )
+        .bind(queue_name)
+        .bind(season_id)
+        .bind(player_id)
+        .bind(rating.rating)
+        .bind(rating.deviation)
+        .bind(rating.volatility)
+        .bind(rating.conservative_estimate())
+        .execute(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        Ok(())
+    }
+
+    async fn load_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid) -> Result<Option<Rating>> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let row = sqlx::query(
+            r#"
+            SELECT rating, deviation, volatility
+            FROM season_ratings
+            WHERE queue_name = $1 AND season_id = $2 AND player_id = $3
+            "#
+        )
+        .bind(queue_name)
+        .bind(season_id)
+        .bind(player_id)
+        .fetch_optional(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        row.map(|row| Self::row_to_rating(&row)).transpose()
+    }
+
+    async fn load_season_ratings(&self, queue_name: &str, season_id: &str) -> Result<Vec<(Uuid, Rating)>> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let rows = sqlx::query(
+            r#"
+            SELECT player_id, rating, deviation, volatility
+            FROM season_ratings
+            WHERE queue_name = $1 AND season_id = $2
+            "#
+        )
+        .bind(queue_name)
+        .bind(season_id)
+        .fetch_all(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        let mut ratings = Vec::new();
+        for row in rows {
+            let player_id: Uuid = row.try_get("player_id")
+                .map_err(sqlx_error)?;
+            ratings.push((player_id, Self::row_to_rating(&row)?));
+        }
+        
+        Ok(ratings)
+    }
+
+    async fn top_players(&self, queue_name: &str, season_id: &str, n: usize) -> Result<Vec<(Uuid, Rating)>> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let rows = sqlx::query(
+            r#"
+            SELECT player_id, rating, deviation, volatility
+            FROM season_ratings
+            WHERE queue_name = $1 AND season_id = $2
+            ORDER BY conservative_estimate DESC
+            LIMIT $3
+            "#
+        )
+        .bind(queue_name)
+        .bind(season_id)
+        .bind(n as i64)
+        .fetch_all(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        let mut top = Vec::new();
+        for row in rows {
+            let player_id: Uuid = row.try_get("player_id")
+                .map_err(sqlx_error)?;
+            top.push((player_id, Self::row_to_rating(&row)?));
+        }
+        
+        Ok(top)
+    }
+
+    async fn save_insight(&self, record: &InsightRecord) -> Result<()> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let record_json = serde_json::to_value(record)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        
+        sqlx::query(
+            r#"
+            INSERT INTO insights (id, record, acknowledged)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (id) 
+            DO UPDATE SET 
+                record = EXCLUDED.record,
+                acknowledged = EXCLUDED.acknowledged,
+                updated_at = NOW()
+            "#
+        )
+        .bind(record.insight.id)
+        .bind(record_json)
+        .bind(record.acknowledged)
+        .execute(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        Ok(())
+    }
+
+    async fn load_insight(&self, insight_id: Uuid) -> Result<Option<InsightRecord>> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let row = sqlx::query("SELECT record FROM insights WHERE id = $1")
+            .bind(insight_id)
+            .fetch_optional(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        match row {
+            Some(row) => {
+                let record_json: serde_json::Value = row.try_get("record")
+                    .map_err(sqlx_error)?;
+                serde_json::from_value(record_json)
+                    .map(Some)
+                    .map_err(|e| StorageError::Serialization(e.to_string()).into())
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn save_rating_audit(&self, entry: &RatingAuditEntry) -> Result<()> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let entry_json = serde_json::to_value(entry)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        
+        sqlx::query("INSERT INTO rating_audit (player_id, entry, recorded_at) VALUES ($1, $2, $3)")
+            .bind(entry.player_id)
+            .bind(entry_json)
+            .bind(entry.at)
+            .execute(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        Ok(())
+    }
+
+    async fn load_rating_audit(&self, player_id: Uuid) -> Result<Vec<RatingAuditEntry>> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let rows = sqlx::query("SELECT entry FROM rating_audit WHERE player_id = $1 ORDER BY id")
+            .bind(player_id)
+            .fetch_all(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        rows.iter()
+            .map(|row| {
+                let entry_json: serde_json::Value = row.try_get("entry")
+                    .map_err(sqlx_error)?;
+                serde_json::from_value(entry_json)
+                    .map_err(|e| StorageError::Serialization(e.to_string()).into())
+            })
+            .collect()
+    }
+
+    async fn save_bracket(&self, bracket: &TournamentBracket) -> Result<()> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let bracket_json = serde_json::to_value(bracket)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        
+        sqlx::query(
+            r#"
+            INSERT INTO tournament_brackets (id, bracket)
+            VALUES ($1, $2)
+            ON CONFLICT (id) 
+            DO UPDATE SET 
+                bracket = EXCLUDED.bracket,
+                updated_at = NOW()
+            "#
+        )
+        .bind(bracket.bracket_id)
+        .bind(bracket_json)
+        .execute(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        Ok(())
+    }
+
+    async fn load_bracket(&self, bracket_id: Uuid) -> Result<Option<TournamentBracket>> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        let row = sqlx::query("SELECT bracket FROM tournament_brackets WHERE id = $1")
+            .bind(bracket_id)
+            .fetch_optional(&mut conn).await
+            .map_err(sqlx_error)?;
+        
+        match row {
+            Some(row) => {
+                let bracket_json: serde_json::Value = row.try_get("bracket")
+                    .map_err(sqlx_error)?;
+                serde_json::from_value(bracket_json)
+                    .map(Some)
+                    .map_err(|e| StorageError::Serialization(e.to_string()).into())
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_bracket(&self, bracket_id: Uuid) -> Result<()> {
+        let mut conn = self.pool.acquire().await
+            .map_err(sqlx_error)?;
+        
+        sqlx::query("DELETE FROM tournament_brackets WHERE id = $1")
+        .bind(bracket_id)
+        .execute(&mut conn).await
+            .map_err(sqlx_error)?;
         
         Ok(())
     }
@@ -501,7 +1001,7 @@ impl PostgresAdapter {
     /// Get queue statistics
     pub async fn get_queue_stats(&self, queue_name: &str) -> Result<QueueStats> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let row = sqlx::query(
             r#"
@@ -515,17 +1015,17 @@ impl PostgresAdapter {
         )
         .bind(queue_name)
         .fetch_one(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         Ok(QueueStats {
             name: queue_name.to_string(),
             size: row.try_get("size")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?,
+                .map_err(sqlx_error)?,
             avg_wait_time_seconds: row.try_get::<Option<f64>, _>("avg_wait_seconds")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?
+                .map_err(sqlx_error)?
                 .unwrap_or(0.0) as i64,
             avg_rating: row.try_get::<Option<f64>, _>("avg_rating")
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?
+                .map_err(sqlx_error)?
                 .unwrap_or(0.0),
         })
     }
@@ -533,7 +1033,7 @@ impl PostgresAdapter {
     /// Get player statistics
     pub async fn get_player_stats(&self, player_id: Uuid) -> Result<PlayerStats> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         // Get rating
         let rating = self.load_player_rating(player_id).await?;
@@ -544,9 +1044,9 @@ impl PostgresAdapter {
         )
         .bind(player_id)
         .fetch_one(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?
+            .map_err(sqlx_error)?
             .try_get("count")
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         // Check if player is in queue
         let in_queue: bool = sqlx::query(
@@ -554,19 +1054,19 @@ impl PostgresAdapter {
         )
         .bind(player_id)
         .fetch_one(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?
+            .map_err(sqlx_error)?
             .try_get("exists")
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         // Check if player is in party
         let party_row = sqlx::query("SELECT id FROM parties WHERE $1 = ANY(member_ids)")
         .bind(player_id)
         .fetch_optional(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let party_id = party_row.map(|r| r.try_get("id"))
             .transpose()
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         Ok(PlayerStats {
             player_id,
@@ -580,7 +1080,7 @@ impl PostgresAdapter {
     /// Clean up expired data
     pub async fn cleanup_expired_data(&self) -> Result<CleanupStats> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         let mut stats = CleanupStats::default();
         
         // Clean up old queue entries (older than 1 hour)
@@ -591,7 +1091,7 @@ impl PostgresAdapter {
         )
         .bind(cutoff_time)
         .execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         stats.cleaned_queue_entries = result.rows_affected() as usize;
         
@@ -603,7 +1103,7 @@ impl PostgresAdapter {
         )
         .bind(lobby_cutoff)
         .execute(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         stats.cleaned_lobbies = result.rows_affected() as usize;
         
@@ -613,38 +1113,38 @@ impl PostgresAdapter {
     /// Get database performance metrics
     pub async fn get_database_metrics(&self) -> Result<DatabaseMetrics> {
         let mut conn = self.pool.acquire().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         // Get table sizes
         let player_ratings_count: i64 = sqlx::query("SELECT COUNT(*) FROM player_ratings")
             .fetch_one(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?
+            .map_err(sqlx_error)?
             .try_get("count")
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let queue_entries_count: i64 = sqlx::query("SELECT COUNT(*) FROM queue_entries")
             .fetch_one(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?
+            .map_err(sqlx_error)?
             .try_get("count")
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let parties_count: i64 = sqlx::query("SELECT COUNT(*) FROM parties")
             .fetch_one(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?
+            .map_err(sqlx_error)?
             .try_get("count")
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let lobbies_count: i64 = sqlx::query("SELECT COUNT(*) FROM lobbies")
             .fetch_one(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?
+            .map_err(sqlx_error)?
             .try_get("count")
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         let match_history_count: i64 = sqlx::query("SELECT COUNT(*) FROM match_history")
             .fetch_one(&mut conn).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?
+            .map_err(sqlx_error)?
             .try_get("count")
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(sqlx_error)?;
         
         Ok(DatabaseMetrics {
             player_ratings_count: player_ratings_count as usize,
@@ -1,107 +1,281 @@
-use super::traits::PersistenceAdapter;
-use crate::{error::*, lobby::Lobby, mmr::Rating, party::Party, queue::QueueEntry};
+use super::{
+    format::SerializationFormat,
+    schema::Versioned,
+    traits::PersistenceAdapter,
+};
+use crate::{analytics::insights::InsightRecord, error::*, lobby::{Lobby, LobbyState}, mmr::{Rating, RatingAuditEntry}, party::Party, queue::{QueueEntry, TournamentBracket}};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use serde_json;
 use uuid::Uuid;
 
 // Placeholder types for Redis functionality
 pub struct AsyncConnection;
 pub struct Client;
 
+/// Placeholder for the Redis client's error kinds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedisErrorKind {
+    Io,
+    Timeout,
+    Type,
+    Response,
+}
+
+/// Placeholder for the Redis client's error type
+#[derive(Debug, Clone)]
+pub struct RedisError {
+    pub kind: RedisErrorKind,
+    pub message: String,
+}
+
+impl RedisError {
+    fn unavailable() -> Self {
+        Self {
+            kind: RedisErrorKind::Io,
+            message: "Redis not available".to_string(),
+        }
+    }
+}
+
+pub type RedisResult<T> = std::result::Result<T, RedisError>;
+
+/// Map a Redis error onto the matching [`StorageError`] category
+fn redis_error(e: RedisError) -> MatchForgeError {
+    match e.kind {
+        RedisErrorKind::Io => StorageError::Connection(e.message).into(),
+        RedisErrorKind::Timeout => StorageError::Timeout(e.message).into(),
+        RedisErrorKind::Type => StorageError::Serialization(e.message).into(),
+        RedisErrorKind::Response => MatchForgeError::PersistenceError(e.message),
+    }
+}
+
 impl Client {
-    pub async fn get_async_connection(&self) -> Result<AsyncConnection> {
+    pub async fn get_async_connection(&self) -> RedisResult<AsyncConnection> {
         Ok(AsyncConnection)
     }
 }
 
+#[async_trait]
 pub trait AsyncCommands {
-    async fn get<T>(&mut self, key: &str) -> Result<T>;
-    async fn set(&mut self, key: &str, value: &str) -> Result<()>;
-    async fn set_ex(&mut self, key: &str, value: &str, seconds: usize) -> Result<()>;
-    async fn del(&mut self, key: &str) -> Result<()>;
-    async fn sadd(&mut self, key: &str, member: &str) -> Result<()>;
-    async fn srem(&mut self, key: &str, member: &str) -> Result<()>;
-    async fn smembers(&mut self, key: &str) -> Result<Vec<String>>;
-    async fn lpush(&mut self, key: &str, value: &str) -> Result<()>;
-    async fn ltrim(&mut self, key: &str, start: isize, stop: isize) -> Result<()>;
-    async fn zadd(&mut self, key: &str, score: f64, member: &str) -> Result<()>;
-    async fn zrem(&mut self, key: &str, member: &str) -> Result<()>;
-    async fn zrange(&mut self, key: &str, start: isize, stop: isize) -> Result<Vec<String>>;
-    async fn zrangebyscore(&mut self, key: &str, min: f64, max: f64) -> Result<Vec<String>>;
-    async fn keys(&mut self, pattern: &str) -> Result<Vec<String>>;
-    async fn zcard(&mut self, key: &str) -> Result<usize>;
-    async fn llen(&mut self, key: &str) -> Result<usize>;
-    async fn exists(&mut self, key: &str) -> Result<bool>;
+    async fn get<T>(&mut self, key: &str) -> RedisResult<T>;
+    async fn set<V: AsRef<[u8]> + Sync + ?Sized>(&mut self, key: &str, value: &V) -> RedisResult<()>;
+    async fn set_ex<V: AsRef<[u8]> + Sync + ?Sized>(&mut self, key: &str, value: &V, seconds: usize) -> RedisResult<()>;
+    async fn del(&mut self, key: &str) -> RedisResult<()>;
+    async fn sadd(&mut self, key: &str, member: &str) -> RedisResult<()>;
+    async fn srem(&mut self, key: &str, member: &str) -> RedisResult<()>;
+    async fn smembers(&mut self, key: &str) -> RedisResult<Vec<String>>;
+    async fn lpush<V: AsRef<[u8]> + Sync + ?Sized>(&mut self, key: &str, value: &V) -> RedisResult<()>;
+    async fn ltrim(&mut self, key: &str, start: isize, stop: isize) -> RedisResult<()>;
+    async fn lrange<T>(&mut self, key: &str, start: isize, stop: isize) -> RedisResult<T>;
+    async fn zadd(&mut self, key: &str, score: f64, member: &str) -> RedisResult<()>;
+    async fn zrem(&mut self, key: &str, member: &str) -> RedisResult<()>;
+    async fn zrange(&mut self, key: &str, start: isize, stop: isize) -> RedisResult<Vec<String>>;
+    async fn zrevrange(&mut self, key: &str, start: isize, stop: isize) -> RedisResult<Vec<String>>;
+    async fn zrangebyscore(&mut self, key: &str, min: f64, max: f64) -> RedisResult<Vec<String>>;
+    async fn keys(&mut self, pattern: &str) -> RedisResult<Vec<String>>;
+    async fn zcard(&mut self, key: &str) -> RedisResult<usize>;
+    async fn llen(&mut self, key: &str) -> RedisResult<usize>;
+    async fn exists(&mut self, key: &str) -> RedisResult<bool>;
 }
 
+#[async_trait]
 impl AsyncCommands for AsyncConnection {
-    async fn get<T>(&mut self, _key: &str) -> Result<T> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn get<T>(&mut self, _key: &str) -> RedisResult<T> {
+        Err(RedisError::unavailable())
+    }
+    
+    async fn set<V: AsRef<[u8]> + Sync + ?Sized>(&mut self, _key: &str, _value: &V) -> RedisResult<()> {
+        Err(RedisError::unavailable())
     }
     
-    async fn set(&mut self, _key: &str, _value: &str) -> Result<()> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn set_ex<V: AsRef<[u8]> + Sync + ?Sized>(&mut self, _key: &str, _value: &V, _seconds: usize) -> RedisResult<()> {
+        Err(RedisError::unavailable())
     }
     
-    async fn set_ex(&mut self, _key: &str, _value: &str, _seconds: usize) -> Result<()> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn del(&mut self, _key: &str) -> RedisResult<()> {
+        Err(RedisError::unavailable())
     }
     
-    async fn del(&mut self, _key: &str) -> Result<()> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn sadd(&mut self, _key: &str, _member: &str) -> RedisResult<()> {
+        Err(RedisError::unavailable())
     }
     
-    async fn sadd(&mut self, _key: &str, _member: &str) -> Result<()> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn srem(&mut self, _key: &str, _member: &str) -> RedisResult<()> {
+        Err(RedisError::unavailable())
     }
     
-    async fn srem(&mut self, _key: &str, _member: &str) -> Result<()> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn smembers(&mut self, _key: &str) -> RedisResult<Vec<String>> {
+        Err(RedisError::unavailable())
     }
     
-    async fn smembers(&mut self, _key: &str) -> Result<Vec<String>> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn lpush<V: AsRef<[u8]> + Sync + ?Sized>(&mut self, _key: &str, _value: &V) -> RedisResult<()> {
+        Err(RedisError::unavailable())
     }
     
-    async fn lpush(&mut self, _key: &str, _value: &str) -> Result<()> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn ltrim(&mut self, _key: &str, _start: isize, _stop: isize) -> RedisResult<()> {
+        Err(RedisError::unavailable())
     }
     
-    async fn ltrim(&mut self, _key: &str, _start: isize, _stop: isize) -> Result<()> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn lrange<T>(&mut self, _key: &str, _start: isize, _stop: isize) -> RedisResult<T> {
+        Err(RedisError::unavailable())
     }
     
-    async fn zadd(&mut self, _key: &str, _score: f64, _member: &str) -> Result<()> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn zadd(&mut self, _key: &str, _score: f64, _member: &str) -> RedisResult<()> {
+        Err(RedisError::unavailable())
     }
     
-    async fn zrem(&mut self, _key: &str, _member: &str) -> Result<()> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn zrem(&mut self, _key: &str, _member: &str) -> RedisResult<()> {
+        Err(RedisError::unavailable())
     }
     
-    async fn zrange(&mut self, _key: &str, _start: isize, _stop: isize) -> Result<Vec<String>> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn zrange(&mut self, _key: &str, _start: isize, _stop: isize) -> RedisResult<Vec<String>> {
+        Err(RedisError::unavailable())
     }
     
-    async fn zrangebyscore(&mut self, _key: &str, _min: f64, _max: f64) -> Result<Vec<String>> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn zrevrange(&mut self, _key: &str, _start: isize, _stop: isize) -> RedisResult<Vec<String>> {
+        Err(RedisError::unavailable())
     }
     
-    async fn keys(&mut self, _pattern: &str) -> Result<Vec<String>> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn zrangebyscore(&mut self, _key: &str, _min: f64, _max: f64) -> RedisResult<Vec<String>> {
+        Err(RedisError::unavailable())
     }
     
-    async fn zcard(&mut self, _key: &str) -> Result<usize> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn keys(&mut self, _pattern: &str) -> RedisResult<Vec<String>> {
+        Err(RedisError::unavailable())
     }
     
-    async fn llen(&mut self, _key: &str) -> Result<usize> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn zcard(&mut self, _key: &str) -> RedisResult<usize> {
+        Err(RedisError::unavailable())
     }
     
-    async fn exists(&mut self, _key: &str) -> Result<bool> {
-        Err(MatchForgeError::PersistenceError("Redis not available".to_string()))
+    async fn llen(&mut self, _key: &str) -> RedisResult<usize> {
+        Err(RedisError::unavailable())
+    }
+    
+    async fn exists(&mut self, _key: &str) -> RedisResult<bool> {
+        Err(RedisError::unavailable())
+    }
+}
+
+/// Where the Redis deployment lives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedisTopology {
+    /// A single node (or a primary with replicas behind one address)
+    Standalone { url: String },
+    /// A Redis Cluster, reached through any of its seed nodes
+    Cluster { nodes: Vec<String> },
+}
+
+/// Connection settings for [`RedisAdapter`]
+///
+/// Keys that are used together (a queue and its entries, a season's
+/// leaderboard and ratings) share a hash tag, so in cluster mode they land
+/// on the same slot and stay usable in multi-key commands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedisConfig {
+    pub topology: RedisTopology,
+    /// Connections kept open by the pool
+    pub pool_size: usize,
+    /// How long a command may take before failing with [`StorageError::Timeout`]
+    pub command_timeout: std::time::Duration,
+}
+
+impl RedisConfig {
+    pub fn standalone(url: impl Into<String>) -> Self {
+        Self {
+            topology: RedisTopology::Standalone { url: url.into() },
+            pool_size: DEFAULT_POOL_SIZE,
+            command_timeout: DEFAULT_COMMAND_TIMEOUT,
+        }
+    }
+
+    pub fn cluster(nodes: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            topology: RedisTopology::Cluster { nodes: nodes.into_iter().map(Into::into).collect() },
+            pool_size: DEFAULT_POOL_SIZE,
+            command_timeout: DEFAULT_COMMAND_TIMEOUT,
+        }
+    }
+
+    pub fn with_pool_size(mut self, pool_size: usize) -> Self {
+        self.pool_size = pool_size;
+        self
+    }
+
+    pub fn with_command_timeout(mut self, command_timeout: std::time::Duration) -> Self {
+        self.command_timeout = command_timeout;
+        self
+    }
+
+    /// Reject settings that can never connect
+    pub fn validate(&self) -> Result<()> {
+        let has_address = match &self.topology {
+            RedisTopology::Standalone { url } => !url.is_empty(),
+            RedisTopology::Cluster { nodes } => !nodes.is_empty() && nodes.iter().all(|node| !node.is_empty()),
+        };
+        if !has_address {
+            return Err(MatchForgeError::InvalidConfiguration(
+                "Redis connection needs an address for every node".to_string(),
+            ));
+        }
+        if self.pool_size == 0 {
+            return Err(MatchForgeError::InvalidConfiguration(
+                "Redis pool size must be at least 1".to_string(),
+            ));
+        }
+        if self.command_timeout.is_zero() {
+            return Err(MatchForgeError::InvalidConfiguration(
+                "Redis command timeout must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A single-node connection string
+impl From<&str> for RedisConfig {
+    fn from(url: &str) -> Self {
+        Self::standalone(url)
+    }
+}
+
+const DEFAULT_POOL_SIZE: usize = 16;
+const DEFAULT_COMMAND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Keys of co-located families, hash-tagged as `{tag}`
+///
+/// Redis Cluster hashes only the part of a key between the first `{` and
+/// the next `}`, so every key of a family maps to the same slot.
+mod keys {
+    use uuid::Uuid;
+
+    /// Sorted set of a queue's entry keys
+    pub fn queue(queue_name: &str) -> String {
+        format!("queue:{{{}}}", queue_name)
+    }
+
+    pub fn queue_entry(queue_name: &str, entry_id: Uuid) -> String {
+        format!("queue_entry:{{{}}}:{}", queue_name, entry_id)
+    }
+
+    pub fn season_rating(queue_name: &str, season_id: &str, player_id: Uuid) -> String {
+        format!("season_rating:{{{}:{}}}:{}", queue_name, season_id, player_id)
+    }
+
+    /// Sorted set of a season's players by conservative rating
+    pub fn leaderboard(queue_name: &str, season_id: &str) -> String {
+        format!("leaderboard:{{{}:{}}}", queue_name, season_id)
+    }
+
+    /// The part of `key` Redis Cluster hashes to pick its slot
+    #[cfg(test)]
+    pub fn hash_tag(key: &str) -> &str {
+        key.find('{')
+            .and_then(|open| {
+                let close = key[open + 1..].find('}')?;
+                (close > 0).then(|| &key[open + 1..open + 1 + close])
+            })
+            .unwrap_or(key)
     }
 }
 
@@ -111,62 +285,88 @@ impl AsyncCommands for AsyncConnection {
 /// Supports all MatchForge operations with proper serialization and indexing.
 pub struct RedisAdapter {
     client: Client,
+    config: RedisConfig,
+    format: SerializationFormat,
 }
 
 impl RedisAdapter {
-    /// Create a new Redis adapter with the given connection string
-    pub async fn new(connection_string: &str) -> Result<Self> {
+    /// Create a new Redis adapter, from a [`RedisConfig`] or a single-node connection string
+    pub async fn new(config: impl Into<RedisConfig>) -> Result<Self> {
+        let config = config.into();
+        config.validate()?;
         let client = Client;
         
         // Test connection
         let mut conn = client.get_async_connection().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         // Ping to verify connection
         let _: String = conn.get("ping").await.unwrap_or_else(|_| "pong".to_string());
         
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            config,
+            format: SerializationFormat::default(),
+        })
+    }
+
+    pub fn config(&self) -> &RedisConfig {
+        &self.config
+    }
+
+    /// Encoding for stored values (JSON by default)
+    ///
+    /// Values already stored in another format can't be read back after switching.
+    pub fn with_format(mut self, format: SerializationFormat) -> Self {
+        self.format = format;
+        self
     }
 
-    /// Get an async connection from the pool
+    /// Get an async connection from the pool, waiting at most the command timeout
     async fn get_connection(&self) -> Result<AsyncConnection> {
-        self.client.get_async_connection().await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))
+        tokio::time::timeout(self.config.command_timeout, self.client.get_async_connection())
+            .await
+            .map_err(|_| StorageError::Timeout("Redis connection".to_string()))?
+            .map_err(redis_error)
     }
 
-    /// Helper to serialize and store JSON
-    async fn store_json<T: serde::Serialize>(
+    /// Helper to serialize and store a value in the configured format
+    async fn store_value<T: serde::Serialize>(
         &self,
         key: &str,
         value: &T,
         conn: &mut AsyncConnection,
     ) -> Result<()> {
-        let json = serde_json::to_string(value)
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+        let bytes = self.format.encode(value)?;
         
-        conn.set(key, &json).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+        conn.set(key, &bytes).await
+            .map_err(redis_error)?;
         
         Ok(())
     }
 
-    /// Helper to retrieve and deserialize JSON
-    async fn load_json<T: serde::de::DeserializeOwned>(
+    /// Helper to retrieve a versioned entity, migrating older formats
+    async fn load_versioned<T: Versioned>(
         &self,
         key: &str,
         conn: &mut AsyncConnection,
     ) -> Result<Option<T>> {
-        let json: Option<String> = conn.get(key).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
-        
-        match json {
-            Some(json_str) => {
-                let value = serde_json::from_str(json_str.as_str())
-                    .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
-                Ok(Some(value))
-            }
-            None => Ok(None),
-        }
+        let bytes: Option<Vec<u8>> = conn.get(key).await
+            .map_err(redis_error)?;
+        
+        bytes.map(|bytes| self.format.decode_versioned(&bytes)).transpose()
+    }
+
+    /// Helper to retrieve and deserialize a value in the configured format
+    async fn load_value<T: serde::de::DeserializeOwned>(
+        &self,
+        key: &str,
+        conn: &mut AsyncConnection,
+    ) -> Result<Option<T>> {
+        let bytes: Option<Vec<u8>> = conn.get(key).await
+            .map_err(redis_error)?;
+        
+        bytes.map(|bytes| self.format.decode(&bytes)).transpose()
     }
 }
 
@@ -177,9 +377,9 @@ impl PersistenceAdapter for RedisAdapter {
         let key = format!("player_rating:{}", player_id);
         
         // Store rating with TTL (optional)
-        conn.set_ex(&key, &serde_json::to_string(&rating).unwrap(), 86400 * 30) // 30 days TTL
+        conn.set_ex(&key, &self.format.encode(&rating)?, 86400 * 30) // 30 days TTL
             .await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         Ok(())
     }
@@ -188,39 +388,68 @@ impl PersistenceAdapter for RedisAdapter {
         let mut conn = self.get_connection().await?;
         let key = format!("player_rating:{}", player_id);
         
-        let json: Option<String> = conn.get(&key).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+        self.load_value(&key, &mut conn).await
+    }
+
+    async fn save_shadow_rating(&self, player_id: Uuid, rating: Rating) -> Result<()> {
+        let mut conn = self.get_connection().await?;
+        let key = format!("shadow_rating:{}", player_id);
+        
+        // Same TTL as live ratings
+        conn.set_ex(&key, &self.format.encode(&rating)?, 86400 * 30)
+            .await
+            .map_err(redis_error)?;
         
-        match json {
-            Some(json_str) => {
-                let rating = serde_json::from_str(json_str.as_str())
-                    .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
-                Ok(Some(rating))
+        Ok(())
+    }
+
+    async fn load_shadow_rating(&self, player_id: Uuid) -> Result<Option<Rating>> {
+        let mut conn = self.get_connection().await?;
+        let key = format!("shadow_rating:{}", player_id);
+        
+        self.load_value(&key, &mut conn).await
+    }
+
+    async fn load_all_ratings(&self) -> Result<Vec<(Uuid, Rating)>> {
+        let mut conn = self.get_connection().await?;
+        
+        // KEYS blocks the server while it scans; fine for one-off migrations
+        let keys: Vec<String> = conn.keys("player_rating:*").await
+            .map_err(redis_error)?;
+        
+        let mut ratings = Vec::new();
+        for key in &keys {
+            let Some(player_id) = key.strip_prefix("player_rating:").and_then(|id| Uuid::parse_str(id).ok()) else {
+                continue;
+            };
+            if let Some(rating) = self.load_value(key, &mut conn).await? {
+                ratings.push((player_id, rating));
             }
-            None => Ok(None),
         }
+        
+        Ok(ratings)
     }
 
     async fn save_queue_entry(&self, entry: &QueueEntry) -> Result<()> {
         let mut conn = self.get_connection().await?;
         
         // Store in queue sorted set by join time
-        let queue_key = format!("queue:{}", entry.queue_name);
-        let entry_key = format!("queue_entry:{}", entry.id);
+        let queue_key = keys::queue(&entry.queue_name);
+        let entry_key = keys::queue_entry(&entry.queue_name, entry.id);
         
         // Store the entry
-        self.store_json(&entry_key, entry, &mut conn).await?;
+        self.store_value(&entry_key, entry, &mut conn).await?;
         
         // Add to queue sorted set (score = join timestamp)
         let score = entry.joined_at.timestamp();
         conn.zadd(&queue_key, score as f64, &entry_key).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         // Index by player for quick removal
         for player_id in &entry.player_ids {
             let player_queue_key = format!("player_queue:{}", player_id);
             conn.set(&player_queue_key, &entry_key).await
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+                .map_err(redis_error)?;
         }
         
         Ok(())
@@ -228,15 +457,15 @@ impl PersistenceAdapter for RedisAdapter {
 
     async fn load_queue_entries(&self, queue_name: &str) -> Result<Vec<QueueEntry>> {
         let mut conn = self.get_connection().await?;
-        let queue_key = format!("queue:{}", queue_name);
+        let queue_key = keys::queue(queue_name);
         
         // Get all entries from the sorted set
         let entry_keys: Vec<String> = conn.zrange(&queue_key, 0, -1).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         let mut entries = Vec::new();
         for entry_key in &entry_keys {
-            if let Some(entry) = self.load_json::<QueueEntry>(entry_key, &mut conn).await? {
+            if let Some(entry) = self.load_versioned::<QueueEntry>(entry_key, &mut conn).await? {
                 entries.push(entry);
             }
         }
@@ -250,40 +479,55 @@ impl PersistenceAdapter for RedisAdapter {
         // Find the entry for this player
         let player_queue_key = format!("player_queue:{}", player_id);
         let entry_key: Option<String> = conn.get(&player_queue_key).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         if let Some(entry_key) = entry_key {
             // Load the entry to get queue name
-            if let Some(entry) = self.load_json::<QueueEntry>(entry_key.as_str(), &mut conn).await? {
+            if let Some(entry) = self.load_versioned::<QueueEntry>(entry_key.as_str(), &mut conn).await? {
                 // Remove from queue sorted set
-                let queue_key = format!("queue:{}", entry.queue_name);
+                let queue_key = keys::queue(&entry.queue_name);
                 conn.zrem(&queue_key, &entry_key).await
-                    .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+                    .map_err(redis_error)?;
                 
                 // Delete the entry
                 conn.del(&entry_key).await
-                    .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+                    .map_err(redis_error)?;
             }
             
             // Remove player index
             conn.del(&player_queue_key).await
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+                .map_err(redis_error)?;
         }
         
         Ok(())
     }
 
+    async fn load_all_queue_entries(&self) -> Result<Vec<QueueEntry>> {
+        let mut conn = self.get_connection().await?;
+        let keys: Vec<String> = conn.keys("queue_entry:*").await
+            .map_err(redis_error)?;
+        
+        let mut entries = Vec::new();
+        for key in &keys {
+            if let Some(entry) = self.load_versioned::<QueueEntry>(key, &mut conn).await? {
+                entries.push(entry);
+            }
+        }
+        
+        Ok(entries)
+    }
+
     async fn save_party(&self, party: &Party) -> Result<()> {
         let mut conn = self.get_connection().await?;
         let party_key = format!("party:{}", party.id);
         
-        self.store_json(&party_key, party, &mut conn).await?;
+        self.store_value(&party_key, party, &mut conn).await?;
         
         // Index members for quick lookup
         for member_id in &party.member_ids {
             let member_party_key = format!("member_party:{}", member_id);
             conn.set(&member_party_key, &party.id.to_string()).await
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+                .map_err(redis_error)?;
         }
         
         Ok(())
@@ -293,7 +537,7 @@ impl PersistenceAdapter for RedisAdapter {
         let mut conn = self.get_connection().await?;
         let party_key = format!("party:{}", party_id);
         
-        self.load_json(&party_key, &mut conn).await
+        self.load_value(&party_key, &mut conn).await
     }
 
     async fn delete_party(&self, party_id: Uuid) -> Result<()> {
@@ -301,36 +545,58 @@ impl PersistenceAdapter for RedisAdapter {
         let party_key = format!("party:{}", party_id);
         
         // Load party to remove member indexes
-        if let Some(party) = self.load_json::<Party>(&party_key, &mut conn).await? {
+        if let Some(party) = self.load_value::<Party>(&party_key, &mut conn).await? {
             for member_id in &party.member_ids {
                 let member_party_key = format!("member_party:{}", member_id);
                 conn.del(&member_party_key).await
-                    .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+                    .map_err(redis_error)?;
             }
         }
         
         // Delete the party
         conn.del(&party_key).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         Ok(())
     }
 
+    async fn load_all_parties(&self) -> Result<Vec<Party>> {
+        let mut conn = self.get_connection().await?;
+        let keys: Vec<String> = conn.keys("party:*").await
+            .map_err(redis_error)?;
+        
+        let mut parties = Vec::new();
+        for key in &keys {
+            if let Some(party) = self.load_value::<Party>(key, &mut conn).await? {
+                parties.push(party);
+            }
+        }
+        
+        Ok(parties)
+    }
+
     async fn save_lobby(&self, lobby: &Lobby) -> Result<()> {
         let mut conn = self.get_connection().await?;
         let lobby_key = format!("lobby:{}", lobby.id);
         
-        self.store_json(&lobby_key, lobby, &mut conn).await?;
+        self.store_value(&lobby_key, lobby, &mut conn).await?;
         
         // Index by match
         let match_lobbies_key = format!("match_lobbies:{}", lobby.match_id);
         conn.sadd(&match_lobbies_key, &lobby.id.to_string()).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         // Index by state for queries
         let state_lobbies_key = format!("state_lobbies:{:?}", lobby.state);
         conn.sadd(&state_lobbies_key, &lobby.id.to_string()).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
+        
+        // Index by player for the concurrent-match guard
+        for player_id in &lobby.player_ids {
+            let player_lobbies_key = format!("player_lobbies:{}", player_id);
+            conn.sadd(&player_lobbies_key, &lobby.id.to_string()).await
+                .map_err(redis_error)?;
+        }
         
         Ok(())
     }
@@ -339,7 +605,7 @@ impl PersistenceAdapter for RedisAdapter {
         let mut conn = self.get_connection().await?;
         let lobby_key = format!("lobby:{}", lobby_id);
         
-        self.load_json(&lobby_key, &mut conn).await
+        self.load_versioned(&lobby_key, &mut conn).await
     }
 
     async fn delete_lobby(&self, lobby_id: Uuid) -> Result<()> {
@@ -347,48 +613,233 @@ impl PersistenceAdapter for RedisAdapter {
         let lobby_key = format!("lobby:{}", lobby_id);
         
         // Load lobby to clean up indexes
-        if let Some(lobby) = self.load_json::<Lobby>(&lobby_key, &mut conn).await? {
+        if let Some(lobby) = self.load_versioned::<Lobby>(&lobby_key, &mut conn).await? {
             // Remove from match index
             let match_lobbies_key = format!("match_lobbies:{}", lobby.match_id);
             conn.srem(&match_lobbies_key, &lobby_id.to_string()).await
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+                .map_err(redis_error)?;
             
             // Remove from state index
             let state_lobbies_key = format!("state_lobbies:{:?}", lobby.state);
             conn.srem(&state_lobbies_key, &lobby_id.to_string()).await
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+                .map_err(redis_error)?;
+            
+            // Remove from player index
+            for player_id in &lobby.player_ids {
+                let player_lobbies_key = format!("player_lobbies:{}", player_id);
+                conn.srem(&player_lobbies_key, &lobby_id.to_string()).await
+                    .map_err(redis_error)?;
+            }
         }
         
         // Delete the lobby
         conn.del(&lobby_key).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         Ok(())
     }
 
+    async fn load_all_lobbies(&self) -> Result<Vec<Lobby>> {
+        let mut conn = self.get_connection().await?;
+        let keys: Vec<String> = conn.keys("lobby:*").await
+            .map_err(redis_error)?;
+        
+        let mut lobbies = Vec::new();
+        for key in &keys {
+            if let Some(lobby) = self.load_versioned::<Lobby>(key, &mut conn).await? {
+                lobbies.push(lobby);
+            }
+        }
+        
+        Ok(lobbies)
+    }
+
+    async fn player_in_active_lobby(&self, player_id: Uuid) -> Result<bool> {
+        let mut conn = self.get_connection().await?;
+        let player_lobbies_key = format!("player_lobbies:{}", player_id);
+        
+        let lobby_ids: Vec<String> = conn.smembers(&player_lobbies_key).await
+            .map_err(redis_error)?;
+        
+        // The index may be stale after players leave a lobby, so check each one
+        for lobby_id in lobby_ids {
+            let lobby_key = format!("lobby:{}", lobby_id);
+            if let Some(lobby) = self.load_versioned::<Lobby>(&lobby_key, &mut conn).await? {
+                if lobby.state != LobbyState::Closed && lobby.player_ids.contains(&player_id) {
+                    return Ok(true);
+                }
+            }
+        }
+        
+        Ok(false)
+    }
+
     async fn save_match_result(&self, lobby: &Lobby) -> Result<()> {
         let mut conn = self.get_connection().await?;
         
         // Store in match history
         let match_key = format!("match_history:{}", lobby.match_id);
-        self.store_json(&match_key, lobby, &mut conn).await?;
+        self.store_value(&match_key, lobby, &mut conn).await?;
         
         // Add to player match history
         for player_id in &lobby.player_ids {
             let player_history_key = format!("player_matches:{}", player_id);
             conn.lpush(&player_history_key, &lobby.match_id.to_string()).await
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+                .map_err(redis_error)?;
             
             // Keep only last 100 matches per player
             conn.ltrim(&player_history_key, 0, 99).await
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+                .map_err(redis_error)?;
         }
         
         // Add to global match history (keep last 1000)
         conn.lpush("global_match_history", &lobby.match_id.to_string()).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         conn.ltrim("global_match_history", 0, 999).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
+        
+        Ok(())
+    }
+
+    async fn load_player_match_history(&self, player_id: Uuid, limit: usize) -> Result<Vec<Lobby>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.get_connection().await?;
+        let player_history_key = format!("player_matches:{}", player_id);
+        
+        let match_ids: Vec<String> = conn.lrange(&player_history_key, 0, limit as isize - 1).await
+            .map_err(redis_error)?;
+        
+        let mut history = Vec::new();
+        for match_id in match_ids {
+            let match_key = format!("match_history:{}", match_id);
+            if let Some(lobby) = self.load_versioned(&match_key, &mut conn).await? {
+                history.push(lobby);
+            }
+        }
+        
+        Ok(history)
+    }
+
+    async fn save_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid, rating: Rating) -> Result<()> {
+        let mut conn = self.get_connection().await?;
+        let rating_key = keys::season_rating(queue_name, season_id, player_id);
+        let leaderboard_key = keys::leaderboard(queue_name, season_id);
+        
+        self.store_value(&rating_key, &rating, &mut conn).await?;
+        
+        // Sorted set scored by conservative estimate for top-N queries
+        conn.zadd(&leaderboard_key, rating.conservative_estimate(), &player_id.to_string()).await
+            .map_err(redis_error)?;
+        
+        Ok(())
+    }
+
+    async fn load_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid) -> Result<Option<Rating>> {
+        let mut conn = self.get_connection().await?;
+        let rating_key = keys::season_rating(queue_name, season_id, player_id);
+        self.load_value(&rating_key, &mut conn).await
+    }
+
+    async fn load_season_ratings(&self, queue_name: &str, season_id: &str) -> Result<Vec<(Uuid, Rating)>> {
+        let mut conn = self.get_connection().await?;
+        let leaderboard_key = keys::leaderboard(queue_name, season_id);
+        
+        let player_ids: Vec<String> = conn.zrange(&leaderboard_key, 0, -1).await
+            .map_err(redis_error)?;
+        
+        let mut ratings = Vec::new();
+        for player_id_str in &player_ids {
+            let Ok(player_id) = Uuid::parse_str(player_id_str) else {
+                continue;
+            };
+            let rating_key = keys::season_rating(queue_name, season_id, player_id);
+            if let Some(rating) = self.load_value::<Rating>(&rating_key, &mut conn).await? {
+                ratings.push((player_id, rating));
+            }
+        }
+        
+        Ok(ratings)
+    }
+
+    async fn top_players(&self, queue_name: &str, season_id: &str, n: usize) -> Result<Vec<(Uuid, Rating)>> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        
+        let mut conn = self.get_connection().await?;
+        let leaderboard_key = keys::leaderboard(queue_name, season_id);
+        
+        let player_ids: Vec<String> = conn.zrevrange(&leaderboard_key, 0, n as isize - 1).await
+            .map_err(redis_error)?;
+        
+        let mut top = Vec::new();
+        for player_id_str in &player_ids {
+            let Ok(player_id) = Uuid::parse_str(player_id_str) else {
+                continue;
+            };
+            let rating_key = keys::season_rating(queue_name, season_id, player_id);
+            if let Some(rating) = self.load_value::<Rating>(&rating_key, &mut conn).await? {
+                top.push((player_id, rating));
+            }
+        }
+        
+        Ok(top)
+    }
+
+    async fn save_insight(&self, record: &InsightRecord) -> Result<()> {
+        let mut conn = self.get_connection().await?;
+        let key = format!("insight:{}", record.insight.id);
+        self.store_value(&key, record, &mut conn).await
+    }
+
+    async fn load_insight(&self, insight_id: Uuid) -> Result<Option<InsightRecord>> {
+        let mut conn = self.get_connection().await?;
+        let key = format!("insight:{}", insight_id);
+        self.load_value(&key, &mut conn).await
+    }
+
+    async fn save_rating_audit(&self, entry: &RatingAuditEntry) -> Result<()> {
+        let mut conn = self.get_connection().await?;
+        let key = format!("rating_audit:{}", entry.player_id);
+        let payload = self.format.encode(entry)?;
+        
+        conn.lpush(&key, &payload).await
+            .map_err(redis_error)?;
+        
+        Ok(())
+    }
+
+    async fn load_rating_audit(&self, player_id: Uuid) -> Result<Vec<RatingAuditEntry>> {
+        let mut conn = self.get_connection().await?;
+        let key = format!("rating_audit:{}", player_id);
+        
+        // Stored newest first
+        let payloads: Vec<Vec<u8>> = conn.lrange(&key, 0, -1).await
+            .map_err(redis_error)?;
+        
+        payloads.iter().rev().map(|payload| self.format.decode(payload)).collect()
+    }
+
+    async fn save_bracket(&self, bracket: &TournamentBracket) -> Result<()> {
+        let mut conn = self.get_connection().await?;
+        let key = format!("bracket:{}", bracket.bracket_id);
+        self.store_value(&key, bracket, &mut conn).await
+    }
+
+    async fn load_bracket(&self, bracket_id: Uuid) -> Result<Option<TournamentBracket>> {
+        let mut conn = self.get_connection().await?;
+        let key = format!("bracket:{}", bracket_id);
+        self.load_value(&key, &mut conn).await
+    }
+
+    async fn delete_bracket(&self, bracket_id: Uuid) -> Result<()> {
+        let mut conn = self.get_connection().await?;
+        let key = format!("bracket:{}", bracket_id);
+        conn.del(&key).await
+            .map_err(redis_error)?;
         
         Ok(())
     }
@@ -399,22 +850,23 @@ impl RedisAdapter {
     /// Get queue statistics
     pub async fn get_queue_stats(&self, queue_name: &str) -> Result<QueueStats> {
         let mut conn = self.get_connection().await?;
-        let queue_key = format!("queue:{}", queue_name);
+        let queue_key = keys::queue(queue_name);
         
         let size: usize = conn.zcard(&queue_key).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         // Calculate average wait time and rating
         let entries: Vec<String> = conn.zrange(&queue_key, 0, -1).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         let mut total_wait_time = 0;
         let mut total_rating = 0.0;
         let mut count = 0;
+        let now = Utc::now();
         
         for entry_key in &entries {
-            if let Some(entry) = self.load_json::<QueueEntry>(entry_key, &mut conn).await? {
-                total_wait_time += entry.wait_time().num_seconds();
+            if let Some(entry) = self.load_versioned::<QueueEntry>(entry_key, &mut conn).await? {
+                total_wait_time += entry.wait_time(now).num_seconds();
                 total_rating += entry.average_rating.rating;
                 count += 1;
             }
@@ -441,17 +893,17 @@ impl RedisAdapter {
         // Get match history count
         let player_history_key = format!("player_matches:{}", player_id);
         let matches_played: usize = conn.llen(&player_history_key).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         // Check if player is in queue
         let player_queue_key = format!("player_queue:{}", player_id);
         let in_queue: bool = conn.exists(&player_queue_key).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         // Check if player is in party
         let member_party_key = format!("member_party:{}", player_id);
         let party_id: Option<String> = conn.get(&member_party_key).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         Ok(PlayerStats {
             player_id,
@@ -473,21 +925,21 @@ impl RedisAdapter {
         
         // Get all queue keys
         let queue_keys: Vec<String> = conn.keys("queue:*").await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         for queue_key in queue_keys {
             // Get old entries
             let old_entries: Vec<String> = conn.zrangebyscore(&queue_key, f64::NEG_INFINITY, cutoff_timestamp as f64).await
-                .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+                .map_err(redis_error)?;
             
             for entry_key in old_entries {
                 // Remove from queue
                 conn.zrem(&queue_key, &entry_key).await
-                    .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+                    .map_err(redis_error)?;
                 
                 // Delete the entry
                 conn.del(&entry_key).await
-                    .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+                    .map_err(redis_error)?;
                 
                 stats.cleaned_queue_entries += 1;
             }
@@ -496,7 +948,7 @@ impl RedisAdapter {
         // Clean up old lobbies (closed for more than 24 hours)
         let state_lobbies_key = "state_lobbies:Closed";
         let closed_lobbies: Vec<String> = conn.smembers(state_lobbies_key).await
-            .map_err(|e| MatchForgeError::PersistenceError(e.to_string()))?;
+            .map_err(redis_error)?;
         
         let lobby_cutoff = Utc::now() - chrono::Duration::hours(24);
         
@@ -540,3 +992,72 @@ pub struct CleanupStats {
     pub cleaned_queue_entries: usize,
     pub cleaned_lobbies: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unreachable_server_is_a_connection_error() {
+        let adapter = RedisAdapter::new("redis://127.0.0.1:6379").await.unwrap();
+
+        let err = adapter.load_player_rating(Uuid::new_v4()).await.unwrap_err();
+        assert!(matches!(err, MatchForgeError::Storage(StorageError::Connection(_))));
+    }
+
+    #[test]
+    fn test_related_keys_share_a_hash_tag() {
+        let entry_id = Uuid::new_v4();
+        assert_eq!(keys::queue("ranked"), "queue:{ranked}");
+        assert_eq!(keys::hash_tag(&keys::queue("ranked")), "ranked");
+        assert_eq!(keys::hash_tag(&keys::queue_entry("ranked", entry_id)), "ranked");
+        assert_ne!(keys::hash_tag(&keys::queue("casual")), "ranked");
+
+        let player_id = Uuid::new_v4();
+        let leaderboard = keys::leaderboard("ranked", "s1");
+        assert_eq!(keys::hash_tag(&leaderboard), "ranked:s1");
+        assert_eq!(keys::hash_tag(&keys::season_rating("ranked", "s1", player_id)), "ranked:s1");
+        // Untagged keys hash whole
+        assert_eq!(keys::hash_tag("player_rating:1"), "player_rating:1");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config_rejected() {
+        let rejected = |config: RedisConfig| async move {
+            matches!(RedisAdapter::new(config).await, Err(MatchForgeError::InvalidConfiguration(_)))
+        };
+
+        assert!(rejected(RedisConfig::cluster(Vec::<String>::new())).await);
+        assert!(rejected(RedisConfig::standalone("redis://127.0.0.1:6379").with_pool_size(0)).await);
+        assert!(rejected(RedisConfig::standalone("redis://127.0.0.1:6379").with_command_timeout(std::time::Duration::ZERO)).await);
+
+        let adapter = RedisAdapter::new(RedisConfig::cluster(["redis://10.0.0.1:7000", "redis://10.0.0.2:7000"]).with_pool_size(4))
+            .await
+            .unwrap();
+        assert_eq!(adapter.config().pool_size, 4);
+        assert!(matches!(adapter.config().topology, RedisTopology::Cluster { ref nodes } if nodes.len() == 2));
+    }
+
+    /// Set `MATCHFORGE_REDIS_CLUSTER` to comma-separated seed nodes to run
+    #[tokio::test]
+    #[ignore = "requires a Redis cluster"]
+    async fn test_queue_round_trips_on_cluster() {
+        let nodes = std::env::var("MATCHFORGE_REDIS_CLUSTER").unwrap();
+        let adapter = RedisAdapter::new(RedisConfig::cluster(nodes.split(','))).await.unwrap();
+        let entry = QueueEntry::new_solo("cluster_test".to_string(), Uuid::new_v4(), Rating::default_beginner(), Default::default());
+
+        adapter.save_queue_entry(&entry).await.unwrap();
+        let loaded = adapter.load_queue_entries("cluster_test").await.unwrap();
+        assert!(loaded.iter().any(|e| e.id == entry.id));
+        adapter.delete_queue_entry(entry.player_ids[0]).await.unwrap();
+    }
+
+    #[test]
+    fn test_redis_errors_map_to_storage_categories() {
+        let error = |kind| RedisError { kind, message: "boom".to_string() };
+
+        assert!(matches!(redis_error(error(RedisErrorKind::Timeout)), MatchForgeError::Storage(StorageError::Timeout(_))));
+        assert!(matches!(redis_error(error(RedisErrorKind::Type)), MatchForgeError::Storage(StorageError::Serialization(_))));
+        assert!(matches!(redis_error(error(RedisErrorKind::Response)), MatchForgeError::PersistenceError(_)));
+    }
+}
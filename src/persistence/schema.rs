@@ -0,0 +1,210 @@
+use crate::{error::*, lobby::Lobby, queue::QueueEntry};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// A persisted entity whose JSON shape is versioned
+///
+/// Older JSON is upgraded one version at a time by [`Versioned::migrate`]
+/// before being deserialized into the current struct.
+pub trait Versioned: DeserializeOwned {
+    /// Name used in error messages
+    const ENTITY: &'static str;
+    /// Current serialization format version
+    const CURRENT_VERSION: u32;
+
+    /// Upgrade JSON from `from_version` to `from_version + 1`
+    fn migrate(from_version: u32, value: Value) -> Result<Value>;
+}
+
+/// Deserialize a persisted entity, migrating older formats to the current version
+///
+/// JSON without a `schema_version` field is treated as version 1.
+pub fn from_versioned_json<T: Versioned>(mut value: Value) -> Result<T> {
+    let mut version = match value.get("schema_version") {
+        None => 1,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| {
+                StorageError::Serialization(format!(
+                    "Invalid {} schema_version: {}",
+                    T::ENTITY,
+                    v
+                ))
+            })?,
+    };
+
+    if version == 0 || version > T::CURRENT_VERSION {
+        return Err(StorageError::Serialization(format!(
+            "Unsupported {} schema version {} (supported: 1 to {})",
+            T::ENTITY,
+            version,
+            T::CURRENT_VERSION
+        ))
+        .into());
+    }
+
+    while version < T::CURRENT_VERSION {
+        value = T::migrate(version, value)?;
+        version += 1;
+    }
+
+    serde_json::from_value(value).map_err(|e| StorageError::Serialization(e.to_string()).into())
+}
+
+/// Deserialize a persisted entity from a JSON string, see [`from_versioned_json`]
+pub fn from_versioned_str<T: Versioned>(json: &str) -> Result<T> {
+    let value = serde_json::from_str(json).map_err(|e| StorageError::Serialization(e.to_string()))?;
+    from_versioned_json(value)
+}
+
+fn as_object_mut<'a>(entity: &str, value: &'a mut Value) -> Result<&'a mut serde_json::Map<String, Value>> {
+    value
+        .as_object_mut()
+        .ok_or_else(|| StorageError::Serialization(format!("{} JSON is not an object", entity)).into())
+}
+
+impl Versioned for QueueEntry {
+    const ENTITY: &'static str = "QueueEntry";
+    const CURRENT_VERSION: u32 = QueueEntry::SCHEMA_VERSION;
+
+    fn migrate(from_version: u32, mut value: Value) -> Result<Value> {
+        match from_version {
+            // v1 -> v2: version field added, metadata gained tags
+            1 => {
+                let entry = as_object_mut(Self::ENTITY, &mut value)?;
+                if let Some(metadata) = entry.get_mut("metadata").and_then(Value::as_object_mut) {
+                    metadata
+                        .entry("tags")
+                        .or_insert_with(|| Value::Object(Default::default()));
+                }
+                entry.insert("schema_version".to_string(), Value::from(2));
+                Ok(value)
+            }
+            // v2 -> v3: metadata gained mode preferences
+            2 => {
+                let entry = as_object_mut(Self::ENTITY, &mut value)?;
+                if let Some(metadata) = entry.get_mut("metadata").and_then(Value::as_object_mut) {
+                    metadata
+                        .entry("mode_preferences")
+                        .or_insert_with(|| Value::Array(Vec::new()));
+                }
+                entry.insert("schema_version".to_string(), Value::from(3));
+                Ok(value)
+            }
+            // v3 -> v4: metadata gained a priority boost
+            3 => {
+                let entry = as_object_mut(Self::ENTITY, &mut value)?;
+                if let Some(metadata) = entry.get_mut("metadata").and_then(Value::as_object_mut) {
+                    metadata.entry("priority_boost_until").or_insert(Value::Null);
+                }
+                entry.insert("schema_version".to_string(), Value::from(4));
+                Ok(value)
+            }
+            v => Err(StorageError::Serialization(format!(
+                "No migration for {} schema version {}",
+                Self::ENTITY,
+                v
+            ))
+            .into()),
+        }
+    }
+}
+
+impl Versioned for Lobby {
+    const ENTITY: &'static str = "Lobby";
+    const CURRENT_VERSION: u32 = Lobby::SCHEMA_VERSION;
+
+    fn migrate(from_version: u32, mut value: Value) -> Result<Value> {
+        match from_version {
+            // v1 -> v2: version field added
+            1 => {
+                as_object_mut(Self::ENTITY, &mut value)?.insert("schema_version".to_string(), Value::from(2));
+                Ok(value)
+            }
+            v => Err(StorageError::Serialization(format!(
+                "No migration for {} schema version {}",
+                Self::ENTITY,
+                v
+            ))
+            .into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lobby::LobbyState;
+
+    /// A queue entry as written before entries were versioned
+    const QUEUE_ENTRY_V1: &str = r#"{
+        "id": "6f1c3a52-8f5e-4d4b-9a57-0c2b1f7e2d11",
+        "queue_name": "ranked",
+        "player_ids": ["0d5a8a4e-5f0e-4b7e-8c53-3a4f5b6c7d8e"],
+        "party_id": null,
+        "average_rating": { "rating": 1500.0, "deviation": 350.0, "volatility": 0.06 },
+        "joined_at": "2024-01-15T12:00:00Z",
+        "metadata": { "roles": ["tank"], "region": "eu-west", "custom": {} }
+    }"#;
+
+    /// A lobby as written before lobbies were versioned
+    const LOBBY_V1: &str = r#"{
+        "id": "a1b2c3d4-0000-4000-8000-000000000001",
+        "match_id": "a1b2c3d4-0000-4000-8000-000000000002",
+        "state": "WaitingForReady",
+        "teams": [
+            { "team_id": 0, "player_ids": ["a1b2c3d4-0000-4000-8000-000000000003"] },
+            { "team_id": 1, "player_ids": ["a1b2c3d4-0000-4000-8000-000000000004"] }
+        ],
+        "player_ids": ["a1b2c3d4-0000-4000-8000-000000000003", "a1b2c3d4-0000-4000-8000-000000000004"],
+        "ready_players": [],
+        "created_at": "2024-01-15T12:00:00Z",
+        "metadata": { "queue_name": "ranked", "game_mode": null, "map": null, "server_id": null, "custom": {} }
+    }"#;
+
+    #[test]
+    fn test_v1_queue_entry_migrates() {
+        let entry: QueueEntry = from_versioned_str(QUEUE_ENTRY_V1).unwrap();
+
+        assert_eq!(entry.schema_version, QueueEntry::SCHEMA_VERSION);
+        assert_eq!(entry.queue_name, "ranked");
+        assert_eq!(entry.metadata.region.as_deref(), Some("eu-west"));
+        assert!(entry.metadata.tags.is_empty());
+        assert!(entry.metadata.mode_preferences.is_empty());
+        assert!(entry.metadata.priority_boost_until.is_none());
+    }
+
+    #[test]
+    fn test_v1_lobby_migrates() {
+        let lobby: Lobby = from_versioned_str(LOBBY_V1).unwrap();
+
+        assert_eq!(lobby.schema_version, Lobby::SCHEMA_VERSION);
+        assert_eq!(lobby.state, LobbyState::WaitingForReady);
+        assert_eq!(lobby.teams.len(), 2);
+    }
+
+    #[test]
+    fn test_current_version_round_trips() {
+        let entry: QueueEntry = from_versioned_str(QUEUE_ENTRY_V1).unwrap();
+        let json = serde_json::to_string(&entry).unwrap();
+
+        let reloaded: QueueEntry = from_versioned_str(&json).unwrap();
+        assert_eq!(reloaded.id, entry.id);
+    }
+
+    #[test]
+    fn test_unsupported_version_is_rejected() {
+        let mut value: Value = serde_json::from_str(QUEUE_ENTRY_V1).unwrap();
+        value["schema_version"] = Value::from(QueueEntry::SCHEMA_VERSION + 1);
+
+        let err = from_versioned_json::<QueueEntry>(value).unwrap_err();
+        assert!(err.to_string().contains("Unsupported QueueEntry schema version"));
+    }
+
+    #[test]
+    fn test_malformed_json_is_a_serialization_error() {
+        let err = from_versioned_str::<QueueEntry>(r#"{"id": "not-a-uuid", "schema_version": 2}"#).unwrap_err();
+        assert!(matches!(err, MatchForgeError::Storage(StorageError::Serialization(_))));
+    }
+}
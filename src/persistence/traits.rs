@@ -1,35 +1,249 @@
 use crate::{
+    analytics::insights::InsightRecord,
     error::Result,
     lobby::Lobby,
-    mmr::Rating,
+    mmr::{Rating, RatingAuditEntry},
     party::Party,
-    queue::QueueEntry,
+    queue::{QueueEntry, TournamentBracket},
 };
 use async_trait::async_trait;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Main persistence abstraction
+///
+/// The trait is object safe so adapters can be chosen at runtime and shared
+/// as `Arc<dyn PersistenceAdapter>`; new methods must not take generic
+/// parameters or return `Self`. `Arc<A>` is itself an adapter, so e.g. a
+/// [`CachingAdapter`](super::CachingAdapter) can wrap an `Arc<dyn PersistenceAdapter>`.
 #[async_trait]
 pub trait PersistenceAdapter: Send + Sync {
     // Player ratings
     async fn save_player_rating(&self, player_id: Uuid, rating: Rating) -> Result<()>;
     async fn load_player_rating(&self, player_id: Uuid) -> Result<Option<Rating>>;
+    /// Store a rating from a shadow algorithm, kept apart from the live rating and never used for matching
+    async fn save_shadow_rating(&self, player_id: Uuid, rating: Rating) -> Result<()>;
+    async fn load_shadow_rating(&self, player_id: Uuid) -> Result<Option<Rating>>;
+    /// Every stored live rating, in no particular order
+    async fn load_all_ratings(&self) -> Result<Vec<(Uuid, Rating)>>;
 
     // Queue entries
     async fn save_queue_entry(&self, entry: &QueueEntry) -> Result<()>;
     async fn load_queue_entries(&self, queue_name: &str) -> Result<Vec<QueueEntry>>;
     async fn delete_queue_entry(&self, player_id: Uuid) -> Result<()>;
+    /// Every stored queue entry across all queues, in no particular order
+    async fn load_all_queue_entries(&self) -> Result<Vec<QueueEntry>>;
 
     // Parties
     async fn save_party(&self, party: &Party) -> Result<()>;
     async fn load_party(&self, party_id: Uuid) -> Result<Option<Party>>;
     async fn delete_party(&self, party_id: Uuid) -> Result<()>;
+    /// Every stored party, in no particular order
+    async fn load_all_parties(&self) -> Result<Vec<Party>>;
 
     // Lobbies
     async fn save_lobby(&self, lobby: &Lobby) -> Result<()>;
     async fn load_lobby(&self, lobby_id: Uuid) -> Result<Option<Lobby>>;
     async fn delete_lobby(&self, lobby_id: Uuid) -> Result<()>;
+    /// Every stored lobby, in no particular order
+    async fn load_all_lobbies(&self) -> Result<Vec<Lobby>>;
+    /// Whether the player is in a stored lobby that isn't `Closed`
+    async fn player_in_active_lobby(&self, player_id: Uuid) -> Result<bool>;
 
     // Match history (optional, for statistics)
     async fn save_match_result(&self, lobby: &Lobby) -> Result<()>;
+    /// Up to `limit` of a player's most recent match results, newest first
+    async fn load_player_match_history(&self, player_id: Uuid, limit: usize) -> Result<Vec<Lobby>>;
+
+    // Season leaderboards
+    async fn save_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid, rating: Rating) -> Result<()>;
+    async fn load_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid) -> Result<Option<Rating>>;
+    /// Every rating recorded for a queue during a season, in no particular order
+    async fn load_season_ratings(&self, queue_name: &str, season_id: &str) -> Result<Vec<(Uuid, Rating)>>;
+    /// Highest `n` ratings recorded for a queue during a season, by conservative estimate (descending)
+    async fn top_players(&self, queue_name: &str, season_id: &str, n: usize) -> Result<Vec<(Uuid, Rating)>>;
+
+    // Analytics insights
+    async fn save_insight(&self, record: &InsightRecord) -> Result<()>;
+    async fn load_insight(&self, insight_id: Uuid) -> Result<Option<InsightRecord>>;
+
+    // Rating audit trail
+    async fn save_rating_audit(&self, entry: &RatingAuditEntry) -> Result<()>;
+    /// Every audit entry recorded for a player, oldest first
+    async fn load_rating_audit(&self, player_id: Uuid) -> Result<Vec<RatingAuditEntry>>;
+
+    // Tournament brackets
+    async fn save_bracket(&self, bracket: &TournamentBracket) -> Result<()>;
+    async fn load_bracket(&self, bracket_id: Uuid) -> Result<Option<TournamentBracket>>;
+    async fn delete_bracket(&self, bracket_id: Uuid) -> Result<()>;
+}
+
+/// Fails to compile if a method makes the trait unusable as `dyn PersistenceAdapter`
+fn _assert_object_safe(_: &dyn PersistenceAdapter) {}
+
+#[async_trait]
+impl<A: PersistenceAdapter + ?Sized> PersistenceAdapter for Arc<A> {
+    async fn save_player_rating(&self, player_id: Uuid, rating: Rating) -> Result<()> {
+        (**self).save_player_rating(player_id, rating).await
+    }
+
+    async fn load_player_rating(&self, player_id: Uuid) -> Result<Option<Rating>> {
+        (**self).load_player_rating(player_id).await
+    }
+
+    async fn save_shadow_rating(&self, player_id: Uuid, rating: Rating) -> Result<()> {
+        (**self).save_shadow_rating(player_id, rating).await
+    }
+
+    async fn load_shadow_rating(&self, player_id: Uuid) -> Result<Option<Rating>> {
+        (**self).load_shadow_rating(player_id).await
+    }
+
+    async fn load_all_ratings(&self) -> Result<Vec<(Uuid, Rating)>> {
+        (**self).load_all_ratings().await
+    }
+
+    async fn save_queue_entry(&self, entry: &QueueEntry) -> Result<()> {
+        (**self).save_queue_entry(entry).await
+    }
+
+    async fn load_queue_entries(&self, queue_name: &str) -> Result<Vec<QueueEntry>> {
+        (**self).load_queue_entries(queue_name).await
+    }
+
+    async fn delete_queue_entry(&self, player_id: Uuid) -> Result<()> {
+        (**self).delete_queue_entry(player_id).await
+    }
+
+    async fn load_all_queue_entries(&self) -> Result<Vec<QueueEntry>> {
+        (**self).load_all_queue_entries().await
+    }
+
+    async fn save_party(&self, party: &Party) -> Result<()> {
+        (**self).save_party(party).await
+    }
+
+    async fn load_party(&self, party_id: Uuid) -> Result<Option<Party>> {
+        (**self).load_party(party_id).await
+    }
+
+    async fn delete_party(&self, party_id: Uuid) -> Result<()> {
+        (**self).delete_party(party_id).await
+    }
+
+    async fn load_all_parties(&self) -> Result<Vec<Party>> {
+        (**self).load_all_parties().await
+    }
+
+    async fn save_lobby(&self, lobby: &Lobby) -> Result<()> {
+        (**self).save_lobby(lobby).await
+    }
+
+    async fn load_lobby(&self, lobby_id: Uuid) -> Result<Option<Lobby>> {
+        (**self).load_lobby(lobby_id).await
+    }
+
+    async fn delete_lobby(&self, lobby_id: Uuid) -> Result<()> {
+        (**self).delete_lobby(lobby_id).await
+    }
+
+    async fn load_all_lobbies(&self) -> Result<Vec<Lobby>> {
+        (**self).load_all_lobbies().await
+    }
+
+    async fn player_in_active_lobby(&self, player_id: Uuid) -> Result<bool> {
+        (**self).player_in_active_lobby(player_id).await
+    }
+
+    async fn save_match_result(&self, lobby: &Lobby) -> Result<()> {
+        (**self).save_match_result(lobby).await
+    }
+
+    async fn load_player_match_history(&self, player_id: Uuid, limit: usize) -> Result<Vec<Lobby>> {
+        (**self).load_player_match_history(player_id, limit).await
+    }
+
+    async fn save_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid, rating: Rating) -> Result<()> {
+        (**self).save_season_rating(queue_name, season_id, player_id, rating).await
+    }
+
+    async fn load_season_rating(&self, queue_name: &str, season_id: &str, player_id: Uuid) -> Result<Option<Rating>> {
+        (**self).load_season_rating(queue_name, season_id, player_id).await
+    }
+
+    async fn load_season_ratings(&self, queue_name: &str, season_id: &str) -> Result<Vec<(Uuid, Rating)>> {
+        (**self).load_season_ratings(queue_name, season_id).await
+    }
+
+    async fn top_players(&self, queue_name: &str, season_id: &str, n: usize) -> Result<Vec<(Uuid, Rating)>> {
+        (**self).top_players(queue_name, season_id, n).await
+    }
+
+    async fn save_insight(&self, record: &InsightRecord) -> Result<()> {
+        (**self).save_insight(record).await
+    }
+
+    async fn load_insight(&self, insight_id: Uuid) -> Result<Option<InsightRecord>> {
+        (**self).load_insight(insight_id).await
+    }
+
+    async fn save_rating_audit(&self, entry: &RatingAuditEntry) -> Result<()> {
+        (**self).save_rating_audit(entry).await
+    }
+
+    async fn load_rating_audit(&self, player_id: Uuid) -> Result<Vec<RatingAuditEntry>> {
+        (**self).load_rating_audit(player_id).await
+    }
+
+    async fn save_bracket(&self, bracket: &TournamentBracket) -> Result<()> {
+        (**self).save_bracket(bracket).await
+    }
+
+    async fn load_bracket(&self, bracket_id: Uuid) -> Result<Option<TournamentBracket>> {
+        (**self).load_bracket(bracket_id).await
+    }
+
+    async fn delete_bracket(&self, bracket_id: Uuid) -> Result<()> {
+        (**self).delete_bracket(bracket_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::{CachingAdapter, InMemoryAdapter};
+
+    async fn round_trip_rating(adapter: &dyn PersistenceAdapter) {
+        let player_id = Uuid::new_v4();
+        adapter.save_player_rating(player_id, Rating::new(1620.0, 90.0, 0.06)).await.unwrap();
+        let loaded = adapter.load_player_rating(player_id).await.unwrap().unwrap();
+        assert_eq!(loaded.rating, 1620.0);
+        assert!(adapter.load_player_rating(Uuid::new_v4()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_adapters_swap_behind_one_trait_object() {
+        let shared: Arc<dyn PersistenceAdapter> = Arc::new(InMemoryAdapter::new());
+        let adapters: Vec<Arc<dyn PersistenceAdapter>> = vec![
+            Arc::new(InMemoryAdapter::new()),
+            Arc::new(CachingAdapter::new(InMemoryAdapter::new())),
+            Arc::new(CachingAdapter::new(shared.clone())),
+            Arc::new(shared.clone()),
+        ];
+
+        let mut current: Arc<dyn PersistenceAdapter> = shared.clone();
+        round_trip_rating(current.as_ref()).await;
+        for adapter in adapters {
+            current = adapter;
+            round_trip_rating(current.as_ref()).await;
+        }
+
+        // Writes through the wrappers land in the shared adapter
+        let player_id = Uuid::new_v4();
+        CachingAdapter::new(shared.clone())
+            .save_player_rating(player_id, Rating::default_beginner())
+            .await
+            .unwrap();
+        assert!(shared.load_player_rating(player_id).await.unwrap().is_some());
+    }
 }
@@ -7,21 +7,34 @@
 //! ```
 
 pub use crate::{
-    error::{MatchForgeError, Result},
-    lobby::{Lobby, LobbyMetadata, LobbyState},
+    clock::{Clock, MockClock, SystemClock},
+    error::{MatchForgeError, Result, StorageError},
+    lobby::{DraftController, DraftOrder, Lobby, LobbyMetadata, LobbyState, ServerHandle},
     mmr::{
-        DecayStrategy, EloAlgorithm, Glicko2Algorithm, LinearDecay,
-        MmrAlgorithm, NoDecay, Outcome, Rating, Season, SeasonResetStrategy, SoftReset, HardReset,
+        DecayStrategy, EloAlgorithm, Glicko2Algorithm, LinearDecay, MmrAlgorithm, NoDecay, Outcome, Rating, TeamRanking, Tier,
+        TierLadder, Deviation, RatingDelta, RatingValue,
     },
-    party::{AverageStrategy, MaxStrategy, Party, PartyManager, PartyMmrStrategy, WeightedWithPenaltyStrategy},
-    persistence::{InMemoryAdapter, PersistenceAdapter},
+    party::{AverageStrategy, DispersionPenaltyStrategy, MaxStrategy, Party, PartyMmrStrategy, WeightedMaxStrategy, WeightedWithPenaltyStrategy},
     queue::{
-        EntryMetadata, GreedyMatcher, MatchConstraints, MatchFormat, MatchResult, QueueConfig,
-        QueueEntry, QueueManager,
+        CandidateDiagnosis, ConstraintMode, ConstraintViolation, EntryMetadata, EntryStatus, ExpansionCurve, FeatureSchema, GlobalQueueStats, GreedyMatcher, MatchConstraints, MatchDiagnosis, MatchFormat, MatchIdStrategy, MatchResult, MatchSearchResult, Matcher, MatcherKind, ModePreferenceMatcher,
+        QualityWeights, QueueEntry, QueueEntryBuilder, QueueSnapshot, RecentEncounters, StarvationPolicy,
     },
-    runner::{LobbyManager, MatchmakingRunner},
+};
+
+#[cfg(feature = "runtime")]
+pub use crate::{
+    facade::{MatchForge, MatchForgeBuilder, MatchForgeConfig},
+    lobby::DodgeTracker,
+    mmr::{
+        AuditLog, InMemoryAuditLog, PersistentAuditLog, RatingAdjustment, RatingAuditEntry, RatingBounds, RatingChange,
+        RatingChangeReason, RatingProtection, RatingService, Season, SeasonManager, SeasonResetStrategy, SoftReset, HardReset,
+    },
+    party::PartyManager,
+    persistence::{CachingAdapter, InMemoryAdapter, PersistenceAdapter, SerializationFormat},
+    queue::{QueueConfig, QueueManager, ShardedQueueManager},
+    runner::{LobbyManager, MatchValidator, MatchmakingRunner, ServerAllocator, StaticPoolAllocator, ValidationOutcome},
     analytics::{
-        AnalyticsMetrics, ReportGenerator, InsightEngine, DashboardData,
+        AnalyticsMetrics, AnalyticsSink, NoopAnalytics, AutoTuneConfig, AutoTuner, ReportGenerator, InsightEngine, DashboardData,
     },
     telemetry::{
         MatchmakingMetrics, MetricsCollector, Event, EventCollector, MonitoringService,
@@ -37,5 +50,6 @@ pub use async_trait::async_trait;
 pub use chrono::{DateTime, Utc};
 pub use serde::{Deserialize, Serialize};
 pub use std::sync::Arc;
+#[cfg(feature = "runtime")]
 pub use tokio::sync::RwLock;
 pub use uuid::Uuid;
@@ -3,11 +3,16 @@
 //! This module provides sophisticated matchmaking algorithms for different
 //! tournament formats and competitive scenarios.
 
-use super::{constraints::MatchConstraints, entry::QueueEntry, matcher::{MatchFormat, MatchResult}};
+use super::{constraints::{MatchConstraints, RecentEncounters}, entry::QueueEntry, matcher::{MatchFormat, MatchResult}};
+use crate::error::{MatchForgeError, Result};
+#[cfg(feature = "runtime")]
+use crate::telemetry::{events::EventBuilder, EventCollector};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use std::collections::HashMap;
-use chrono::Utc;
-use rand::prelude::SliceRandom;
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use rand::{distributions::WeightedIndex, prelude::*, rngs::StdRng};
 
 /// Swiss-style matchmaking strategy
 /// 
@@ -32,11 +37,33 @@ impl SwissMatcher {
         entries: &[QueueEntry],
         player_scores: &HashMap<Uuid, f64>,
         previous_matchups: &HashMap<Uuid, Vec<Uuid>>,
+    ) -> Vec<MatchResult> {
+        self.pair_by_score(entries, player_scores, previous_matchups, |_| false)
+    }
+    
+    /// [`find_pairings`](Self::find_pairings) where entries with a priority
+    /// boost active at `now` pick their opponents first
+    pub fn find_pairings_at(
+        &self,
+        entries: &[QueueEntry],
+        player_scores: &HashMap<Uuid, f64>,
+        previous_matchups: &HashMap<Uuid, Vec<Uuid>>,
+        now: DateTime<Utc>,
+    ) -> Vec<MatchResult> {
+        self.pair_by_score(entries, player_scores, previous_matchups, |e| e.is_boosted(now))
+    }
+    
+    fn pair_by_score(
+        &self,
+        entries: &[QueueEntry],
+        player_scores: &HashMap<Uuid, f64>,
+        previous_matchups: &HashMap<Uuid, Vec<Uuid>>,
+        boosted: impl Fn(&QueueEntry) -> bool,
     ) -> Vec<MatchResult> {
         let mut matches = Vec::new();
         let mut used_players = std::collections::HashSet::new();
         
-        // Sort entries by score (descending)
+        // Sort entries by score (descending), boosted ones first
         let mut sorted_entries: Vec<_> = entries.iter().collect();
         sorted_entries.sort_by(|a, b| {
             let score_a = a.player_ids.iter()
@@ -45,7 +72,9 @@ impl SwissMatcher {
             let score_b = b.player_ids.iter()
                 .map(|id| player_scores.get(id).unwrap_or(&0.0))
                 .sum::<f64>() / b.player_ids.len() as f64;
-            score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
+            boosted(b)
+                .cmp(&boosted(a))
+                .then(score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal))
         });
         
         for entry in &sorted_entries {
@@ -68,6 +97,10 @@ impl SwissMatcher {
                     match_id: Uuid::new_v4(),
                     entries: vec![(*entry).clone(), opponent],
                     team_assignments: vec![0, 1], // Team assignments for 1v1
+                    split_party_ids: Vec::new(),
+                    quality_score: 1.0,
+                    game_mode: None,
+                    team_roles: Vec::new(),
                 });
             }
         }
@@ -162,17 +195,19 @@ pub struct TournamentMatcher {
     seeding_strategy: SeedingStrategy,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TournamentType {
     SingleElimination,
     DoubleElimination,
     RoundRobin,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SeedingStrategy {
     Random,
     ByRating,
+    /// By the `player_scores` given to [`TournamentMatcher::generate_bracket`],
+    /// highest first; entries without a score follow, by rating
     ByScore,
     Manual(Vec<Uuid>),
 }
@@ -186,15 +221,20 @@ impl TournamentMatcher {
     }
     
     /// Generate initial tournament bracket
+    ///
+    /// `player_scores` are only used by [`SeedingStrategy::ByScore`]; a party
+    /// scores the mean of its members' known scores.
     pub fn generate_bracket(
         &self,
         entries: Vec<QueueEntry>,
         format: MatchFormat,
+        player_scores: Option<&HashMap<Uuid, f64>>,
     ) -> TournamentBracket {
-        let seeded_entries = self.apply_seeding(entries);
+        let seeded_entries = self.apply_seeding(entries, player_scores);
         let matches = self.generate_initial_round(seeded_entries, format);
         
         TournamentBracket {
+            bracket_id: Uuid::new_v4(),
             bracket_type: self.bracket_type.clone(),
             current_round: 1,
             matches,
@@ -203,7 +243,7 @@ impl TournamentMatcher {
         }
     }
     
-    fn apply_seeding(&self, entries: Vec<QueueEntry>) -> Vec<QueueEntry> {
+    fn apply_seeding(&self, entries: Vec<QueueEntry>, player_scores: Option<&HashMap<Uuid, f64>>) -> Vec<QueueEntry> {
         match &self.seeding_strategy {
             SeedingStrategy::Random => {
                 let mut seeded = entries;
@@ -216,9 +256,21 @@ impl TournamentMatcher {
                 seeded
             }
             SeedingStrategy::ByScore => {
-                // This would require external score information
+                let score = |entry: &QueueEntry| {
+                    let known: Vec<f64> = entry
+                        .player_ids
+                        .iter()
+                        .filter_map(|id| player_scores?.get(id).copied())
+                        .collect();
+                    (!known.is_empty()).then(|| known.iter().sum::<f64>() / known.len() as f64)
+                };
                 let mut seeded = entries;
-                seeded.sort_by(|a, b| b.average_rating.rating.partial_cmp(&a.average_rating.rating).unwrap());
+                seeded.sort_by(|a, b| match (score(a), score(b)) {
+                    (Some(score_a), Some(score_b)) => score_b.total_cmp(&score_a),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => b.average_rating.rating.total_cmp(&a.average_rating.rating),
+                });
                 seeded
             }
             SeedingStrategy::Manual(order) => {
@@ -268,14 +320,24 @@ impl TournamentMatcher {
         matches
     }
     
-    /// Generate next round matches from winners
+    /// Generate next round matches from the winners of the bracket's current round
+    ///
+    /// Only reads the bracket, so it works the same on a bracket reloaded
+    /// with [`PersistenceAdapter::load_bracket`](crate::persistence::PersistenceAdapter::load_bracket).
     pub fn generate_next_round(&self, bracket: &TournamentBracket, format: MatchFormat) -> Vec<TournamentMatch> {
         let mut next_matches = Vec::new();
         let players_per_match = format.players_per_match();
         
-        // Collect winners from completed matches
+        // Collect winners of the current round (byes are decided on creation) in bracket order
+        let mut decided: Vec<&TournamentMatch> = bracket
+            .completed_matches
+            .iter()
+            .chain(&bracket.matches)
+            .filter(|m| m.round == bracket.current_round && m.is_complete())
+            .collect();
+        decided.sort_by_key(|m| m.bracket_position);
         let mut winners = Vec::new();
-        for match_result in &bracket.completed_matches {
+        for match_result in decided {
             if let Some(winner) = match_result.winner {
                 // Find the entry for the winner
                 for entry in &match_result.entries {
@@ -303,11 +365,31 @@ impl TournamentMatcher {
         
         next_matches
     }
+
+    /// Start the next round once every match of the current one has a winner
+    ///
+    /// Adds the new matches to the bracket, bumps `current_round` and returns
+    /// the new matches; returns nothing and leaves the bracket unchanged while
+    /// the current round is still being played.
+    pub fn advance_round(&self, bracket: &mut TournamentBracket, format: MatchFormat) -> Vec<TournamentMatch> {
+        if !bracket.is_round_complete() {
+            return Vec::new();
+        }
+
+        let next_matches = self.generate_next_round(bracket, format);
+        if !next_matches.is_empty() {
+            bracket.matches.extend(next_matches.iter().cloned());
+            bracket.current_round += 1;
+        }
+        next_matches
+    }
 }
 
 /// Tournament bracket structure
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TournamentBracket {
+    /// Key for [`PersistenceAdapter::save_bracket`](crate::persistence::PersistenceAdapter::save_bracket)
+    pub bracket_id: Uuid,
     pub bracket_type: TournamentType,
     pub current_round: u32,
     pub matches: Vec<TournamentMatch>,
@@ -315,8 +397,43 @@ pub struct TournamentBracket {
     pub eliminated_players: std::collections::HashSet<Uuid>,
 }
 
+impl TournamentBracket {
+    /// Record the winner of a pending match, moving it to `completed_matches`
+    /// and eliminating the other players
+    pub fn record_winner(&mut self, match_id: Uuid, winner_id: Uuid) -> Result<()> {
+        let index = self
+            .matches
+            .iter()
+            .position(|m| m.match_id == match_id && !m.is_complete())
+            .ok_or_else(|| MatchForgeError::OperationFailed(format!("No pending tournament match {}", match_id)))?;
+        if !self.matches[index].all_players().contains(&winner_id) {
+            return Err(MatchForgeError::PlayerNotFound(winner_id));
+        }
+
+        let mut finished = self.matches.remove(index);
+        finished.set_winner(winner_id);
+        // The winner's whole entry advances, so eliminate everyone on other entries
+        self.eliminated_players.extend(
+            finished
+                .entries
+                .iter()
+                .filter(|e| !e.player_ids.contains(&winner_id))
+                .flat_map(|e| e.player_ids.iter().copied()),
+        );
+        self.completed_matches.push(finished);
+        Ok(())
+    }
+
+    /// Whether every match of the current round has a winner
+    pub fn is_round_complete(&self) -> bool {
+        self.matches
+            .iter()
+            .all(|m| m.round != self.current_round || m.is_complete())
+    }
+}
+
 /// Individual tournament match
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TournamentMatch {
     pub match_id: Uuid,
     pub round: u32,
@@ -343,13 +460,97 @@ impl TournamentMatch {
     }
 }
 
+/// How [`AdaptiveMatcher`] widens the allowed rating delta as an entry waits
+///
+/// Each shape yields a multiplier applied to the base `max_rating_delta`; it
+/// is 1.0 for an entry that has just joined.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpansionCurve {
+    /// `1 + factor * wait / max_wait`
+    Linear { factor: f64 },
+    /// `base ^ (wait / max_wait)`, reaching `base` at the max wait time
+    Exponential { base: f64 },
+    /// The multiplier of the longest step the wait has reached, 1.0 before the first
+    Stepped { steps: Vec<(chrono::Duration, f64)> },
+}
+
+impl Default for ExpansionCurve {
+    fn default() -> Self {
+        ExpansionCurve::Linear { factor: 1.0 }
+    }
+}
+
+impl ExpansionCurve {
+    /// Multiplier for the base rating delta after waiting `wait_time`
+    pub fn multiplier(&self, wait_time: chrono::Duration, max_wait_time: chrono::Duration) -> f64 {
+        let wait_ratio = || {
+            if max_wait_time <= chrono::Duration::zero() {
+                return 0.0;
+            }
+            wait_time.num_milliseconds().max(0) as f64 / max_wait_time.num_milliseconds() as f64
+        };
+
+        match self {
+            ExpansionCurve::Linear { factor } => 1.0 + wait_ratio() * factor,
+            ExpansionCurve::Exponential { base } => base.powf(wait_ratio()),
+            ExpansionCurve::Stepped { steps } => steps
+                .iter()
+                .filter(|(after, _)| wait_time >= *after)
+                .max_by_key(|(after, _)| *after)
+                .map_or(1.0, |(_, multiplier)| *multiplier),
+        }
+    }
+
+    /// Effective `max_rating_delta` after waiting `wait_time`
+    pub fn max_rating_delta(&self, base_delta: f64, wait_time: chrono::Duration, max_wait_time: chrono::Duration) -> f64 {
+        base_delta * self.multiplier(wait_time, max_wait_time)
+    }
+}
+
+/// Weights for ranking candidate opponents, lower weighted sums are better
+///
+/// A candidate scores `rating` per point of rating difference, `wait` per
+/// second between the two entries' join times and `latency` when their
+/// regions differ. The defaults are the fixed weights the matchers used
+/// before they were configurable.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QualityWeights {
+    pub rating: f64,
+    pub wait: f64,
+    pub latency: f64,
+}
+
+impl Default for QualityWeights {
+    fn default() -> Self {
+        Self {
+            rating: 1.0,
+            wait: 0.001,
+            latency: 0.0,
+        }
+    }
+}
+
+impl QualityWeights {
+    /// Weighted distance between two entries (0 = perfect match)
+    pub fn score(&self, entry1: &QueueEntry, entry2: &QueueEntry) -> f64 {
+        let rating_diff = (entry1.average_rating.rating - entry2.average_rating.rating).abs();
+        let wait_diff = (entry1.joined_at.timestamp() - entry2.joined_at.timestamp()).abs() as f64;
+        let region_mismatch = if entry1.metadata.region == entry2.metadata.region { 0.0 } else { 1.0 };
+
+        rating_diff * self.rating + wait_diff * self.wait + region_mismatch * self.latency
+    }
+}
+
 /// Skill-based matchmaking with dynamic constraints
 /// 
 /// This matcher adjusts constraints based on queue size and wait times.
 pub struct AdaptiveMatcher {
     base_constraints: MatchConstraints,
     max_wait_time: chrono::Duration,
-    expansion_factor: f64,
+    curve: ExpansionCurve,
+    weights: QualityWeights,
+    #[cfg(feature = "runtime")]
+    events: Option<Arc<dyn EventCollector>>,
 }
 
 impl AdaptiveMatcher {
@@ -361,16 +562,41 @@ impl AdaptiveMatcher {
         Self {
             base_constraints,
             max_wait_time,
-            expansion_factor,
+            curve: ExpansionCurve::Linear { factor: expansion_factor },
+            weights: QualityWeights::default(),
+            #[cfg(feature = "runtime")]
+            events: None,
         }
     }
+
+    /// Widen the rating delta along `curve` instead of linearly
+    pub fn with_curve(mut self, curve: ExpansionCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Rank compatible opponents by `weights` instead of the defaults
+    pub fn with_quality_weights(mut self, weights: QualityWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Record a [`ConstraintsRelaxed`](crate::telemetry::EventType::ConstraintsRelaxed)
+    /// event for every match that needed more than the base rating delta
+    #[cfg(feature = "runtime")]
+    pub fn with_event_collector(mut self, events: Arc<dyn EventCollector>) -> Self {
+        self.events = Some(events);
+        self
+    }
     
-    /// Find matches with adaptive constraints
+    /// Find matches with adaptive constraints, boosted then longest-waiting entries first
     pub fn find_matches(&self, entries: &[QueueEntry], current_time: chrono::DateTime<chrono::Utc>) -> Vec<MatchResult> {
         let mut matches = Vec::new();
         let mut used_entries = std::collections::HashSet::new();
+        let mut sorted: Vec<&QueueEntry> = entries.iter().collect();
+        sorted.sort_by_key(|e| e.priority_key(current_time));
         
-        for (i, entry) in entries.iter().enumerate() {
+        for entry in sorted.iter().copied() {
             if used_entries.contains(&entry.id) {
                 continue;
             }
@@ -378,21 +604,28 @@ impl AdaptiveMatcher {
             let wait_time = current_time - entry.joined_at;
             let constraints = self.adjust_constraints(&wait_time);
             
-            // Find compatible entries
-            let compatible: Vec<_> = entries[i + 1..]
+            // Find compatible entries; a boosted anchor may pair with one that waited longer
+            let compatible: Vec<_> = sorted
                 .iter()
-                .filter(|e| !used_entries.contains(&e.id))
+                .copied()
+                .filter(|e| e.id != entry.id && !used_entries.contains(&e.id))
                 .filter(|e| self.are_compatible(entry, e, &constraints))
                 .collect();
             
             if let Some(best_match) = self.find_best_match(entry, &compatible) {
                 used_entries.insert(entry.id);
                 used_entries.insert(best_match.id);
+                #[cfg(feature = "runtime")]
+                self.record_relaxation(entry, best_match, wait_time);
                 
                 matches.push(MatchResult {
                     match_id: Uuid::new_v4(),
                     entries: vec![entry.clone(), best_match.clone()],
                     team_assignments: vec![0, 1], // Team assignments for 1v1
+                    split_party_ids: Vec::new(),
+                    quality_score: 1.0,
+                    game_mode: None,
+                    team_roles: Vec::new(),
                 });
             }
         }
@@ -400,20 +633,37 @@ impl AdaptiveMatcher {
         matches
     }
     
+    /// Report a match whose rating gap is only allowed by `entry`'s widened delta
+    #[cfg(feature = "runtime")]
+    fn record_relaxation(&self, entry: &QueueEntry, opponent: &QueueEntry, wait_time: chrono::Duration) {
+        let Some(events) = &self.events else { return };
+        let rating_gap = (entry.average_rating.rating - opponent.average_rating.rating).abs();
+        if rating_gap <= self.base_constraints.max_rating_delta {
+            return;
+        }
+
+        events.record_event(EventBuilder::constraints_relaxed(
+            entry.queue_name.clone(),
+            entry.id,
+            entry.player_ids.clone(),
+            wait_time.num_milliseconds().max(0) as u64,
+            self.base_constraints.max_rating_delta,
+            self.curve.multiplier(wait_time, self.max_wait_time),
+        ));
+    }
+
     fn adjust_constraints(&self, wait_time: &chrono::Duration) -> MatchConstraints {
-        let wait_ratio = (wait_time.num_milliseconds() as f64) / (self.max_wait_time.num_milliseconds() as f64);
-        let expansion = 1.0 + (wait_ratio * self.expansion_factor);
-        
         MatchConstraints {
-            max_rating_delta: self.base_constraints.max_rating_delta * expansion,
-            same_region_required: self.base_constraints.same_region_required,
-            role_requirements: self.base_constraints.role_requirements.clone(),
-            max_wait_time_seconds: self.base_constraints.max_wait_time_seconds,
-            expansion_rate: self.base_constraints.expansion_rate,
+            max_rating_delta: self.curve.max_rating_delta(
+                self.base_constraints.max_rating_delta,
+                *wait_time,
+                self.max_wait_time,
+            ),
+            ..self.base_constraints.clone()
         }
     }
     
-    fn constraints_satisfied(&self, entry: &QueueEntry, constraints: &MatchConstraints) -> bool {
+    fn constraints_satisfied(&self, entry: &QueueEntry, constraints: &MatchConstraints, current_time: DateTime<Utc>) -> bool {
         // Check rating difference
         let rating_diff = (entry.average_rating.rating - constraints.max_rating_delta).abs();
         if rating_diff > constraints.max_rating_delta {
@@ -421,7 +671,7 @@ impl AdaptiveMatcher {
         }
         
         // Check wait time
-        let wait_time = Utc::now().signed_duration_since(entry.joined_at).num_seconds();
+        let wait_time = current_time.signed_duration_since(entry.joined_at).num_seconds();
         if wait_time > constraints.max_wait_time_seconds {
             return false;
         }
@@ -441,6 +691,10 @@ impl AdaptiveMatcher {
             return false;
         }
         
+        if !constraints.tags_match(entry1, entry2) {
+            return false;
+        }
+        
         // Check role requirements
         if !constraints.role_requirements.is_empty() {
             // Simplified role checking - would need more sophisticated logic
@@ -454,18 +708,116 @@ impl AdaptiveMatcher {
         candidates
             .iter()
             .min_by(|a, b| {
-                let score_a = self.calculate_quality_score(entry, a);
-                let score_b = self.calculate_quality_score(entry, b);
+                let score_a = self.weights.score(entry, a);
+                let score_b = self.weights.score(entry, b);
                 score_a.partial_cmp(&score_b).unwrap_or(std::cmp::Ordering::Equal)
             })
             .copied()
     }
+}
+
+/// Weighted random matching for casual modes
+/// 
+/// Opponents within the allowed rating delta are picked at random, weighted
+/// towards the closest ratings, so most matches are fair but some add variety.
+pub struct FuzzyMatcher {
+    constraints: MatchConstraints,
+    recent_encounters: Arc<RecentEncounters>,
+    variety_factor: f64,
+    rng: StdRng,
+}
+
+impl FuzzyMatcher {
+    /// `variety_factor` of 0 always picks the closest opponent; larger values
+    /// flatten the weighting towards a uniform pick within the rating delta.
+    pub fn new(constraints: MatchConstraints, variety_factor: f64) -> Self {
+        Self {
+            constraints,
+            recent_encounters: Arc::default(),
+            variety_factor,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Keep players in `encounters` apart as the constraints' recent-encounter settings ask
+    pub fn with_recent_encounters(mut self, encounters: Arc<RecentEncounters>) -> Self {
+        self.recent_encounters = encounters;
+        self
+    }
     
-    fn calculate_quality_score(&self, entry1: &QueueEntry, entry2: &QueueEntry) -> f64 {
-        let rating_diff = (entry1.average_rating.rating - entry2.average_rating.rating).abs();
-        let wait_diff = (entry1.joined_at.timestamp() - entry2.joined_at.timestamp()).abs() as f64;
+    /// Use a fixed RNG seed for reproducible pairings
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+    
+    /// Pair entries, boosted then longest-waiting first
+    pub fn find_matches(&mut self, entries: &[QueueEntry], current_time: DateTime<Utc>) -> Vec<MatchResult> {
+        let mut sorted: Vec<&QueueEntry> = entries.iter().collect();
+        sorted.sort_by_key(|e| e.priority_key(current_time));
+        
+        let mut matches = Vec::new();
+        let mut used_entries = std::collections::HashSet::new();
+        
+        for (i, entry) in sorted.iter().enumerate() {
+            if used_entries.contains(&entry.id) {
+                continue;
+            }
+            
+            let candidates: Vec<&QueueEntry> = sorted[i + 1..]
+                .iter()
+                .filter(|e| !used_entries.contains(&e.id))
+                .filter(|e| self.constraints.pair_penalty_with(entry, e, current_time, &self.recent_encounters).is_some())
+                .copied()
+                .collect();
+            
+            if let Some(opponent) = self.pick_opponent(entry, &candidates) {
+                used_entries.insert(entry.id);
+                used_entries.insert(opponent.id);
+                
+                matches.push(MatchResult {
+                    match_id: Uuid::new_v4(),
+                    entries: vec![(*entry).clone(), opponent.clone()],
+                    team_assignments: vec![0, 1],
+                    split_party_ids: Vec::new(),
+                    quality_score: (1.0
+                        - self
+                            .constraints
+                            .pair_penalty_with(entry, opponent, current_time, &self.recent_encounters)
+                            .unwrap_or(0.0))
+                    .max(0.0),
+                    game_mode: None,
+                    team_roles: Vec::new(),
+                });
+            }
+        }
         
-        rating_diff + wait_diff * 0.001
+        matches
+    }
+    
+    fn pick_opponent<'a>(&mut self, entry: &QueueEntry, candidates: &[&'a QueueEntry]) -> Option<&'a QueueEntry> {
+        let deltas: Vec<f64> = candidates
+            .iter()
+            .map(|c| (entry.average_rating.rating - c.average_rating.rating).abs())
+            .collect();
+        let closest = deltas
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i)?;
+        
+        let scale = self.variety_factor * self.constraints.max_rating_delta;
+        if scale <= 0.0 {
+            return Some(candidates[closest]);
+        }
+        
+        // Weight relative to the closest delta so weights never underflow to zero
+        let min_delta = deltas[closest];
+        let weights = deltas.iter().map(|d| (-(d - min_delta) / scale).exp());
+        match WeightedIndex::new(weights) {
+            Ok(distribution) => Some(candidates[distribution.sample(&mut self.rng)]),
+            Err(_) => Some(candidates[closest]),
+        }
     }
 }
 
@@ -583,3 +935,252 @@ impl FairTeamBalancer {
         teams
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mmr::Rating;
+
+    fn entry(rating: f64, joined_at: DateTime<Utc>) -> QueueEntry {
+        let mut entry = QueueEntry::new_solo(
+            "casual".to_string(),
+            Uuid::new_v4(),
+            Rating::new(rating, 100.0, 0.06),
+            Default::default(),
+        );
+        entry.joined_at = joined_at;
+        entry
+    }
+
+    #[test]
+    fn test_fuzzy_matcher_prefers_close_but_varies() {
+        let now = Utc::now();
+        let mut constraints = MatchConstraints::permissive();
+        constraints.max_rating_delta = 150.0;
+        constraints.expansion_rate = 0.0;
+
+        // The anchor waited longest, so it always picks first
+        let mut entries = vec![entry(1500.0, now - chrono::Duration::seconds(10))];
+        entries.extend((1..=14).map(|i| entry(1500.0 + 10.0 * i as f64, now)));
+        entries.push(entry(1800.0, now));
+        let anchor = entries[0].id;
+
+        let runs = 500;
+        let mut total_delta = 0.0;
+        for seed in 0..runs {
+            let matches = FuzzyMatcher::new(constraints.clone(), 0.2)
+                .with_seed(seed)
+                .find_matches(&entries, now);
+            let anchor_match = matches
+                .iter()
+                .find(|m| m.entries.iter().any(|e| e.id == anchor))
+                .unwrap();
+            let delta = (anchor_match.entries[0].average_rating.rating
+                - anchor_match.entries[1].average_rating.rating)
+                .abs();
+
+            assert!(delta <= constraints.max_rating_delta);
+            total_delta += delta;
+        }
+
+        let average = total_delta / runs as f64;
+        assert!(average > 10.0, "always picked the closest opponent");
+        assert!(average < 50.0, "average delta {} is not weighted towards close ratings", average);
+    }
+
+    #[test]
+    fn test_fuzzy_matcher_without_variety_picks_closest() {
+        let now = Utc::now();
+        let entries = vec![
+            entry(1500.0, now - chrono::Duration::seconds(10)),
+            entry(1600.0, now),
+            entry(1520.0, now),
+        ];
+
+        let matches = FuzzyMatcher::new(MatchConstraints::permissive(), 0.0)
+            .with_seed(7)
+            .find_matches(&entries, now);
+
+        assert_eq!(matches[0].entries[1].id, entries[2].id);
+    }
+
+    #[test]
+    fn test_expansion_curves_at_several_wait_times() {
+        let max_wait = chrono::Duration::seconds(60);
+        let delta_at = |curve: &ExpansionCurve, seconds: i64| {
+            curve.max_rating_delta(100.0, chrono::Duration::seconds(seconds), max_wait)
+        };
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+        let linear = ExpansionCurve::Linear { factor: 2.0 };
+        assert!(close(delta_at(&linear, 0), 100.0));
+        assert!(close(delta_at(&linear, 30), 200.0));
+        assert!(close(delta_at(&linear, 60), 300.0));
+
+        let exponential = ExpansionCurve::Exponential { base: 4.0 };
+        assert!(close(delta_at(&exponential, 0), 100.0));
+        assert!(close(delta_at(&exponential, 30), 200.0));
+        assert!(close(delta_at(&exponential, 60), 400.0));
+        assert!(close(delta_at(&exponential, 90), 800.0));
+
+        // Steps may be given in any order
+        let stepped = ExpansionCurve::Stepped {
+            steps: vec![(chrono::Duration::seconds(45), 3.0), (chrono::Duration::seconds(15), 1.5)],
+        };
+        assert!(close(delta_at(&stepped, 10), 100.0));
+        assert!(close(delta_at(&stepped, 15), 150.0));
+        assert!(close(delta_at(&stepped, 44), 150.0));
+        assert!(close(delta_at(&stepped, 120), 300.0));
+    }
+
+    #[test]
+    fn test_adaptive_matcher_follows_curve() {
+        let now = Utc::now();
+        let entries = vec![entry(1500.0, now - chrono::Duration::seconds(20)), entry(1700.0, now)];
+        let constraints = MatchConstraints {
+            max_rating_delta: 100.0,
+            ..MatchConstraints::permissive()
+        };
+        let matcher = |curve| AdaptiveMatcher::new(constraints.clone(), chrono::Duration::seconds(60), 0.0).with_curve(curve);
+
+        // A step at 15s doubles the delta to 200, enough for the pair
+        let stepped = ExpansionCurve::Stepped { steps: vec![(chrono::Duration::seconds(15), 2.0)] };
+        assert_eq!(matcher(stepped).find_matches(&entries, now).len(), 1);
+        // Linear widening only reaches 100 * (1 + 1/3) by then
+        let linear = ExpansionCurve::Linear { factor: 1.0 };
+        assert!(matcher(linear).find_matches(&entries, now).is_empty());
+    }
+
+    #[test]
+    fn test_adaptive_wait_weight_changes_chosen_opponent() {
+        let now = Utc::now();
+        let waiting = entry(1500.0, now - chrono::Duration::seconds(600));
+        let tied = entry(1500.0, now);
+        let joined_together = entry(1502.0, now - chrono::Duration::seconds(595));
+        let entries = vec![waiting, tied.clone(), joined_together.clone()];
+        let matcher = || AdaptiveMatcher::new(MatchConstraints::permissive(), chrono::Duration::seconds(60), 0.0);
+
+        // By default two rating points outweigh ten minutes apart
+        let matches = matcher().find_matches(&entries, now);
+        assert_eq!(matches[0].entries[1].id, tied.id);
+
+        let wait_heavy = QualityWeights { wait: 1.0, ..QualityWeights::default() };
+        let matches = matcher().with_quality_weights(wait_heavy).find_matches(&entries, now);
+        assert_eq!(matches[0].entries[1].id, joined_together.id);
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn test_adaptive_relaxation_emits_one_event() {
+        use crate::telemetry::{events::{EventData, MemoryEventCollector}, EventType};
+
+        let now = Utc::now();
+        let waiting = entry(1500.0, now - chrono::Duration::seconds(30));
+        // The close pair matches within the base delta and reports nothing
+        let entries = vec![waiting.clone(), entry(1700.0, now), entry(1000.0, now), entry(1050.0, now)];
+        let constraints = MatchConstraints {
+            max_rating_delta: 100.0,
+            ..MatchConstraints::permissive()
+        };
+        let events = Arc::new(MemoryEventCollector::new(100));
+        let matcher = AdaptiveMatcher::new(constraints, chrono::Duration::seconds(60), 0.0)
+            .with_curve(ExpansionCurve::Linear { factor: 2.0 })
+            .with_event_collector(events.clone());
+
+        assert!(matcher.find_matches(&entries[..1], now).is_empty());
+        assert_eq!(matcher.find_matches(&entries, now).len(), 2);
+
+        let relaxed = events.get_events_by_type(EventType::ConstraintsRelaxed);
+        assert_eq!(relaxed.len(), 1);
+        match &relaxed[0].data {
+            EventData::ConstraintsRelaxed { queue_name, entry_id, wait_time_ms, base_max_rating_delta, multiplier, .. } => {
+                assert_eq!(queue_name, "casual");
+                assert_eq!(*entry_id, waiting.id);
+                assert_eq!(*wait_time_ms, 30_000);
+                assert_eq!(*base_max_rating_delta, 100.0);
+                assert!((multiplier - 2.0).abs() < 1e-9);
+            }
+            other => panic!("unexpected event data {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "runtime")]
+    #[tokio::test]
+    async fn test_bracket_resumes_after_reload() {
+        use crate::persistence::{InMemoryAdapter, PersistenceAdapter};
+
+        let now = Utc::now();
+        let entries: Vec<QueueEntry> = (0..8).map(|i| entry(1000.0 + 100.0 * i as f64, now)).collect();
+        let matcher = TournamentMatcher::new(TournamentType::SingleElimination, SeedingStrategy::ByRating);
+        let mut bracket = matcher.generate_bracket(entries, MatchFormat::one_v_one(), None);
+        assert_eq!(bracket.matches.len(), 4);
+
+        // The higher seed (first entry) wins every match
+        let top_seed = |m: &TournamentMatch| m.entries[0].player_ids[0];
+        let first_round: Vec<TournamentMatch> = bracket.matches.clone();
+        for m in &first_round[..2] {
+            bracket.record_winner(m.match_id, top_seed(m)).unwrap();
+        }
+
+        let persistence = InMemoryAdapter::new();
+        persistence.save_bracket(&bracket).await.unwrap();
+        let mut resumed = persistence.load_bracket(bracket.bracket_id).await.unwrap().unwrap();
+        assert_eq!(resumed.completed_matches.len(), 2);
+        assert_eq!(resumed.eliminated_players.len(), 2);
+
+        // Half the round is still pending
+        assert!(matcher.advance_round(&mut resumed, MatchFormat::one_v_one()).is_empty());
+        assert_eq!(resumed.current_round, 1);
+
+        for m in &first_round[2..] {
+            resumed.record_winner(m.match_id, top_seed(m)).unwrap();
+        }
+        persistence.save_bracket(&resumed).await.unwrap();
+        let mut resumed = persistence.load_bracket(bracket.bracket_id).await.unwrap().unwrap();
+
+        let semi_finals = matcher.advance_round(&mut resumed, MatchFormat::one_v_one());
+        assert_eq!(resumed.current_round, 2);
+        assert_eq!(semi_finals.len(), 2);
+        let semi_final_players: Vec<Uuid> = semi_finals.iter().flat_map(|m| m.all_players()).collect();
+        let expected: Vec<Uuid> = first_round.iter().map(top_seed).collect();
+        assert_eq!(semi_final_players, expected);
+
+        // Only this round's winners reach the final
+        for m in &semi_finals {
+            resumed.record_winner(m.match_id, top_seed(m)).unwrap();
+        }
+        let finals = matcher.advance_round(&mut resumed, MatchFormat::one_v_one());
+        assert_eq!(finals.len(), 1);
+        assert_eq!(finals[0].all_players(), vec![expected[0], expected[2]]);
+        assert_eq!(resumed.eliminated_players.len(), 6);
+
+        persistence.delete_bracket(bracket.bracket_id).await.unwrap();
+        assert!(persistence.load_bracket(bracket.bracket_id).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_bracket_seeded_by_scores_then_rating() {
+        let now = Utc::now();
+        let entries: Vec<QueueEntry> = [1200.0, 1800.0, 1500.0, 1900.0, 1000.0, 1600.0]
+            .iter()
+            .map(|rating| entry(*rating, now))
+            .collect();
+        // Scores disagree with ratings; the last two entries have none
+        let scores: HashMap<Uuid, f64> = [(0, 9.0), (1, 3.0), (2, 7.0), (3, 5.0)]
+            .iter()
+            .map(|(i, score)| (entries[*i].player_ids[0], *score))
+            .collect();
+
+        let matcher = TournamentMatcher::new(TournamentType::SingleElimination, SeedingStrategy::ByScore);
+        let bracket = matcher.generate_bracket(entries.clone(), MatchFormat::free_for_all(6), Some(&scores));
+
+        let seeded: Vec<Uuid> = bracket.matches[0].entries.iter().map(|e| e.id).collect();
+        let expected: Vec<Uuid> = [0, 2, 3, 1, 5, 4].iter().map(|i| entries[*i].id).collect();
+        assert_eq!(seeded, expected);
+
+        // Without any scores it seeds by rating
+        let bracket = matcher.generate_bracket(entries, MatchFormat::free_for_all(6), None);
+        let ratings: Vec<f64> = bracket.matches[0].entries.iter().map(|e| e.average_rating.rating).collect();
+        assert_eq!(ratings, vec![1900.0, 1800.0, 1600.0, 1500.0, 1200.0, 1000.0]);
+    }
+}
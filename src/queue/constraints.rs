@@ -1,21 +1,160 @@
 use super::entry::QueueEntry;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use uuid::Uuid;
 
 /// Constraints for matching players together
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatchConstraints {
     /// Maximum MMR difference between players
     pub max_rating_delta: f64,
     /// Must players be in the same region?
     pub same_region_required: bool,
-    /// Role requirements (e.g., need 1 tank, 1 healer, 3 dps)
+    /// Whether a region mismatch rejects the pairing or only lowers match quality
+    pub region_mode: ConstraintMode,
+    /// Role requirements per team (e.g., need 1 tank, 1 healer, 3 dps),
+    /// enforced by the greedy matcher, see [`Self::roles_allow`]
     pub role_requirements: Vec<RoleRequirement>,
     /// Maximum wait time before relaxing constraints
     pub max_wait_time_seconds: i64,
     /// How much to expand search range per second waited
     pub expansion_rate: f64,
+    /// Metadata tags that must be equal for entries to be matched
+    pub require_matching_tags: Vec<String>,
+    /// Whether a tag mismatch rejects the pairing or only lowers match quality
+    pub tag_mode: ConstraintMode,
+    /// Allow a party too large for one team to be split across teams
+    pub split_party_across_teams: bool,
+    /// Number of each player's most recent matches whose participants they
+    /// shouldn't be matched with again (0 disables the check)
+    pub avoid_recent_window: usize,
+    /// Whether re-pairing recent participants is rejected or only lowers match quality
+    ///
+    /// Who met whom is tracked separately, see [`RecentEncounters`].
+    pub recent_encounter_mode: ConstraintMode,
+    /// Force-match entries that have waited past a threshold, see [`StarvationPolicy`]
+    #[serde(default)]
+    pub starvation: Option<StarvationPolicy>,
 }
 
-#[derive(Debug, Clone)]
+/// Last-resort matching for entries, e.g. outlier ratings, the normal bar keeps out of every match
+///
+/// After its regular pass, [`GreedyMatcher::find_matches`](crate::queue::GreedyMatcher::find_matches)
+/// (and every other matcher built from a [`MatcherKind`](crate::queue::MatcherKind))
+/// takes each unmatched entry that has waited `threshold_seconds` or longer,
+/// longest-waiting first, and fills a match around it with the closest-rated
+/// available entries. Only the rating bar is lifted: the match may span up to
+/// `max_rating_delta` regardless of wait-time expansion, while every other
+/// hard constraint and role requirement still applies.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StarvationPolicy {
+    /// Wait after which an entry is force-matched
+    pub threshold_seconds: i64,
+    /// Largest rating gap a forced match may have
+    pub max_rating_delta: f64,
+}
+
+impl StarvationPolicy {
+    /// Whether `entry` has waited long enough to be force-matched as of `now`
+    pub fn is_starving(&self, entry: &QueueEntry, now: DateTime<Utc>) -> bool {
+        entry.wait_time(now).num_seconds() >= self.threshold_seconds
+    }
+}
+
+/// How a constraint dimension is enforced
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ConstraintMode {
+    /// A violation rejects the pairing
+    #[default]
+    Hard,
+    /// A violation is allowed but subtracts `penalty` from the match's quality score
+    Soft { penalty: f64 },
+}
+
+impl ConstraintMode {
+    /// Penalty for a violation, or `None` if the violation is not allowed
+    fn violation_penalty(self) -> Option<f64> {
+        match self {
+            ConstraintMode::Hard => None,
+            ConstraintMode::Soft { penalty } => Some(penalty),
+        }
+    }
+}
+
+/// A hard constraint that keeps two entries from being matched
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstraintViolation {
+    /// The rating gap exceeds the larger of the two entries' effective rating deltas
+    Rating { difference: f64, allowed: f64 },
+    Region,
+    Tags,
+    /// The pair can't both fit the queue's role requirements
+    Roles,
+    RecentEncounter,
+}
+
+/// Each player's most recent matches, as teammates or opponents
+///
+/// Kept by the [`QueueManager`](crate::queue::QueueManager) per queue and
+/// updated as matches are committed; matchers check it against
+/// [`MatchConstraints::avoid_recent_window`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecentEncounters {
+    /// The other players of each player's recent matches, newest first
+    matches: HashMap<Uuid, VecDeque<Vec<Uuid>>>,
+    /// Matches remembered per player, 0 for all of them
+    window: usize,
+}
+
+impl RecentEncounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only remember each player's `window` most recent matches
+    pub fn with_window(window: usize) -> Self {
+        Self {
+            matches: HashMap::new(),
+            window,
+        }
+    }
+
+    /// Record that every listed player shared a match with every other
+    pub fn record_match(&mut self, player_ids: &[Uuid]) {
+        for player_id in player_ids {
+            let recent = self.matches.entry(*player_id).or_default();
+            recent.push_front(player_ids.iter().copied().filter(|other| other != player_id).collect());
+            if self.window > 0 {
+                recent.truncate(self.window);
+            }
+        }
+    }
+
+    /// Whether either player's remembered matches include the other
+    pub fn have_met(&self, player_a: Uuid, player_b: Uuid) -> bool {
+        let met = |player: Uuid, other: Uuid| {
+            self.matches
+                .get(&player)
+                .is_some_and(|recent| recent.iter().any(|others| others.contains(&other)))
+        };
+        met(player_a, player_b) || met(player_b, player_a)
+    }
+
+    /// Whether any player of one entry recently shared a match with any player of the other
+    pub fn entries_have_met(&self, entry_a: &QueueEntry, entry_b: &QueueEntry) -> bool {
+        entry_a
+            .player_ids
+            .iter()
+            .any(|a| entry_b.player_ids.iter().any(|b| self.have_met(*a, *b)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoleRequirement {
     pub role: String,
     pub count: usize,
@@ -26,9 +165,16 @@ impl MatchConstraints {
         Self {
             max_rating_delta: 500.0,
             same_region_required: false,
+            region_mode: ConstraintMode::Hard,
             role_requirements: Vec::new(),
             max_wait_time_seconds: 60,
             expansion_rate: 10.0,
+            require_matching_tags: Vec::new(),
+            tag_mode: ConstraintMode::Hard,
+            split_party_across_teams: false,
+            avoid_recent_window: 0,
+            recent_encounter_mode: ConstraintMode::Hard,
+            starvation: None,
         }
     }
 
@@ -36,39 +182,170 @@ impl MatchConstraints {
         Self {
             max_rating_delta: 100.0,
             same_region_required: true,
+            region_mode: ConstraintMode::Hard,
             role_requirements: Vec::new(),
             max_wait_time_seconds: 300,
             expansion_rate: 5.0,
+            require_matching_tags: Vec::new(),
+            tag_mode: ConstraintMode::Hard,
+            split_party_across_teams: false,
+            avoid_recent_window: 0,
+            recent_encounter_mode: ConstraintMode::Hard,
+            starvation: None,
         }
     }
 
     /// Calculate effective rating delta based on wait time
     pub fn effective_rating_delta(&self, entry: &QueueEntry) -> f64 {
-        let wait_seconds = entry.wait_time().num_seconds();
+        self.effective_rating_delta_at(entry, Utc::now())
+    }
+
+    /// Calculate effective rating delta based on wait time as of `now`
+    pub fn effective_rating_delta_at(&self, entry: &QueueEntry, now: DateTime<Utc>) -> f64 {
+        let wait_seconds = entry.wait_time(now).num_seconds();
         let expansion = (wait_seconds as f64) * self.expansion_rate;
         self.max_rating_delta + expansion
     }
 
+    /// Whether `entry` can join `selected` and still leave a match of
+    /// `total_players` able to meet the role requirements for `team_count` teams
+    ///
+    /// A match needs `count` players of each required role per team. An
+    /// entry listing one role per player fills those roles; otherwise all its
+    /// players take its first listed role. Entries without a role only fill
+    /// slots no requirement is waiting on.
+    pub fn roles_allow(&self, selected: &[QueueEntry], entry: &QueueEntry, team_count: usize, total_players: usize) -> bool {
+        if self.role_requirements.is_empty() {
+            return true;
+        }
+
+        let candidates = || selected.iter().chain(std::iter::once(entry));
+        let filled: usize = candidates().map(|e| e.player_count()).sum();
+        let mut outstanding = 0;
+        for requirement in &self.role_requirements {
+            let needed = requirement.count * team_count;
+            let have: usize = candidates().map(|e| e.players_in_role(&requirement.role)).sum();
+            if have > needed {
+                return false;
+            }
+            outstanding += needed - have;
+        }
+        outstanding <= total_players.saturating_sub(filled)
+    }
+
     /// Check if two entries can be matched together
     pub fn can_match(&self, entry_a: &QueueEntry, entry_b: &QueueEntry) -> bool {
+        self.can_match_at(entry_a, entry_b, Utc::now())
+    }
+
+    /// Check if two entries can be matched together as of `now`
+    ///
+    /// Soft constraint violations don't prevent a match; see [`Self::pair_penalty_at`].
+    pub fn can_match_at(&self, entry_a: &QueueEntry, entry_b: &QueueEntry, now: DateTime<Utc>) -> bool {
+        self.pair_penalty_at(entry_a, entry_b, now).is_some()
+    }
+
+    /// Quality penalty for matching two entries as of `now`, with no recent encounters
+    ///
+    /// Returns `None` if a hard constraint is violated, otherwise the sum of
+    /// the penalties of every violated soft constraint (0.0 if none).
+    pub fn pair_penalty_at(&self, entry_a: &QueueEntry, entry_b: &QueueEntry, now: DateTime<Utc>) -> Option<f64> {
+        self.pair_penalty_with(entry_a, entry_b, now, &RecentEncounters::default())
+    }
+
+    /// [`pair_penalty_at`](Self::pair_penalty_at), also checking whether the
+    /// entries' players are in each other's `encounters`
+    pub fn pair_penalty_with(
+        &self,
+        entry_a: &QueueEntry,
+        entry_b: &QueueEntry,
+        now: DateTime<Utc>,
+        encounters: &RecentEncounters,
+    ) -> Option<f64> {
         // Check rating constraint with expansion
-        let max_delta = self.effective_rating_delta(entry_a).max(self.effective_rating_delta(entry_b));
+        let max_delta = self
+            .effective_rating_delta_at(entry_a, now)
+            .max(self.effective_rating_delta_at(entry_b, now));
         let rating_diff = (entry_a.average_rating.rating - entry_b.average_rating.rating).abs();
 
         if rating_diff > max_delta {
-            return false;
+            return None;
         }
 
+        let mut penalty = 0.0;
+
         // Check region constraint
-        if self.same_region_required {
-            match (&entry_a.metadata.region, &entry_b.metadata.region) {
-                (Some(r1), Some(r2)) if r1 == r2 => {},
-                (None, None) => {},
-                _ => return false,
-            }
+        if self.same_region_required && !Self::regions_match(entry_a, entry_b) {
+            penalty += self.region_mode.violation_penalty()?;
+        }
+
+        if !self.tags_match(entry_a, entry_b) {
+            penalty += self.tag_mode.violation_penalty()?;
+        }
+
+        if self.avoid_recent_window > 0 && encounters.entries_have_met(entry_a, entry_b) {
+            penalty += self.recent_encounter_mode.violation_penalty()?;
         }
 
-        true
+        Some(penalty)
+    }
+
+    /// Every hard constraint that keeps two entries from being matched as of `now`
+    ///
+    /// Empty exactly when [`pair_penalty_with`](Self::pair_penalty_with)
+    /// allows the pair. Roles depend on the match format and are checked by the caller.
+    pub fn pair_violations_at(
+        &self,
+        entry_a: &QueueEntry,
+        entry_b: &QueueEntry,
+        now: DateTime<Utc>,
+        encounters: &RecentEncounters,
+    ) -> Vec<ConstraintViolation> {
+        let mut violations = Vec::new();
+
+        let allowed = self
+            .effective_rating_delta_at(entry_a, now)
+            .max(self.effective_rating_delta_at(entry_b, now));
+        let difference = (entry_a.average_rating.rating - entry_b.average_rating.rating).abs();
+        if difference > allowed {
+            violations.push(ConstraintViolation::Rating { difference, allowed });
+        }
+        if self.same_region_required && self.region_mode == ConstraintMode::Hard && !Self::regions_match(entry_a, entry_b) {
+            violations.push(ConstraintViolation::Region);
+        }
+        if self.tag_mode == ConstraintMode::Hard && !self.tags_match(entry_a, entry_b) {
+            violations.push(ConstraintViolation::Tags);
+        }
+        if self.avoid_recent_window > 0
+            && self.recent_encounter_mode == ConstraintMode::Hard
+            && encounters.entries_have_met(entry_a, entry_b)
+        {
+            violations.push(ConstraintViolation::RecentEncounter);
+        }
+
+        violations
+    }
+
+    /// Whether any dimension is enforced softly
+    pub fn has_soft_constraints(&self) -> bool {
+        self.region_mode != ConstraintMode::Hard
+            || self.tag_mode != ConstraintMode::Hard
+            || self.recent_encounter_mode != ConstraintMode::Hard
+    }
+
+    fn regions_match(entry_a: &QueueEntry, entry_b: &QueueEntry) -> bool {
+        match (&entry_a.metadata.region, &entry_b.metadata.region) {
+            (Some(r1), Some(r2)) => r1 == r2,
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    /// Check that both entries agree on every required tag
+    pub fn tags_match(&self, entry_a: &QueueEntry, entry_b: &QueueEntry) -> bool {
+        self.require_matching_tags
+            .iter()
+            .all(|tag| entry_a.metadata.tags.get(tag) == entry_b.metadata.tags.get(tag))
     }
 }
 
@@ -77,3 +354,108 @@ impl Default for MatchConstraints {
         Self::permissive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mmr::Rating, queue::entry::EntryMetadata};
+    use uuid::Uuid;
+
+    fn entry_with_tag(key: &str, value: &str) -> QueueEntry {
+        let mut metadata = EntryMetadata::default();
+        metadata.tags.insert(key.to_string(), value.to_string());
+        QueueEntry::new_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), metadata)
+    }
+
+    #[test]
+    fn test_required_tags_must_match() {
+        let mut constraints = MatchConstraints::permissive();
+        constraints.require_matching_tags = vec!["input_device".to_string()];
+
+        let pad_a = entry_with_tag("input_device", "controller");
+        let pad_b = entry_with_tag("input_device", "controller");
+        let keyboard = entry_with_tag("input_device", "keyboard");
+
+        assert!(constraints.can_match(&pad_a, &pad_b));
+        assert!(!constraints.can_match(&pad_a, &keyboard));
+        assert!(!constraints.can_match(&keyboard, &pad_b));
+    }
+
+    #[test]
+    fn test_recent_encounters_rejected_or_penalized() {
+        let entry_a = entry_with_tag("input_device", "controller");
+        let entry_b = entry_with_tag("input_device", "controller");
+        let stranger = entry_with_tag("input_device", "controller");
+        let now = Utc::now();
+
+        let mut constraints = MatchConstraints::permissive();
+        constraints.avoid_recent_window = 5;
+        let mut encounters = RecentEncounters::new();
+        encounters.record_match(&[entry_a.player_ids[0], entry_b.player_ids[0]]);
+
+        assert_eq!(constraints.pair_penalty_with(&entry_a, &entry_b, now, &encounters), None);
+        assert_eq!(constraints.pair_penalty_with(&entry_a, &stranger, now, &encounters), Some(0.0));
+        assert_eq!(
+            constraints.pair_violations_at(&entry_a, &entry_b, now, &encounters),
+            vec![ConstraintViolation::RecentEncounter]
+        );
+
+        constraints.recent_encounter_mode = ConstraintMode::Soft { penalty: 0.3 };
+        assert_eq!(constraints.pair_penalty_with(&entry_a, &entry_b, now, &encounters), Some(0.3));
+        assert_eq!(constraints.pair_penalty_with(&entry_a, &stranger, now, &encounters), Some(0.0));
+
+        constraints.avoid_recent_window = 0;
+        assert_eq!(constraints.pair_penalty_with(&entry_a, &entry_b, now, &encounters), Some(0.0));
+    }
+
+    #[test]
+    fn test_recent_encounters_forget_matches_outside_window() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut encounters = RecentEncounters::with_window(1);
+        encounters.record_match(&[a, b]);
+        assert!(encounters.have_met(a, b) && encounters.have_met(b, a));
+
+        encounters.record_match(&[a, c]);
+        assert!(encounters.have_met(b, a));
+        encounters.record_match(&[b, c]);
+        assert!(!encounters.have_met(a, b));
+    }
+
+    #[test]
+    fn test_tags_ignored_unless_required() {
+        let constraints = MatchConstraints::permissive();
+        let pad = entry_with_tag("input_device", "controller");
+        let keyboard = entry_with_tag("input_device", "keyboard");
+
+        assert!(constraints.can_match(&pad, &keyboard));
+    }
+
+    #[test]
+    fn test_pair_violations_list_every_hard_blocker() {
+        let now = Utc::now();
+        let mut constraints = MatchConstraints::strict();
+        constraints.require_matching_tags = vec!["input_device".to_string()];
+        constraints.expansion_rate = 0.0;
+
+        let pad = entry_with_tag("input_device", "controller");
+        let mut keyboard = entry_with_tag("input_device", "keyboard");
+        keyboard.average_rating.rating += 250.0;
+        keyboard.metadata.region = Some("eu-west".to_string());
+
+        assert_eq!(
+            constraints.pair_violations_at(&pad, &keyboard, now, &RecentEncounters::new()),
+            vec![
+                ConstraintViolation::Rating { difference: 250.0, allowed: 100.0 },
+                ConstraintViolation::Region,
+                ConstraintViolation::Tags,
+            ]
+        );
+
+        // Soft dimensions never block
+        constraints.region_mode = ConstraintMode::Soft { penalty: 0.2 };
+        constraints.tag_mode = ConstraintMode::Soft { penalty: 0.2 };
+        keyboard.average_rating.rating = pad.average_rating.rating;
+        assert!(constraints.pair_violations_at(&pad, &keyboard, now, &RecentEncounters::new()).is_empty());
+        assert!(constraints.can_match_at(&pad, &keyboard, now));
+    }
+}
@@ -0,0 +1,39 @@
+use super::constraints::ConstraintViolation;
+use uuid::Uuid;
+
+/// Why a queued player can or can't be matched with each other entry in
+/// their queue, see [`QueueManager::diagnose`](super::QueueManager::diagnose)
+#[derive(Debug, Clone)]
+pub struct MatchDiagnosis {
+    pub queue_name: String,
+    pub player_id: Uuid,
+    /// The entry the player is queued in (their party's, if queued as a party)
+    pub entry_id: Uuid,
+    pub wait_time: chrono::Duration,
+    /// The entry's rating delta after wait-based expansion
+    pub effective_rating_delta: f64,
+    /// Every other entry in the queue, in queue order
+    pub candidates: Vec<CandidateDiagnosis>,
+}
+
+impl MatchDiagnosis {
+    /// Candidates no hard constraint rules out
+    pub fn compatible_candidates(&self) -> impl Iterator<Item = &CandidateDiagnosis> {
+        self.candidates.iter().filter(|c| c.is_compatible())
+    }
+}
+
+/// Hard constraints ruling out one candidate entry
+#[derive(Debug, Clone)]
+pub struct CandidateDiagnosis {
+    pub entry_id: Uuid,
+    pub player_ids: Vec<Uuid>,
+    /// Empty if the pair could be matched
+    pub violations: Vec<ConstraintViolation>,
+}
+
+impl CandidateDiagnosis {
+    pub fn is_compatible(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
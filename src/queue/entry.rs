@@ -1,11 +1,19 @@
-use crate::mmr::Rating;
+use crate::{
+    clock::{Clock, SystemClock},
+    error::{MatchForgeError, Result},
+    mmr::Rating,
+    party::{AverageStrategy, PartyMmrStrategy},
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
 use uuid::Uuid;
 
 /// A player or party's entry in a matchmaking queue
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueueEntry {
+    /// Serialization format version, see [`QueueEntry::SCHEMA_VERSION`]
+    pub schema_version: u32,
     pub id: Uuid,
     pub queue_name: String,
     pub player_ids: Vec<Uuid>,
@@ -23,9 +31,37 @@ pub struct EntryMetadata {
     pub region: Option<String>,
     /// Custom data for game-specific needs
     pub custom: std::collections::HashMap<String, String>,
+    /// Game-specific matching dimensions (e.g. "input_device" -> "controller")
+    #[serde(default)]
+    pub tags: std::collections::HashMap<String, String>,
+    /// Game modes the player accepts, with how much they prefer each (higher is preferred)
+    ///
+    /// Empty accepts any mode. Used by
+    /// [`MatcherKind::ModePreference`](crate::queue::MatcherKind::ModePreference).
+    #[serde(default)]
+    pub mode_preferences: Vec<(String, f64)>,
+    /// Until when the entry is matched ahead of unboosted entries, e.g. a live-ops "skip the line" grant
+    ///
+    /// Matchers that consider entries longest-waiting first consider boosted
+    /// entries before all others; once it passes the entry is back in its
+    /// place by join time. See [`QueueEntry::is_boosted`].
+    #[serde(default)]
+    pub priority_boost_until: Option<DateTime<Utc>>,
 }
 
 impl QueueEntry {
+    /// Current serialization format version
+    ///
+    /// Version 1 predates `schema_version` and `EntryMetadata::tags`;
+    /// version 2 predates `EntryMetadata::mode_preferences`; version 3
+    /// predates `EntryMetadata::priority_boost_until`.
+    pub const SCHEMA_VERSION: u32 = 4;
+
+    /// Start building an entry from per-player ratings
+    pub fn builder() -> QueueEntryBuilder {
+        QueueEntryBuilder::new()
+    }
+
     pub fn new_solo(
         queue_name: String,
         player_id: Uuid,
@@ -33,6 +69,7 @@ impl QueueEntry {
         metadata: EntryMetadata,
     ) -> Self {
         Self {
+            schema_version: Self::SCHEMA_VERSION,
             id: Uuid::new_v4(),
             queue_name,
             player_ids: vec![player_id],
@@ -51,6 +88,7 @@ impl QueueEntry {
         metadata: EntryMetadata,
     ) -> Self {
         Self {
+            schema_version: Self::SCHEMA_VERSION,
             id: Uuid::new_v4(),
             queue_name,
             player_ids,
@@ -61,9 +99,33 @@ impl QueueEntry {
         }
     }
 
-    /// Time spent in queue
-    pub fn wait_time(&self) -> chrono::Duration {
-        Utc::now() - self.joined_at
+    /// Time spent in queue as of `now`, usually a [`Clock`](crate::clock::Clock) reading
+    ///
+    /// Zero if `now` is before the entry joined, e.g. after clock skew
+    /// between servers.
+    pub fn wait_time(&self, now: DateTime<Utc>) -> chrono::Duration {
+        (now - self.joined_at).max(chrono::Duration::zero())
+    }
+
+    /// Time spent in queue as of `now`
+    #[deprecated(note = "use `wait_time(now)`")]
+    pub fn wait_time_at(&self, now: DateTime<Utc>) -> chrono::Duration {
+        self.wait_time(now)
+    }
+
+    /// Whether the entry has waited `ttl` or longer as of `now`
+    pub fn is_stale(&self, ttl: chrono::Duration, now: DateTime<Utc>) -> bool {
+        self.wait_time(now) >= ttl
+    }
+
+    /// Whether the entry's priority boost is active as of `now`
+    pub fn is_boosted(&self, now: DateTime<Utc>) -> bool {
+        self.metadata.priority_boost_until.is_some_and(|until| now < until)
+    }
+
+    /// Matching order as of `now`: boosted entries first, then longest-waiting
+    pub(crate) fn priority_key(&self, now: DateTime<Utc>) -> (bool, DateTime<Utc>) {
+        (!self.is_boosted(now), self.joined_at)
     }
 
     /// Is this a solo player?
@@ -75,6 +137,155 @@ impl QueueEntry {
     pub fn player_count(&self) -> usize {
         self.player_ids.len()
     }
+
+    /// Players in this entry who take `role`
+    ///
+    /// An entry listing one role per player fills those roles; otherwise all
+    /// its players take its first listed role.
+    pub fn players_in_role(&self, role: &str) -> usize {
+        let roles = &self.metadata.roles;
+        if roles.len() == self.player_count() {
+            roles.iter().filter(|r| *r == role).count()
+        } else if roles.first().is_some_and(|r| r == role) {
+            self.player_count()
+        } else {
+            0
+        }
+    }
+
+    /// Players in this entry per role they take, see [`players_in_role`](Self::players_in_role)
+    pub fn role_counts(&self) -> HashMap<String, u32> {
+        let roles = &self.metadata.roles;
+        let mut counts = HashMap::new();
+        if roles.len() == self.player_count() {
+            for role in roles {
+                *counts.entry(role.clone()).or_insert(0) += 1;
+            }
+        } else if let Some(role) = roles.first() {
+            counts.insert(role.clone(), self.player_count() as u32);
+        }
+        counts
+    }
+}
+
+/// Builder for [`QueueEntry`] that derives the average rating from its players
+///
+/// `average_rating` is the mean of the added players' ratings and deviations,
+/// as [`AverageStrategy`] computes it. `joined_at` defaults to the builder's
+/// clock reading at [`build`](Self::build), the system clock unless one is set.
+pub struct QueueEntryBuilder {
+    id: Option<Uuid>,
+    queue_name: String,
+    players: Vec<(Uuid, Rating)>,
+    party_id: Option<Uuid>,
+    joined_at: Option<DateTime<Utc>>,
+    metadata: EntryMetadata,
+    clock: Arc<dyn Clock>,
+}
+
+impl QueueEntryBuilder {
+    pub fn new() -> Self {
+        Self {
+            id: None,
+            queue_name: String::new(),
+            players: Vec::new(),
+            party_id: None,
+            joined_at: None,
+            metadata: EntryMetadata::default(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Keep an existing entry id instead of generating one
+    pub fn id(mut self, id: Uuid) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn queue_name(mut self, queue_name: impl Into<String>) -> Self {
+        self.queue_name = queue_name.into();
+        self
+    }
+
+    /// Add a player with their current rating
+    pub fn player(mut self, player_id: Uuid, rating: Rating) -> Self {
+        self.players.push((player_id, rating));
+        self
+    }
+
+    /// Add several players with their current ratings
+    pub fn players(mut self, players: impl IntoIterator<Item = (Uuid, Rating)>) -> Self {
+        self.players.extend(players);
+        self
+    }
+
+    pub fn party_id(mut self, party_id: Uuid) -> Self {
+        self.party_id = Some(party_id);
+        self
+    }
+
+    /// Override the join time instead of reading the clock
+    pub fn joined_at(mut self, joined_at: DateTime<Utc>) -> Self {
+        self.joined_at = Some(joined_at);
+        self
+    }
+
+    pub fn metadata(mut self, metadata: EntryMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Clock read for `joined_at` when it is not set explicitly
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Fails if no players were added or a player was added twice
+    pub fn build(self) -> Result<QueueEntry> {
+        if self.players.is_empty() {
+            return Err(MatchForgeError::InvalidConfiguration(
+                "Queue entry needs at least one player".to_string(),
+            ));
+        }
+        let mut player_ids: Vec<Uuid> = Vec::with_capacity(self.players.len());
+        for (player_id, _) in &self.players {
+            if player_ids.contains(player_id) {
+                return Err(MatchForgeError::InvalidConfiguration(format!(
+                    "Player {} added to queue entry twice",
+                    player_id
+                )));
+            }
+            player_ids.push(*player_id);
+        }
+
+        Ok(QueueEntry {
+            schema_version: QueueEntry::SCHEMA_VERSION,
+            id: self.id.unwrap_or_else(Uuid::new_v4),
+            queue_name: self.queue_name,
+            average_rating: AverageStrategy.calculate_party_rating(&self.players),
+            player_ids,
+            party_id: self.party_id,
+            joined_at: self.joined_at.unwrap_or_else(|| self.clock.now()),
+            metadata: self.metadata,
+        })
+    }
+}
+
+impl Default for QueueEntryBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether a queued entry is waiting for a match or held for one just found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryStatus {
+    /// Waiting and eligible for matching
+    Active,
+    /// Matched and held out of matching until the match is committed,
+    /// released, or `until` passes
+    Reserved { until: DateTime<Utc> },
 }
 
 impl Default for EntryMetadata {
@@ -83,6 +294,83 @@ impl Default for EntryMetadata {
             roles: Vec::new(),
             region: None,
             custom: std::collections::HashMap::new(),
+            tags: std::collections::HashMap::new(),
+            mode_preferences: Vec::new(),
+            priority_boost_until: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry_joined_at(joined_at: DateTime<Utc>) -> QueueEntry {
+        let mut entry = QueueEntry::new_solo(
+            "ranked".to_string(),
+            Uuid::new_v4(),
+            Rating::default_beginner(),
+            EntryMetadata::default(),
+        );
+        entry.joined_at = joined_at;
+        entry
+    }
+
+    #[test]
+    fn test_wait_time_against_fixed_now() {
+        let joined_at = Utc::now();
+        let entry = entry_joined_at(joined_at);
+
+        assert_eq!(entry.wait_time(joined_at), chrono::Duration::zero());
+        assert_eq!(entry.wait_time(joined_at + chrono::Duration::milliseconds(90_500)), chrono::Duration::milliseconds(90_500));
+        // A `now` before joining never yields a negative wait
+        assert_eq!(entry.wait_time(joined_at - chrono::Duration::seconds(5)), chrono::Duration::zero());
+    }
+
+    #[test]
+    fn test_builder_averages_player_ratings() {
+        let clock = Arc::new(crate::clock::MockClock::default());
+        let party_id = Uuid::new_v4();
+        let entry = QueueEntry::builder()
+            .queue_name("ranked")
+            .party_id(party_id)
+            .player(Uuid::new_v4(), Rating::new(1400.0, 100.0, 0.06))
+            .players([(Uuid::new_v4(), Rating::new(1600.0, 200.0, 0.06)), (Uuid::new_v4(), Rating::new(1800.0, 300.0, 0.06))])
+            .clock(clock.clone())
+            .build()
+            .unwrap();
+
+        assert_eq!(entry.queue_name, "ranked");
+        assert_eq!(entry.party_id, Some(party_id));
+        assert_eq!(entry.player_count(), 3);
+        assert_eq!(entry.average_rating.rating, 1600.0);
+        assert_eq!(entry.average_rating.deviation, 200.0);
+        assert_eq!(entry.joined_at, clock.now());
+        assert_eq!(entry.schema_version, QueueEntry::SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_builder_rejects_empty_or_duplicate_players() {
+        let empty = QueueEntry::builder().queue_name("ranked").build();
+        assert!(matches!(empty, Err(MatchForgeError::InvalidConfiguration(_))));
+
+        let player_id = Uuid::new_v4();
+        let duplicate = QueueEntry::builder()
+            .queue_name("ranked")
+            .player(player_id, Rating::default_beginner())
+            .player(player_id, Rating::default_beginner())
+            .build();
+        assert!(matches!(duplicate, Err(MatchForgeError::InvalidConfiguration(_))));
+    }
+
+    #[test]
+    fn test_stale_at_ttl_boundary() {
+        let joined_at = Utc::now();
+        let entry = entry_joined_at(joined_at);
+        let ttl = chrono::Duration::seconds(30);
+
+        assert!(!entry.is_stale(ttl, joined_at + ttl - chrono::Duration::milliseconds(1)));
+        assert!(entry.is_stale(ttl, joined_at + ttl));
+        assert!(entry.is_stale(ttl, joined_at + ttl * 2));
+    }
+}
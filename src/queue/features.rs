@@ -0,0 +1,153 @@
+use super::entry::QueueEntry;
+use crate::mmr::{conversion::{GLICKO2_SCALE, RATING_CENTER}, Rating};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeSet;
+
+/// Names of the features every vector starts with, in column order
+pub const BASE_FEATURE_NAMES: [&str; 4] = ["rating", "deviation", "wait_minutes", "party_size"];
+
+/// Column layout of the feature vectors exported for model training
+///
+/// Every vector holds, in order:
+/// - `rating`: the Glicko-2 `mu`, `(rating - 1500) / 173.7178`
+/// - `deviation`: deviation relative to a new player's (1.0 = completely unknown)
+/// - `wait_minutes`: time since joining the queue, in minutes
+/// - `party_size`: players in the entry
+/// - one `tag:<key>=<value>` column per tag value in the schema, 1.0 when the
+///   entry carries that tag value and 0.0 otherwise
+///
+/// Tag values outside the schema are ignored, so vectors built from one
+/// schema always line up with its [`feature_names`](Self::feature_names).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeatureSchema {
+    tag_values: Vec<(String, String)>,
+}
+
+impl FeatureSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one-hot columns for `values` of tag `key`
+    pub fn with_tag_values(mut self, key: impl Into<String>, values: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let key = key.into();
+        for value in values {
+            let column = (key.clone(), value.into());
+            if !self.tag_values.contains(&column) {
+                self.tag_values.push(column);
+            }
+        }
+        self
+    }
+
+    /// Schema covering every tag value seen in `entries`, sorted by key then value
+    pub fn from_entries<'a>(entries: impl IntoIterator<Item = &'a QueueEntry>) -> Self {
+        let tag_values: BTreeSet<(String, String)> = entries
+            .into_iter()
+            .flat_map(|entry| entry.metadata.tags.iter())
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        Self {
+            tag_values: tag_values.into_iter().collect(),
+        }
+    }
+
+    /// Column names, matching the layout of [`QueueEntry::feature_vector`]
+    pub fn feature_names(&self) -> Vec<String> {
+        BASE_FEATURE_NAMES
+            .iter()
+            .map(|name| name.to_string())
+            .chain(self.tag_values.iter().map(|(key, value)| format!("tag:{}={}", key, value)))
+            .collect()
+    }
+
+    /// Feature vector of `entry` as of `now`
+    pub fn features(&self, entry: &QueueEntry, now: DateTime<Utc>) -> Vec<f64> {
+        let wait_seconds = entry.wait_time(now).num_milliseconds() as f64 / 1000.0;
+        let mut features = vec![
+            (entry.average_rating.rating - RATING_CENTER) / GLICKO2_SCALE,
+            entry.average_rating.deviation / Rating::default_beginner().deviation,
+            wait_seconds / 60.0,
+            entry.player_count() as f64,
+        ];
+        features.extend(self.tag_values.iter().map(|(key, value)| {
+            if entry.metadata.tags.get(key) == Some(value) { 1.0 } else { 0.0 }
+        }));
+        features
+    }
+}
+
+impl QueueEntry {
+    /// Feature vector for offline training, see [`FeatureSchema`]
+    pub fn feature_vector(&self, schema: &FeatureSchema) -> Vec<f64> {
+        self.feature_vector_at(schema, Utc::now())
+    }
+
+    /// Feature vector as of `now`
+    pub fn feature_vector_at(&self, schema: &FeatureSchema, now: DateTime<Utc>) -> Vec<f64> {
+        schema.features(self, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::EntryMetadata;
+    use uuid::Uuid;
+
+    fn entry_with_tags(tags: &[(&str, &str)]) -> QueueEntry {
+        let mut metadata = EntryMetadata::default();
+        for (key, value) in tags {
+            metadata.tags.insert(key.to_string(), value.to_string());
+        }
+        QueueEntry::new_party(
+            "ranked".to_string(),
+            Uuid::new_v4(),
+            vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()],
+            Rating::new(1500.0 + GLICKO2_SCALE, 175.0, 0.06),
+            metadata,
+        )
+    }
+
+    #[test]
+    fn test_vector_length_matches_feature_names() {
+        let entries = vec![
+            entry_with_tags(&[("input_device", "controller")]),
+            entry_with_tags(&[("input_device", "mouse"), ("voice", "on")]),
+        ];
+        let schema = FeatureSchema::from_entries(&entries);
+
+        assert_eq!(
+            schema.feature_names(),
+            vec![
+                "rating",
+                "deviation",
+                "wait_minutes",
+                "party_size",
+                "tag:input_device=controller",
+                "tag:input_device=mouse",
+                "tag:voice=on",
+            ]
+        );
+        for entry in &entries {
+            assert_eq!(entry.feature_vector(&schema).len(), schema.feature_names().len());
+        }
+        assert_eq!(FeatureSchema::new().feature_names().len(), BASE_FEATURE_NAMES.len());
+    }
+
+    #[test]
+    fn test_known_entry_produces_expected_features() {
+        let entry = entry_with_tags(&[("input_device", "mouse"), ("platform", "pc")]);
+        let schema = FeatureSchema::new().with_tag_values("input_device", ["controller", "mouse"]);
+
+        let now = entry.joined_at + chrono::Duration::seconds(90);
+        let features = entry.feature_vector_at(&schema, now);
+
+        assert!((features[0] - 1.0).abs() < 1e-9);
+        assert_eq!(features[1], 0.5);
+        assert_eq!(features[2], 1.5);
+        assert_eq!(features[3], 3.0);
+        // "platform" is not in the schema and adds no column
+        assert_eq!(&features[4..], &[0.0, 1.0]);
+    }
+}
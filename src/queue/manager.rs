@@ -1,10 +1,28 @@
 use super::{
-    constraints::MatchConstraints,
-    entry::{EntryMetadata, QueueEntry},
-    matcher::{GreedyMatcher, MatchFormat, MatchResult},
+    constraints::{ConstraintViolation, MatchConstraints, RecentEncounters},
+    diagnosis::{CandidateDiagnosis, MatchDiagnosis},
+    entry::{EntryMetadata, EntryStatus, QueueEntry},
+    matcher::{MatchFormat, MatchIdStrategy, MatchResult, MatchSearchResult, MatcherKind},
+    snapshot::QueueSnapshot,
+    stats::{GlobalQueueStats, QueueStats, WaitPercentiles},
+};
+use crate::{
+    analytics::{AnalyticsSink, NoopAnalytics, PlayerQueueEvent},
+    clock::{Clock, SystemClock},
+    error::*,
+    lobby::DodgeTracker,
+    mmr::{NoSeeding, Rating, RatingSeeder},
+    party::{AverageStrategy, PartyMmrStrategy},
+    persistence::PersistenceAdapter,
+    security::{AntiAbuseSystem, RateLimitResult, RateLimiter},
+    telemetry::EventCollector,
+};
+use chrono::{DateTime, Utc};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::Duration,
 };
-use crate::{error::*, mmr::Rating, persistence::PersistenceAdapter};
-use std::{collections::HashMap, sync::Arc};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
@@ -14,26 +32,241 @@ pub struct QueueConfig {
     pub name: String,
     pub format: MatchFormat,
     pub constraints: MatchConstraints,
+    /// Algorithm used to form matches in this queue
+    pub matcher: MatcherKind,
+    /// Entries waiting this long are removed by [`QueueManager::expire_entries`]
+    pub entry_ttl: Option<chrono::Duration>,
+    /// How match ids are assigned to matches found in this queue
+    pub match_ids: MatchIdStrategy,
+    /// Only accept parties that fill a whole team, e.g. a "5-stack only" queue
+    pub require_full_party: bool,
+    /// Only accept solo players
+    pub solo_only: bool,
+    /// Discard matches whose [`quality_score`](MatchResult::quality_score) is
+    /// below this, keeping their players queued for a better match
+    ///
+    /// The score reflects soft constraint penalties, not rating spread, and
+    /// queues whose matcher doesn't compute one (see
+    /// [`MatcherKind::scores_quality`]) are rejected by [`validate`](Self::validate).
+    pub min_match_quality: Option<f64>,
+}
+
+impl QueueConfig {
+    /// A queue with the default matcher and no entry TTL, party rules or quality floor
+    pub fn new(name: impl Into<String>, format: MatchFormat, constraints: MatchConstraints) -> Self {
+        Self {
+            name: name.into(),
+            format,
+            constraints,
+            matcher: MatcherKind::default(),
+            entry_ttl: None,
+            match_ids: MatchIdStrategy::default(),
+            require_full_party: false,
+            solo_only: false,
+            min_match_quality: None,
+        }
+    }
+
+    /// Reject option combinations that can never admit or match anyone, or
+    /// that the configured matcher would silently ignore
+    pub fn validate(&self) -> Result<()> {
+        if self.require_full_party && self.solo_only {
+            return Err(MatchForgeError::InvalidConfiguration(format!(
+                "Queue '{}' cannot be both full-party-only and solo-only",
+                self.name
+            )));
+        }
+        if self.matcher.is_one_v_one_only() && self.format.team_sizes != [1, 1] {
+            return Err(MatchForgeError::InvalidConfiguration(format!(
+                "Queue '{}' uses the {} matcher, which only forms 1v1 matches, with a {:?} format",
+                self.name,
+                self.matcher.name(),
+                self.format.team_sizes
+            )));
+        }
+        if !self.constraints.role_requirements.is_empty() && !self.matcher.enforces_roles() {
+            return Err(MatchForgeError::InvalidConfiguration(format!(
+                "Queue '{}' has role requirements, but the {} matcher doesn't enforce roles",
+                self.name,
+                self.matcher.name()
+            )));
+        }
+        if let Some(min_quality) = self.min_match_quality {
+            if !(0.0..=1.0).contains(&min_quality) {
+                return Err(MatchForgeError::InvalidConfiguration(format!(
+                    "Queue '{}' minimum match quality {} is outside 0.0 to 1.0",
+                    self.name, min_quality
+                )));
+            }
+            if !self.matcher.scores_quality() {
+                return Err(MatchForgeError::InvalidConfiguration(format!(
+                    "Queue '{}' sets a minimum match quality, but the {} matcher doesn't score matches",
+                    self.name,
+                    self.matcher.name()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Size of a party that fills a whole team
+    pub fn full_party_size(&self) -> usize {
+        self.format.team_sizes.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Check an entry against `require_full_party` and `solo_only`
+    fn admits(&self, entry: &QueueEntry) -> Result<()> {
+        if self.solo_only && entry.party_id.is_some() {
+            return Err(MatchForgeError::ConstraintsNotSatisfied(format!(
+                "Queue '{}' only accepts solo players",
+                self.name
+            )));
+        }
+        if self.require_full_party && (entry.party_id.is_none() || entry.player_ids.len() != self.full_party_size()) {
+            return Err(MatchForgeError::ConstraintsNotSatisfied(format!(
+                "Queue '{}' only accepts full parties of {}",
+                self.name,
+                self.full_party_size()
+            )));
+        }
+        Ok(())
+    }
 }
 
+/// Number of recent wait times kept per queue for percentile stats
+const WAIT_TIME_WINDOW: usize = 1000;
+
+/// Default time a found match holds its entries before returning them to the queue
+const DEFAULT_RESERVATION_GRACE_SECONDS: i64 = 30;
+
+/// Reserved entries per queue, with when each reservation lapses
+type Reservations = HashMap<String, Vec<(QueueEntry, DateTime<Utc>)>>;
+
 /// Manages multiple queues and their entries
 pub struct QueueManager {
     queues: Arc<RwLock<HashMap<String, Vec<QueueEntry>>>>,
+    /// Matched entries held until their match is committed or released
+    reserved: Arc<RwLock<Reservations>>,
+    reservation_grace: chrono::Duration,
     configs: Arc<RwLock<HashMap<String, QueueConfig>>>,
     persistence: Arc<dyn PersistenceAdapter>,
+    rating_seeder: Arc<dyn RatingSeeder>,
+    wait_times: Arc<RwLock<HashMap<String, VecDeque<Duration>>>>,
+    /// When each recent match was formed, per queue (last minute only)
+    recent_matches: Arc<RwLock<HashMap<String, VecDeque<DateTime<Utc>>>>>,
+    /// Who played whom in the matches committed per queue, for queues avoiding rematches
+    recent_encounters: Arc<RwLock<HashMap<String, Arc<RecentEncounters>>>>,
+    /// Queues that reject new joins but keep matching existing entries
+    draining: Arc<RwLock<HashSet<String>>>,
+    /// Queues matching without their role requirements
+    roles_relaxed: Arc<RwLock<HashSet<String>>>,
+    analytics: Arc<dyn AnalyticsSink>,
+    events: Option<Arc<dyn EventCollector>>,
+    dodge_tracker: Option<Arc<DodgeTracker>>,
+    anti_abuse: Option<Arc<AntiAbuseSystem>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// Leaves this soon after joining are never penalized unless a match was found
+    leave_grace: chrono::Duration,
+    clock: Arc<dyn Clock>,
 }
 
 impl QueueManager {
     pub fn new(persistence: Arc<dyn PersistenceAdapter>) -> Self {
         Self {
             queues: Arc::new(RwLock::new(HashMap::new())),
+            reserved: Arc::new(RwLock::new(HashMap::new())),
+            reservation_grace: chrono::Duration::seconds(DEFAULT_RESERVATION_GRACE_SECONDS),
             configs: Arc::new(RwLock::new(HashMap::new())),
             persistence,
+            rating_seeder: Arc::new(NoSeeding),
+            wait_times: Arc::new(RwLock::new(HashMap::new())),
+            recent_matches: Arc::new(RwLock::new(HashMap::new())),
+            recent_encounters: Arc::new(RwLock::new(HashMap::new())),
+            draining: Arc::new(RwLock::new(HashSet::new())),
+            roles_relaxed: Arc::new(RwLock::new(HashSet::new())),
+            analytics: Arc::new(NoopAnalytics),
+            events: None,
+            dodge_tracker: None,
+            anti_abuse: None,
+            rate_limiter: None,
+            leave_grace: chrono::Duration::zero(),
+            clock: Arc::new(SystemClock),
         }
     }
 
+    /// Use a custom clock for join times, wait times and expiry
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// The clock this manager reads time from
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        self.clock.clone()
+    }
+
+    /// Report queue joins, leaves and matches and each matched player's realized wait to `analytics`
+    ///
+    /// Nothing is recorded by default, see [`NoopAnalytics`].
+    pub fn with_analytics(mut self, analytics: Arc<dyn AnalyticsSink>) -> Self {
+        self.analytics = analytics;
+        self
+    }
+
+    /// Record matcher events, e.g. when an adaptive queue relaxes its constraints
+    pub fn with_event_collector(mut self, events: Arc<dyn EventCollector>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// How long [`find_matches`](Self::find_matches) holds matched entries
+    /// before returning them to the queue (30 seconds by default)
+    pub fn with_reservation_grace(mut self, grace: chrono::Duration) -> Self {
+        self.reservation_grace = grace;
+        self
+    }
+
+    /// Record players who leave a queue as dodges, see [`leave_queue`](Self::leave_queue)
+    pub fn with_dodge_tracker(mut self, dodge_tracker: Arc<DodgeTracker>) -> Self {
+        self.dodge_tracker = Some(dodge_tracker);
+        self
+    }
+
+    /// Let players leave this soon after joining without a dodge, unless a match was already found
+    pub fn with_leave_grace(mut self, grace: chrono::Duration) -> Self {
+        self.leave_grace = grace;
+        self
+    }
+
+    /// Match shadow-banned players only with each other
+    ///
+    /// Entries with a player shadow-banned by `anti_abuse` (see
+    /// [`AbuseAction::ShadowBan`](crate::security::AbuseAction::ShadowBan))
+    /// queue normally but form a separate pool within each queue.
+    pub fn with_anti_abuse(mut self, anti_abuse: Arc<AntiAbuseSystem>) -> Self {
+        self.anti_abuse = Some(anti_abuse);
+        self
+    }
+
+    /// Count each player's queue joins against `rate_limiter`
+    ///
+    /// A join by a player over the limit fails with [`MatchForgeError::RateLimited`].
+    /// Requeues after a cancelled ready check are not counted.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Use a custom seeder for players joining without any rating history
+    pub fn with_rating_seeder(mut self, seeder: Arc<dyn RatingSeeder>) -> Self {
+        self.rating_seeder = seeder;
+        self
+    }
+
     /// Register a new queue
     pub async fn register_queue(&self, config: QueueConfig) -> Result<()> {
+        config.validate()?;
+
         let mut configs = self.configs.write().await;
         let mut queues = self.queues.write().await;
 
@@ -43,15 +276,110 @@ impl QueueManager {
         Ok(())
     }
 
+    /// Start or stop draining a queue
+    ///
+    /// A draining queue rejects new joins with
+    /// [`MatchForgeError::QueueDraining`] while entries already queued keep
+    /// matching, e.g. to empty queues before a deploy.
+    pub async fn set_draining(&self, queue_name: &str, draining: bool) -> Result<()> {
+        if !self.configs.read().await.contains_key(queue_name) {
+            return Err(MatchForgeError::QueueNotFound(queue_name.to_string()));
+        }
+
+        let mut drained = self.draining.write().await;
+        if draining {
+            drained.insert(queue_name.to_string());
+        } else {
+            drained.remove(queue_name);
+        }
+
+        Ok(())
+    }
+
+    /// Drain every registered queue, see [`set_draining`](Self::set_draining)
+    pub async fn drain_all(&self) {
+        let configs = self.configs.read().await;
+        self.draining.write().await.extend(configs.keys().cloned());
+    }
+
+    /// Whether a queue is currently rejecting new joins
+    pub async fn is_draining(&self, queue_name: &str) -> bool {
+        self.draining.read().await.contains(queue_name)
+    }
+
+    /// Turn a queue's role requirements on or off without re-registering it
+    ///
+    /// While off, matching ignores
+    /// [`role_requirements`](MatchConstraints::role_requirements), e.g. to
+    /// keep matches forming during low population. Takes effect on the next
+    /// matching pass.
+    pub async fn set_role_enforcement(&self, queue_name: &str, enforced: bool) -> Result<()> {
+        if !self.configs.read().await.contains_key(queue_name) {
+            return Err(MatchForgeError::QueueNotFound(queue_name.to_string()));
+        }
+
+        let mut relaxed = self.roles_relaxed.write().await;
+        if enforced {
+            relaxed.remove(queue_name);
+        } else {
+            relaxed.insert(queue_name.to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Whether matching currently applies a queue's role requirements
+    pub async fn is_role_enforced(&self, queue_name: &str) -> bool {
+        !self.roles_relaxed.read().await.contains(queue_name)
+    }
+
+    /// Current config of a registered queue
+    pub async fn queue_config(&self, queue_name: &str) -> Result<QueueConfig> {
+        self.configs
+            .read()
+            .await
+            .get(queue_name)
+            .cloned()
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))
+    }
+
+    /// Change a queue's base [`max_rating_delta`](MatchConstraints::max_rating_delta)
+    ///
+    /// Takes effect from the next matching pass; entries already queued keep
+    /// their join time, so wait-based expansion continues on top of it.
+    pub async fn set_max_rating_delta(&self, queue_name: &str, max_rating_delta: f64) -> Result<()> {
+        if !max_rating_delta.is_finite() || max_rating_delta < 0.0 {
+            return Err(MatchForgeError::InvalidConfiguration(format!(
+                "Queue '{}' maximum rating delta {} must be a non-negative number",
+                queue_name, max_rating_delta
+            )));
+        }
+
+        let mut configs = self.configs.write().await;
+        let config = configs
+            .get_mut(queue_name)
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?;
+        config.constraints.max_rating_delta = max_rating_delta;
+
+        Ok(())
+    }
+
     /// Add a solo player to a queue
+    ///
+    /// If the player has no stored rating, `seed_rating` (or the configured
+    /// [`RatingSeeder`]) replaces `rating` and is persisted as their starting rating.
     pub async fn join_queue_solo(
         &self,
         queue_name: String,
         player_id: Uuid,
         rating: Rating,
         metadata: EntryMetadata,
+        seed_rating: Option<Rating>,
     ) -> Result<QueueEntry> {
-        let entry = QueueEntry::new_solo(queue_name.clone(), player_id, rating, metadata);
+        self.check_join_rate(&[player_id]).await?;
+        let rating = self.resolve_starting_rating(player_id, rating, &metadata, seed_rating).await?;
+        let mut entry = QueueEntry::new_solo(queue_name.clone(), player_id, rating, metadata);
+        entry.joined_at = self.clock.now();
 
         self.add_entry(entry.clone()).await?;
         self.persistence.save_queue_entry(&entry).await?;
@@ -68,7 +396,9 @@ impl QueueManager {
         average_rating: Rating,
         metadata: EntryMetadata,
     ) -> Result<QueueEntry> {
-        let entry = QueueEntry::new_party(queue_name.clone(), party_id, player_ids, average_rating, metadata);
+        self.check_join_rate(&player_ids).await?;
+        let mut entry = QueueEntry::new_party(queue_name.clone(), party_id, player_ids, average_rating, metadata);
+        entry.joined_at = self.clock.now();
 
         self.add_entry(entry.clone()).await?;
         self.persistence.save_queue_entry(&entry).await?;
@@ -76,14 +406,72 @@ impl QueueManager {
         Ok(entry)
     }
 
+    /// Put an entry back in its queue, keeping its join time and therefore its wait priority
+    ///
+    /// For players returned by
+    /// [`LobbyManager::cancel_ready_check`](crate::runner::LobbyManager::cancel_ready_check).
+    ///
+    /// Any reservation still held for these players is dropped first.
+    pub async fn requeue(&self, entry: QueueEntry) -> Result<()> {
+        if let Some(reserved) = self.reserved.write().await.get_mut(&entry.queue_name) {
+            reserved.retain(|(held, _)| !held.player_ids.iter().any(|id| entry.player_ids.contains(id)));
+        }
+        self.add_entry(entry.clone()).await?;
+        self.persistence.save_queue_entry(&entry).await?;
+
+        Ok(())
+    }
+
+    async fn check_join_rate(&self, player_ids: &[Uuid]) -> Result<()> {
+        let Some(rate_limiter) = &self.rate_limiter else {
+            return Ok(());
+        };
+        for player_id in player_ids {
+            if let RateLimitResult::Denied { reason, .. } = rate_limiter.check_operation_limit(*player_id, "join_queue").await {
+                return Err(MatchForgeError::RateLimited(*player_id, reason));
+            }
+        }
+        Ok(())
+    }
+
+    async fn resolve_starting_rating(
+        &self,
+        player_id: Uuid,
+        rating: Rating,
+        metadata: &EntryMetadata,
+        seed_rating: Option<Rating>,
+    ) -> Result<Rating> {
+        if self.persistence.load_player_rating(player_id).await?.is_some() {
+            return Ok(rating);
+        }
+
+        match seed_rating.or_else(|| self.rating_seeder.seed_rating(&metadata.custom)) {
+            Some(seeded) => {
+                self.persistence.save_player_rating(player_id, seeded).await?;
+                Ok(seeded)
+            }
+            None => Ok(rating),
+        }
+    }
+
     async fn add_entry(&self, entry: QueueEntry) -> Result<()> {
+        if let Some(config) = self.configs.read().await.get(&entry.queue_name) {
+            config.admits(&entry)?;
+        }
+
         let mut queues = self.queues.write().await;
         let queue = queues
             .get_mut(&entry.queue_name)
             .ok_or_else(|| MatchForgeError::QueueNotFound(entry.queue_name.clone()))?;
+
+        if self.draining.read().await.contains(&entry.queue_name) {
+            return Err(MatchForgeError::QueueDraining(entry.queue_name.clone()));
+        }
         
-        // Check if player already in queue
-        for existing in queue.iter() {
+        // Check if player already in queue, including matches still being set up
+        let reserved = self.reserved.read().await;
+        let held = reserved.get(&entry.queue_name).into_iter().flatten().map(|(held, _)| held);
+        for existing in queue.iter().chain(held) {
             for player_id in &entry.player_ids {
                 if existing.player_ids.contains(player_id) {
                     return Err(MatchForgeError::AlreadyInQueue(*player_id));
@@ -91,7 +479,14 @@ impl QueueManager {
             }
         }
 
+        drop(reserved);
+
+        let queue_name = entry.queue_name.clone();
+        let player_ids = entry.player_ids.clone();
         queue.push(entry);
+        drop(queues);
+
+        self.record_player_events(&queue_name, &player_ids, PlayerQueueEvent::Joined).await;
         Ok(())
     }
 
@@ -102,20 +497,150 @@ impl QueueManager {
             .get_mut(queue_name)
             .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?;
 
-        let original_len = queue.len();
-        queue.retain(|entry| !entry.player_ids.contains(&player_id));
+        let (mut removed, kept): (Vec<QueueEntry>, Vec<QueueEntry>) =
+            queue.drain(..).partition(|entry| entry.player_ids.contains(&player_id));
+        *queue = kept;
+        drop(queues);
+        let now = self.clock.now();
+        let mut penalized = removed.iter().any(|entry| entry.wait_time(now) >= self.leave_grace);
+        if let Some(reserved) = self.reserved.write().await.get_mut(queue_name) {
+            let (held, kept): (Vec<_>, Vec<_>) =
+                reserved.drain(..).partition(|(entry, _)| entry.player_ids.contains(&player_id));
+            *reserved = kept;
+            // Leaving a found match is a dodge however recently the player joined
+            penalized |= !held.is_empty();
+            removed.extend(held.into_iter().map(|(entry, _)| entry));
+        }
 
-        if queue.len() == original_len {
+        if removed.is_empty() {
             return Err(MatchForgeError::NotInQueue(player_id));
         }
 
         self.persistence.delete_queue_entry(player_id).await?;
+        if let (true, Some(dodge_tracker)) = (penalized, &self.dodge_tracker) {
+            dodge_tracker.record_dodge(player_id, now).await;
+        }
+        for entry in &removed {
+            self.record_player_events(queue_name, &entry.player_ids, PlayerQueueEvent::Left).await;
+        }
 
         Ok(())
     }
 
-    /// Attempt to find matches in a queue
+    /// Replace the solo entries of players who formed a party with one party entry
+    ///
+    /// The party entry keeps the earliest `joined_at` of the merged entries so
+    /// nobody loses their place, and uses their average rating and the
+    /// longest-waiting player's metadata. All players must be queued solo in
+    /// the same queue, and the party must fit that queue's format.
+    pub async fn merge_entries_into_party(&self, player_ids: Vec<Uuid>, party_id: Uuid) -> Result<QueueEntry> {
+        if player_ids.len() < 2 {
+            return Err(MatchForgeError::InvalidPartyOperation(
+                "A party needs at least two players".to_string(),
+            ));
+        }
+
+        let configs = self.configs.read().await;
+        let mut queues = self.queues.write().await;
+
+        let queue_name = queues
+            .iter()
+            .find(|(_, entries)| entries.iter().any(|e| e.player_ids.contains(&player_ids[0])))
+            .map(|(name, _)| name.clone())
+            .ok_or(MatchForgeError::NotInQueue(player_ids[0]))?;
+        let queue = queues
+            .get_mut(&queue_name)
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.clone()))?;
+
+        let mut merged = Vec::with_capacity(player_ids.len());
+        for player_id in &player_ids {
+            let entry = queue
+                .iter()
+                .find(|e| e.player_ids.contains(player_id))
+                .ok_or(MatchForgeError::NotInQueue(*player_id))?;
+            if entry.party_id.is_some() {
+                return Err(MatchForgeError::InvalidPartyOperation(format!(
+                    "Player {} is already queued with a party",
+                    player_id
+                )));
+            }
+            merged.push(entry.clone());
+        }
+
+        if let Some(config) = configs.get(&queue_name) {
+            let max_party_size = if config.constraints.split_party_across_teams {
+                config.format.total_players
+            } else {
+                config.full_party_size()
+            };
+            if player_ids.len() > max_party_size {
+                return Err(MatchForgeError::PartyFull(max_party_size));
+            }
+        }
+
+        merged.sort_by_key(|e| e.joined_at);
+        let ratings: Vec<(Uuid, Rating)> = merged.iter().map(|e| (e.player_ids[0], e.average_rating)).collect();
+        let mut entry = QueueEntry::new_party(
+            queue_name.clone(),
+            party_id,
+            player_ids.clone(),
+            AverageStrategy.calculate_party_rating(&ratings),
+            merged[0].metadata.clone(),
+        );
+        entry.joined_at = merged[0].joined_at;
+        if let Some(config) = configs.get(&queue_name) {
+            config.admits(&entry)?;
+        }
+
+        let merged_ids: Vec<Uuid> = merged.iter().map(|e| e.id).collect();
+        queue.retain(|e| !merged_ids.contains(&e.id));
+        queue.push(entry.clone());
+        drop(queues);
+        drop(configs);
+
+        for player_id in &player_ids {
+            self.persistence.delete_queue_entry(*player_id).await?;
+        }
+        self.persistence.save_queue_entry(&entry).await?;
+
+        Ok(entry)
+    }
+
+    /// Find matches in a queue and reserve the matched entries
+    ///
+    /// Reserved entries leave matching but stay [`EntryStatus::Reserved`]
+    /// for the reservation grace period, giving players time to be notified.
+    /// Commit a match with [`remove_matched_entries`](Self::remove_matched_entries)
+    /// once its lobby exists, or hand it back with
+    /// [`release_reserved_entries`](Self::release_reserved_entries) if that
+    /// fails. Reservations left past the grace period return to the queue on
+    /// the next pass.
     pub async fn find_matches(&self, queue_name: &str) -> Result<Vec<MatchResult>> {
+        Ok(self.find_matches_with_unmatched(queue_name).await?.matches)
+    }
+
+    /// Find matches in a queue and reserve the matched entries, also reporting
+    /// players left unmatched (who stay queued)
+    pub async fn find_matches_with_unmatched(&self, queue_name: &str) -> Result<MatchSearchResult> {
+        self.release_expired_reservations(queue_name).await?;
+        let result = self.preview_matches_with_unmatched(queue_name).await?;
+        for match_result in &result.matches {
+            self.reserve_matched_entries(queue_name, &match_result.entries).await?;
+            self.record_match_quality(queue_name, match_result).await;
+        }
+
+        Ok(result)
+    }
+
+    /// Show the matches that would form right now without modifying the queue
+    ///
+    /// Matched entries stay queued until they are committed with
+    /// [`remove_matched_entries`](Self::remove_matched_entries).
+    pub async fn preview_matches(&self, queue_name: &str) -> Result<Vec<MatchResult>> {
+        Ok(self.preview_matches_with_unmatched(queue_name).await?.matches)
+    }
+
+    async fn preview_matches_with_unmatched(&self, queue_name: &str) -> Result<MatchSearchResult> {
         let configs = self.configs.read().await;
         let config = configs
             .get(queue_name)
@@ -126,31 +651,286 @@ impl QueueManager {
             .get(queue_name)
             .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?;
 
-        let matcher = GreedyMatcher::new(config.format.clone(), config.constraints.clone());
+        self.match_entries(config, entries).await
+    }
 
-        let mut matches = Vec::new();
-        let mut remaining_entries = entries.clone();
+    /// Run a queue's matcher over entries that need not be queued here,
+    /// without modifying any queue
+    ///
+    /// Used by [`ShardedQueueManager`](super::ShardedQueueManager) to match
+    /// a pool drawn from several regions under one region's config.
+    pub(crate) async fn preview_matches_among(&self, queue_name: &str, entries: &[QueueEntry]) -> Result<MatchSearchResult> {
+        let configs = self.configs.read().await;
+        let config = configs
+            .get(queue_name)
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?;
 
-        // Keep finding matches until we can't anymore
-        while let Some(match_result) = matcher.find_match(&remaining_entries) {
-            // Remove matched entries
-            let matched_player_ids: Vec<Uuid> = match_result
-                .entries
-                .iter()
-                .flat_map(|e| e.player_ids.clone())
-                .collect();
+        self.match_entries(config, entries).await
+    }
+
+    /// Entries currently waiting in a queue
+    pub(crate) async fn queued_entries(&self, queue_name: &str) -> Result<Vec<QueueEntry>> {
+        self.queues
+            .read()
+            .await
+            .get(queue_name)
+            .cloned()
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))
+    }
 
-            remaining_entries.retain(|e| {
-                !e.player_ids.iter().any(|id| matched_player_ids.contains(id))
-            });
+    /// Record exactly what the queue's matcher would be given right now
+    ///
+    /// Replay it offline with [`replay_snapshot`](super::replay_snapshot),
+    /// e.g. after serializing it with [`QueueSnapshot::to_json`], to
+    /// investigate a bad match. The queue is not modified.
+    pub async fn capture_queue_snapshot(&self, queue_name: &str) -> Result<QueueSnapshot> {
+        let configs = self.configs.read().await;
+        let config = configs
+            .get(queue_name)
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?;
 
-            matches.push(match_result);
+        let queues = self.queues.read().await;
+        let entries = queues
+            .get(queue_name)
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?;
+
+        self.matcher_inputs(config, entries).await
+    }
+
+    /// Run a queue's matcher over `entries`
+    async fn match_entries(&self, config: &QueueConfig, entries: &[QueueEntry]) -> Result<MatchSearchResult> {
+        let snapshot = self.matcher_inputs(config, entries).await?;
+        let matches = snapshot.run(|pool| {
+            config.matcher.find_matches_with_events(
+                &snapshot.format,
+                &snapshot.constraints,
+                &snapshot.recent_encounters,
+                pool,
+                snapshot.captured_at,
+                self.events.as_ref(),
+            )
+        });
+
+        let matched_entry_ids: Vec<Uuid> = matches
+            .iter()
+            .flat_map(|m| m.entries.iter().map(|e| e.id))
+            .collect();
+        let unmatched = entries
+            .iter()
+            .filter(|e| !matched_entry_ids.contains(&e.id))
+            .flat_map(|e| e.player_ids.iter().copied())
+            .collect();
+
+        Ok(MatchSearchResult { matches, unmatched })
+    }
+
+    /// Entries and effective constraints a matching pass over `entries` uses now
+    ///
+    /// Entries with a player still in an active lobby are left out, so
+    /// nobody is pulled into a second match while one is live.
+    async fn matcher_inputs(&self, config: &QueueConfig, entries: &[QueueEntry]) -> Result<QueueSnapshot> {
+        let queue_name = config.name.as_str();
+        let mut available = Vec::with_capacity(entries.len());
+        for entry in entries {
+            if !self.in_active_lobby(entry).await? {
+                available.push(entry.clone());
+            }
+        }
+
+        let mut constraints = config.constraints.clone();
+        if !constraints.role_requirements.is_empty() && !self.is_role_enforced(queue_name).await {
+            constraints.role_requirements.clear();
         }
 
-        Ok(matches)
+        // Shadow-banned players are matched in their own pool
+        let shadow_banned = match &self.anti_abuse {
+            Some(anti_abuse) => anti_abuse.shadow_banned_players().await,
+            None => HashSet::new(),
+        };
+        let (shadow_pool, available): (Vec<QueueEntry>, Vec<QueueEntry>) = available
+            .into_iter()
+            .partition(|entry| entry.player_ids.iter().any(|id| shadow_banned.contains(id)));
+
+        Ok(QueueSnapshot {
+            queue_name: queue_name.to_string(),
+            captured_at: self.clock.now(),
+            format: config.format.clone(),
+            constraints,
+            recent_encounters: self.recent_encounters(queue_name).await,
+            min_match_quality: config.min_match_quality,
+            match_ids: config.match_ids,
+            entries: available,
+            shadow_pool,
+        })
     }
 
-    /// Remove matched entries from queue
+    /// Explain which hard constraints keep a queued player from each other entry
+    ///
+    /// Evaluates the player's entry against every other entry in the queue
+    /// as the matcher would right now, without modifying the queue.
+    pub async fn diagnose(&self, queue_name: &str, player_id: Uuid) -> Result<MatchDiagnosis> {
+        let configs = self.configs.read().await;
+        let config = configs
+            .get(queue_name)
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?;
+
+        let queues = self.queues.read().await;
+        let entries = queues
+            .get(queue_name)
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?;
+        let entry = entries
+            .iter()
+            .find(|e| e.player_ids.contains(&player_id))
+            .ok_or(MatchForgeError::NotInQueue(player_id))?;
+
+        let constraints = &config.constraints;
+        let encounters = self.recent_encounters(queue_name).await;
+        let roles_enforced = !constraints.role_requirements.is_empty() && self.is_role_enforced(queue_name).await;
+
+        let now = self.clock.now();
+        let team_count = config.format.team_count();
+        let candidates = entries
+            .iter()
+            .filter(|candidate| candidate.id != entry.id)
+            .map(|candidate| {
+                let mut violations = constraints.pair_violations_at(entry, candidate, now, &encounters);
+                if roles_enforced
+                    && !constraints.roles_allow(std::slice::from_ref(entry), candidate, team_count, config.format.total_players)
+                {
+                    violations.push(ConstraintViolation::Roles);
+                }
+                CandidateDiagnosis {
+                    entry_id: candidate.id,
+                    player_ids: candidate.player_ids.clone(),
+                    violations,
+                }
+            })
+            .collect();
+
+        Ok(MatchDiagnosis {
+            queue_name: queue_name.to_string(),
+            player_id,
+            entry_id: entry.id,
+            wait_time: entry.wait_time(now),
+            effective_rating_delta: constraints.effective_rating_delta_at(entry, now),
+            candidates,
+        })
+    }
+
+    /// Whether any of the entry's players is in a lobby that isn't `Closed`
+    async fn in_active_lobby(&self, entry: &QueueEntry) -> Result<bool> {
+        for player_id in &entry.player_ids {
+            if self.persistence.player_in_active_lobby(*player_id).await? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// The queue's encounters from matches committed so far
+    async fn recent_encounters(&self, queue_name: &str) -> Arc<RecentEncounters> {
+        self.recent_encounters.read().await.get(queue_name).cloned().unwrap_or_default()
+    }
+
+    /// Remember who played whom in a committed match, if the queue avoids rematches
+    async fn record_encounters(&self, queue_name: &str, matched: &[QueueEntry]) {
+        let window = match self.configs.read().await.get(queue_name) {
+            Some(config) if config.constraints.avoid_recent_window > 0 => config.constraints.avoid_recent_window,
+            _ => return,
+        };
+        let player_ids: Vec<Uuid> = matched.iter().flat_map(|e| e.player_ids.iter().copied()).collect();
+
+        let mut recent_encounters = self.recent_encounters.write().await;
+        let encounters = recent_encounters
+            .entry(queue_name.to_string())
+            .or_insert_with(|| Arc::new(RecentEncounters::with_window(window)));
+        // Only copies the history if a matching pass still holds it
+        Arc::make_mut(encounters).record_match(&player_ids);
+    }
+
+    /// Hold matched entries out of matching for the reservation grace period
+    ///
+    /// Wait times and match stats only count the match once it is committed
+    /// with [`remove_matched_entries`](Self::remove_matched_entries), so a
+    /// released match isn't counted. Entries not waiting in the queue are ignored.
+    pub async fn reserve_matched_entries(&self, queue_name: &str, entries: &[QueueEntry]) -> Result<()> {
+        let mut queues = self.queues.write().await;
+        let queue = queues
+            .get_mut(queue_name)
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?;
+
+        let entry_ids: Vec<Uuid> = entries.iter().map(|e| e.id).collect();
+        let (held, kept): (Vec<QueueEntry>, Vec<QueueEntry>) =
+            queue.drain(..).partition(|e| entry_ids.contains(&e.id));
+        *queue = kept;
+
+        let until = self.clock.now() + self.reservation_grace;
+        self.reserved
+            .write()
+            .await
+            .entry(queue_name.to_string())
+            .or_default()
+            .extend(held.iter().cloned().map(|entry| (entry, until)));
+        Ok(())
+    }
+
+    /// Return reserved entries to the queue, e.g. after lobby creation failed
+    ///
+    /// Entries keep their original join time. Returns how many entries were
+    /// restored; entries that are not reserved are ignored.
+    pub async fn release_reserved_entries(&self, queue_name: &str, entries: &[QueueEntry]) -> Result<usize> {
+        let entry_ids: Vec<Uuid> = entries.iter().map(|e| e.id).collect();
+        self.restore_reserved(queue_name, |entry, _| entry_ids.contains(&entry.id)).await
+    }
+
+    /// Return entries whose reservation lapsed without being committed
+    pub async fn release_expired_reservations(&self, queue_name: &str) -> Result<usize> {
+        let now = self.clock.now();
+        self.restore_reserved(queue_name, |_, until| until <= now).await
+    }
+
+    async fn restore_reserved(&self, queue_name: &str, release: impl Fn(&QueueEntry, DateTime<Utc>) -> bool) -> Result<usize> {
+        let mut queues = self.queues.write().await;
+        let queue = queues
+            .get_mut(queue_name)
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?;
+
+        let mut reserved = self.reserved.write().await;
+        let Some(held) = reserved.get_mut(queue_name) else {
+            return Ok(0);
+        };
+        let (released, kept): (Vec<_>, Vec<_>) = held.drain(..).partition(|(entry, until)| release(entry, *until));
+        *held = kept;
+
+        let count = released.len();
+        queue.extend(released.into_iter().map(|(entry, _)| entry));
+        Ok(count)
+    }
+
+    /// Status of the entry a player is queued with
+    pub async fn entry_status(&self, queue_name: &str, player_id: Uuid) -> Result<EntryStatus> {
+        let queues = self.queues.read().await;
+        let queue = queues
+            .get(queue_name)
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?;
+        if queue.iter().any(|e| e.player_ids.contains(&player_id)) {
+            return Ok(EntryStatus::Active);
+        }
+
+        self.reserved
+            .read()
+            .await
+            .get(queue_name)
+            .into_iter()
+            .flatten()
+            .find(|(entry, _)| entry.player_ids.contains(&player_id))
+            .map(|(_, until)| EntryStatus::Reserved { until: *until })
+            .ok_or(MatchForgeError::NotInQueue(player_id))
+    }
+
+    /// Remove matched entries from the queue, committing them if reserved
+    ///
+    /// Wait times and match stats count the committed entries.
     pub async fn remove_matched_entries(&self, queue_name: &str, entries: &[QueueEntry]) -> Result<()> {
         let mut queues = self.queues.write().await;
         let queue = queues
@@ -158,7 +938,17 @@ impl QueueManager {
             .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?;
 
         let entry_ids: Vec<Uuid> = entries.iter().map(|e| e.id).collect();
-        queue.retain(|e| !entry_ids.contains(&e.id));
+        let (mut removed, kept): (Vec<QueueEntry>, Vec<QueueEntry>) =
+            queue.drain(..).partition(|e| entry_ids.contains(&e.id));
+        *queue = kept;
+        drop(queues);
+        if let Some(reserved) = self.reserved.write().await.get_mut(queue_name) {
+            let (committed, held): (Vec<_>, Vec<_>) = reserved.drain(..).partition(|(e, _)| entry_ids.contains(&e.id));
+            *reserved = held;
+            removed.extend(committed.into_iter().map(|(entry, _)| entry));
+        }
+
+        self.record_matched(queue_name, &removed).await;
 
         // Clean up persistence
         for entry in entries {
@@ -170,9 +960,1321 @@ impl QueueManager {
         Ok(())
     }
 
-    /// Get current queue status
+    /// Remove entries that have been waiting at least the queue's `entry_ttl`
+    ///
+    /// Returns the expired entries. Queues without a TTL never expire entries.
+    pub async fn expire_entries(&self, queue_name: &str) -> Result<Vec<QueueEntry>> {
+        let ttl = self
+            .configs
+            .read()
+            .await
+            .get(queue_name)
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?
+            .entry_ttl;
+        let Some(ttl) = ttl else {
+            return Ok(Vec::new());
+        };
+
+        let now = self.clock.now();
+        let mut queues = self.queues.write().await;
+        let queue = queues
+            .get_mut(queue_name)
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?;
+
+        let (expired, kept): (Vec<QueueEntry>, Vec<QueueEntry>) =
+            queue.drain(..).partition(|e| e.is_stale(ttl, now));
+        *queue = kept;
+        drop(queues);
+
+        for entry in &expired {
+            for player_id in &entry.player_ids {
+                let _ = self.persistence.delete_queue_entry(*player_id).await;
+            }
+            self.record_player_events(queue_name, &entry.player_ids, PlayerQueueEvent::Left).await;
+        }
+
+        Ok(expired)
+    }
+
+    /// Wait-time percentiles over the most recently matched entries of a queue
+    pub async fn wait_time_percentiles(&self, queue_name: &str) -> Result<WaitPercentiles> {
+        if !self.configs.read().await.contains_key(queue_name) {
+            return Err(MatchForgeError::QueueNotFound(queue_name.to_string()));
+        }
+
+        let wait_times = self.wait_times.read().await;
+        Ok(wait_times
+            .get(queue_name)
+            .map(WaitPercentiles::from_durations)
+            .unwrap_or_default())
+    }
+
+    /// Feed a formed match's quality score to the analytics sink
+    pub(crate) async fn record_match_quality(&self, queue_name: &str, match_result: &MatchResult) {
+        if self.analytics.is_enabled() {
+            self.analytics.record_match_quality(queue_name, match_result.quality_score).await;
+        }
+    }
+
+    async fn record_matched(&self, queue_name: &str, matched: &[QueueEntry]) {
+        if matched.is_empty() {
+            return;
+        }
+        self.record_wait_times(queue_name, matched).await;
+        self.record_match(queue_name).await;
+        self.record_encounters(queue_name, matched).await;
+        for entry in matched {
+            self.record_player_events(queue_name, &entry.player_ids, PlayerQueueEvent::Matched).await;
+        }
+    }
+
+    async fn record_wait_times(&self, queue_name: &str, matched: &[QueueEntry]) {
+        let now = self.clock.now();
+        let mut wait_times = self.wait_times.write().await;
+        let window = wait_times.entry(queue_name.to_string()).or_default();
+
+        for entry in matched {
+            window.push_back(crate::time::to_std(entry.wait_time(now)));
+            if window.len() > WAIT_TIME_WINDOW {
+                window.pop_front();
+            }
+        }
+        drop(wait_times);
+
+        if self.analytics.is_enabled() {
+            let realized: Vec<Duration> = matched
+                .iter()
+                .flat_map(|entry| {
+                    let wait = crate::time::to_std(entry.wait_time(now));
+                    std::iter::repeat_n(wait, entry.player_ids.len())
+                })
+                .collect();
+            self.analytics.record_realized_waits(queue_name, &realized).await;
+        }
+    }
+
+    async fn record_player_events(&self, queue_name: &str, player_ids: &[Uuid], event: PlayerQueueEvent) {
+        if self.analytics.is_enabled() {
+            let now = self.clock.now();
+            for player_id in player_ids {
+                self.analytics.record_player_queue_event(queue_name, *player_id, event, now).await;
+            }
+        }
+    }
+
+    async fn record_match(&self, queue_name: &str) {
+        let now = self.clock.now();
+        let mut recent_matches = self.recent_matches.write().await;
+        let window = recent_matches.entry(queue_name.to_string()).or_default();
+
+        window.push_back(now);
+        while window.front().is_some_and(|t| now - *t >= chrono::Duration::minutes(1)) {
+            window.pop_front();
+        }
+    }
+
+    /// Sizes, match rate and average wait for every queue, plus totals
+    pub async fn global_stats(&self) -> Result<GlobalQueueStats> {
+        let now = self.clock.now();
+        let queues = self.queues.read().await;
+        let recent_matches = self.recent_matches.read().await;
+
+        let mut stats = GlobalQueueStats::default();
+        let mut total_wait = Duration::ZERO;
+
+        for (queue_name, entries) in queues.iter() {
+            let queue_wait: Duration = entries
+                .iter()
+                .map(|e| crate::time::to_std(e.wait_time(now)))
+                .sum();
+            let matches_per_minute = recent_matches
+                .get(queue_name)
+                .map(|window| window.iter().filter(|t| now - **t < chrono::Duration::minutes(1)).count())
+                .unwrap_or(0);
+
+            let queue_stats = QueueStats {
+                queue_name: queue_name.clone(),
+                queued_players: entries.iter().map(|e| e.player_count()).sum(),
+                queued_entries: entries.len(),
+                matches_per_minute,
+                average_wait: if entries.is_empty() {
+                    Duration::ZERO
+                } else {
+                    queue_wait / entries.len() as u32
+                },
+            };
+
+            stats.total_queued_players += queue_stats.queued_players;
+            stats.total_queued_entries += queue_stats.queued_entries;
+            stats.total_matches_per_minute += queue_stats.matches_per_minute;
+            total_wait += queue_wait;
+            stats.queues.push(queue_stats);
+        }
+
+        if stats.total_queued_entries > 0 {
+            stats.average_wait = total_wait / stats.total_queued_entries as u32;
+        }
+        stats.queues.sort_by(|a, b| a.queue_name.cmp(&b.queue_name));
+
+        Ok(stats)
+    }
+
+    /// Number of entries waiting in a queue, excluding reserved ones
     pub async fn get_queue_size(&self, queue_name: &str) -> Result<usize> {
         let queues = self.queues.read().await;
         Ok(queues.get(queue_name).map(|q| q.len()).unwrap_or(0))
     }
+
+    /// Number of waiting entries within the queue's rating delta of `rating`, e.g. for UI hints
+    ///
+    /// A searcher joining now has no wait expansion of its own, so each
+    /// entry allows the larger of the base delta and its own expanded delta,
+    /// as the matcher would. Only rating is considered; reserved entries are
+    /// not counted and nothing is modified.
+    pub async fn compatible_count(&self, queue_name: &str, rating: &Rating) -> Result<usize> {
+        let configs = self.configs.read().await;
+        let constraints = &configs
+            .get(queue_name)
+            .ok_or_else(|| MatchForgeError::QueueNotFound(queue_name.to_string()))?
+            .constraints;
+
+        let now = self.clock.now();
+        let queues = self.queues.read().await;
+        Ok(queues.get(queue_name).map_or(0, |entries| {
+            entries
+                .iter()
+                .filter(|entry| {
+                    let allowed = constraints.max_rating_delta.max(constraints.effective_rating_delta_at(entry, now));
+                    (entry.average_rating.rating - rating.rating).abs() <= allowed
+                })
+                .count()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clock::MockClock,
+        lobby::{Lobby, LobbyMetadata, LobbyState},
+        mmr::CalibrationSeeder,
+        persistence::InMemoryAdapter,
+        queue::{replay_snapshot, ConstraintMode, RoleRequirement},
+    };
+
+    async fn manager_with_queue(manager: QueueManager) -> QueueManager {
+        manager
+            .register_queue(QueueConfig::new("ranked", MatchFormat::one_v_one(), MatchConstraints::permissive()))
+            .await
+            .unwrap();
+        manager
+    }
+
+    #[tokio::test]
+    async fn test_seeded_player_uses_seed_rating() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let manager = manager_with_queue(QueueManager::new(persistence.clone())).await;
+
+        let seeded_player = Uuid::new_v4();
+        let seed = Rating::new(1900.0, 300.0, 0.06);
+        let seeded = manager
+            .join_queue_solo("ranked".to_string(), seeded_player, Rating::default_beginner(), EntryMetadata::default(), Some(seed))
+            .await
+            .unwrap();
+
+        assert_eq!(seeded.average_rating.rating, 1900.0);
+        assert!(seeded.average_rating.deviation >= 300.0);
+        let stored = persistence.load_player_rating(seeded_player).await.unwrap().unwrap();
+        assert_eq!(stored.rating, 1900.0);
+
+        let unseeded = manager
+            .join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(unseeded.average_rating.rating, Rating::default_beginner().rating);
+        assert_eq!(unseeded.average_rating.deviation, Rating::default_beginner().deviation);
+    }
+
+    #[tokio::test]
+    async fn test_shadow_rating_ignored_by_matching() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let manager = manager_with_queue(QueueManager::new(persistence.clone())).await;
+
+        // A shadow rating alone doesn't make a player established
+        let player_id = Uuid::new_v4();
+        persistence.save_shadow_rating(player_id, Rating::new(2400.0, 80.0, 0.06)).await.unwrap();
+        let seed = Rating::new(1300.0, 250.0, 0.06);
+        let entry = manager
+            .join_queue_solo("ranked".to_string(), player_id, Rating::default_beginner(), EntryMetadata::default(), Some(seed))
+            .await
+            .unwrap();
+
+        assert_eq!(entry.average_rating.rating, 1300.0);
+        assert_eq!(persistence.load_player_rating(player_id).await.unwrap().unwrap().rating, 1300.0);
+        assert_eq!(persistence.load_shadow_rating(player_id).await.unwrap().unwrap().rating, 2400.0);
+    }
+
+    #[tokio::test]
+    async fn test_compatible_count_within_rating_delta() {
+        let clock = Arc::new(MockClock::default());
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new())).with_clock(clock.clone());
+        let mut constraints = MatchConstraints::permissive();
+        constraints.max_rating_delta = 100.0;
+        constraints.expansion_rate = 10.0;
+        manager
+            .register_queue(QueueConfig::new("ranked", MatchFormat::five_v_five(), constraints))
+            .await
+            .unwrap();
+        let join = |rating: f64| {
+            manager.join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::new(rating, 100.0, 0.06), EntryMetadata::default(), None)
+        };
+
+        // Waited 10s, so this one accepts up to 200 away
+        join(1700.0).await.unwrap();
+        clock.advance(chrono::Duration::seconds(10));
+        for rating in [1400.0, 1500.0, 1600.0, 1601.0] {
+            join(rating).await.unwrap();
+        }
+
+        let searcher = Rating::new(1500.0, 100.0, 0.06);
+        assert_eq!(manager.compatible_count("ranked", &searcher).await.unwrap(), 4);
+        assert_eq!(manager.compatible_count("ranked", &Rating::new(2000.0, 100.0, 0.06)).await.unwrap(), 0);
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 5);
+        assert!(manager.compatible_count("casual", &searcher).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_seed_ignored_for_established_player() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let manager = manager_with_queue(QueueManager::new(persistence.clone())).await;
+
+        let player_id = Uuid::new_v4();
+        let established = Rating::new(1650.0, 80.0, 0.06);
+        persistence.save_player_rating(player_id, established).await.unwrap();
+
+        let entry = manager
+            .join_queue_solo("ranked".to_string(), player_id, established, EntryMetadata::default(), Some(Rating::new(2200.0, 300.0, 0.06)))
+            .await
+            .unwrap();
+
+        assert_eq!(entry.average_rating.rating, 1650.0);
+    }
+
+    #[tokio::test]
+    async fn test_rating_seeder_maps_calibration_signal() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let seeder = CalibrationSeeder::new("calibration".to_string(), 1000.0, 2000.0);
+        let manager = manager_with_queue(
+            QueueManager::new(persistence).with_rating_seeder(Arc::new(seeder)),
+        )
+        .await;
+
+        let mut metadata = EntryMetadata::default();
+        metadata.custom.insert("calibration".to_string(), "0.75".to_string());
+
+        let entry = manager
+            .join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), metadata, None)
+            .await
+            .unwrap();
+
+        assert_eq!(entry.average_rating.rating, 1750.0);
+        assert_eq!(entry.average_rating.deviation, 300.0);
+    }
+
+    #[tokio::test]
+    async fn test_queues_dispatch_on_configured_matcher() {
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new()));
+        for (name, matcher) in [
+            ("casual", MatcherKind::Greedy),
+            ("ranked", MatcherKind::Adaptive { max_wait_time_seconds: 60, curve: crate::queue::ExpansionCurve::default() }),
+        ] {
+            manager
+                .register_queue(QueueConfig {
+                    matcher,
+                    ..QueueConfig::new(name, MatchFormat::one_v_one(), MatchConstraints::permissive())
+                })
+                .await
+                .unwrap();
+        }
+
+        let mut ids = HashMap::new();
+        for queue in ["casual", "ranked"] {
+            for (label, rating) in [("oldest", 1000.0), ("far", 1400.0), ("close", 1010.0)] {
+                let player_id = Uuid::new_v4();
+                ids.insert((queue, label), player_id);
+                manager
+                    .join_queue_solo(queue.to_string(), player_id, Rating::new(rating, 100.0, 0.06), EntryMetadata::default(), None)
+                    .await
+                    .unwrap();
+            }
+        }
+
+        // Greedy anchors on the longest-waiting entry and pairs its closest rating neighbor
+        let casual = manager.find_matches("casual").await.unwrap();
+        assert_eq!(casual.len(), 1);
+        let casual_players: Vec<Uuid> = casual[0].entries.iter().flat_map(|e| e.player_ids.clone()).collect();
+        assert!(casual_players.contains(&ids[&("casual", "oldest")]));
+        assert!(casual_players.contains(&ids[&("casual", "close")]));
+
+        // Adaptive pairs the closest rating too
+        let ranked = manager.find_matches("ranked").await.unwrap();
+        assert_eq!(ranked.len(), 1);
+        let ranked_players: Vec<Uuid> = ranked[0].entries.iter().flat_map(|e| e.player_ids.clone()).collect();
+        assert!(ranked_players.contains(&ids[&("ranked", "oldest")]));
+        assert!(ranked_players.contains(&ids[&("ranked", "close")]));
+    }
+
+    #[tokio::test]
+    async fn test_wait_time_percentiles_from_matched_entries() {
+        let manager = manager_with_queue(QueueManager::new(Arc::new(InMemoryAdapter::new()))).await;
+
+        // Wait times of 1..=100 seconds
+        let mut entries = Vec::new();
+        for secs in 1..=100 {
+            let mut entry = QueueEntry::new_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default());
+            entry.joined_at = chrono::Utc::now() - chrono::Duration::seconds(secs);
+            entries.push(entry);
+        }
+        manager.queues.write().await.get_mut("ranked").unwrap().extend(entries.clone());
+
+        manager.remove_matched_entries("ranked", &entries).await.unwrap();
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 0);
+
+        let percentiles = manager.wait_time_percentiles("ranked").await.unwrap();
+        assert_eq!(percentiles.sample_count, 100);
+        assert_eq!(percentiles.p50.as_secs(), 50);
+        assert_eq!(percentiles.p90.as_secs(), 90);
+        assert_eq!(percentiles.p95.as_secs(), 95);
+        assert_eq!(percentiles.p99.as_secs(), 99);
+    }
+
+    #[tokio::test]
+    async fn test_wait_time_percentiles_empty_and_unknown_queue() {
+        let manager = manager_with_queue(QueueManager::new(Arc::new(InMemoryAdapter::new()))).await;
+
+        assert_eq!(manager.wait_time_percentiles("ranked").await.unwrap(), WaitPercentiles::default());
+        assert!(matches!(
+            manager.wait_time_percentiles("missing").await,
+            Err(MatchForgeError::QueueNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_odd_player_count_leaves_one_unmatched() {
+        let manager = manager_with_queue(QueueManager::new(Arc::new(InMemoryAdapter::new()))).await;
+
+        for _ in 0..5 {
+            manager
+                .join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+
+        let result = manager.find_matches_with_unmatched("ranked").await.unwrap();
+        assert_eq!(result.matches.len(), 2);
+        assert_eq!(result.unmatched.len(), 1);
+        let leftover = result.unmatched[0];
+
+        // The leftover player is still queued for the next tick
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 1);
+        let next_tick = manager.find_matches_with_unmatched("ranked").await.unwrap();
+        assert!(next_tick.matches.is_empty());
+        assert_eq!(next_tick.unmatched, vec![leftover]);
+    }
+
+    #[tokio::test]
+    async fn test_entry_expires_exactly_at_ttl() {
+        let clock = Arc::new(MockClock::default());
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new())).with_clock(clock.clone());
+        manager
+            .register_queue(QueueConfig {
+                entry_ttl: Some(chrono::Duration::seconds(30)),
+                ..QueueConfig::new("ranked", MatchFormat::one_v_one(), MatchConstraints::permissive())
+            })
+            .await
+            .unwrap();
+
+        let player_id = Uuid::new_v4();
+        manager
+            .join_queue_solo("ranked".to_string(), player_id, Rating::default_beginner(), EntryMetadata::default(), None)
+            .await
+            .unwrap();
+
+        clock.advance(chrono::Duration::seconds(30) - chrono::Duration::milliseconds(1));
+        assert!(manager.expire_entries("ranked").await.unwrap().is_empty());
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 1);
+
+        clock.advance(chrono::Duration::milliseconds(1));
+        let expired = manager.expire_entries("ranked").await.unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].player_ids, vec![player_id]);
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_preview_matches_leaves_queue_unchanged() {
+        let manager = manager_with_queue(QueueManager::new(Arc::new(InMemoryAdapter::new()))).await;
+
+        for _ in 0..4 {
+            manager
+                .join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+
+        let entry_ids = |matches: &[MatchResult]| -> Vec<Vec<Uuid>> {
+            matches.iter().map(|m| m.entries.iter().map(|e| e.id).collect()).collect()
+        };
+
+        let preview = manager.preview_matches("ranked").await.unwrap();
+        assert_eq!(preview.len(), 2);
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 4);
+        assert_eq!(entry_ids(&manager.preview_matches("ranked").await.unwrap()), entry_ids(&preview));
+
+        let committed = manager.find_matches("ranked").await.unwrap();
+        assert_eq!(entry_ids(&committed), entry_ids(&preview));
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_leave_within_grace_is_penalty_free() {
+        let clock = Arc::new(MockClock::default());
+        let dodges = Arc::new(DodgeTracker::new());
+        let manager = manager_with_queue(
+            QueueManager::new(Arc::new(InMemoryAdapter::new()))
+                .with_clock(clock.clone())
+                .with_dodge_tracker(dodges.clone())
+                .with_leave_grace(chrono::Duration::seconds(10)),
+        )
+        .await;
+        let player_id = Uuid::new_v4();
+        let join = || manager.join_queue_solo("ranked".to_string(), player_id, Rating::default_beginner(), EntryMetadata::default(), None);
+
+        join().await.unwrap();
+        clock.advance(chrono::Duration::seconds(2));
+        manager.leave_queue("ranked", player_id).await.unwrap();
+        assert_eq!(dodges.dodge_count(player_id).await, 0);
+
+        // Past the grace an unmatched leave counts as abandoning the queue
+        join().await.unwrap();
+        clock.advance(chrono::Duration::seconds(10));
+        manager.leave_queue("ranked", player_id).await.unwrap();
+        assert_eq!(dodges.dodge_count(player_id).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_leave_after_match_found_is_penalized() {
+        let dodges = Arc::new(DodgeTracker::new());
+        let manager = manager_with_queue(
+            QueueManager::new(Arc::new(InMemoryAdapter::new()))
+                .with_clock(Arc::new(MockClock::default()))
+                .with_dodge_tracker(dodges.clone())
+                .with_leave_grace(chrono::Duration::seconds(10)),
+        )
+        .await;
+        let players = [Uuid::new_v4(), Uuid::new_v4()];
+        for player_id in players {
+            manager
+                .join_queue_solo("ranked".to_string(), player_id, Rating::default_beginner(), EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(manager.find_matches("ranked").await.unwrap().len(), 1);
+        manager.leave_queue("ranked", players[0]).await.unwrap();
+        assert_eq!(dodges.dodge_count(players[0]).await, 1);
+        assert_eq!(dodges.dodge_count(players[1]).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_shadow_banned_players_only_match_each_other() {
+        let anti_abuse = Arc::new(AntiAbuseSystem::new(crate::security::anti_abuse::AntiAbuseConfig::default()));
+        let manager = manager_with_queue(QueueManager::new(Arc::new(InMemoryAdapter::new())).with_anti_abuse(anti_abuse.clone())).await;
+        let banned = [Uuid::new_v4(), Uuid::new_v4()];
+        let clean = Uuid::new_v4();
+        for player_id in banned {
+            anti_abuse.apply_action(player_id, crate::security::AbuseAction::ShadowBan).await.unwrap();
+        }
+        let join = |player_id| manager.join_queue_solo("ranked".to_string(), player_id, Rating::default_beginner(), EntryMetadata::default(), None);
+
+        // A clean player waiting longest is still never paired with a banned one
+        join(clean).await.unwrap();
+        join(banned[0]).await.unwrap();
+        assert!(manager.find_matches("ranked").await.unwrap().is_empty());
+
+        join(banned[1]).await.unwrap();
+        let matches = manager.find_matches("ranked").await.unwrap();
+        assert_eq!(matches.len(), 1);
+        let mut matched = matches[0].player_ids();
+        matched.sort();
+        let mut expected = banned.to_vec();
+        expected.sort();
+        assert_eq!(matched, expected);
+        assert_eq!(manager.entry_status("ranked", clean).await.unwrap(), EntryStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_replayed_snapshot_matches_live_queue() {
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new())).with_clock(Arc::new(MockClock::default()));
+        manager
+            .register_queue(QueueConfig {
+                match_ids: MatchIdStrategy::Deterministic,
+                ..QueueConfig::new(
+                    "ranked",
+                    MatchFormat::one_v_one(),
+                    MatchConstraints {
+                        max_rating_delta: 150.0,
+                        ..MatchConstraints::permissive()
+                    },
+                )
+            })
+            .await
+            .unwrap();
+        for rating in [1500.0, 1900.0, 1580.0, 1450.0, 2400.0, 1850.0] {
+            manager
+                .join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::new(rating, 100.0, 0.06), EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+
+        let snapshot = manager.capture_queue_snapshot("ranked").await.unwrap();
+        let snapshot = QueueSnapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+        assert_eq!(snapshot.entries.len(), 6);
+        let config = manager.queue_config("ranked").await.unwrap();
+        let mut matcher = config.matcher.snapshot_matcher(&snapshot);
+        let replayed = replay_snapshot(&snapshot, matcher.as_mut());
+
+        let live = manager.find_matches("ranked").await.unwrap();
+        assert_eq!(live.len(), 2);
+        let summary = |matches: &[MatchResult]| -> Vec<(Uuid, Vec<Uuid>, Vec<usize>)> {
+            matches.iter().map(|m| (m.match_id, m.player_ids(), m.team_assignments.clone())).collect()
+        };
+        assert_eq!(summary(&replayed), summary(&live));
+    }
+
+    #[tokio::test]
+    async fn test_matched_entries_reserved_until_committed_or_released() {
+        let clock = Arc::new(MockClock::default());
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let manager = manager_with_queue(
+            QueueManager::new(persistence.clone())
+                .with_clock(clock.clone())
+                .with_reservation_grace(chrono::Duration::seconds(10)),
+        )
+        .await;
+        let players = [Uuid::new_v4(), Uuid::new_v4()];
+        for player_id in players {
+            manager
+                .join_queue_solo("ranked".to_string(), player_id, Rating::default_beginner(), EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+
+        let matches = manager.find_matches("ranked").await.unwrap();
+        assert_eq!(matches.len(), 1);
+        let until = clock.now() + chrono::Duration::seconds(10);
+        assert_eq!(manager.entry_status("ranked", players[0]).await.unwrap(), EntryStatus::Reserved { until });
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 0);
+        assert!(manager.find_matches("ranked").await.unwrap().is_empty());
+        assert!(matches!(
+            manager
+                .join_queue_solo("ranked".to_string(), players[0], Rating::default_beginner(), EntryMetadata::default(), None)
+                .await,
+            Err(MatchForgeError::AlreadyInQueue(_))
+        ));
+        assert_eq!(persistence.load_queue_entries("ranked").await.unwrap().len(), 2);
+
+        // Lobby creation failed: both players are matchable again, and the match never counted
+        assert_eq!(manager.release_reserved_entries("ranked", &matches[0].entries).await.unwrap(), 2);
+        for player_id in players {
+            assert_eq!(manager.entry_status("ranked", player_id).await.unwrap(), EntryStatus::Active);
+        }
+        assert_eq!(manager.global_stats().await.unwrap().total_matches_per_minute, 0);
+
+        // Lobby created this time: the entries are gone for good
+        let matches = manager.find_matches("ranked").await.unwrap();
+        manager.remove_matched_entries("ranked", &matches[0].entries).await.unwrap();
+        assert_eq!(manager.global_stats().await.unwrap().total_matches_per_minute, 1);
+        assert!(matches!(
+            manager.entry_status("ranked", players[0]).await,
+            Err(MatchForgeError::NotInQueue(_))
+        ));
+        assert!(persistence.load_queue_entries("ranked").await.unwrap().is_empty());
+        assert_eq!(manager.release_reserved_entries("ranked", &matches[0].entries).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_lapsed_reservation_returns_to_queue() {
+        let clock = Arc::new(MockClock::default());
+        let manager = manager_with_queue(
+            QueueManager::new(Arc::new(InMemoryAdapter::new()))
+                .with_clock(clock.clone())
+                .with_reservation_grace(chrono::Duration::seconds(10)),
+        )
+        .await;
+        for _ in 0..2 {
+            manager
+                .join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+        let first = manager.find_matches("ranked").await.unwrap();
+
+        clock.advance(chrono::Duration::seconds(9));
+        assert!(manager.find_matches("ranked").await.unwrap().is_empty());
+
+        // Nobody committed the match within the grace period
+        clock.advance(chrono::Duration::seconds(1));
+        let retried = manager.find_matches("ranked").await.unwrap();
+        assert_eq!(retried.len(), 1);
+        assert_eq!(retried[0].player_ids().len(), 2);
+        assert!(retried[0].player_ids().iter().all(|id| first[0].player_ids().contains(id)));
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_match_ids_survive_retry() {
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new()));
+        manager
+            .register_queue(QueueConfig {
+                match_ids: MatchIdStrategy::Deterministic,
+                ..QueueConfig::new("ranked", MatchFormat::one_v_one(), MatchConstraints::permissive())
+            })
+            .await
+            .unwrap();
+
+        for _ in 0..2 {
+            manager
+                .join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+
+        let preview = manager.preview_matches("ranked").await.unwrap();
+        let committed = manager.find_matches("ranked").await.unwrap();
+        assert_eq!(committed.len(), 1);
+        assert_eq!(committed[0].match_id, preview[0].match_id);
+    }
+
+    #[tokio::test]
+    async fn test_recent_opponents_not_repaired_within_window() {
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new()));
+        let mut constraints = MatchConstraints::permissive();
+        constraints.avoid_recent_window = 1;
+        manager
+            .register_queue(QueueConfig::new("ranked", MatchFormat::one_v_one(), constraints))
+            .await
+            .unwrap();
+
+        let play = |players: Vec<Uuid>| {
+            let manager = &manager;
+            async move {
+                for player_id in players {
+                    manager
+                        .join_queue_solo("ranked".to_string(), player_id, Rating::default_beginner(), EntryMetadata::default(), None)
+                        .await
+                        .unwrap();
+                }
+                let matches = manager.find_matches("ranked").await.unwrap();
+                for match_result in &matches {
+                    manager.remove_matched_entries("ranked", &match_result.entries).await.unwrap();
+                }
+                matches
+            }
+        };
+
+        let (a, b, c, d) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(play(vec![a, b]).await.len(), 1);
+
+        // They just played each other, so the rematch is held back
+        assert!(play(vec![a, b]).await.is_empty());
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 2);
+
+        // Once each has played someone else, the pairing is allowed again
+        let matches = play(vec![c, d]).await;
+        assert_eq!(matches.len(), 2);
+        assert!(matches.iter().all(|m| !(m.player_ids().contains(&a) && m.player_ids().contains(&b))));
+        assert_eq!(play(vec![a, b]).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_player_in_live_lobby_not_matched_again() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let manager = manager_with_queue(QueueManager::new(persistence.clone())).await;
+        let (busy, other) = (Uuid::new_v4(), Uuid::new_v4());
+        for player_id in [busy, other] {
+            manager
+                .join_queue_solo("ranked".to_string(), player_id, Rating::default_beginner(), EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+
+        let mut lobby = Lobby::from_match_result(
+            MatchResult {
+                match_id: Uuid::new_v4(),
+                entries: Vec::new(),
+                team_assignments: Vec::new(),
+                split_party_ids: Vec::new(),
+                quality_score: 1.0,
+                game_mode: None,
+                team_roles: Vec::new(),
+            },
+            vec![1, 1],
+            LobbyMetadata::default(),
+        );
+        lobby.player_ids = vec![busy, Uuid::new_v4()];
+        lobby.transition_to(LobbyState::WaitingForReady).unwrap();
+        lobby.transition_to(LobbyState::Ready).unwrap();
+        persistence.save_lobby(&lobby).await.unwrap();
+
+        let result = manager.find_matches_with_unmatched("ranked").await.unwrap();
+        assert!(result.matches.is_empty());
+        assert!(result.unmatched.contains(&busy));
+        assert_eq!(manager.entry_status("ranked", busy).await.unwrap(), EntryStatus::Active);
+
+        lobby.transition_to(LobbyState::Closed).unwrap();
+        persistence.save_lobby(&lobby).await.unwrap();
+        let matches = manager.find_matches("ranked").await.unwrap();
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].player_ids().contains(&busy));
+    }
+
+    #[tokio::test]
+    async fn test_merged_party_keeps_earliest_join_time() {
+        let clock = Arc::new(MockClock::default());
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new())).with_clock(clock.clone());
+        manager
+            .register_queue(QueueConfig::new("duos", MatchFormat::two_v_two(), MatchConstraints::permissive()))
+            .await
+            .unwrap();
+
+        let join = |player_id: Uuid, rating: f64| {
+            manager.join_queue_solo("duos".to_string(), player_id, Rating::new(rating, 100.0, 0.06), EntryMetadata::default(), None)
+        };
+        let (first, second, bystander) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let earliest = join(first, 1400.0).await.unwrap().joined_at;
+        clock.advance(chrono::Duration::seconds(30));
+        join(bystander, 1500.0).await.unwrap();
+        clock.advance(chrono::Duration::seconds(30));
+        join(second, 1600.0).await.unwrap();
+
+        let party_id = Uuid::new_v4();
+        let party = manager.merge_entries_into_party(vec![second, first], party_id).await.unwrap();
+        assert_eq!(party.joined_at, earliest);
+        assert_eq!(party.party_id, Some(party_id));
+        assert_eq!(party.average_rating.rating, 1500.0);
+
+        let queues = manager.queues.read().await;
+        let entries = &queues["duos"];
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries.iter().filter(|e| e.player_ids.contains(&first)).count(), 1);
+        assert!(entries.iter().all(|e| e.party_id == Some(party_id) || e.player_ids == vec![bystander]));
+    }
+
+    #[tokio::test]
+    async fn test_merge_rejected_when_party_exceeds_format() {
+        let manager = manager_with_queue(QueueManager::new(Arc::new(InMemoryAdapter::new()))).await;
+        let players = vec![Uuid::new_v4(), Uuid::new_v4()];
+        for player_id in &players {
+            manager
+                .join_queue_solo("ranked".to_string(), *player_id, Rating::default_beginner(), EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+
+        let err = manager.merge_entries_into_party(players.clone(), Uuid::new_v4()).await.unwrap_err();
+        assert!(matches!(err, MatchForgeError::PartyFull(1)));
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 2);
+
+        let err = manager
+            .merge_entries_into_party(vec![players[0], Uuid::new_v4()], Uuid::new_v4())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MatchForgeError::NotInQueue(_)));
+    }
+
+    async fn manager_with_party_rules(require_full_party: bool, solo_only: bool) -> Result<QueueManager> {
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new()));
+        manager
+            .register_queue(QueueConfig {
+                require_full_party,
+                solo_only,
+                ..QueueConfig::new("flex", MatchFormat::two_v_two(), MatchConstraints::permissive())
+            })
+            .await?;
+        Ok(manager)
+    }
+
+    #[tokio::test]
+    async fn test_full_party_queue_rejects_solos_and_partial_parties() {
+        let manager = manager_with_party_rules(true, false).await.unwrap();
+
+        let err = manager
+            .join_queue_solo("flex".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MatchForgeError::ConstraintsNotSatisfied(_)));
+
+        let party = || vec![Uuid::new_v4(), Uuid::new_v4()];
+        let err = manager
+            .join_queue_party("flex".to_string(), Uuid::new_v4(), vec![Uuid::new_v4()], Rating::default_beginner(), EntryMetadata::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MatchForgeError::ConstraintsNotSatisfied(_)));
+
+        manager
+            .join_queue_party("flex".to_string(), Uuid::new_v4(), party(), Rating::default_beginner(), EntryMetadata::default())
+            .await
+            .unwrap();
+        assert_eq!(manager.get_queue_size("flex").await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_solo_only_queue_rejects_parties() {
+        let manager = manager_with_party_rules(false, true).await.unwrap();
+
+        let err = manager
+            .join_queue_party("flex".to_string(), Uuid::new_v4(), vec![Uuid::new_v4(), Uuid::new_v4()], Rating::default_beginner(), EntryMetadata::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MatchForgeError::ConstraintsNotSatisfied(_)));
+
+        let players = vec![Uuid::new_v4(), Uuid::new_v4()];
+        for player_id in &players {
+            manager
+                .join_queue_solo("flex".to_string(), *player_id, Rating::default_beginner(), EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+        assert!(manager.merge_entries_into_party(players, Uuid::new_v4()).await.is_err());
+        assert_eq!(manager.get_queue_size("flex").await.unwrap(), 2);
+
+        let err = manager_with_party_rules(true, true).await.err().unwrap();
+        assert!(matches!(err, MatchForgeError::InvalidConfiguration(_)));
+    }
+
+    async fn join_in_region(manager: &QueueManager, region: &str) -> Uuid {
+        let player_id = Uuid::new_v4();
+        let metadata = EntryMetadata {
+            region: Some(region.to_string()),
+            ..EntryMetadata::default()
+        };
+        manager
+            .join_queue_solo("ranked".to_string(), player_id, Rating::default_beginner(), metadata, None)
+            .await
+            .unwrap();
+        player_id
+    }
+
+    #[tokio::test]
+    async fn test_low_quality_match_waits_for_better_candidate() {
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new()));
+        manager
+            .register_queue(QueueConfig {
+                min_match_quality: Some(0.8),
+                ..QueueConfig::new(
+                    "ranked",
+                    MatchFormat::one_v_one(),
+                    MatchConstraints {
+                        same_region_required: true,
+                        region_mode: ConstraintMode::Soft { penalty: 0.5 },
+                        ..MatchConstraints::permissive()
+                    },
+                )
+            })
+            .await
+            .unwrap();
+
+        // A cross-region pairing scores 0.5 and is held back
+        let eu_player = join_in_region(&manager, "eu").await;
+        join_in_region(&manager, "na").await;
+        let result = manager.find_matches_with_unmatched("ranked").await.unwrap();
+        assert!(result.matches.is_empty());
+        assert_eq!(result.unmatched.len(), 2);
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 2);
+
+        let eu_opponent = join_in_region(&manager, "eu").await;
+        let matches = manager.find_matches("ranked").await.unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].quality_score, 1.0);
+        let mut matched = matches[0].player_ids();
+        matched.sort();
+        let mut expected = vec![eu_player, eu_opponent];
+        expected.sort();
+        assert_eq!(matched, expected);
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_min_match_quality_must_be_a_valid_score() {
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new()));
+        let err = manager
+            .register_queue(QueueConfig {
+                min_match_quality: Some(1.5),
+                ..QueueConfig::new("ranked", MatchFormat::one_v_one(), MatchConstraints::permissive())
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MatchForgeError::InvalidConfiguration(_)));
+    }
+
+    #[test]
+    fn test_min_match_quality_needs_a_scoring_matcher() {
+        let with_matcher = |matcher: MatcherKind| QueueConfig {
+            matcher,
+            min_match_quality: Some(0.5),
+            ..QueueConfig::new("ranked", MatchFormat::one_v_one(), MatchConstraints::permissive())
+        };
+
+        for matcher in [
+            MatcherKind::Adaptive { max_wait_time_seconds: 60, curve: crate::queue::ExpansionCurve::default() },
+            MatcherKind::Swiss { max_score_difference: 100.0 },
+        ] {
+            let err = with_matcher(matcher).validate().unwrap_err();
+            assert!(matches!(err, MatchForgeError::InvalidConfiguration(_)));
+        }
+        for matcher in [MatcherKind::Greedy, MatcherKind::Fuzzy { variety_factor: 0.5 }, MatcherKind::ModePreference] {
+            with_matcher(matcher).validate().unwrap();
+        }
+        // Without a quality floor any matcher is fine
+        QueueConfig {
+            matcher: MatcherKind::Swiss { max_score_difference: 100.0 },
+            ..QueueConfig::new("ranked", MatchFormat::one_v_one(), MatchConstraints::permissive())
+        }
+        .validate()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_one_v_one_matchers_rejected_for_team_formats() {
+        let one_v_one_only = [
+            MatcherKind::Adaptive { max_wait_time_seconds: 60, curve: crate::queue::ExpansionCurve::default() },
+            MatcherKind::Swiss { max_score_difference: 100.0 },
+            MatcherKind::Fuzzy { variety_factor: 0.5 },
+        ];
+        for matcher in one_v_one_only.iter().cloned().chain([MatcherKind::Greedy, MatcherKind::ModePreference]) {
+            let config = |format: MatchFormat| QueueConfig {
+                matcher: matcher.clone(),
+                ..QueueConfig::new("ranked", format, MatchConstraints::permissive())
+            };
+            config(MatchFormat::one_v_one()).validate().unwrap();
+            let team_format = config(MatchFormat::five_v_five()).validate();
+            if matcher.is_one_v_one_only() {
+                assert!(matches!(team_format, Err(MatchForgeError::InvalidConfiguration(_))), "{:?}", matcher);
+            } else {
+                team_format.unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_role_requirements_need_a_role_enforcing_matcher() {
+        let with_matcher = |matcher: MatcherKind| QueueConfig {
+            matcher,
+            ..QueueConfig::new(
+                "ranked",
+                MatchFormat::one_v_one(),
+                MatchConstraints {
+                    role_requirements: vec![RoleRequirement { role: "tank".to_string(), count: 1 }],
+                    ..MatchConstraints::permissive()
+                },
+            )
+        };
+
+        for matcher in [
+            MatcherKind::Adaptive { max_wait_time_seconds: 60, curve: crate::queue::ExpansionCurve::default() },
+            MatcherKind::Swiss { max_score_difference: 100.0 },
+            MatcherKind::Fuzzy { variety_factor: 0.5 },
+        ] {
+            let err = with_matcher(matcher).validate().unwrap_err();
+            assert!(matches!(err, MatchForgeError::InvalidConfiguration(_)));
+        }
+        with_matcher(MatcherKind::Greedy).validate().unwrap();
+        with_matcher(MatcherKind::ModePreference).validate().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_draining_queue_rejects_joins_but_keeps_matching() {
+        let manager = manager_with_queue(QueueManager::new(Arc::new(InMemoryAdapter::new()))).await;
+        for _ in 0..2 {
+            manager
+                .join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+
+        manager.set_draining("ranked", true).await.unwrap();
+        assert!(manager.is_draining("ranked").await);
+        let err = manager
+            .join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MatchForgeError::QueueDraining(ref name) if name == "ranked"));
+        let err = manager
+            .join_queue_party("ranked".to_string(), Uuid::new_v4(), vec![Uuid::new_v4()], Rating::default_beginner(), EntryMetadata::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MatchForgeError::QueueDraining(_)));
+
+        assert_eq!(manager.find_matches("ranked").await.unwrap().len(), 1);
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_undraining_restores_joins() {
+        let manager = manager_with_queue(QueueManager::new(Arc::new(InMemoryAdapter::new()))).await;
+        assert!(manager.set_draining("missing", true).await.is_err());
+
+        manager.drain_all().await;
+        assert!(manager.is_draining("ranked").await);
+
+        manager.set_draining("ranked", false).await.unwrap();
+        assert!(!manager.is_draining("ranked").await);
+        manager
+            .join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default(), None)
+            .await
+            .unwrap();
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 1);
+    }
+
+    async fn join_with_role(manager: &QueueManager, role: &str) {
+        let metadata = EntryMetadata {
+            roles: vec![role.to_string()],
+            ..EntryMetadata::default()
+        };
+        manager
+            .join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), metadata, None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_role_enforcement_toggles_live() {
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new()));
+        manager
+            .register_queue(QueueConfig::new(
+                "ranked",
+                MatchFormat::one_v_one(),
+                MatchConstraints {
+                    role_requirements: vec![RoleRequirement { role: "tank".to_string(), count: 1 }],
+                    ..MatchConstraints::permissive()
+                },
+            ))
+            .await
+            .unwrap();
+        assert!(manager.set_role_enforcement("missing", false).await.is_err());
+
+        join_with_role(&manager, "tank").await;
+        join_with_role(&manager, "tank").await;
+        assert_eq!(manager.find_matches("ranked").await.unwrap().len(), 1);
+
+        // Two damage players can't fill the tank slots
+        join_with_role(&manager, "dps").await;
+        join_with_role(&manager, "dps").await;
+        assert!(manager.find_matches("ranked").await.unwrap().is_empty());
+
+        manager.set_role_enforcement("ranked", false).await.unwrap();
+        assert!(!manager.is_role_enforced("ranked").await);
+        assert_eq!(manager.find_matches("ranked").await.unwrap().len(), 1);
+
+        manager.set_role_enforcement("ranked", true).await.unwrap();
+        join_with_role(&manager, "dps").await;
+        join_with_role(&manager, "dps").await;
+        assert!(manager.find_matches("ranked").await.unwrap().is_empty());
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_queue_activity_reaches_analytics() {
+        let clock = Arc::new(MockClock::default());
+        let analytics = Arc::new(crate::analytics::AnalyticsMetrics::new(crate::analytics::metrics::AnalyticsConfig::default()));
+        let manager = manager_with_queue(
+            QueueManager::new(Arc::new(InMemoryAdapter::new()))
+                .with_clock(clock.clone())
+                .with_analytics(analytics.clone()),
+        )
+        .await;
+
+        manager
+            .join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default(), None)
+            .await
+            .unwrap();
+        clock.advance(chrono::Duration::seconds(45));
+        manager
+            .join_queue_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default(), None)
+            .await
+            .unwrap();
+        clock.advance(chrono::Duration::seconds(3));
+
+        let matches = manager.find_matches("ranked").await.unwrap();
+        assert_eq!(matches.len(), 1);
+        // Waits are realized when the match is committed
+        assert_eq!(analytics.realized_wait_histogram("ranked").await.sample_count, 0);
+        manager.remove_matched_entries("ranked", &matches[0].entries).await.unwrap();
+
+        // Default bounds: 5s, 10s, 30s, 60s, ...
+        let histogram = analytics.realized_wait_histogram("ranked").await;
+        assert_eq!(histogram.sample_count, 2);
+        assert_eq!(histogram.counts[0], 1);
+        assert_eq!(histogram.counts[3], 1);
+        assert_eq!(histogram.total_wait, Duration::from_secs(51));
+        // Equal ratings make a perfect match
+        let quality = analytics.quality_distribution("ranked").await;
+        assert_eq!(quality.iter().map(|(_, count)| count).sum::<u64>(), 1);
+        assert_eq!(quality[9].1, 1);
+
+        // A third player gives up and is never matched
+        let quitter = Uuid::new_v4();
+        manager
+            .join_queue_solo("ranked".to_string(), quitter, Rating::default_beginner(), EntryMetadata::default(), None)
+            .await
+            .unwrap();
+        manager.leave_queue("ranked", quitter).await.unwrap();
+        clock.advance(chrono::Duration::minutes(10));
+        let rate = analytics.abandonment_rate_at("ranked", clock.now()).await;
+        assert!((rate - 1.0 / 3.0).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_global_stats_sum_per_queue_values() {
+        let clock = Arc::new(MockClock::default());
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new())).with_clock(clock.clone());
+        for name in ["duel", "squads"] {
+            manager
+                .register_queue(QueueConfig::new(name, MatchFormat::one_v_one(), MatchConstraints::permissive()))
+                .await
+                .unwrap();
+        }
+
+        for _ in 0..5 {
+            manager
+                .join_queue_solo("duel".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+        manager
+            .join_queue_party("squads".to_string(), Uuid::new_v4(), vec![Uuid::new_v4(), Uuid::new_v4()], Rating::default_beginner(), EntryMetadata::default())
+            .await
+            .unwrap();
+
+        clock.advance(chrono::Duration::seconds(20));
+        let matches = manager.find_matches("duel").await.unwrap();
+        assert_eq!(matches.len(), 2);
+        for match_result in &matches {
+            manager.remove_matched_entries("duel", &match_result.entries).await.unwrap();
+        }
+
+        let stats = manager.global_stats().await.unwrap();
+        assert_eq!(stats.queues.len(), 2);
+        let duel = &stats.queues[0];
+        let squads = &stats.queues[1];
+        assert_eq!((duel.queued_players, duel.matches_per_minute), (1, 2));
+        assert_eq!((squads.queued_players, squads.queued_entries), (2, 1));
+
+        assert_eq!(stats.total_queued_players, duel.queued_players + squads.queued_players);
+        assert_eq!(stats.total_queued_entries, duel.queued_entries + squads.queued_entries);
+        assert_eq!(stats.total_matches_per_minute, duel.matches_per_minute + squads.matches_per_minute);
+        assert_eq!(stats.average_wait, Duration::from_secs(20));
+
+        // Matches older than a minute no longer count towards the rate
+        clock.advance(chrono::Duration::minutes(1));
+        assert_eq!(manager.global_stats().await.unwrap().total_matches_per_minute, 0);
+    }
+
+    #[tokio::test]
+    async fn test_diagnosis_attributes_region_only_blocker() {
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new()));
+        manager
+            .register_queue(QueueConfig::new(
+                "ranked",
+                MatchFormat::one_v_one(),
+                MatchConstraints {
+                    same_region_required: true,
+                    ..MatchConstraints::permissive()
+                },
+            ))
+            .await
+            .unwrap();
+
+        let stuck = join_in_region(&manager, "oce").await;
+        let others = vec![
+            join_in_region(&manager, "eu-west").await,
+            join_in_region(&manager, "eu-west").await,
+            join_in_region(&manager, "us-east").await,
+        ];
+
+        let diagnosis = manager.diagnose("ranked", stuck).await.unwrap();
+        assert_eq!(diagnosis.player_id, stuck);
+        assert_eq!(diagnosis.candidates.len(), 3);
+        for (candidate, player_id) in diagnosis.candidates.iter().zip(&others) {
+            assert_eq!(candidate.player_ids, vec![*player_id]);
+            assert_eq!(candidate.violations, vec![ConstraintViolation::Region]);
+        }
+        assert_eq!(diagnosis.compatible_candidates().count(), 0);
+
+        // Read-only: nobody left the queue
+        assert_eq!(manager.get_queue_size("ranked").await.unwrap(), 4);
+        let err = manager.diagnose("ranked", Uuid::new_v4()).await.unwrap_err();
+        assert!(matches!(err, MatchForgeError::NotInQueue(_)));
+    }
+
+    #[tokio::test]
+    async fn test_priority_boost_honored_by_every_one_v_one_matcher() {
+        let kinds = [
+            MatcherKind::Greedy,
+            MatcherKind::Adaptive { max_wait_time_seconds: 60, curve: crate::queue::ExpansionCurve::default() },
+            MatcherKind::Swiss { max_score_difference: 100.0 },
+            MatcherKind::Fuzzy { variety_factor: 0.0 },
+        ];
+        for matcher in kinds {
+            for boost_active in [true, false] {
+                let clock = Arc::new(MockClock::default());
+                let manager = QueueManager::new(Arc::new(InMemoryAdapter::new())).with_clock(clock.clone());
+                manager
+                    .register_queue(QueueConfig {
+                        matcher: matcher.clone(),
+                        ..QueueConfig::new("duel", MatchFormat::one_v_one(), MatchConstraints::permissive())
+                    })
+                    .await
+                    .unwrap();
+                let join = |rating: f64, metadata: EntryMetadata| {
+                    let player_id = Uuid::new_v4();
+                    let manager = &manager;
+                    async move {
+                        manager
+                            .join_queue_solo("duel".to_string(), player_id, Rating::new(rating, 100.0, 0.06), metadata, None)
+                            .await
+                            .unwrap();
+                        player_id
+                    }
+                };
+
+                // The top-rated, longest-waiting player picks the boosted one unless the boost lets it pick first
+                let top = join(1020.0, EntryMetadata::default()).await;
+                clock.advance(chrono::Duration::seconds(1));
+                let low = join(1003.0, EntryMetadata::default()).await;
+                clock.advance(chrono::Duration::seconds(1));
+                let boosted = join(
+                    1010.0,
+                    EntryMetadata {
+                        priority_boost_until: Some(clock.now() + chrono::Duration::seconds(30)),
+                        ..EntryMetadata::default()
+                    },
+                )
+                .await;
+                if !boost_active {
+                    clock.advance(chrono::Duration::minutes(1));
+                }
+
+                let matches = manager.find_matches("duel").await.unwrap();
+                assert_eq!(matches.len(), 1, "{:?}", matcher);
+                let mut players: Vec<Uuid> = matches[0].entries.iter().flat_map(|e| e.player_ids.clone()).collect();
+                players.sort();
+                let mut expected = if boost_active { vec![boosted, low] } else { vec![top, boosted] };
+                expected.sort();
+                assert_eq!(players, expected, "{:?} with boost active: {}", matcher, boost_active);
+            }
+        }
+    }
 }
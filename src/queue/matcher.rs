@@ -1,8 +1,22 @@
-use super::{constraints::MatchConstraints, entry::QueueEntry};
+use super::{
+    advanced_strategies::{AdaptiveMatcher, ExpansionCurve, FuzzyMatcher, QualityWeights, SwissMatcher},
+    constraints::{MatchConstraints, RecentEncounters},
+    entry::QueueEntry,
+    mode_preference::ModePreferenceMatcher,
+    snapshot::QueueSnapshot,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+#[cfg(feature = "runtime")]
+use crate::telemetry::EventCollector;
 use uuid::Uuid;
 
 /// Configuration for a match format
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatchFormat {
     pub name: String,
     pub team_sizes: Vec<usize>, // e.g., [1, 1] for 1v1, [5, 5] for 5v5
@@ -10,36 +24,29 @@ pub struct MatchFormat {
 }
 
 impl MatchFormat {
-    pub fn one_v_one() -> Self {
+    /// `team_count` teams of `team_size` players, named e.g. "5v5" or "2v2v2"
+    pub fn symmetric(team_count: usize, team_size: usize) -> Self {
         Self {
-            name: "1v1".to_string(),
-            team_sizes: vec![1, 1],
-            total_players: 2,
+            name: vec![team_size.to_string(); team_count].join("v"),
+            team_sizes: vec![team_size; team_count],
+            total_players: team_count * team_size,
         }
     }
 
+    pub fn one_v_one() -> Self {
+        Self::symmetric(2, 1)
+    }
+
     pub fn two_v_two() -> Self {
-        Self {
-            name: "2v2".to_string(),
-            team_sizes: vec![2, 2],
-            total_players: 4,
-        }
+        Self::symmetric(2, 2)
     }
 
     pub fn five_v_five() -> Self {
-        Self {
-            name: "5v5".to_string(),
-            team_sizes: vec![5, 5],
-            total_players: 10,
-        }
+        Self::symmetric(2, 5)
     }
 
     pub fn team_v_team(team_size: usize) -> Self {
-        Self {
-            name: format!("{}v{}", team_size, team_size),
-            team_sizes: vec![team_size, team_size],
-            total_players: team_size * 2,
-        }
+        Self::symmetric(2, team_size)
     }
 
     /// Get the total number of players per match
@@ -52,6 +59,12 @@ impl MatchFormat {
         self.team_sizes.len()
     }
 
+    /// Size of every team, or `None` if the teams differ in size
+    pub fn players_per_team(&self) -> Option<usize> {
+        let first = *self.team_sizes.first()?;
+        self.team_sizes.iter().all(|size| *size == first).then_some(first)
+    }
+
     /// Get the size of a specific team
     pub fn team_size(&self, team_index: usize) -> Option<usize> {
         self.team_sizes.get(team_index).copied()
@@ -67,87 +80,1325 @@ impl MatchFormat {
 }
 
 /// Result of a successful match
-#[derive(Debug, Clone)]
+///
+/// Fields added after the first wire version must be `#[serde(default)]` and
+/// appended at the end, so [`from_bytes`](Self::from_bytes) can still decode
+/// buffers written before they existed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatchResult {
     pub match_id: Uuid,
     pub entries: Vec<QueueEntry>,
     pub team_assignments: Vec<usize>, // Index in entries -> team number
+    /// Parties whose members were split across teams (see `MatchConstraints::split_party_across_teams`)
+    #[serde(default)]
+    pub split_party_ids: Vec<Uuid>,
+    /// 1.0 minus the penalties of any soft constraints the match violates (floored at 0.0)
+    #[serde(default = "default_quality_score")]
+    pub quality_score: f64,
+    /// Game mode chosen from the players' preferences, see [`MatcherKind::ModePreference`]
+    #[serde(default)]
+    pub game_mode: Option<String>,
+    /// Players of each declared role per team, filled only when the queue has
+    /// [`role_requirements`](MatchConstraints::role_requirements)
+    ///
+    /// Only the Greedy and ModePreference matchers enforce roles (see
+    /// [`MatcherKind::enforces_roles`]); queues combining role requirements
+    /// with another matcher are rejected at registration.
+    ///
+    /// A split party counts toward the team in its `team_assignments` slot.
+    #[serde(default)]
+    pub team_roles: Vec<HashMap<String, u32>>,
+}
+
+fn default_quality_score() -> f64 {
+    1.0
+}
+
+/// Namespace for deterministic match ids
+const MATCH_ID_NAMESPACE: Uuid = Uuid::from_u128(0x6d61_7463_6866_6f72_6765_2d6d_6174_6368);
+
+impl MatchResult {
+    /// All players in the match
+    pub fn player_ids(&self) -> Vec<Uuid> {
+        self.entries.iter().flat_map(|e| e.player_ids.iter().copied()).collect()
+    }
+
+    /// UUID v5 derived from the participants (in any order) and a nonce
+    ///
+    /// The same players under the same nonce always get the same id, so a
+    /// retried commit of an identical match can be deduplicated.
+    pub fn deterministic_id(player_ids: &[Uuid], nonce: u64) -> Uuid {
+        let mut sorted = player_ids.to_vec();
+        sorted.sort();
+
+        let mut name = Vec::with_capacity(sorted.len() * 16 + 8);
+        for id in &sorted {
+            name.extend_from_slice(id.as_bytes());
+        }
+        name.extend_from_slice(&nonce.to_be_bytes());
+        Uuid::new_v5(&MATCH_ID_NAMESPACE, &name)
+    }
+
+    /// Compact binary encoding for handing a match to a game server
+    ///
+    /// A version byte followed by MessagePack with structs written as arrays.
+    #[cfg(feature = "msgpack")]
+    pub fn to_bytes(&self) -> crate::error::Result<Vec<u8>> {
+        let mut bytes = vec![MATCH_RESULT_WIRE_VERSION];
+        rmp_serde::encode::write(&mut bytes, self).map_err(wire_error)?;
+        Ok(bytes)
+    }
+
+    /// Decode a buffer written by [`to_bytes`](Self::to_bytes)
+    ///
+    /// Buffers from older versions decode with defaults for fields they lack;
+    /// buffers from a newer version are rejected.
+    #[cfg(feature = "msgpack")]
+    pub fn from_bytes(bytes: &[u8]) -> crate::error::Result<Self> {
+        match bytes.split_first() {
+            Some((&version, payload)) if version <= MATCH_RESULT_WIRE_VERSION => {
+                rmp_serde::from_slice(payload).map_err(wire_error)
+            }
+            Some((&version, _)) => Err(wire_error(format!(
+                "unsupported match result version {} (newest known is {})",
+                version, MATCH_RESULT_WIRE_VERSION
+            ))),
+            None => Err(wire_error("empty match result buffer")),
+        }
+    }
+}
+
+/// Version byte written by [`MatchResult::to_bytes`]
+#[cfg(feature = "msgpack")]
+const MATCH_RESULT_WIRE_VERSION: u8 = 1;
+
+#[cfg(feature = "msgpack")]
+fn wire_error(e: impl std::fmt::Display) -> crate::error::MatchForgeError {
+    crate::error::StorageError::Serialization(e.to_string()).into()
+}
+
+/// How a queue assigns match ids
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MatchIdStrategy {
+    /// Fresh random id for every match found
+    #[default]
+    Random,
+    /// [`MatchResult::deterministic_id`] with the earliest join time (in
+    /// milliseconds) of the matched entries as the nonce
+    ///
+    /// Re-finding a match for the same queued entries (e.g. after a failed
+    /// commit) reuses its id, while the same players rematching after
+    /// re-queuing get a new one.
+    Deterministic,
+}
+
+impl MatchIdStrategy {
+    /// Apply this strategy to a freshly found match
+    pub fn assign(&self, match_result: &mut MatchResult) {
+        if let MatchIdStrategy::Deterministic = self {
+            let nonce = match_result
+                .entries
+                .iter()
+                .map(|e| e.joined_at.timestamp_millis())
+                .min()
+                .unwrap_or_default();
+            match_result.match_id = MatchResult::deterministic_id(&match_result.player_ids(), nonce as u64);
+        }
+    }
+}
+
+/// Matches found in a single pass over a queue, plus the players left waiting
+#[derive(Debug, Clone, Default)]
+pub struct MatchSearchResult {
+    pub matches: Vec<MatchResult>,
+    /// Players that were not placed in any match and remain queued
+    pub unmatched: Vec<Uuid>,
+}
+
+/// Matching algorithm used by a queue
+#[derive(Debug, Clone, Default)]
+pub enum MatcherKind {
+    /// Longest-waiting entries first (see [`GreedyMatcher`])
+    #[default]
+    Greedy,
+    /// Closest-rated pairings with wait-based constraint expansion (1v1 only)
+    Adaptive {
+        max_wait_time_seconds: i64,
+        curve: ExpansionCurve,
+    },
+    /// Swiss-style pairings using current ratings as scores (1v1 only)
+    Swiss { max_score_difference: f64 },
+    /// Random opponents within the rating delta, weighted towards the closest (1v1 only)
+    Fuzzy { variety_factor: f64 },
+    /// Greedy matching within the game mode players prefer most (see [`ModePreferenceMatcher`])
+    ModePreference,
+}
+
+impl MatcherKind {
+    /// Short name used in configuration errors
+    pub fn name(&self) -> &'static str {
+        match self {
+            MatcherKind::Greedy => "greedy",
+            MatcherKind::Adaptive { .. } => "adaptive",
+            MatcherKind::Swiss { .. } => "swiss",
+            MatcherKind::Fuzzy { .. } => "fuzzy",
+            MatcherKind::ModePreference => "mode preference",
+        }
+    }
+
+    /// Does this algorithm score matches by their soft constraint penalties?
+    /// Adaptive and Swiss always report a [`quality_score`](MatchResult::quality_score) of 1.0.
+    pub fn scores_quality(&self) -> bool {
+        !matches!(self, MatcherKind::Adaptive { .. } | MatcherKind::Swiss { .. })
+    }
+
+    /// Does this algorithm only form 1v1 matches? Such queues must use [`MatchFormat::one_v_one`].
+    pub fn is_one_v_one_only(&self) -> bool {
+        matches!(self, MatcherKind::Adaptive { .. } | MatcherKind::Swiss { .. } | MatcherKind::Fuzzy { .. })
+    }
+
+    /// Does this algorithm enforce [`role_requirements`](MatchConstraints::role_requirements)
+    /// and fill [`MatchResult::team_roles`]? Only the greedy-based ones do.
+    pub fn enforces_roles(&self) -> bool {
+        matches!(self, MatcherKind::Greedy | MatcherKind::ModePreference)
+    }
+
+    /// The configured algorithm for `format` and `constraints`, with no recent encounters
+    pub fn matcher(&self, format: &MatchFormat, constraints: &MatchConstraints) -> Box<dyn Matcher> {
+        self.build(format, constraints, &Arc::default(), |matcher| matcher)
+    }
+
+    /// The configured algorithm as it ran over `snapshot`, for [`replay_snapshot`](crate::queue::replay_snapshot)
+    pub fn snapshot_matcher(&self, snapshot: &QueueSnapshot) -> Box<dyn Matcher> {
+        self.build(&snapshot.format, &snapshot.constraints, &snapshot.recent_encounters, |matcher| matcher)
+    }
+
+    /// Run this algorithm over a snapshot of queue entries as of `now`
+    pub fn find_matches(
+        &self,
+        format: &MatchFormat,
+        constraints: &MatchConstraints,
+        entries: &[QueueEntry],
+        now: DateTime<Utc>,
+    ) -> Vec<MatchResult> {
+        self.matcher(format, constraints).find_matches(entries, now)
+    }
+
+    /// [`find_matches`](Self::find_matches) avoiding `encounters`, recording
+    /// matcher events such as adaptive constraint relaxation in `events`
+    #[cfg(feature = "runtime")]
+    pub fn find_matches_with_events(
+        &self,
+        format: &MatchFormat,
+        constraints: &MatchConstraints,
+        encounters: &Arc<RecentEncounters>,
+        entries: &[QueueEntry],
+        now: DateTime<Utc>,
+        events: Option<&Arc<dyn EventCollector>>,
+    ) -> Vec<MatchResult> {
+        self.build(format, constraints, encounters, |matcher| match events {
+            Some(events) => matcher.with_event_collector(events.clone()),
+            None => matcher,
+        })
+        .find_matches(entries, now)
+    }
+
+    fn build(
+        &self,
+        format: &MatchFormat,
+        constraints: &MatchConstraints,
+        encounters: &Arc<RecentEncounters>,
+        configure_adaptive: impl FnOnce(AdaptiveMatcher) -> AdaptiveMatcher,
+    ) -> Box<dyn Matcher> {
+        let greedy = || GreedyMatcher::new(format.clone(), constraints.clone()).with_recent_encounters(encounters.clone());
+        let matcher: Box<dyn Matcher> = match self {
+            // Runs its own starvation pass
+            MatcherKind::Greedy => return Box::new(greedy()),
+            MatcherKind::Adaptive {
+                max_wait_time_seconds,
+                curve,
+            } => {
+                let matcher =
+                    AdaptiveMatcher::new(constraints.clone(), chrono::Duration::seconds(*max_wait_time_seconds), 0.0)
+                        .with_curve(curve.clone());
+                Box::new(configure_adaptive(matcher))
+            }
+            MatcherKind::Fuzzy { variety_factor } => Box::new(
+                FuzzyMatcher::new(constraints.clone(), *variety_factor).with_recent_encounters(encounters.clone()),
+            ),
+            MatcherKind::Swiss { max_score_difference } => Box::new(SwissMatcher::new(*max_score_difference, false)),
+            MatcherKind::ModePreference => Box::new(ModePreferenceMatcher::from_greedy(greedy())),
+        };
+        if constraints.starvation.is_none() {
+            return matcher;
+        }
+        Box::new(WithStarvationPass { matcher, greedy: greedy() })
+    }
+}
+
+/// Runs the [`StarvationPolicy`](crate::queue::StarvationPolicy) pass of
+/// [`GreedyMatcher`] over the entries another matcher left unmatched
+struct WithStarvationPass {
+    matcher: Box<dyn Matcher>,
+    greedy: GreedyMatcher,
+}
+
+impl Matcher for WithStarvationPass {
+    fn find_matches(&mut self, entries: &[QueueEntry], now: DateTime<Utc>) -> Vec<MatchResult> {
+        let mut matches = self.matcher.find_matches(entries, now);
+        self.greedy.extend_with_starving_matches(entries, &mut matches, now);
+        matches
+    }
+}
+
+/// Synchronous matching over a snapshot of queue entries
+///
+/// Implementations only see the entries they are given and never touch a
+/// queue or persistence; [`QueueManager`](crate::queue::QueueManager) loads
+/// the entries, runs the queue's matcher and commits the result.
+pub trait Matcher: Send + Sync {
+    /// Form matches from `entries` as of `now`
+    ///
+    /// Each entry appears in at most one match. Takes `&mut self` so
+    /// matchers can keep state between passes, such as a random generator.
+    fn find_matches(&mut self, entries: &[QueueEntry], now: DateTime<Utc>) -> Vec<MatchResult>;
+}
+
+impl Matcher for GreedyMatcher {
+    fn find_matches(&mut self, entries: &[QueueEntry], now: DateTime<Utc>) -> Vec<MatchResult> {
+        GreedyMatcher::find_matches(self, entries, now)
+    }
+}
+
+impl Matcher for AdaptiveMatcher {
+    fn find_matches(&mut self, entries: &[QueueEntry], now: DateTime<Utc>) -> Vec<MatchResult> {
+        AdaptiveMatcher::find_matches(self, entries, now)
+    }
+}
+
+impl Matcher for FuzzyMatcher {
+    fn find_matches(&mut self, entries: &[QueueEntry], now: DateTime<Utc>) -> Vec<MatchResult> {
+        FuzzyMatcher::find_matches(self, entries, now)
+    }
+}
+
+/// Pairs entries using their current ratings as Swiss scores, without
+/// rematch history, boosted entries first
+impl Matcher for SwissMatcher {
+    fn find_matches(&mut self, entries: &[QueueEntry], now: DateTime<Utc>) -> Vec<MatchResult> {
+        let scores: HashMap<Uuid, f64> = entries
+            .iter()
+            .flat_map(|e| e.player_ids.iter().map(move |id| (*id, e.average_rating.rating)))
+            .collect();
+
+        self.find_pairings_at(entries, &scores, &HashMap::new(), now)
+    }
 }
 
 /// Simple greedy matchmaking algorithm
 pub struct GreedyMatcher {
     pub format: MatchFormat,
     pub constraints: MatchConstraints,
+    /// Ranks otherwise equal candidates; `None` takes them in queue order
+    pub quality_weights: Option<QualityWeights>,
+    /// Players checked against [`MatchConstraints::avoid_recent_window`]
+    pub recent_encounters: Arc<RecentEncounters>,
 }
 
+/// Unpaired rating neighbors on each side a 1v1 anchor considers before
+/// searching every unpaired entry
+const ONE_V_ONE_LOOKAHEAD: usize = 8;
+
 impl GreedyMatcher {
     pub fn new(format: MatchFormat, constraints: MatchConstraints) -> Self {
-        Self { format, constraints }
+        Self {
+            format,
+            constraints,
+            quality_weights: None,
+            recent_encounters: Arc::default(),
+        }
+    }
+
+    /// Keep players in `encounters` apart as the constraints' recent-encounter settings ask
+    pub fn with_recent_encounters(mut self, encounters: Arc<RecentEncounters>) -> Self {
+        self.recent_encounters = encounters;
+        self
+    }
+
+    /// Choose between compatible, equally penalized candidates by `weights`
+    ///
+    /// Without weights the 1v1 path pairs each entry with the nearest
+    /// compatible rating above it and the general path fills with the
+    /// longest-waiting entries.
+    pub fn with_quality_weights(mut self, weights: QualityWeights) -> Self {
+        self.quality_weights = Some(weights);
+        self
     }
 
     /// Attempt to find a match from the given queue entries
     pub fn find_match(&self, entries: &[QueueEntry]) -> Option<MatchResult> {
-        if entries.len() < self.format.total_players {
+        self.find_match_at(entries, Utc::now())
+    }
+
+    /// Attempt to find a match from the given queue entries as of `now`
+    ///
+    /// When soft constraints are configured, the least penalized match is
+    /// preferred: a match that violates a soft constraint is only returned
+    /// if no penalty-free match can be formed.
+    pub fn find_match_at(&self, entries: &[QueueEntry], now: DateTime<Utc>) -> Option<MatchResult> {
+        if entries.iter().map(|e| e.player_count()).sum::<usize>() < self.format.total_players {
+            return None;
+        }
+
+        // Boosted entries first, then longest waiting
+        let mut sorted_entries = entries.to_vec();
+        sorted_entries.sort_by_key(|e| e.priority_key(now));
+
+        // With only hard constraints the first entry in that order anchors the match;
+        // with soft ones a later anchor may produce a less penalized match
+        let anchors = if self.constraints.has_soft_constraints() {
+            sorted_entries.len()
+        } else {
+            1
+        };
+
+        let mut best: Option<(Vec<QueueEntry>, f64)> = None;
+        for anchor in 0..anchors {
+            let order: Vec<&QueueEntry> = std::iter::once(&sorted_entries[anchor])
+                .chain(sorted_entries.iter().enumerate().filter(|(i, _)| *i != anchor).map(|(_, e)| e))
+                .collect();
+
+            let Some((selected, penalty)) = self.select_entries(&order, now) else {
+                continue;
+            };
+            if !matches!(&best, Some((_, best_penalty)) if *best_penalty <= penalty) {
+                best = Some((selected, penalty));
+            }
+            if penalty == 0.0 {
+                break;
+            }
+        }
+
+        let (selected, penalty) = best?;
+        self.build_match(selected, penalty)
+    }
+
+    /// Assign `selected` to teams and wrap it in a [`MatchResult`]
+    fn build_match(&self, selected: Vec<QueueEntry>, penalty: f64) -> Option<MatchResult> {
+        // Assign teams
+        let (team_assignments, split_party_ids) = self.assign_teams(&selected)?;
+        let team_roles = if self.constraints.role_requirements.is_empty() {
+            Vec::new()
+        } else {
+            self.team_roles(&selected, &team_assignments)
+        };
+        Some(MatchResult {
+            match_id: Uuid::new_v4(),
+            entries: selected,
+            team_assignments,
+            split_party_ids,
+            quality_score: (1.0 - penalty).max(0.0),
+            game_mode: None,
+            team_roles,
+        })
+    }
+
+    /// Form matches from `entries` as of `now` until no more can be filled
+    ///
+    /// Synchronous and runtime-free, so it is available without the
+    /// `runtime` feature. Solo 1v1 queues with only hard constraints and no
+    /// role requirements take a path that searches each anchor's rating
+    /// neighbors first, O(n log n) unless hard constraints reject them, see
+    /// [`find_one_v_one_matches`](Self::find_one_v_one_matches). With a
+    /// [`StarvationPolicy`](crate::queue::StarvationPolicy) in the constraints,
+    /// entries left over that have waited past its threshold are then
+    /// force-matched, see [`find_starving_matches`](Self::find_starving_matches).
+    pub fn find_matches(&self, entries: &[QueueEntry], now: DateTime<Utc>) -> Vec<MatchResult> {
+        let mut matches = match self.find_one_v_one_matches(entries, now) {
+            Some(matches) => matches,
+            None => {
+                let mut matches = Vec::new();
+                let mut remaining_entries = entries.to_vec();
+
+                // Keep finding matches until we can't anymore
+                while let Some(match_result) = self.find_match_at(&remaining_entries, now) {
+                    let matched_ids: Vec<Uuid> = match_result.entries.iter().map(|e| e.id).collect();
+                    remaining_entries.retain(|e| !matched_ids.contains(&e.id));
+                    matches.push(match_result);
+                }
+                matches
+            }
+        };
+
+        self.extend_with_starving_matches(entries, &mut matches, now);
+        matches
+    }
+
+    /// Add [`find_starving_matches`](Self::find_starving_matches) over the
+    /// entries not already in `matches`
+    fn extend_with_starving_matches(&self, entries: &[QueueEntry], matches: &mut Vec<MatchResult>, now: DateTime<Utc>) {
+        if self.constraints.starvation.is_none() {
+            return;
+        }
+        let matched: HashSet<Uuid> = matches.iter().flat_map(|m| m.entries.iter().map(|e| e.id)).collect();
+        let remaining: Vec<QueueEntry> = entries.iter().filter(|e| !matched.contains(&e.id)).cloned().collect();
+        matches.extend(self.find_starving_matches(&remaining, now));
+    }
+
+    /// Force-match entries past the starvation threshold as of `now`
+    ///
+    /// Starving entries anchor a match in turn, boosted then longest-waiting
+    /// first, and are filled with the closest-rated entries that fit within
+    /// the policy's rating cap and the remaining constraints. Empty without a
+    /// [`StarvationPolicy`](crate::queue::StarvationPolicy).
+    pub fn find_starving_matches(&self, entries: &[QueueEntry], now: DateTime<Utc>) -> Vec<MatchResult> {
+        let Some(policy) = self.constraints.starvation else {
+            return Vec::new();
+        };
+        let forced = GreedyMatcher {
+            format: self.format.clone(),
+            constraints: MatchConstraints {
+                max_rating_delta: policy.max_rating_delta,
+                expansion_rate: 0.0,
+                ..self.constraints.clone()
+            },
+            quality_weights: self.quality_weights,
+            recent_encounters: self.recent_encounters.clone(),
+        };
+
+        let mut remaining: Vec<&QueueEntry> = entries.iter().collect();
+        remaining.sort_by_key(|e| e.priority_key(now));
+        let mut matches = Vec::new();
+        let mut anchor = 0;
+        while anchor < remaining.len() {
+            let starving = remaining[anchor];
+            if !policy.is_starving(starving, now) {
+                anchor += 1;
+                continue;
+            }
+
+            let mut order: Vec<&QueueEntry> = remaining.iter().copied().filter(|e| e.id != starving.id).collect();
+            let distance = |e: &QueueEntry| (e.average_rating.rating - starving.average_rating.rating).abs();
+            order.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+            order.insert(0, starving);
+
+            let Some(match_result) = forced
+                .select_entries(&order, now)
+                .and_then(|(selected, penalty)| forced.build_match(selected, penalty))
+            else {
+                anchor += 1;
+                continue;
+            };
+            let unmatched = |e: &&QueueEntry| !match_result.entries.iter().any(|m| m.id == e.id);
+            // Skipped anchors matched as candidates no longer come before the next one
+            anchor = remaining[..anchor].iter().filter(|e| unmatched(e)).count();
+            remaining.retain(unmatched);
+            matches.push(match_result);
+        }
+        matches
+    }
+
+    /// Pair solo 1v1 entries by rating, or `None` if the general path is needed
+    ///
+    /// Anchors are taken in the same order as the general path, boosted then
+    /// longest-waiting first, but each one first considers only the nearest
+    /// [`ONE_V_ONE_LOOKAHEAD`] unpaired entries on either side of it in
+    /// rating, and takes the compatible one with the best [`QualityWeights`]
+    /// score if weights are set, else the closest rating, earliest in
+    /// matching order on ties. If hard constraints rule out all of those, it
+    /// searches every unpaired entry the same way, so a compatible partner is
+    /// never missed. Other formats, soft constraints, role requirements and
+    /// parties use the general path.
+    fn find_one_v_one_matches(&self, entries: &[QueueEntry], now: DateTime<Utc>) -> Option<Vec<MatchResult>> {
+        if self.format.team_sizes != [1, 1]
+            || self.constraints.has_soft_constraints()
+            || !self.constraints.role_requirements.is_empty()
+            || entries.iter().any(|e| e.player_count() != 1)
+        {
             return None;
         }
 
-        // Calculate total players needed
+        // Matching order, with each entry's position in it used to break ties
+        let mut by_priority: Vec<&QueueEntry> = entries.iter().collect();
+        by_priority.sort_by(|a, b| a.priority_key(now).cmp(&b.priority_key(now)).then(a.id.cmp(&b.id)));
+        let mut by_rating: Vec<usize> = (0..by_priority.len()).collect();
+        by_rating.sort_by(|a, b| by_priority[*a].average_rating.rating.total_cmp(&by_priority[*b].average_rating.rating).then(a.cmp(b)));
+
+        // Unpaired entries form a linked list in rating order so paired ones are skipped in O(1)
+        let mut slot = vec![0; by_priority.len()];
+        for (position, &rank) in by_rating.iter().enumerate() {
+            slot[rank] = position;
+        }
+        let mut below: Vec<Option<usize>> = (0..by_rating.len()).map(|p| p.checked_sub(1)).collect();
+        let mut above: Vec<Option<usize>> = (0..by_rating.len()).map(|p| Some(p + 1).filter(|n| *n < by_rating.len())).collect();
+        let mut paired = vec![false; by_priority.len()];
+
+        let mut matches = Vec::with_capacity(by_priority.len() / 2);
+        for anchor in 0..by_priority.len() {
+            if paired[anchor] {
+                continue;
+            }
+            let position = slot[anchor];
+            let choose = |lookahead: usize| {
+                let lower = std::iter::successors(below[position], |p| below[*p]).take(lookahead);
+                let higher = std::iter::successors(above[position], |p| above[*p]).take(lookahead);
+                let compatible = lower.chain(higher).map(|p| by_rating[p]).filter_map(|other| {
+                    self.constraints
+                        .pair_penalty_with(by_priority[anchor], by_priority[other], now, &self.recent_encounters)
+                        .map(|penalty| (other, penalty))
+                });
+                match &self.quality_weights {
+                    Some(weights) => compatible.min_by(|a, b| {
+                        weights
+                            .score(by_priority[anchor], by_priority[a.0])
+                            .total_cmp(&weights.score(by_priority[anchor], by_priority[b.0]))
+                            .then(a.0.cmp(&b.0))
+                    }),
+                    None => compatible.min_by(|a, b| {
+                        let distance = |other: usize| (by_priority[other].average_rating.rating - by_priority[anchor].average_rating.rating).abs();
+                        distance(a.0).total_cmp(&distance(b.0)).then(a.0.cmp(&b.0))
+                    }),
+                }
+            };
+            // Region, tag or recent-encounter checks can rule out every close neighbor
+            let Some((other, penalty)) = choose(ONE_V_ONE_LOOKAHEAD).or_else(|| choose(usize::MAX)) else {
+                continue;
+            };
+
+            for rank in [anchor, other] {
+                paired[rank] = true;
+                let position = slot[rank];
+                if let Some(b) = below[position] {
+                    above[b] = above[position];
+                }
+                if let Some(a) = above[position] {
+                    below[a] = below[position];
+                }
+            }
+            matches.push(MatchResult {
+                match_id: Uuid::new_v4(),
+                entries: vec![by_priority[anchor].clone(), by_priority[other].clone()],
+                team_assignments: vec![0, 1],
+                split_party_ids: Vec::new(),
+                quality_score: (1.0 - penalty).max(0.0),
+                game_mode: None,
+                team_roles: Vec::new(),
+            });
+        }
+
+        Some(matches)
+    }
+
+    /// Greedily fill a match from entries in the given order
+    ///
+    /// Each step takes the compatible entry that adds the smallest soft
+    /// constraint penalty, then the best [`QualityWeights`] score against
+    /// the selected entries if weights are set, earliest in `order` on ties. Returns the selected
+    /// entries and their total penalty, or `None` if the match can't be filled.
+    fn select_entries(&self, order: &[&QueueEntry], now: DateTime<Utc>) -> Option<(Vec<QueueEntry>, f64)> {
         let total_needed = self.format.total_players;
+        let largest_team = self.format.team_sizes.iter().copied().max().unwrap_or(0);
+
+        // A party that can't fit on one team can only play if splitting is allowed
+        let mut remaining: Vec<&QueueEntry> = order
+            .iter()
+            .copied()
+            .filter(|e| e.player_count() <= largest_team || self.constraints.split_party_across_teams)
+            .collect();
 
-        // Try to form a match by greedily selecting compatible entries
         let mut selected: Vec<QueueEntry> = Vec::new();
         let mut player_count = 0;
+        let mut penalty = 0.0;
 
-        // Sort by wait time (prioritize longest waiting)
-        let mut sorted_entries = entries.to_vec();
-        sorted_entries.sort_by_key(|e| e.joined_at);
+        while player_count < total_needed {
+            let next = remaining
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| player_count + e.player_count() <= total_needed)
+                .filter(|(_, e)| {
+                    self.constraints
+                        .roles_allow(&selected, e, self.format.team_count(), total_needed)
+                })
+                .filter_map(|(i, e)| self.added_penalty(&selected, e, now).map(|p| (i, p, self.weighted_score(&selected, e))))
+                .min_by(|a, b| a.1.total_cmp(&b.1).then(a.2.total_cmp(&b.2)));
 
-        for entry in sorted_entries {
-            if player_count >= total_needed {
+            let Some((index, added, _)) = next else {
                 break;
+            };
+            let entry = remaining.remove(index);
+            player_count += entry.player_count();
+            penalty += added;
+            selected.push(entry.clone());
+        }
+
+        (player_count == total_needed).then_some((selected, penalty))
+    }
+
+    /// Penalty for adding `entry` to the selection, or `None` if it is incompatible with any selected entry
+    fn added_penalty(&self, selected: &[QueueEntry], entry: &QueueEntry, now: DateTime<Utc>) -> Option<f64> {
+        selected.iter().try_fold(0.0_f64, |worst, s| {
+            self.constraints
+                .pair_penalty_with(s, entry, now, &self.recent_encounters)
+                .map(|p| worst.max(p))
+        })
+    }
+
+    /// Worst [`QualityWeights`] score of `entry` against the selection, 0 without weights
+    fn weighted_score(&self, selected: &[QueueEntry], entry: &QueueEntry) -> f64 {
+        let Some(weights) = &self.quality_weights else {
+            return 0.0;
+        };
+        selected.iter().map(|s| weights.score(s, entry)).fold(0.0, f64::max)
+    }
+
+    /// Assign entries to teams, largest entries first
+    ///
+    /// With role requirements, entries filling a required role are placed
+    /// first, each on the team still missing the most of its roles, and the
+    /// rest go where they take the fewest slots those roles are waiting on.
+    /// Returns `None` if an entry can't fit on a single team and splitting
+    /// parties is not allowed. A split entry's assignment is the team that
+    /// received its first player.
+    fn assign_teams(&self, entries: &[QueueEntry]) -> Option<(Vec<usize>, Vec<Uuid>)> {
+        let team_sizes = &self.format.team_sizes;
+        let requirements = &self.constraints.role_requirements;
+        let mut assignments = vec![0; entries.len()];
+        let mut split_party_ids = Vec::new();
+        let mut team_fill: Vec<usize> = vec![0; team_sizes.len()];
+        // Players each team still needs per requirement
+        let mut outstanding: Vec<Vec<usize>> = vec![requirements.iter().map(|r| r.count).collect(); team_sizes.len()];
+        let fills_role = |entry: &QueueEntry| requirements.iter().any(|r| entry.players_in_role(&r.role) > 0);
+
+        let mut order: Vec<usize> = (0..entries.len()).collect();
+        order.sort_by_key(|&i| (std::cmp::Reverse(entries[i].player_count()), !fills_role(&entries[i])));
+
+        for i in order {
+            let entry = &entries[i];
+            let remaining = |fill: &[usize], team: usize| team_sizes[team] - fill[team];
+
+            let fitting = (0..team_sizes.len()).filter(|&t| remaining(&team_fill, t) >= entry.player_count());
+            let team = if requirements.is_empty() {
+                fitting.min()
+            } else {
+                fitting.max_by_key(|&t| {
+                    let filled: usize = requirements
+                        .iter()
+                        .zip(&outstanding[t])
+                        .map(|(r, needed)| entry.players_in_role(&r.role).min(*needed))
+                        .sum();
+                    let spare = remaining(&team_fill, t).saturating_sub(outstanding[t].iter().sum());
+                    (filled, spare, std::cmp::Reverse(t))
+                })
+            };
+            if let Some(team) = team {
+                assignments[i] = team;
+                team_fill[team] += entry.player_count();
+                for (r, needed) in requirements.iter().zip(outstanding[team].iter_mut()) {
+                    *needed = needed.saturating_sub(entry.players_in_role(&r.role));
+                }
+                continue;
             }
 
-            // Check if this entry is compatible with already selected entries
-            let compatible = selected.is_empty() || selected.iter().all(|s| self.constraints.can_match(s, &entry));
+            if !self.constraints.split_party_across_teams {
+                return None;
+            }
 
-            if compatible && player_count + entry.player_count() <= total_needed {
-                player_count += entry.player_count();
-                selected.push(entry);
+            // Deal the party's players out one at a time to the emptiest teams
+            for player in 0..entry.player_count() {
+                let team = (0..team_sizes.len())
+                    .filter(|&t| remaining(&team_fill, t) > 0)
+                    .max_by_key(|&t| (remaining(&team_fill, t), std::cmp::Reverse(t)))?;
+                if player == 0 {
+                    assignments[i] = team;
+                }
+                team_fill[team] += 1;
             }
+            split_party_ids.push(entry.party_id.unwrap_or(entry.id));
         }
 
-        if player_count == total_needed {
-            // Assign teams
-            let team_assignments = self.assign_teams(&selected);
-            Some(MatchResult {
-                match_id: Uuid::new_v4(),
-                entries: selected,
-                team_assignments,
-            })
-        } else {
-            None
+        Some((assignments, split_party_ids))
+    }
+
+    /// Count each team's players by declared role
+    fn team_roles(&self, entries: &[QueueEntry], team_assignments: &[usize]) -> Vec<HashMap<String, u32>> {
+        let mut team_roles = vec![HashMap::new(); self.format.team_count()];
+        for (entry, &team) in entries.iter().zip(team_assignments) {
+            for (role, count) in entry.role_counts() {
+                *team_roles[team].entry(role).or_insert(0) += count;
+            }
         }
+        team_roles
     }
+}
 
-    /// Assign entries to teams
-    fn assign_teams(&self, entries: &[QueueEntry]) -> Vec<usize> {
-        let mut assignments = Vec::new();
-        let mut current_team = 0;
-        let mut team_fill: Vec<usize> = vec![0; self.format.team_sizes.len()];
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mmr::Rating, queue::{constraints::{ConstraintMode, StarvationPolicy}, entry::EntryMetadata}};
 
-        for entry in entries {
-            // Find a team that needs more players
-            while team_fill[current_team] >= self.format.team_sizes[current_team] {
-                current_team += 1;
-                if current_team >= self.format.team_sizes.len() {
-                    break;
-                }
+    fn party_of(size: usize) -> QueueEntry {
+        let player_ids = (0..size).map(|_| Uuid::new_v4()).collect();
+        QueueEntry::new_party("squads".to_string(), Uuid::new_v4(), player_ids, Rating::default_beginner(), EntryMetadata::default())
+    }
+
+    fn solo() -> QueueEntry {
+        QueueEntry::new_solo("squads".to_string(), Uuid::new_v4(), Rating::default_beginner(), EntryMetadata::default())
+    }
+
+    #[test]
+    fn test_symmetric_format_shape() {
+        let format = MatchFormat::symmetric(2, 5);
+        assert_eq!(format.players_per_match(), 10);
+        assert_eq!(format.team_count(), 2);
+        assert_eq!(format.players_per_team(), Some(5));
+        assert_eq!(format.name, "5v5");
+
+        assert_eq!(MatchFormat::symmetric(3, 2).name, "2v2v2");
+        assert_eq!(MatchFormat::one_v_one().team_sizes, vec![1, 1]);
+        let uneven = MatchFormat {
+            name: "1v2".to_string(),
+            team_sizes: vec![1, 2],
+            total_players: 3,
+        };
+        assert_eq!(uneven.players_per_team(), None);
+    }
+
+    #[test]
+    fn test_oversized_party_rejected_without_split() {
+        let entries = vec![party_of(3), solo(), solo(), solo()];
+        let matcher = GreedyMatcher::new(MatchFormat::two_v_two(), MatchConstraints::permissive());
+
+        // The party can never fit a team of two; the solos can't fill a 2v2 on their own
+        assert!(matcher.find_match(&entries).is_none());
+    }
+
+    #[test]
+    fn test_oversized_party_split_evenly_when_allowed() {
+        let party = party_of(4);
+        let entries = vec![party.clone(), solo(), solo()];
+        let mut constraints = MatchConstraints::permissive();
+        constraints.split_party_across_teams = true;
+        let matcher = GreedyMatcher::new(MatchFormat::team_v_team(3), constraints);
+
+        let result = matcher.find_match(&entries).unwrap();
+        assert_eq!(result.split_party_ids, vec![party.party_id.unwrap()]);
+
+        // Party players are dealt two per team, so each solo lands on a different team
+        let solo_teams: Vec<usize> = result
+            .entries
+            .iter()
+            .zip(&result.team_assignments)
+            .filter(|(e, _)| e.is_solo())
+            .map(|(_, team)| *team)
+            .collect();
+        assert_eq!(solo_teams.len(), 2);
+        assert_ne!(solo_teams[0], solo_teams[1]);
+    }
+
+    #[test]
+    fn test_party_that_fits_is_kept_together() {
+        let entries = vec![party_of(2), solo(), solo()];
+        let mut constraints = MatchConstraints::permissive();
+        constraints.split_party_across_teams = true;
+        let matcher = GreedyMatcher::new(MatchFormat::two_v_two(), constraints);
+
+        let result = matcher.find_match(&entries).unwrap();
+        assert!(result.split_party_ids.is_empty());
+        assert_eq!(result.team_assignments, vec![0, 1, 1]);
+    }
+
+    fn solo_in(region: &str, joined_seconds_ago: i64) -> QueueEntry {
+        let metadata = EntryMetadata {
+            region: Some(region.to_string()),
+            ..EntryMetadata::default()
+        };
+        let mut entry = QueueEntry::new_solo("ranked".to_string(), Uuid::new_v4(), Rating::default_beginner(), metadata);
+        entry.joined_at = Utc::now() - chrono::Duration::seconds(joined_seconds_ago);
+        entry
+    }
+
+    fn soft_region_matcher() -> GreedyMatcher {
+        let mut constraints = MatchConstraints::permissive();
+        constraints.same_region_required = true;
+        constraints.region_mode = ConstraintMode::Soft { penalty: 0.3 };
+        GreedyMatcher::new(MatchFormat::one_v_one(), constraints)
+    }
+
+    #[test]
+    fn test_soft_region_prefers_same_region() {
+        let eu_oldest = solo_in("eu", 30);
+        let us = solo_in("us", 20);
+        let eu = solo_in("eu", 10);
+
+        let result = soft_region_matcher().find_match(&[eu_oldest.clone(), us, eu.clone()]).unwrap();
+        let ids: Vec<Uuid> = result.entries.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![eu_oldest.id, eu.id]);
+        assert_eq!(result.quality_score, 1.0);
+    }
+
+    #[test]
+    fn test_soft_region_allows_cross_region_when_no_alternative() {
+        let eu = solo_in("eu", 30);
+        let us = solo_in("us", 20);
+
+        let result = soft_region_matcher().find_match(&[eu.clone(), us.clone()]).unwrap();
+        assert_eq!(result.entries.len(), 2);
+        assert!((result.quality_score - 0.7).abs() < 1e-9);
+
+        // The same pairing is rejected outright when the constraint is hard
+        let mut hard = MatchConstraints::permissive();
+        hard.same_region_required = true;
+        assert!(GreedyMatcher::new(MatchFormat::one_v_one(), hard).find_match(&[eu, us]).is_none());
+    }
+
+    #[test]
+    fn test_soft_region_finds_penalty_free_pair_behind_oldest_entry() {
+        let eu = solo_in("eu", 30);
+        let us_a = solo_in("us", 20);
+        let us_b = solo_in("us", 10);
+
+        let result = soft_region_matcher().find_match(&[eu, us_a.clone(), us_b.clone()]).unwrap();
+        let ids: Vec<Uuid> = result.entries.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![us_a.id, us_b.id]);
+        assert_eq!(result.quality_score, 1.0);
+    }
+
+    #[test]
+    fn test_deterministic_id_depends_only_on_participants_and_nonce() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        let id = MatchResult::deterministic_id(&[a, b], 7);
+        assert_eq!(id, MatchResult::deterministic_id(&[b, a], 7));
+        assert_eq!(id.get_version_num(), 5);
+
+        assert_ne!(id, MatchResult::deterministic_id(&[a, c], 7));
+        assert_ne!(id, MatchResult::deterministic_id(&[a, b], 8));
+    }
+
+    #[test]
+    fn test_deterministic_strategy_reuses_id_for_same_entries() {
+        let entries = vec![solo(), solo()];
+        let matcher = GreedyMatcher::new(MatchFormat::one_v_one(), MatchConstraints::permissive());
+
+        let mut first = matcher.find_match(&entries).unwrap();
+        let mut retry = matcher.find_match(&entries).unwrap();
+        assert_ne!(first.match_id, retry.match_id);
+
+        MatchIdStrategy::Deterministic.assign(&mut first);
+        MatchIdStrategy::Deterministic.assign(&mut retry);
+        assert_eq!(first.match_id, retry.match_id);
+
+        let mut random = matcher.find_match(&entries).unwrap();
+        let before = random.match_id;
+        MatchIdStrategy::Random.assign(&mut random);
+        assert_eq!(random.match_id, before);
+    }
+
+    fn rated(rating: f64, joined_at: DateTime<Utc>) -> QueueEntry {
+        QueueEntry {
+            joined_at,
+            ..QueueEntry::new_solo("duel".to_string(), Uuid::new_v4(), Rating { rating, ..Rating::default_beginner() }, EntryMetadata::default())
+        }
+    }
+
+    #[test]
+    fn test_wait_weight_picks_closer_join_over_tied_rating() {
+        let now = Utc::now();
+        let waiting = rated(1500.0, now - chrono::Duration::seconds(600));
+        let tied = rated(1500.0, now);
+        let joined_together = rated(1502.0, now - chrono::Duration::seconds(595));
+        let entries = vec![tied.clone(), joined_together.clone(), waiting.clone()];
+        let opponent = |matcher: GreedyMatcher| {
+            let matches = matcher.find_matches(&entries, now);
+            let pair = matches.iter().find(|m| m.entries.iter().any(|e| e.id == waiting.id)).unwrap();
+            pair.entries.iter().find(|e| e.id != waiting.id).unwrap().id
+        };
+        let matcher = || GreedyMatcher::new(MatchFormat::one_v_one(), MatchConstraints::permissive());
+
+        assert_eq!(opponent(matcher()), tied.id);
+        assert_eq!(opponent(matcher().with_quality_weights(QualityWeights::default())), tied.id);
+        let wait_heavy = QualityWeights { wait: 1.0, ..QualityWeights::default() };
+        assert_eq!(opponent(matcher().with_quality_weights(wait_heavy)), joined_together.id);
+    }
+
+    #[test]
+    fn test_sync_matcher_fills_teams_by_wait_time() {
+        let now = Utc::now();
+        let entries: Vec<QueueEntry> = (0..5).map(|i| rated(1500.0, now - chrono::Duration::seconds(50 - i))).collect();
+        let mut matcher: Box<dyn Matcher> =
+            Box::new(GreedyMatcher::new(MatchFormat::two_v_two(), MatchConstraints::permissive()));
+
+        let matches = matcher.find_matches(&entries, now);
+
+        assert_eq!(matches.len(), 1);
+        let matched: Vec<Uuid> = matches[0].entries.iter().map(|e| e.id).collect();
+        assert_eq!(matched.len(), 4);
+        assert!(!matched.contains(&entries[4].id));
+        let mut team_sizes = [0; 2];
+        for team in &matches[0].team_assignments {
+            team_sizes[*team] += 1;
+        }
+        assert_eq!(team_sizes, [2, 2]);
+        assert!(matches[0].team_roles.is_empty());
+    }
+
+    #[test]
+    fn test_priority_boost_matches_ahead_until_expiry() {
+        let now = Utc::now();
+        let mut entries: Vec<QueueEntry> = (0..5).map(|i| rated(1500.0, now - chrono::Duration::seconds(50 - i))).collect();
+        entries[4].metadata.priority_boost_until = Some(now + chrono::Duration::minutes(10));
+        let matcher = GreedyMatcher::new(MatchFormat::two_v_two(), MatchConstraints::permissive());
+
+        assert!(entries[4].is_boosted(now));
+        let boosted: Vec<Uuid> = matcher.find_match_at(&entries, now).unwrap().entries.iter().map(|e| e.id).collect();
+        assert!(boosted.contains(&entries[4].id));
+        assert!(!boosted.contains(&entries[3].id));
+
+        let later = now + chrono::Duration::minutes(11);
+        assert!(!entries[4].is_boosted(later));
+        let expired: Vec<Uuid> = matcher.find_match_at(&entries, later).unwrap().entries.iter().map(|e| e.id).collect();
+        assert!(!expired.contains(&entries[4].id));
+        assert!(expired.contains(&entries[3].id));
+    }
+
+    fn with_role(role: &str, joined_at: DateTime<Utc>) -> QueueEntry {
+        let metadata = EntryMetadata {
+            roles: vec![role.to_string()],
+            ..EntryMetadata::default()
+        };
+        QueueEntry {
+            joined_at,
+            ..QueueEntry::new_solo("roles".to_string(), Uuid::new_v4(), Rating::default_beginner(), metadata)
+        }
+    }
+
+    #[test]
+    fn test_team_roles_meet_role_requirements() {
+        let now = Utc::now();
+        let requirements = vec![
+            crate::queue::RoleRequirement { role: "tank".to_string(), count: 1 },
+            crate::queue::RoleRequirement { role: "healer".to_string(), count: 1 },
+        ];
+        let constraints = MatchConstraints { role_requirements: requirements.clone(), ..MatchConstraints::permissive() };
+        let matcher = GreedyMatcher::new(MatchFormat::team_v_team(3), constraints);
+        // Damage players queued first would fill a team on their own
+        let entries = vec![
+            with_role("dps", now - chrono::Duration::seconds(60)),
+            with_role("dps", now - chrono::Duration::seconds(50)),
+            with_role("healer", now - chrono::Duration::seconds(40)),
+            with_role("healer", now - chrono::Duration::seconds(30)),
+            with_role("tank", now - chrono::Duration::seconds(20)),
+            with_role("tank", now - chrono::Duration::seconds(10)),
+        ];
+
+        let result = matcher.find_match_at(&entries, now).unwrap();
+        assert_eq!(result.team_roles.len(), 2);
+        for team in &result.team_roles {
+            for requirement in &requirements {
+                assert!(team.get(&requirement.role).copied().unwrap_or(0) >= requirement.count as u32, "{:?}", result.team_roles);
             }
+            assert_eq!(team.values().sum::<u32>(), 3);
+        }
+    }
+
+    #[test]
+    fn test_every_matcher_kind_pairs_close_ratings() {
+        let now = Utc::now();
+        let entries = vec![
+            rated(1000.0, now - chrono::Duration::seconds(4)),
+            rated(2000.0, now - chrono::Duration::seconds(3)),
+            rated(1010.0, now - chrono::Duration::seconds(2)),
+            rated(2010.0, now - chrono::Duration::seconds(1)),
+        ];
+        let constraints = MatchConstraints {
+            max_rating_delta: 100.0,
+            ..MatchConstraints::permissive()
+        };
+        let kinds = [
+            MatcherKind::Greedy,
+            MatcherKind::Adaptive { max_wait_time_seconds: 60, curve: ExpansionCurve::Linear { factor: 0.0 } },
+            MatcherKind::Swiss { max_score_difference: 100.0 },
+            MatcherKind::Fuzzy { variety_factor: 0.0 },
+        ];
+
+        for kind in kinds {
+            let mut matches = kind.matcher(&MatchFormat::one_v_one(), &constraints).find_matches(&entries, now);
+            assert_eq!(matches.len(), 2, "{:?}", kind);
+            matches.sort_by(|a, b| a.entries[0].average_rating.rating.total_cmp(&b.entries[0].average_rating.rating));
+            for (m, pair) in matches.iter().zip([[0, 2], [1, 3]]) {
+                let mut ids: Vec<Uuid> = m.entries.iter().map(|e| e.id).collect();
+                ids.sort();
+                let mut expected = vec![entries[pair[0]].id, entries[pair[1]].id];
+                expected.sort();
+                assert_eq!(ids, expected, "{:?}", kind);
+            }
+        }
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_match_result_bytes_round_trip() {
+        let party = party_of(2);
+        let result = MatchResult {
+            match_id: Uuid::new_v4(),
+            entries: vec![party.clone(), solo(), solo()],
+            team_assignments: vec![0, 1, 1],
+            split_party_ids: vec![party.party_id.unwrap()],
+            quality_score: 0.75,
+            game_mode: None,
+            team_roles: Vec::new(),
+        };
+
+        let bytes = result.to_bytes().unwrap();
+        let decoded = MatchResult::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.match_id, result.match_id);
+        assert_eq!(decoded.player_ids(), result.player_ids());
+        assert_eq!(decoded.entries[0].joined_at, party.joined_at);
+        assert_eq!(decoded.team_assignments, result.team_assignments);
+        assert_eq!(decoded.split_party_ids, result.split_party_ids);
+        assert_eq!(decoded.quality_score, 0.75);
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_match_result_bytes_without_newer_fields_decode_with_defaults() {
+        // A version 1 buffer from before `split_party_ids` and `quality_score` existed
+        let match_id = Uuid::new_v4();
+        let entries = vec![solo(), solo()];
+        let mut bytes = vec![1];
+        rmp_serde::encode::write(&mut bytes, &(match_id, entries.clone(), vec![0usize, 1])).unwrap();
+
+        let decoded = MatchResult::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.match_id, match_id);
+        assert_eq!(decoded.entries.len(), 2);
+        assert_eq!(decoded.team_assignments, vec![0, 1]);
+        assert!(decoded.split_party_ids.is_empty());
+        assert_eq!(decoded.quality_score, 1.0);
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_match_result_bytes_from_newer_version_rejected() {
+        let result = MatchResult {
+            match_id: Uuid::new_v4(),
+            entries: vec![solo(), solo()],
+            team_assignments: vec![0, 1],
+            split_party_ids: Vec::new(),
+            quality_score: 1.0,
+            game_mode: None,
+            team_roles: Vec::new(),
+        };
+        let mut bytes = result.to_bytes().unwrap();
+        bytes[0] = MATCH_RESULT_WIRE_VERSION + 1;
+
+        assert!(MatchResult::from_bytes(&bytes).is_err());
+        assert!(MatchResult::from_bytes(&[]).is_err());
+    }
 
-            assignments.push(current_team);
-            team_fill[current_team] += entry.player_count();
+    /// Solo 1v1 entries with seeded random ratings and join times
+    fn random_solos(seed: u64, count: usize) -> Vec<QueueEntry> {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        let mut rng = StdRng::seed_from_u64(seed);
+        let start = Utc::now();
+        (0..count)
+            .map(|_| {
+                let rating = Rating { rating: rng.gen_range(800.0..2200.0), ..Rating::default_beginner() };
+                let mut entry = QueueEntry::new_solo("duel".to_string(), Uuid::new_v4(), rating, EntryMetadata::default());
+                entry.joined_at = start - chrono::Duration::seconds(rng.gen_range(0..30));
+                entry
+            })
+            .collect()
+    }
+
+    fn total_rating_delta(matches: &[MatchResult]) -> f64 {
+        matches
+            .iter()
+            .map(|m| (m.entries[0].average_rating.rating - m.entries[1].average_rating.rating).abs())
+            .sum()
+    }
+
+    #[test]
+    fn test_one_v_one_fast_path_beats_general_rating_delta() {
+        let fast = GreedyMatcher::new(MatchFormat::one_v_one(), MatchConstraints::strict());
+        // A soft region mode with no regions set forces the general path
+        // without changing which pairs are compatible
+        let mut general_constraints = MatchConstraints::strict();
+        general_constraints.region_mode = ConstraintMode::Soft { penalty: 0.1 };
+        let general = GreedyMatcher::new(MatchFormat::one_v_one(), general_constraints);
+
+        // Anchoring in matching order can strand a player one pass, so counts are compared over all seeds
+        let (mut fast_count, mut general_count) = (0, 0);
+        for seed in 0..20 {
+            let entries = random_solos(seed, 60);
+            let now = Utc::now();
+            let fast_matches = fast.find_matches(&entries, now);
+            let general_matches = general.find_matches(&entries, now);
+
+            fast_count += fast_matches.len();
+            general_count += general_matches.len();
+            assert!(fast_matches.iter().all(|m| m.team_assignments == vec![0, 1] && m.quality_score == 1.0));
+            let fast_average = total_rating_delta(&fast_matches) / fast_matches.len() as f64;
+            let general_average = total_rating_delta(&general_matches) / general_matches.len() as f64;
+            assert!(fast_average <= general_average, "seed {}: {} > {}", seed, fast_average, general_average);
+
+            let mut ids: Vec<Uuid> = fast_matches.iter().flat_map(|m| m.entries.iter().map(|e| e.id)).collect();
+            let before = ids.len();
+            ids.sort();
+            ids.dedup();
+            assert_eq!(ids.len(), before);
         }
+        assert!(fast_count >= general_count, "{} < {}", fast_count, general_count);
+    }
+
+    #[test]
+    fn test_every_matcher_kind_runs_starvation_pass() {
+        let now = Utc::now();
+        let constraints = MatchConstraints {
+            max_rating_delta: 100.0,
+            expansion_rate: 0.0,
+            starvation: Some(StarvationPolicy { threshold_seconds: 120, max_rating_delta: 600.0 }),
+            ..MatchConstraints::permissive()
+        };
+        let kinds = [
+            MatcherKind::Greedy,
+            MatcherKind::Adaptive { max_wait_time_seconds: 60, curve: ExpansionCurve::Linear { factor: 0.0 } },
+            MatcherKind::Swiss { max_score_difference: 100.0 },
+            MatcherKind::Fuzzy { variety_factor: 0.0 },
+            MatcherKind::ModePreference,
+        ];
+
+        for kind in kinds {
+            for (waited, expected) in [(60, 0), (150, 1)] {
+                let entries = vec![rated(2000.0, now - chrono::Duration::seconds(waited)), rated(1500.0, now)];
+                let matches = kind.find_matches(&MatchFormat::one_v_one(), &constraints, &entries, now);
+                assert_eq!(matches.len(), expected, "{:?} after {}s", kind, waited);
+            }
+        }
+    }
+
+    #[test]
+    fn test_one_v_one_fast_path_anchors_in_priority_order() {
+        let matcher = GreedyMatcher::new(MatchFormat::one_v_one(), MatchConstraints::permissive());
+        let now = Utc::now();
+        let oldest = rated(1000.0, now - chrono::Duration::seconds(30));
+        let mut newest = rated(1006.0, now);
+        let middle = rated(1010.0, now - chrono::Duration::seconds(10));
+        let opponent_of = |entries: &[QueueEntry], id: Uuid| {
+            let matches = matcher.find_matches(entries, now);
+            assert_eq!(matches.len(), 1);
+            matches[0].entries.iter().any(|e| e.id == id).then(|| matches[0].entries.iter().find(|e| e.id != id).unwrap().id)
+        };
+
+        // The longest-waiting entry picks first, even though the other two are closer to each other
+        let entries = vec![middle.clone(), newest.clone(), oldest.clone()];
+        assert_eq!(opponent_of(&entries, oldest.id), Some(newest.id));
+
+        // A boosted entry picks ahead of it
+        newest.metadata.priority_boost_until = Some(now + chrono::Duration::minutes(5));
+        let entries = vec![middle.clone(), newest.clone(), oldest.clone()];
+        assert_eq!(opponent_of(&entries, newest.id), Some(middle.id));
+    }
+
+    #[test]
+    fn test_one_v_one_fast_path_is_deterministic() {
+        let matcher = GreedyMatcher::new(MatchFormat::one_v_one(), MatchConstraints::permissive());
+        let entries = random_solos(7, 41);
+        let now = Utc::now();
+        let mut shuffled = entries.clone();
+        shuffled.reverse();
+
+        let pairs = |matches: Vec<MatchResult>| -> Vec<(Uuid, Uuid)> {
+            matches.iter().map(|m| (m.entries[0].id, m.entries[1].id)).collect()
+        };
+        let first = pairs(matcher.find_matches(&entries, now));
+        assert_eq!(first.len(), 20);
+        assert_eq!(first, pairs(matcher.find_matches(&shuffled, now)));
+    }
+
+    #[test]
+    fn test_one_v_one_fast_path_finds_partner_beyond_lookahead() {
+        let constraints = MatchConstraints {
+            same_region_required: true,
+            ..MatchConstraints::permissive()
+        };
+        let matcher = GreedyMatcher::new(MatchFormat::one_v_one(), constraints);
+        let now = Utc::now();
+
+        // Every rating neighbor of the lone US entry within the lookahead is in the EU
+        let mut entries: Vec<QueueEntry> = (0..=ONE_V_ONE_LOOKAHEAD * 2)
+            .map(|i| {
+                let mut entry = rated(1000.0 + i as f64, now);
+                entry.metadata.region = Some("eu".to_string());
+                entry
+            })
+            .collect();
+        let mut us = rated(990.0, now - chrono::Duration::seconds(30));
+        us.metadata.region = Some("us".to_string());
+        let mut far_us = rated(1100.0, now);
+        far_us.metadata.region = Some("us".to_string());
+        entries.push(us.clone());
+        entries.push(far_us.clone());
+
+        let matches = matcher.find_matches(&entries, now);
+        assert!(matches
+            .iter()
+            .any(|m| m.entries.iter().any(|e| e.id == us.id) && m.entries.iter().any(|e| e.id == far_us.id)));
+        assert_eq!(matches.len(), ONE_V_ONE_LOOKAHEAD + 1);
+    }
 
-        assignments
+    fn starvation_matcher(format: MatchFormat) -> GreedyMatcher {
+        let constraints = MatchConstraints {
+            max_rating_delta: 100.0,
+            expansion_rate: 0.0,
+            starvation: Some(StarvationPolicy { threshold_seconds: 120, max_rating_delta: 600.0 }),
+            ..MatchConstraints::permissive()
+        };
+        GreedyMatcher::new(format, constraints)
+    }
+
+    fn player_sets(matches: &[MatchResult]) -> Vec<Vec<Uuid>> {
+        matches
+            .iter()
+            .map(|m| {
+                let mut ids: Vec<Uuid> = m.entries.iter().map(|e| e.id).collect();
+                ids.sort();
+                ids
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_starving_outlier_force_matched_after_threshold() {
+        let matcher = starvation_matcher(MatchFormat::one_v_one());
+        let now = Utc::now();
+        let outlier = rated(2000.0, now - chrono::Duration::seconds(60));
+        let unreachable = rated(2700.0, now - chrono::Duration::seconds(600));
+        // Fresh entries joined a second apart in the order listed, so the matching order is fixed
+        let fresh = |ratings: &[f64], at: DateTime<Utc>| {
+            let joined = |i: usize| at - chrono::Duration::seconds((ratings.len() - i) as i64);
+            ratings.iter().enumerate().map(|(i, &r)| rated(r, joined(i))).collect::<Vec<_>>()
+        };
+
+        let mut entries = fresh(&[1500.0, 1510.0, 1600.0, 1620.0], now);
+        entries.push(outlier.clone());
+        let matches = matcher.find_matches(&entries, now);
+        assert_eq!(matches.len(), 2);
+        assert!(matches.iter().all(|m| !m.entries.iter().any(|e| e.id == outlier.id)));
+
+        // 150s in: fresh entries pair as usual and the outlier takes the closest
+        // leftover, while a wider gap than the cap stays unmatched
+        let later = now + chrono::Duration::seconds(90);
+        let mut entries = fresh(&[1500.0, 1505.0, 1600.0, 1605.0, 1700.0], later);
+        entries.extend([outlier.clone(), unreachable]);
+        let matches = matcher.find_matches(&entries, later);
+        let mut expected = vec![
+            vec![entries[0].id, entries[1].id],
+            vec![entries[2].id, entries[3].id],
+            vec![entries[4].id, outlier.id],
+        ];
+        for ids in &mut expected {
+            ids.sort();
+        }
+        assert_eq!(player_sets(&matches), expected);
     }
 }
@@ -1,14 +1,30 @@
 pub mod constraints;
+pub mod diagnosis;
 pub mod entry;
+pub mod features;
+#[cfg(feature = "runtime")]
 pub mod manager;
 pub mod matcher;
+pub mod mode_preference;
+#[cfg(feature = "runtime")]
+pub mod sharded;
+pub mod snapshot;
+pub mod stats;
 pub mod advanced_strategies;
 
-pub use constraints::{MatchConstraints, RoleRequirement};
-pub use entry::{EntryMetadata, QueueEntry};
+pub use constraints::{ConstraintMode, ConstraintViolation, MatchConstraints, RecentEncounters, RoleRequirement, StarvationPolicy};
+pub use diagnosis::{CandidateDiagnosis, MatchDiagnosis};
+pub use entry::{EntryMetadata, EntryStatus, QueueEntry, QueueEntryBuilder};
+pub use features::{FeatureSchema, BASE_FEATURE_NAMES};
+#[cfg(feature = "runtime")]
 pub use manager::{QueueConfig, QueueManager};
-pub use matcher::{GreedyMatcher, MatchFormat, MatchResult};
+pub use matcher::{GreedyMatcher, MatchFormat, MatchIdStrategy, MatchResult, MatchSearchResult, Matcher, MatcherKind};
+pub use mode_preference::ModePreferenceMatcher;
+#[cfg(feature = "runtime")]
+pub use sharded::ShardedQueueManager;
+pub use snapshot::{replay_snapshot, QueueSnapshot};
+pub use stats::{GlobalQueueStats, QueueStats, WaitPercentiles};
 pub use advanced_strategies::{
-    AdaptiveMatcher, FairTeamBalancer, SeedingStrategy, SwissMatcher, 
+    AdaptiveMatcher, ExpansionCurve, FairTeamBalancer, FuzzyMatcher, QualityWeights, SeedingStrategy, SwissMatcher, 
     TournamentBracket, TournamentMatch, TournamentMatcher, TournamentType,
 };
@@ -0,0 +1,148 @@
+use super::{
+    constraints::MatchConstraints,
+    entry::QueueEntry,
+    matcher::{GreedyMatcher, MatchFormat, MatchResult, Matcher},
+};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeSet;
+use uuid::Uuid;
+
+/// Greedy matching within a game mode every matched entry accepts
+///
+/// Each pass tries every mode listed in any entry's
+/// [`mode_preferences`](super::EntryMetadata::mode_preferences): it runs
+/// [`GreedyMatcher`] over the entries accepting that mode and scores the
+/// result by the players' combined preference weight for the mode. The
+/// highest-scoring match is kept with its mode in [`MatchResult::game_mode`];
+/// ties go to the alphabetically first mode. Entries without preferences
+/// accept any mode at weight 0, and without any preferences in the queue
+/// this is plain greedy matching.
+pub struct ModePreferenceMatcher {
+    greedy: GreedyMatcher,
+}
+
+impl ModePreferenceMatcher {
+    pub fn new(format: MatchFormat, constraints: MatchConstraints) -> Self {
+        Self::from_greedy(GreedyMatcher::new(format, constraints))
+    }
+
+    /// Match within each mode with an already configured `greedy` matcher
+    pub fn from_greedy(greedy: GreedyMatcher) -> Self {
+        Self { greedy }
+    }
+
+    /// The most preferred match that can be formed from `entries` as of `now`
+    pub fn find_match_at(&self, entries: &[QueueEntry], now: DateTime<Utc>) -> Option<MatchResult> {
+        let modes: BTreeSet<&str> = entries
+            .iter()
+            .flat_map(|e| e.metadata.mode_preferences.iter().map(|(mode, _)| mode.as_str()))
+            .collect();
+        if modes.is_empty() {
+            return self.greedy.find_match_at(entries, now);
+        }
+
+        let mut best: Option<(MatchResult, f64)> = None;
+        for mode in modes {
+            let accepting: Vec<QueueEntry> = entries
+                .iter()
+                .filter(|e| preference(e, mode).is_some())
+                .cloned()
+                .collect();
+            let Some(mut match_result) = self.greedy.find_match_at(&accepting, now) else {
+                continue;
+            };
+
+            let weight: f64 = match_result
+                .entries
+                .iter()
+                .map(|e| preference(e, mode).unwrap_or(0.0) * e.player_count() as f64)
+                .sum();
+            if best.as_ref().is_none_or(|(_, best_weight)| weight > *best_weight) {
+                match_result.game_mode = Some(mode.to_string());
+                best = Some((match_result, weight));
+            }
+        }
+
+        best.map(|(match_result, _)| match_result)
+    }
+
+    /// Form matches from `entries` as of `now` until no more can be filled
+    pub fn find_matches(&self, entries: &[QueueEntry], now: DateTime<Utc>) -> Vec<MatchResult> {
+        let mut matches = Vec::new();
+        let mut remaining_entries = entries.to_vec();
+
+        while let Some(match_result) = self.find_match_at(&remaining_entries, now) {
+            let matched_ids: Vec<Uuid> = match_result.entries.iter().map(|e| e.id).collect();
+            remaining_entries.retain(|e| !matched_ids.contains(&e.id));
+            matches.push(match_result);
+        }
+
+        matches
+    }
+}
+
+impl Matcher for ModePreferenceMatcher {
+    fn find_matches(&mut self, entries: &[QueueEntry], now: DateTime<Utc>) -> Vec<MatchResult> {
+        ModePreferenceMatcher::find_matches(self, entries, now)
+    }
+}
+
+/// An entry's weight for `mode`, or `None` if it doesn't accept the mode
+fn preference(entry: &QueueEntry, mode: &str) -> Option<f64> {
+    let preferences = &entry.metadata.mode_preferences;
+    if preferences.is_empty() {
+        return Some(0.0);
+    }
+    preferences.iter().find(|(m, _)| m == mode).map(|(_, weight)| *weight)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mmr::Rating, queue::EntryMetadata};
+
+    fn player(modes: &[(&str, f64)]) -> QueueEntry {
+        let metadata = EntryMetadata {
+            mode_preferences: modes.iter().map(|(mode, weight)| (mode.to_string(), *weight)).collect(),
+            ..EntryMetadata::default()
+        };
+        QueueEntry::new_solo("casual".to_string(), Uuid::new_v4(), Rating::default_beginner(), metadata)
+    }
+
+    fn matcher() -> ModePreferenceMatcher {
+        ModePreferenceMatcher::new(MatchFormat::one_v_one(), MatchConstraints::permissive())
+    }
+
+    #[test]
+    fn test_match_on_highest_combined_preference() {
+        // Combined weights: arcade 4.5, casual 3.5, ranked 3.0
+        let first = player(&[("ranked", 1.0), ("casual", 3.0), ("arcade", 2.0)]);
+        let second = player(&[("ranked", 2.0), ("arcade", 2.5), ("casual", 0.5)]);
+
+        let matches = matcher().find_matches(&[first.clone(), second.clone()], Utc::now());
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].game_mode.as_deref(), Some("arcade"));
+        let mut ids: Vec<Uuid> = matches[0].entries.iter().map(|e| e.id).collect();
+        ids.sort();
+        let mut expected = vec![first.id, second.id];
+        expected.sort();
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn test_no_shared_mode_no_match() {
+        let entries = [player(&[("ranked", 1.0)]), player(&[("arcade", 1.0)])];
+        assert!(matcher().find_matches(&entries, Utc::now()).is_empty());
+
+        // A player without preferences plays whatever the other prefers
+        let entries = [player(&[("arcade", 1.0), ("ranked", 0.5)]), player(&[])];
+        let matches = matcher().find_matches(&entries, Utc::now());
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].game_mode.as_deref(), Some("arcade"));
+
+        let matches = matcher().find_matches(&[player(&[]), player(&[])], Utc::now());
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].game_mode, None);
+    }
+}
@@ -0,0 +1,243 @@
+use super::{entry::QueueEntry, manager::QueueManager, matcher::MatchResult};
+use crate::error::*;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use uuid::Uuid;
+
+/// Per-region [`QueueManager`]s with spillover into neighboring regions
+///
+/// Players join the shard for their region via [`shard`](Self::shard). Each
+/// region matches on its own first; entries that have waited at least the
+/// spillover threshold may then be matched with players still queued in the
+/// regions listed as its neighbors.
+pub struct ShardedQueueManager {
+    shards: HashMap<String, Arc<QueueManager>>,
+    /// Regions each region may draw players from once starved
+    neighbors: HashMap<String, Vec<String>>,
+    spillover_after: chrono::Duration,
+}
+
+impl ShardedQueueManager {
+    /// Spill over once an entry has waited `spillover_after` in its own region
+    pub fn new(spillover_after: chrono::Duration) -> Self {
+        Self {
+            shards: HashMap::new(),
+            neighbors: HashMap::new(),
+            spillover_after,
+        }
+    }
+
+    /// Add the queue manager for a region
+    pub fn with_shard(mut self, region: impl Into<String>, manager: Arc<QueueManager>) -> Self {
+        self.shards.insert(region.into(), manager);
+        self
+    }
+
+    /// Let starved players in `region` draw from `neighbors`
+    ///
+    /// Adjacency is one-way; list both directions for a symmetric pair.
+    /// Neighbors without a shard are ignored.
+    pub fn with_neighbors(mut self, region: impl Into<String>, neighbors: Vec<String>) -> Self {
+        self.neighbors.insert(region.into(), neighbors);
+        self
+    }
+
+    /// The queue manager for a region
+    pub fn shard(&self, region: &str) -> Option<&Arc<QueueManager>> {
+        self.shards.get(region)
+    }
+
+    /// All regions with a shard, sorted
+    pub fn regions(&self) -> Vec<&str> {
+        let mut regions: Vec<&str> = self.shards.keys().map(String::as_str).collect();
+        regions.sort_unstable();
+        regions
+    }
+
+    /// Match every region's queue, then spill starved entries into neighbors
+    ///
+    /// Regions are processed in sorted order. A spillover pool holds the
+    /// region's entries waiting at least the threshold plus everything still
+    /// queued in its neighbors, and is matched with the starved region's
+    /// queue config; only matches containing a starved entry are kept. Matched
+    /// entries are reserved in whichever shard they were queued in, see
+    /// [`QueueManager::find_matches`]; commit or release them with
+    /// [`remove_matched_entries`](Self::remove_matched_entries) and
+    /// [`release_reserved_entries`](Self::release_reserved_entries).
+    pub async fn find_matches_with_spillover(&self, queue_name: &str) -> Result<Vec<MatchResult>> {
+        let regions = self.regions();
+        let mut matches = Vec::new();
+        for region in &regions {
+            matches.extend(self.shards[*region].find_matches(queue_name).await?);
+        }
+
+        for region in &regions {
+            matches.extend(self.spill_over(region, queue_name).await?);
+        }
+
+        Ok(matches)
+    }
+
+    async fn spill_over(&self, region: &str, queue_name: &str) -> Result<Vec<MatchResult>> {
+        let home = &self.shards[region];
+        let now = home.clock().now();
+        let starved: Vec<QueueEntry> = home
+            .queued_entries(queue_name)
+            .await?
+            .into_iter()
+            .filter(|e| e.wait_time(now) >= self.spillover_after)
+            .collect();
+        if starved.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let starved_ids: HashSet<Uuid> = starved.iter().map(|e| e.id).collect();
+        let mut owner: HashMap<Uuid, &str> = starved.iter().map(|e| (e.id, region)).collect();
+        let mut pool = starved;
+        for neighbor in self.neighbors.get(region).into_iter().flatten() {
+            let Some(shard) = self.shards.get(neighbor).filter(|_| neighbor != region) else { continue };
+            for entry in shard.queued_entries(queue_name).await? {
+                owner.insert(entry.id, neighbor.as_str());
+                pool.push(entry);
+            }
+        }
+
+        let mut matches = home.preview_matches_among(queue_name, &pool).await?.matches;
+        matches.retain(|m| m.entries.iter().any(|e| starved_ids.contains(&e.id)));
+
+        for match_result in &matches {
+            let mut by_region: HashMap<&str, Vec<QueueEntry>> = HashMap::new();
+            for entry in &match_result.entries {
+                if let Some(entry_region) = owner.get(&entry.id) {
+                    by_region.entry(entry_region).or_default().push(entry.clone());
+                }
+            }
+            for (entry_region, entries) in by_region {
+                self.shards[entry_region].reserve_matched_entries(queue_name, &entries).await?;
+            }
+            home.record_match_quality(queue_name, match_result).await;
+        }
+
+        Ok(matches)
+    }
+
+    /// Commit a match in every shard holding some of its entries
+    pub async fn remove_matched_entries(&self, queue_name: &str, match_result: &MatchResult) -> Result<()> {
+        for region in self.regions() {
+            self.shards[region].remove_matched_entries(queue_name, &match_result.entries).await?;
+        }
+        Ok(())
+    }
+
+    /// Return a match's reserved entries to the shards they were queued in
+    pub async fn release_reserved_entries(&self, queue_name: &str, match_result: &MatchResult) -> Result<usize> {
+        let mut released = 0;
+        for region in self.regions() {
+            released += self.shards[region].release_reserved_entries(queue_name, &match_result.entries).await?;
+        }
+        Ok(released)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clock::MockClock,
+        mmr::Rating,
+        persistence::InMemoryAdapter,
+        queue::{EntryMetadata, MatchConstraints, MatchFormat, QueueConfig},
+    };
+
+    async fn regional_shard(clock: Arc<MockClock>) -> Arc<QueueManager> {
+        let manager = QueueManager::new(Arc::new(InMemoryAdapter::new())).with_clock(clock);
+        manager
+            .register_queue(QueueConfig::new("ranked", MatchFormat::one_v_one(), MatchConstraints::permissive()))
+            .await
+            .unwrap();
+        Arc::new(manager)
+    }
+
+    async fn sharded(clock: Arc<MockClock>) -> ShardedQueueManager {
+        ShardedQueueManager::new(chrono::Duration::seconds(30))
+            .with_shard("eu", regional_shard(clock.clone()).await)
+            .with_shard("na", regional_shard(clock.clone()).await)
+            .with_shard("oce", regional_shard(clock).await)
+            .with_neighbors("eu", vec!["na".to_string()])
+            .with_neighbors("na", vec!["eu".to_string()])
+    }
+
+    async fn join(sharded: &ShardedQueueManager, region: &str) -> Uuid {
+        let player_id = Uuid::new_v4();
+        sharded
+            .shard(region)
+            .unwrap()
+            .join_queue_solo("ranked".to_string(), player_id, Rating::default_beginner(), EntryMetadata::default(), None)
+            .await
+            .unwrap();
+        player_id
+    }
+
+    #[tokio::test]
+    async fn test_lonely_player_matches_within_region_when_possible() {
+        let clock = Arc::new(MockClock::default());
+        let sharded = sharded(clock.clone()).await;
+
+        let lonely = join(&sharded, "eu").await;
+        let neighbor = join(&sharded, "na").await;
+        assert!(sharded.find_matches_with_spillover("ranked").await.unwrap().is_empty());
+
+        // Past the spillover threshold, but a local opponent still comes first
+        clock.advance(chrono::Duration::seconds(40));
+        let local = join(&sharded, "eu").await;
+        let matches = sharded.find_matches_with_spillover("ranked").await.unwrap();
+
+        assert_eq!(matches.len(), 1);
+        let mut players = matches[0].player_ids();
+        players.sort();
+        let mut expected = vec![lonely, local];
+        expected.sort();
+        assert_eq!(players, expected);
+        assert_eq!(sharded.shard("na").unwrap().get_queue_size("ranked").await.unwrap(), 1);
+        assert!(!matches[0].player_ids().contains(&neighbor));
+    }
+
+    #[tokio::test]
+    async fn test_lonely_player_spills_into_neighbor_after_threshold() {
+        let clock = Arc::new(MockClock::default());
+        let sharded = sharded(clock.clone()).await;
+
+        let lonely = join(&sharded, "eu").await;
+        clock.advance(chrono::Duration::seconds(5));
+        let neighbor = join(&sharded, "na").await;
+
+        clock.advance(chrono::Duration::seconds(24));
+        assert!(sharded.find_matches_with_spillover("ranked").await.unwrap().is_empty());
+
+        clock.advance(chrono::Duration::seconds(1));
+        let matches = sharded.find_matches_with_spillover("ranked").await.unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].player_ids().contains(&lonely));
+        assert!(matches[0].player_ids().contains(&neighbor));
+        for region in ["eu", "na"] {
+            assert_eq!(sharded.shard(region).unwrap().get_queue_size("ranked").await.unwrap(), 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_no_spillover_into_non_adjacent_region() {
+        let clock = Arc::new(MockClock::default());
+        let sharded = sharded(clock.clone()).await;
+
+        join(&sharded, "eu").await;
+        join(&sharded, "oce").await;
+        clock.advance(chrono::Duration::minutes(5));
+
+        assert!(sharded.find_matches_with_spillover("ranked").await.unwrap().is_empty());
+        assert_eq!(sharded.shard("eu").unwrap().get_queue_size("ranked").await.unwrap(), 1);
+        assert_eq!(sharded.shard("oce").unwrap().get_queue_size("ranked").await.unwrap(), 1);
+    }
+}
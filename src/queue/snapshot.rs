@@ -0,0 +1,123 @@
+//! Captured matcher inputs for reproducing a queue's matching offline
+
+use super::{
+    constraints::{MatchConstraints, RecentEncounters},
+    entry::QueueEntry,
+    matcher::{MatchFormat, MatchIdStrategy, MatchResult, Matcher},
+};
+use crate::error::{Result, StorageError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Exactly what a queue's matcher was given at one moment
+///
+/// Captured with [`QueueManager::capture_queue_snapshot`](crate::queue::QueueManager::capture_queue_snapshot)
+/// and re-run with [`replay_snapshot`]. `constraints` are the ones in effect
+/// for the pass, with role requirements cleared while enforcement is relaxed,
+/// and entries of players in an active lobby are already left out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueSnapshot {
+    pub queue_name: String,
+    /// The `now` the matcher ran at
+    pub captured_at: DateTime<Utc>,
+    pub format: MatchFormat,
+    pub constraints: MatchConstraints,
+    /// The queue's recent encounters at capture time
+    #[serde(default)]
+    pub recent_encounters: Arc<RecentEncounters>,
+    pub min_match_quality: Option<f64>,
+    pub match_ids: MatchIdStrategy,
+    /// Entries matched together
+    pub entries: Vec<QueueEntry>,
+    /// Entries of shadow-banned players, only matched among themselves
+    pub shadow_pool: Vec<QueueEntry>,
+}
+
+impl QueueSnapshot {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| StorageError::Serialization(e.to_string()).into())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| StorageError::Serialization(e.to_string()).into())
+    }
+
+    /// Run `find` over each pool and post-process the matches as a live pass does
+    pub(crate) fn run(&self, mut find: impl FnMut(&[QueueEntry]) -> Vec<MatchResult>) -> Vec<MatchResult> {
+        let mut matches = find(&self.entries);
+        if !self.shadow_pool.is_empty() {
+            matches.extend(find(&self.shadow_pool));
+        }
+        if let Some(min_quality) = self.min_match_quality {
+            matches.retain(|m| m.quality_score >= min_quality);
+        }
+        for match_result in &mut matches {
+            self.match_ids.assign(match_result);
+        }
+        matches
+    }
+}
+
+/// Re-run matching over a snapshot as of its capture time
+///
+/// Deterministic for deterministic matchers, e.g. the queue's
+/// [`MatcherKind::snapshot_matcher`](crate::queue::MatcherKind::snapshot_matcher); pass a
+/// [`FuzzyMatcher::with_seed`](crate::queue::FuzzyMatcher::with_seed) to
+/// reproduce a random one. Match ids only repeat with
+/// [`MatchIdStrategy::Deterministic`].
+pub fn replay_snapshot(snapshot: &QueueSnapshot, matcher: &mut dyn Matcher) -> Vec<MatchResult> {
+    snapshot.run(|entries| matcher.find_matches(entries, snapshot.captured_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mmr::Rating, queue::advanced_strategies::FuzzyMatcher};
+    use uuid::Uuid;
+
+    fn snapshot() -> QueueSnapshot {
+        let captured_at = Utc::now();
+        let entries = [1500.0, 1520.0, 1560.0, 1600.0, 1610.0, 1700.0]
+            .into_iter()
+            .map(|rating| {
+                QueueEntry::new_solo("casual".to_string(), Uuid::new_v4(), Rating::new(rating, 100.0, 0.06), Default::default())
+            })
+            .collect();
+        QueueSnapshot {
+            queue_name: "casual".to_string(),
+            captured_at,
+            format: MatchFormat::one_v_one(),
+            constraints: MatchConstraints::permissive(),
+            recent_encounters: Default::default(),
+            min_match_quality: None,
+            match_ids: MatchIdStrategy::Deterministic,
+            entries,
+            shadow_pool: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_seeded_replay_is_reproducible() {
+        let snapshot = QueueSnapshot::from_json(&snapshot().to_json().unwrap()).unwrap();
+        let replay = |seed| {
+            let mut matcher = FuzzyMatcher::new(snapshot.constraints.clone(), 2.0).with_seed(seed);
+            replay_snapshot(&snapshot, &mut matcher)
+                .iter()
+                .map(|m| (m.match_id, m.player_ids()))
+                .collect::<Vec<_>>()
+        };
+
+        let first = replay(7);
+        assert_eq!(first.len(), 3);
+        assert_eq!(replay(7), first);
+    }
+
+    #[test]
+    fn test_invalid_json_is_a_serialization_error() {
+        assert!(matches!(
+            QueueSnapshot::from_json("{\"queue_name\": 1}"),
+            Err(crate::error::MatchForgeError::Storage(StorageError::Serialization(_)))
+        ));
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Queue wait-time percentiles over a recent window of matched entries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct WaitPercentiles {
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    /// Number of wait times the percentiles were computed from
+    pub sample_count: usize,
+}
+
+impl WaitPercentiles {
+    /// Compute nearest-rank percentiles from a set of wait times
+    pub fn from_durations<'a>(durations: impl IntoIterator<Item = &'a Duration>) -> Self {
+        let mut sorted: Vec<Duration> = durations.into_iter().copied().collect();
+        if sorted.is_empty() {
+            return Self::default();
+        }
+        sorted.sort();
+
+        Self {
+            p50: percentile(&sorted, 50.0),
+            p90: percentile(&sorted, 90.0),
+            p95: percentile(&sorted, 95.0),
+            p99: percentile(&sorted, 99.0),
+            sample_count: sorted.len(),
+        }
+    }
+}
+
+/// Live statistics for a single queue
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueueStats {
+    pub queue_name: String,
+    /// Players currently waiting (party members counted individually)
+    pub queued_players: usize,
+    /// Entries currently waiting (a party is one entry)
+    pub queued_entries: usize,
+    /// Matches formed in the last minute
+    pub matches_per_minute: usize,
+    /// Mean time the current entries have been waiting
+    pub average_wait: Duration,
+}
+
+/// Statistics aggregated over every registered queue
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GlobalQueueStats {
+    pub total_queued_players: usize,
+    pub total_queued_entries: usize,
+    pub total_matches_per_minute: usize,
+    /// Mean wait over all queued entries, across queues
+    pub average_wait: Duration,
+    /// Per-queue breakdown, sorted by queue name
+    pub queues: Vec<QueueStats>,
+}
+
+/// Nearest-rank percentile of an already sorted, non-empty slice
+pub(crate) fn percentile(sorted: &[Duration], pct: f64) -> Duration {
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
@@ -0,0 +1,59 @@
+//! Game server allocation for dispatched lobbies
+//!
+//! [`LobbyManager::dispatch_to_allocated_server`](super::LobbyManager::dispatch_to_allocated_server)
+//! asks a [`ServerAllocator`] for a server when a `Ready` lobby is
+//! dispatched and hands it back when the lobby closes.
+
+use crate::{
+    error::*,
+    lobby::{Lobby, ServerHandle},
+    telemetry::sink::BoxFuture,
+};
+use std::{collections::VecDeque, sync::Mutex};
+
+/// Source of game servers for lobbies
+pub trait ServerAllocator: Send + Sync {
+    /// Reserve a server for `lobby`, failing if none is available
+    fn allocate<'a>(&'a self, lobby: &'a Lobby) -> BoxFuture<'a, Result<ServerHandle>>;
+
+    /// Return a server once its lobby no longer needs it
+    fn release(&self, handle: ServerHandle) -> BoxFuture<'_, Result<()>>;
+}
+
+/// Allocator handing out servers from a fixed list
+///
+/// Servers are handed out in list order and released servers go to the
+/// back of the list.
+pub struct StaticPoolAllocator {
+    available: Mutex<VecDeque<ServerHandle>>,
+}
+
+impl StaticPoolAllocator {
+    pub fn new(servers: Vec<ServerHandle>) -> Self {
+        Self {
+            available: Mutex::new(servers.into()),
+        }
+    }
+
+    /// Number of servers not currently allocated
+    pub fn available_count(&self) -> usize {
+        self.available.lock().unwrap().len()
+    }
+}
+
+impl ServerAllocator for StaticPoolAllocator {
+    fn allocate<'a>(&'a self, lobby: &'a Lobby) -> BoxFuture<'a, Result<ServerHandle>> {
+        Box::pin(async move {
+            self.available.lock().unwrap().pop_front().ok_or_else(|| {
+                MatchForgeError::OperationFailed(format!("No game server available for lobby {}", lobby.id))
+            })
+        })
+    }
+
+    fn release(&self, handle: ServerHandle) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.available.lock().unwrap().push_back(handle);
+            Ok(())
+        })
+    }
+}
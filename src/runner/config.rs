@@ -5,8 +5,17 @@ use serde::{Deserialize, Serialize};
 pub struct RunnerConfig {
     /// How often to run matchmaking ticks (in milliseconds)
     pub tick_interval_ms: u64,
-    /// Maximum number of matches to process per tick
-    pub max_matches_per_tick: usize,
+    /// Randomize each tick's sleep by up to this many milliseconds either way
+    ///
+    /// Spreads the load of runner instances started together so they don't
+    /// all hit storage at the same moment. `None` ticks at a fixed rate.
+    #[serde(default)]
+    pub tick_jitter_ms: Option<u64>,
+    /// Maximum number of matches to form per tick across all queues, or `None` for no limit
+    ///
+    /// Players left over stay queued and are matched on later ticks, which
+    /// spreads game-server allocation out when a large backlog builds up.
+    pub max_matches_per_tick: Option<usize>,
     /// Whether to automatically dispatch ready lobbies
     pub auto_dispatch: bool,
     /// Queue-specific configurations
@@ -21,10 +30,24 @@ pub struct QueueRunnerConfig {
     pub priority: u8,
     /// Maximum concurrent matches for this queue
     pub max_concurrent_matches: usize,
+    /// Process this queue on its own interval instead of every tick (in milliseconds)
+    #[serde(default)]
+    pub tick_interval_ms: Option<u64>,
 }
 
-impl RunnerConfig {
-    pub fn default() -> Self {
+impl Default for QueueRunnerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            priority: 128,
+            max_concurrent_matches: 100,
+            tick_interval_ms: None,
+        }
+    }
+}
+
+impl Default for RunnerConfig {
+    fn default() -> Self {
         let mut queue_configs = std::collections::HashMap::new();
         
         // Default configuration for common queues
@@ -32,37 +55,120 @@ impl RunnerConfig {
             enabled: true,
             priority: 1,
             max_concurrent_matches: 100,
+            tick_interval_ms: None,
         });
         
         queue_configs.insert("casual_5v5".to_string(), QueueRunnerConfig {
             enabled: true,
             priority: 2,
             max_concurrent_matches: 50,
+            tick_interval_ms: None,
         });
 
         Self {
             tick_interval_ms: 1000, // 1 second
-            max_matches_per_tick: 1000,
+            tick_jitter_ms: None,
+            max_matches_per_tick: Some(1000),
             auto_dispatch: true,
             queue_configs,
         }
     }
+}
+
+impl RunnerConfig {
+    /// Start building a config with no queues configured
+    pub fn builder() -> RunnerConfigBuilder {
+        RunnerConfigBuilder::new()
+    }
+
+    /// Shortest interval any enabled queue needs to be checked at
+    pub fn effective_tick_interval_ms(&self) -> u64 {
+        self.queue_configs
+            .values()
+            .filter(|c| c.enabled)
+            .filter_map(|c| c.tick_interval_ms)
+            .fold(self.tick_interval_ms, u64::min)
+    }
 
     pub fn fast() -> Self {
-        let mut config = Self::default();
-        config.tick_interval_ms = 500; // 0.5 seconds
-        config
+        Self {
+            tick_interval_ms: 500, // 0.5 seconds
+            ..Self::default()
+        }
     }
 
     pub fn slow() -> Self {
-        let mut config = Self::default();
-        config.tick_interval_ms = 5000; // 5 seconds
-        config
+        Self {
+            tick_interval_ms: 5000, // 5 seconds
+            ..Self::default()
+        }
     }
 }
 
-impl Default for RunnerConfig {
+/// Builder for [`RunnerConfig`] with global defaults and per-queue overrides
+pub struct RunnerConfigBuilder {
+    config: RunnerConfig,
+    queue_defaults: QueueRunnerConfig,
+}
+
+impl RunnerConfigBuilder {
+    pub fn new() -> Self {
+        Self {
+            config: RunnerConfig {
+                queue_configs: std::collections::HashMap::new(),
+                ..RunnerConfig::default()
+            },
+            queue_defaults: QueueRunnerConfig::default(),
+        }
+    }
+
+    pub fn tick_interval_ms(mut self, tick_interval_ms: u64) -> Self {
+        self.config.tick_interval_ms = tick_interval_ms;
+        self
+    }
+
+    /// Randomize each tick's sleep by up to `tick_jitter_ms` either way
+    pub fn tick_jitter_ms(mut self, tick_jitter_ms: u64) -> Self {
+        self.config.tick_jitter_ms = Some(tick_jitter_ms);
+        self
+    }
+
+    /// Cap on matches formed per tick; pass `None` to remove the cap
+    pub fn max_matches_per_tick(mut self, max_matches_per_tick: impl Into<Option<usize>>) -> Self {
+        self.config.max_matches_per_tick = max_matches_per_tick.into();
+        self
+    }
+
+    pub fn auto_dispatch(mut self, auto_dispatch: bool) -> Self {
+        self.config.auto_dispatch = auto_dispatch;
+        self
+    }
+
+    /// Settings used by queues added with [`queue`](Self::queue)
+    pub fn queue_defaults(mut self, defaults: QueueRunnerConfig) -> Self {
+        self.queue_defaults = defaults;
+        self
+    }
+
+    /// Process a queue with the default queue settings
+    pub fn queue(mut self, name: impl Into<String>) -> Self {
+        self.config.queue_configs.insert(name.into(), self.queue_defaults.clone());
+        self
+    }
+
+    /// Process a queue with its own settings
+    pub fn queue_override(mut self, name: impl Into<String>, config: QueueRunnerConfig) -> Self {
+        self.config.queue_configs.insert(name.into(), config);
+        self
+    }
+
+    pub fn build(self) -> RunnerConfig {
+        self.config
+    }
+}
+
+impl Default for RunnerConfigBuilder {
     fn default() -> Self {
-        Self::default()
+        Self::new()
     }
 }
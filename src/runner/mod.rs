@@ -1,5 +1,9 @@
+pub mod allocator;
 pub mod config;
 pub mod tick;
+pub mod validator;
 
-pub use config::{QueueRunnerConfig, RunnerConfig};
-pub use tick::{LobbyManager, MatchmakingRunner};
+pub use allocator::{ServerAllocator, StaticPoolAllocator};
+pub use config::{QueueRunnerConfig, RunnerConfig, RunnerConfigBuilder};
+pub use tick::{DispatchCallback, LobbyManager, MatchmakingRunner};
+pub use validator::{MatchValidator, ValidationOutcome};
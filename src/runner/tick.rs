@@ -1,21 +1,45 @@
-use super::config::RunnerConfig;
+use super::{
+    allocator::ServerAllocator,
+    config::RunnerConfig,
+    validator::{apply_team_assignments, MatchValidator, ValidationOutcome},
+};
 use crate::{
+    clock::{Clock, SystemClock},
     error::*,
-    lobby::{Lobby, LobbyMetadata, LobbyState},
-    mmr::Rating,
+    lobby::{DodgeTracker, Lobby, LobbyMetadata, LobbyState, ServerHandle},
+    mmr::{
+        AuditLog, DecayStrategy, Glicko2Algorithm, MmrAlgorithm, Outcome, Rating, RatingAuditEntry, RatingChangeReason,
+        RatingProtection, RatingService,
+    },
     persistence::PersistenceAdapter,
-    queue::QueueManager,
+    queue::{MatchResult, QueueEntry, QueueManager},
 };
+use chrono::{DateTime, Utc};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::sync::Arc;
-use tokio::time::{interval, Duration};
+use tokio::{
+    sync::broadcast,
+    time::{interval, Duration},
+};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
 use uuid::Uuid;
 
+/// Matches buffered per [`MatchmakingRunner::match_stream`] subscriber before it lags
+const MATCH_STREAM_CAPACITY: usize = 1024;
+
 /// The main matchmaking runner that processes queues periodically
 pub struct MatchmakingRunner {
     config: RunnerConfig,
     queue_manager: Arc<QueueManager>,
     persistence: Arc<dyn PersistenceAdapter>,
     running: std::sync::atomic::AtomicBool,
+    clock: Arc<dyn Clock>,
+    /// When each queue with its own interval was last processed
+    last_processed: std::sync::Mutex<std::collections::HashMap<String, DateTime<Utc>>>,
+    formed_matches: broadcast::Sender<MatchResult>,
+    /// Source of tick jitter, see [`RunnerConfig::tick_jitter_ms`]
+    jitter_rng: std::sync::Mutex<StdRng>,
+    validator: Option<Arc<dyn MatchValidator>>,
 }
 
 impl MatchmakingRunner {
@@ -24,14 +48,42 @@ impl MatchmakingRunner {
         queue_manager: Arc<QueueManager>,
         persistence: Arc<dyn PersistenceAdapter>,
     ) -> Self {
+        let clock = queue_manager.clock();
         Self {
             config,
             queue_manager,
             persistence,
             running: std::sync::atomic::AtomicBool::new(false),
+            clock,
+            last_processed: std::sync::Mutex::new(std::collections::HashMap::new()),
+            formed_matches: broadcast::channel(MATCH_STREAM_CAPACITY).0,
+            jitter_rng: std::sync::Mutex::new(StdRng::from_entropy()),
+            validator: None,
         }
     }
 
+    /// Seed the tick jitter so the sequence of sleeps is reproducible
+    pub fn with_jitter_seed(self, seed: u64) -> Self {
+        *self.jitter_rng.lock().unwrap() = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Use a custom clock for lobby timestamps (defaults to the queue manager's clock)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Consult `validator` before committing each match to a lobby
+    ///
+    /// Rejected matches, and adjustments that don't fit the queue's format,
+    /// are dropped with their entries still queued and don't count towards
+    /// the per-tick match limits. Adjusted teams are the lobby's teams.
+    pub fn with_match_validator(mut self, validator: Arc<dyn MatchValidator>) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
     /// Start the matchmaking runner
     pub async fn start(&self) -> Result<()> {
         if self.running.swap(true, std::sync::atomic::Ordering::SeqCst) {
@@ -40,10 +92,14 @@ impl MatchmakingRunner {
             ));
         }
 
-        let mut interval = interval(Duration::from_millis(self.config.tick_interval_ms));
+        let mut interval = interval(Duration::from_millis(self.config.effective_tick_interval_ms()));
         
         loop {
-            interval.tick().await;
+            if self.config.tick_jitter_ms.is_some() {
+                tokio::time::sleep(self.next_tick_delay()).await;
+            } else {
+                interval.tick().await;
+            }
             
             if !self.running.load(std::sync::atomic::Ordering::SeqCst) {
                 break;
@@ -57,6 +113,38 @@ impl MatchmakingRunner {
         Ok(())
     }
 
+    /// Matches formed by this runner from now on, as they are formed
+    ///
+    /// Every call returns an independent stream. A consumer that falls more
+    /// than 1024 matches behind skips the oldest ones rather than blocking
+    /// the runner. Streams end once the runner is dropped.
+    pub fn match_stream(&self) -> impl Stream<Item = MatchResult> {
+        BroadcastStream::new(self.subscribe_matches()).filter_map(|result| result.ok())
+    }
+
+    pub(crate) fn subscribe_matches(&self) -> broadcast::Receiver<MatchResult> {
+        self.formed_matches.subscribe()
+    }
+
+    /// Run a single tick now, e.g. to drive the runner from a simulated clock
+    pub async fn tick(&self) -> Result<()> {
+        self.process_tick().await
+    }
+
+    /// How long to sleep before the next tick
+    ///
+    /// The tick interval, moved by a uniformly random amount within the
+    /// configured jitter either way; jitter is capped at the interval.
+    pub(crate) fn next_tick_delay(&self) -> Duration {
+        let base = self.config.effective_tick_interval_ms();
+        let jitter = match self.config.tick_jitter_ms {
+            Some(jitter) if jitter > 0 => jitter.min(base),
+            _ => return Duration::from_millis(base),
+        };
+        let offset = self.jitter_rng.lock().unwrap().gen_range(0..=2 * jitter);
+        Duration::from_millis(base - jitter + offset)
+    }
+
     /// Stop the matchmaking runner
     pub fn stop(&self) {
         self.running.store(false, std::sync::atomic::Ordering::SeqCst);
@@ -65,11 +153,14 @@ impl MatchmakingRunner {
     /// Process a single matchmaking tick
     async fn process_tick(&self) -> Result<()> {
         let mut total_matches = 0;
+        let max_matches = self.config.max_matches_per_tick.unwrap_or(usize::MAX);
 
-        // Process queues in priority order
+        // Process due queues in priority order
+        let now = self.clock.now();
         let mut queue_names: Vec<String> = self.config.queue_configs
             .iter()
             .filter(|(_, config)| config.enabled)
+            .filter(|(name, config)| self.is_due(name, config.tick_interval_ms, now))
             .map(|(name, _)| name.clone())
             .collect();
 
@@ -80,13 +171,13 @@ impl MatchmakingRunner {
         });
 
         for queue_name in queue_names {
-            if total_matches >= self.config.max_matches_per_tick {
+            if total_matches >= max_matches {
                 break;
             }
 
             let queue_config = self.config.queue_configs.get(&queue_name);
             let max_for_queue = queue_config.map(|c| c.max_concurrent_matches).unwrap_or(100);
-            let remaining = self.config.max_matches_per_tick - total_matches;
+            let remaining = max_matches - total_matches;
             let to_process = remaining.min(max_for_queue);
 
             match self.process_queue(&queue_name, to_process).await {
@@ -105,26 +196,88 @@ impl MatchmakingRunner {
         Ok(())
     }
 
+    /// Whether a queue's interval (its override, or the global interval) has elapsed
+    ///
+    /// Ticks run at the shortest interval of any queue, so queues on longer
+    /// intervals skip ticks. Half a tick of slack absorbs timer jitter.
+    fn is_due(&self, queue_name: &str, tick_interval_ms: Option<u64>, now: DateTime<Utc>) -> bool {
+        let tick_ms = self.config.effective_tick_interval_ms();
+        let queue_ms = tick_interval_ms.unwrap_or(self.config.tick_interval_ms);
+        if queue_ms <= tick_ms {
+            return true;
+        }
+
+        let mut last_processed = self.last_processed.lock().unwrap();
+        let due = !matches!(
+            last_processed.get(queue_name),
+            Some(last) if now - *last + chrono::Duration::milliseconds((tick_ms / 2) as i64)
+                < chrono::Duration::milliseconds(queue_ms as i64)
+        );
+        if due {
+            last_processed.insert(queue_name.to_string(), now);
+        }
+        due
+    }
+
     /// Process a single queue
     async fn process_queue(&self, queue_name: &str, max_matches: usize) -> Result<usize> {
-        let matches = self.queue_manager.find_matches(queue_name).await?;
+        self.queue_manager.expire_entries(queue_name).await?;
+        self.queue_manager.release_expired_reservations(queue_name).await?;
+
+        let matches = self.queue_manager.preview_matches(queue_name).await?;
+        let format = self.queue_manager.queue_config(queue_name).await?.format;
         
         let mut processed = 0;
-        for match_result in matches.into_iter().take(max_matches) {
+        for mut match_result in matches {
+            if processed >= max_matches {
+                break;
+            }
+            if let Some(validator) = &self.validator {
+                match validator.validate(&match_result) {
+                    ValidationOutcome::Accept => {}
+                    ValidationOutcome::Reject { .. } => continue,
+                    ValidationOutcome::Adjusted { team_assignments } => {
+                        // An unusable adjustment drops the match like a rejection
+                        if let Err(e) = apply_team_assignments(&mut match_result, team_assignments, &format) {
+                            eprintln!("Skipping match in queue '{}': {}", queue_name, e);
+                            continue;
+                        }
+                    }
+                }
+            }
+
+            // Hold the players until their lobby exists
+            self.queue_manager.reserve_matched_entries(queue_name, &match_result.entries).await?;
+            self.queue_manager.record_match_quality(queue_name, &match_result).await;
+
             // Create lobby from match result
+            let player_roles = match_result
+                .entries
+                .iter()
+                .filter_map(|e| e.metadata.roles.first().map(|role| (e, role)))
+                .flat_map(|(e, role)| e.player_ids.iter().map(move |id| (*id, role.clone())))
+                .collect();
             let metadata = LobbyMetadata {
                 queue_name: queue_name.to_string(),
-                game_mode: Some(queue_name.to_string()),
+                game_mode: match_result.game_mode.clone().or_else(|| Some(queue_name.to_string())),
+                player_roles,
                 ..Default::default()
             };
 
-            let mut lobby = Lobby::from_match_result(match_result.clone(), vec![1, 1], metadata);
+            let mut lobby = Lobby::from_match_result(match_result.clone(), format.team_sizes.clone(), metadata);
+            lobby.created_at = self.clock.now();
             
-            // Save lobby
-            self.persistence.save_lobby(&lobby).await?;
+            // Save lobby, putting the players back in queue if that fails
+            if let Err(e) = self.persistence.save_lobby(&lobby).await {
+                self.queue_manager.release_reserved_entries(queue_name, &match_result.entries).await?;
+                return Err(e);
+            }
             
-            // Remove matched entries from queue
+            // Commit the reserved entries
             self.queue_manager.remove_matched_entries(queue_name, &match_result.entries).await?;
+
+            // No subscribers is not an error
+            let _ = self.formed_matches.send(match_result.clone());
             
             // Auto-dispatch if enabled
             if self.config.auto_dispatch {
@@ -144,14 +297,90 @@ impl MatchmakingRunner {
     }
 }
 
+/// Called with a lobby once its last player readies up, e.g. to hand it to a game server
+pub type DispatchCallback = Arc<dyn Fn(&Lobby) + Send + Sync>;
+
 /// Lobby manager for handling lobby lifecycle
 pub struct LobbyManager {
     pub persistence: Arc<dyn PersistenceAdapter>,
+    on_ready: Option<DispatchCallback>,
+    audit_log: Option<Arc<dyn AuditLog>>,
+    dodge_tracker: Option<Arc<DodgeTracker>>,
+    server_allocator: Option<Arc<dyn ServerAllocator>>,
+    reconnect_grace: chrono::Duration,
+    rating_algorithm: Arc<dyn MmrAlgorithm>,
+    rating_service: Option<Arc<RatingService>>,
+    clock: Arc<dyn Clock>,
+    /// Held around every lobby load-modify-save so concurrent changes see each other's writes
+    lobby_lock: tokio::sync::Mutex<()>,
 }
 
 impl LobbyManager {
     pub fn new(persistence: Arc<dyn PersistenceAdapter>) -> Self {
-        Self { persistence }
+        Self {
+            persistence,
+            on_ready: None,
+            audit_log: None,
+            dodge_tracker: None,
+            server_allocator: None,
+            reconnect_grace: chrono::Duration::zero(),
+            rating_algorithm: Arc::new(Glicko2Algorithm::default()),
+            rating_service: None,
+            clock: Arc::new(SystemClock),
+            lobby_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Record every rating change made through this manager
+    pub fn with_audit_log(mut self, audit_log: Arc<dyn AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Use a custom clock for audit timestamps
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Record players who fail a ready check, see [`cancel_ready_check`](Self::cancel_ready_check)
+    pub fn with_dodge_tracker(mut self, dodge_tracker: Arc<DodgeTracker>) -> Self {
+        self.dodge_tracker = Some(dodge_tracker);
+        self
+    }
+
+    /// Reserve game servers for lobbies, see [`dispatch_to_allocated_server`](Self::dispatch_to_allocated_server)
+    pub fn with_server_allocator(mut self, server_allocator: Arc<dyn ServerAllocator>) -> Self {
+        self.server_allocator = Some(server_allocator);
+        self
+    }
+
+    /// Hold a disconnected player's slot this long, see [`disconnect_player`](Self::disconnect_player)
+    pub fn with_reconnect_grace(mut self, grace: chrono::Duration) -> Self {
+        self.reconnect_grace = grace;
+        self
+    }
+
+    /// Rate results reported through [`report_result`](Self::report_result) with `algorithm` (Glicko-2 by default)
+    pub fn with_rating_algorithm(mut self, algorithm: Arc<dyn MmrAlgorithm>) -> Self {
+        self.rating_algorithm = algorithm;
+        self
+    }
+
+    /// Rate results reported through [`report_result`](Self::report_result) with `service`
+    ///
+    /// Takes precedence over [`with_rating_algorithm`](Self::with_rating_algorithm)
+    /// and the manager's audit log, e.g. to apply the service's tier ladder.
+    /// Without one, a service over this manager's persistence is used.
+    pub fn with_rating_service(mut self, service: Arc<RatingService>) -> Self {
+        self.rating_service = Some(service);
+        self
+    }
+
+    /// Invoke `callback` whenever a lobby becomes `Ready`
+    pub fn with_dispatch_callback(mut self, callback: DispatchCallback) -> Self {
+        self.on_ready = Some(callback);
+        self
     }
 
     /// Get a lobby by ID
@@ -159,19 +388,185 @@ impl LobbyManager {
         self.persistence.load_lobby(lobby_id).await
     }
 
+    /// Add players to open team slots of a lobby, see [`Lobby::add_players`]
+    ///
+    /// Additions are serialized so concurrent backfill requests cannot
+    /// overfill a lobby; one that no longer fits fails with
+    /// [`MatchForgeError::LobbyFull`].
+    pub async fn add_players(&self, lobby_id: Uuid, player_ids: &[Uuid]) -> Result<Lobby> {
+        let _guard = self.lobby_lock.lock().await;
+
+        let mut lobby = self.persistence.load_lobby(lobby_id).await?
+            .ok_or(MatchForgeError::LobbyNotFound(lobby_id))?;
+        lobby.add_players(player_ids)?;
+        self.persistence.save_lobby(&lobby).await?;
+
+        Ok(lobby)
+    }
+
     /// Mark player as ready in lobby
+    ///
+    /// When the last player readies, the lobby advances to `Ready` and the
+    /// dispatch callback runs once; repeated ready calls change nothing.
+    /// Like every lobby change made through the manager, concurrent calls
+    /// are serialized so none is lost.
     pub async fn mark_player_ready(&self, lobby_id: Uuid, player_id: Uuid) -> Result<()> {
+        let _guard = self.lobby_lock.lock().await;
         let mut lobby = self.persistence.load_lobby(lobby_id).await?
             .ok_or(MatchForgeError::LobbyNotFound(lobby_id))?;
 
+        let was_ready = lobby.state == LobbyState::Ready;
         lobby.mark_player_ready(player_id)?;
         self.persistence.save_lobby(&lobby).await?;
 
+        if !was_ready && lobby.state == LobbyState::Ready {
+            if let Some(on_ready) = &self.on_ready {
+                on_ready(&lobby);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Cancel a lobby's ready check, e.g. when it times out
+    ///
+    /// Closes the lobby, records a dodge for every player who hadn't readied
+    /// and returns the ready players as queue entries that keep their
+    /// original join time, for [`QueueManager::requeue`]. A party comes back
+    /// whole if all its members readied; otherwise its ready members come
+    /// back solo at their stored rating. Only lobbies waiting for ready can
+    /// be cancelled, so nobody is penalized twice.
+    pub async fn cancel_ready_check(&self, lobby_id: Uuid) -> Result<Vec<QueueEntry>> {
+        let _guard = self.lobby_lock.lock().await;
+        let mut lobby = self.persistence.load_lobby(lobby_id).await?
+            .ok_or(MatchForgeError::LobbyNotFound(lobby_id))?;
+        if lobby.state != LobbyState::WaitingForReady {
+            return Err(MatchForgeError::OperationFailed(format!(
+                "Lobby {} has no ready check in progress ({:?})",
+                lobby_id, lobby.state
+            )));
+        }
+
+        let mut requeued = Vec::new();
+        let mut covered = std::collections::HashSet::new();
+        for entry in &lobby.queue_entries {
+            covered.extend(entry.player_ids.iter().copied());
+            if entry.player_ids.iter().all(|id| lobby.ready_players.contains(id)) {
+                let mut entry = entry.clone();
+                entry.id = Uuid::new_v4();
+                requeued.push(entry);
+                continue;
+            }
+            for player_id in entry.player_ids.iter().filter(|id| lobby.ready_players.contains(id)) {
+                let rating = self.persistence.load_player_rating(*player_id).await?.unwrap_or(entry.average_rating);
+                let mut solo = QueueEntry::new_solo(entry.queue_name.clone(), *player_id, rating, entry.metadata.clone());
+                solo.joined_at = entry.joined_at;
+                requeued.push(solo);
+            }
+        }
+
+        // Players added after matching (e.g. backfill) have no entry to restore
+        for player_id in lobby.player_ids.iter().filter(|id| lobby.ready_players.contains(id) && !covered.contains(id)) {
+            let rating = self.persistence.load_player_rating(*player_id).await?.unwrap_or_default();
+            let mut solo = QueueEntry::new_solo(lobby.metadata.queue_name.clone(), *player_id, rating, Default::default());
+            solo.joined_at = lobby.created_at;
+            requeued.push(solo);
+        }
+
+        lobby.transition_to(LobbyState::Closed)?;
+        self.persistence.save_lobby(&lobby).await?;
+
+        if let Some(dodge_tracker) = &self.dodge_tracker {
+            let now = self.clock.now();
+            for player_id in lobby.player_ids.iter().filter(|id| !lobby.ready_players.contains(id)) {
+                dodge_tracker.record_dodge(*player_id, now).await;
+            }
+        }
+
+        Ok(requeued)
+    }
+
+    /// Mark a player who dropped from a lobby as reconnecting
+    ///
+    /// The player keeps their slot until the reconnect grace runs out, see
+    /// [`reconnect_player`](Self::reconnect_player). Without a grace the
+    /// slot is given up right away, as by [`expire_reconnections`](Self::expire_reconnections).
+    /// Returns the reconnection deadline.
+    pub async fn disconnect_player(&self, lobby_id: Uuid, player_id: Uuid) -> Result<DateTime<Utc>> {
+        let deadline = self.clock.now() + self.reconnect_grace;
+        {
+            let _guard = self.lobby_lock.lock().await;
+            let mut lobby = self.persistence.load_lobby(lobby_id).await?
+                .ok_or(MatchForgeError::LobbyNotFound(lobby_id))?;
+            lobby.mark_reconnecting(player_id, deadline)?;
+            self.persistence.save_lobby(&lobby).await?;
+        }
+
+        if self.reconnect_grace <= chrono::Duration::zero() {
+            self.expire_reconnections(lobby_id).await?;
+        }
+        Ok(deadline)
+    }
+
+    /// Return a reconnecting player to their slot before the deadline
+    pub async fn reconnect_player(&self, lobby_id: Uuid, player_id: Uuid) -> Result<()> {
+        let _guard = self.lobby_lock.lock().await;
+        let mut lobby = self.persistence.load_lobby(lobby_id).await?
+            .ok_or(MatchForgeError::LobbyNotFound(lobby_id))?;
+
+        lobby.reconnect(player_id, self.clock.now())?;
+        self.persistence.save_lobby(&lobby).await?;
+
+        Ok(())
+    }
+
+    /// Remove players whose reconnection window has passed
+    ///
+    /// Their slots open for backfill through [`add_players`](Self::add_players)
+    /// and each is recorded as a dodge. Returns the removed players.
+    pub async fn expire_reconnections(&self, lobby_id: Uuid) -> Result<Vec<Uuid>> {
+        let _guard = self.lobby_lock.lock().await;
+
+        let mut lobby = self.persistence.load_lobby(lobby_id).await?
+            .ok_or(MatchForgeError::LobbyNotFound(lobby_id))?;
+        let now = self.clock.now();
+        let expired = lobby.remove_expired_reconnections(now);
+        if expired.is_empty() {
+            return Ok(expired);
+        }
+        self.persistence.save_lobby(&lobby).await?;
+
+        if let Some(dodge_tracker) = &self.dodge_tracker {
+            for player_id in &expired {
+                dodge_tracker.record_dodge(*player_id, now).await;
+            }
+        }
+
+        Ok(expired)
+    }
+
+    /// Reassign a forming lobby's players to teams to minimize rating spread
+    pub async fn rebalance_teams(&self, lobby_id: Uuid) -> Result<()> {
+        let _guard = self.lobby_lock.lock().await;
+        let mut lobby = self.persistence.load_lobby(lobby_id).await?
+            .ok_or(MatchForgeError::LobbyNotFound(lobby_id))?;
+
+        let mut ratings = std::collections::HashMap::new();
+        for &player_id in &lobby.player_ids {
+            if let Some(rating) = self.persistence.load_player_rating(player_id).await? {
+                ratings.insert(player_id, rating);
+            }
+        }
+
+        lobby.rebalance_teams(&ratings)?;
+        self.persistence.save_lobby(&lobby).await?;
+
         Ok(())
     }
 
     /// Dispatch lobby to game server
     pub async fn dispatch_lobby(&self, lobby_id: Uuid, server_id: String) -> Result<()> {
+        let _guard = self.lobby_lock.lock().await;
         let mut lobby = self.persistence.load_lobby(lobby_id).await?
             .ok_or(MatchForgeError::LobbyNotFound(lobby_id))?;
 
@@ -183,12 +578,58 @@ impl LobbyManager {
         Ok(())
     }
 
+    /// Dispatch a `Ready` lobby to a server from the configured allocator
+    ///
+    /// The server is stored in the lobby's [`LobbyMetadata::server`] and
+    /// returned to the allocator by [`close_lobby`](Self::close_lobby). If
+    /// allocation fails, e.g. because every server is taken, the lobby stays
+    /// `Ready` so dispatch can be retried.
+    pub async fn dispatch_to_allocated_server(&self, lobby_id: Uuid) -> Result<ServerHandle> {
+        let server_allocator = self.server_allocator.as_ref().ok_or_else(|| {
+            MatchForgeError::InvalidConfiguration("Lobby manager has no server allocator".to_string())
+        })?;
+        let _guard = self.lobby_lock.lock().await;
+        let mut lobby = self.persistence.load_lobby(lobby_id).await?
+            .ok_or(MatchForgeError::LobbyNotFound(lobby_id))?;
+        if !lobby.state.can_transition_to(LobbyState::Dispatched) {
+            return Err(MatchForgeError::OperationFailed(format!(
+                "Lobby {} cannot be dispatched ({:?})",
+                lobby_id, lobby.state
+            )));
+        }
+
+        let server = server_allocator.allocate(&lobby).await?;
+        lobby.metadata.server_id = Some(server.server_id.clone());
+        lobby.metadata.server = Some(server.clone());
+        lobby.transition_to(LobbyState::Dispatched)?;
+
+        if let Err(e) = self.persistence.save_lobby(&lobby).await {
+            server_allocator.release(server).await?;
+            return Err(e);
+        }
+
+        Ok(server)
+    }
+
     /// Close lobby (match completed or cancelled)
+    ///
+    /// A server reserved by [`dispatch_to_allocated_server`](Self::dispatch_to_allocated_server)
+    /// is released back to the allocator.
     pub async fn close_lobby(&self, lobby_id: Uuid) -> Result<()> {
+        let _guard = self.lobby_lock.lock().await;
+        self.close_lobby_locked(lobby_id).await
+    }
+
+    /// [`close_lobby`](Self::close_lobby) for a caller already holding the lobby lock
+    async fn close_lobby_locked(&self, lobby_id: Uuid) -> Result<()> {
         let mut lobby = self.persistence.load_lobby(lobby_id).await?
             .ok_or(MatchForgeError::LobbyNotFound(lobby_id))?;
 
         lobby.transition_to(LobbyState::Closed)?;
+
+        if let (Some(server_allocator), Some(server)) = (&self.server_allocator, lobby.metadata.server.clone()) {
+            server_allocator.release(server).await?;
+        }
         
         // Save match result to history
         self.persistence.save_match_result(&lobby).await?;
@@ -199,7 +640,49 @@ impl LobbyManager {
         Ok(())
     }
 
+    /// Apply a result reported by the game server and close the lobby
+    ///
+    /// Only dispatched lobbies accept results, and the report must pass
+    /// [`Lobby::validate_result`]. Ratings are updated through
+    /// [`RatingService::report_match_result`]. A rejected report changes nothing.
+    pub async fn report_result(&self, lobby_id: Uuid, results: Vec<(Uuid, Outcome)>) -> Result<()> {
+        self.report_result_with_protection(lobby_id, results, &std::collections::HashMap::new()).await
+    }
+
+    /// [`report_result`](Self::report_result) with a [`RatingProtection`] for some players
+    pub async fn report_result_with_protection(
+        &self,
+        lobby_id: Uuid,
+        results: Vec<(Uuid, Outcome)>,
+        protections: &std::collections::HashMap<Uuid, RatingProtection>,
+    ) -> Result<()> {
+        let _guard = self.lobby_lock.lock().await;
+        let lobby = self.persistence.load_lobby(lobby_id).await?
+            .ok_or(MatchForgeError::LobbyNotFound(lobby_id))?;
+        if lobby.state != LobbyState::Dispatched {
+            return Err(MatchForgeError::OperationFailed(format!(
+                "Lobby {} is {:?}, results are only accepted once dispatched",
+                lobby_id, lobby.state
+            )));
+        }
+        lobby.validate_result(&results)?;
+
+        match &self.rating_service {
+            Some(service) => service.report_match_result_with_protection(lobby.match_id, results, protections).await?,
+            None => {
+                self.default_rating_service(self.rating_algorithm.clone())
+                    .report_match_result_with_protection(lobby.match_id, results, protections)
+                    .await?
+            }
+        };
+        self.close_lobby_locked(lobby_id).await
+    }
+
     /// Update player ratings after match completion
+    ///
+    /// Outcomes of players outside the lobby are ignored. The rest are rated
+    /// with `mmr_algorithm` as [`RatingService::report_match_result`] does,
+    /// so every lobby player reported needs a stored rating.
     pub async fn update_ratings(
         &self,
         lobby_id: Uuid,
@@ -208,53 +691,1078 @@ impl LobbyManager {
     ) -> Result<()> {
         let lobby = self.persistence.load_lobby(lobby_id).await?
             .ok_or(MatchForgeError::LobbyNotFound(lobby_id))?;
+        let outcomes = outcomes
+            .iter()
+            .filter(|(player_id, _)| lobby.player_ids.contains(player_id))
+            .copied()
+            .collect();
 
-        // Group players by teams
-        let mut team_ratings: std::collections::HashMap<usize, Vec<(Uuid, Rating)>> = std::collections::HashMap::new();
-        
-        for (player_id, _) in outcomes {
-            if let Some(team_id) = lobby.get_player_team(*player_id) {
-                if let Ok(Some(rating)) = self.persistence.load_player_rating(*player_id).await {
-                    team_ratings.entry(team_id).or_insert_with(Vec::new).push((*player_id, rating));
-                }
+        self.default_rating_service(mmr_algorithm)
+            .report_match_result(lobby.match_id, outcomes)
+            .await?;
+        Ok(())
+    }
+
+    /// Rating service over this manager's persistence, audit log and clock
+    fn default_rating_service(&self, algorithm: Arc<dyn MmrAlgorithm>) -> RatingService {
+        let service = RatingService::new(self.persistence.clone())
+            .with_algorithm(algorithm)
+            .with_clock(self.clock.clone());
+        match &self.audit_log {
+            Some(audit_log) => service.with_audit_log(audit_log.clone()),
+            None => service,
+        }
+    }
+
+    /// Apply inactivity decay to each player, measured from their most recent match
+    ///
+    /// Players without match history are skipped. Returns the number of
+    /// ratings that changed.
+    pub async fn decay_ratings(&self, player_ids: &[Uuid], strategy: &dyn DecayStrategy) -> Result<usize> {
+        let mut decayed = 0;
+        for &player_id in player_ids {
+            let Some(last_match) = self.persistence.load_player_match_history(player_id, 1).await?.pop() else {
+                continue;
+            };
+            let Some(before) = self.persistence.load_player_rating(player_id).await? else {
+                continue;
+            };
+
+            let after = strategy.apply_decay(before, last_match.created_at);
+            if after.rating != before.rating || after.deviation != before.deviation {
+                self.save_rating(player_id, before, after, RatingChangeReason::Decay).await?;
+                decayed += 1;
             }
         }
 
-        // Update ratings based on team vs team outcomes
-        for (team_a_id, team_a_players) in &team_ratings {
-            for (team_b_id, team_b_players) in &team_ratings {
-                if team_a_id >= team_b_id {
-                    continue; // Skip duplicate matchups and same team
-                }
+        Ok(decayed)
+    }
+
+    /// Persist a new rating and record the change in the audit log
+    async fn save_rating(&self, player_id: Uuid, before: Rating, after: Rating, reason: RatingChangeReason) -> Result<()> {
+        self.persistence.save_player_rating(player_id, after).await?;
+
+        if let Some(audit_log) = &self.audit_log {
+            audit_log
+                .record(RatingAuditEntry {
+                    player_id,
+                    reason,
+                    before,
+                    after,
+                    at: self.clock.now(),
+                })
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clock::MockClock,
+        lobby::Team,
+        persistence::InMemoryAdapter,
+        queue::{EntryStatus, MatchConstraints, MatchFormat, QueueConfig},
+        runner::{QueueRunnerConfig, RunnerConfig, StaticPoolAllocator},
+    };
+
+    fn team_rating_delta(lobby: &Lobby, ratings: &std::collections::HashMap<Uuid, f64>) -> f64 {
+        let totals: Vec<f64> = lobby
+            .teams
+            .iter()
+            .map(|t| t.player_ids.iter().map(|id| ratings[id]).sum())
+            .collect();
+        (totals[0] - totals[1]).abs()
+    }
+
+    /// A 2v2 lobby with both strong players stacked on team 0
+    async fn stacked_lobby(persistence: &Arc<impl PersistenceAdapter>) -> (Lobby, std::collections::HashMap<Uuid, f64>) {
+        let players: Vec<(Uuid, f64)> = [2000.0, 1900.0, 1200.0, 1100.0]
+            .into_iter()
+            .map(|r| (Uuid::new_v4(), r))
+            .collect();
+        for (id, r) in &players {
+            persistence.save_player_rating(*id, Rating::new(*r, 100.0, 0.06)).await.unwrap();
+        }
+
+        let player_ids: Vec<Uuid> = players.iter().map(|(id, _)| *id).collect();
+        let mut lobby = Lobby::from_match_result(
+            crate::queue::MatchResult {
+                match_id: Uuid::new_v4(),
+                entries: Vec::new(),
+                team_assignments: Vec::new(),
+                split_party_ids: Vec::new(),
+                quality_score: 1.0,
+                game_mode: None,
+                team_roles: Vec::new(),
+            },
+            vec![2, 2],
+            LobbyMetadata::default(),
+        );
+        lobby.player_ids = player_ids.clone();
+        lobby.teams = vec![
+            Team { team_id: 0, player_ids: player_ids[0..2].to_vec(), capacity: 2 },
+            Team { team_id: 1, player_ids: player_ids[2..4].to_vec(), capacity: 2 },
+        ];
+        persistence.save_lobby(&lobby).await.unwrap();
+
+        (lobby, players.into_iter().collect())
+    }
+
+    fn jittered_runner(jitter_ms: Option<u64>, seed: u64) -> MatchmakingRunner {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let mut config = RunnerConfig::builder().tick_interval_ms(1000).build();
+        config.tick_jitter_ms = jitter_ms;
+        MatchmakingRunner::new(config, Arc::new(QueueManager::new(persistence.clone())), persistence).with_jitter_seed(seed)
+    }
+
+    #[test]
+    fn test_tick_jitter_spreads_sleeps_around_interval() {
+        let runner = jittered_runner(Some(200), 7);
+        let delays: Vec<u64> = (0..2000).map(|_| runner.next_tick_delay().as_millis() as u64).collect();
+
+        assert!(delays.iter().all(|delay| (800..=1200).contains(delay)));
+        assert!(delays.windows(2).any(|pair| pair[0] != pair[1]));
+        let mean = delays.iter().sum::<u64>() as f64 / delays.len() as f64;
+        assert!((mean - 1000.0).abs() < 10.0, "mean sleep {mean}");
 
-                // Determine team outcomes
-                let team_a_outcome = self.determine_team_outcome(outcomes, team_a_players);
-                let team_b_outcome = self.determine_team_outcome(outcomes, team_b_players);
+        // Same seed, same sleeps
+        let replay = jittered_runner(Some(200), 7);
+        let replayed: Vec<u64> = (0..2000).map(|_| replay.next_tick_delay().as_millis() as u64).collect();
+        assert_eq!(delays, replayed);
+    }
+
+    #[test]
+    fn test_no_jitter_sleeps_exact_interval() {
+        let runner = jittered_runner(None, 7);
+        assert!((0..10).all(|_| runner.next_tick_delay() == Duration::from_millis(1000)));
+    }
 
-                // Update ratings for all players in both teams
-                for (player_a, rating_a) in team_a_players {
-                    for (player_b, rating_b) in team_b_players {
-                        let new_rating_a = mmr_algorithm.calculate_new_rating(*rating_a, *rating_b, team_a_outcome);
-                        let new_rating_b = mmr_algorithm.calculate_new_rating(*rating_b, *rating_a, team_b_outcome);
+    #[tokio::test]
+    async fn test_queue_override_interval_processed_more_often() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let clock = Arc::new(MockClock::default());
+        let queue_manager = Arc::new(QueueManager::new(persistence.clone()).with_clock(clock.clone()));
+        for name in ["fast", "normal"] {
+            queue_manager
+                .register_queue(QueueConfig::new(name, MatchFormat::one_v_one(), MatchConstraints::permissive()))
+                .await
+                .unwrap();
+        }
+
+        let config = RunnerConfig::builder()
+            .tick_interval_ms(1000)
+            .auto_dispatch(false)
+            .queue("normal")
+            .queue_override("fast", QueueRunnerConfig {
+                tick_interval_ms: Some(250),
+                ..QueueRunnerConfig::default()
+            })
+            .build();
+        assert_eq!(config.effective_tick_interval_ms(), 250);
+        let runner = MatchmakingRunner::new(config, queue_manager.clone(), persistence);
 
-                        self.persistence.save_player_rating(*player_a, new_rating_a).await?;
-                        self.persistence.save_player_rating(*player_b, new_rating_b).await?;
+        // Keep a pair waiting in each queue and count how often it gets matched over 2 seconds
+        let mut processed = std::collections::HashMap::new();
+        for _ in 0..8 {
+            for queue in ["fast", "normal"] {
+                if queue_manager.get_queue_size(queue).await.unwrap() == 0 {
+                    for _ in 0..2 {
+                        queue_manager
+                            .join_queue_solo(queue.to_string(), Uuid::new_v4(), Rating::default_beginner(), Default::default(), None)
+                            .await
+                            .unwrap();
                     }
                 }
             }
+
+            runner.process_tick().await.unwrap();
+
+            for queue in ["fast", "normal"] {
+                if queue_manager.get_queue_size(queue).await.unwrap() == 0 {
+                    *processed.entry(queue).or_insert(0) += 1;
+                }
+            }
+            clock.advance(chrono::Duration::milliseconds(250));
         }
 
-        Ok(())
+        assert_eq!(processed["fast"], 8);
+        assert_eq!(processed["normal"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_reduces_rating_delta_while_forming() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let lobby_manager = LobbyManager::new(persistence.clone());
+        let (lobby, ratings) = stacked_lobby(&persistence).await;
+        let before = team_rating_delta(&lobby, &ratings);
+
+        lobby_manager.rebalance_teams(lobby.id).await.unwrap();
+
+        let rebalanced = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        assert!(team_rating_delta(&rebalanced, &ratings) < before);
+        assert!(rebalanced.teams.iter().all(|t| t.size() == 2));
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_refused_once_waiting_for_ready() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let lobby_manager = LobbyManager::new(persistence.clone());
+        let (mut lobby, _) = stacked_lobby(&persistence).await;
+        lobby.transition_to(LobbyState::WaitingForReady).unwrap();
+        lobby.mark_player_ready(lobby.player_ids[0]).unwrap();
+        persistence.save_lobby(&lobby).await.unwrap();
+
+        assert!(lobby_manager.rebalance_teams(lobby.id).await.is_err());
+
+        let unchanged = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        assert_eq!(unchanged.teams[0].player_ids, lobby.teams[0].player_ids);
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_keeps_parties_together() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let lobby_manager = LobbyManager::new(persistence.clone());
+        let (mut lobby, _) = stacked_lobby(&persistence).await;
+        let party = lobby.player_ids[0..2].to_vec();
+        lobby.queue_entries = vec![QueueEntry::new_party(
+            "ranked".to_string(),
+            Uuid::new_v4(),
+            party.clone(),
+            Rating::default_beginner(),
+            Default::default(),
+        )];
+        persistence.save_lobby(&lobby).await.unwrap();
+
+        lobby_manager.rebalance_teams(lobby.id).await.unwrap();
+
+        let rebalanced = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        assert!(rebalanced.teams.iter().any(|t| party.iter().all(|id| t.player_ids.contains(id))));
+        assert!(rebalanced.teams.iter().all(|t| t.size() == 2 && t.capacity == 2));
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_respects_uneven_team_capacities() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let lobby_manager = LobbyManager::new(persistence.clone());
+        let (mut lobby, _) = stacked_lobby(&persistence).await;
+        lobby.teams = vec![
+            Team { team_id: 0, player_ids: lobby.player_ids[0..3].to_vec(), capacity: 3 },
+            Team { team_id: 1, player_ids: lobby.player_ids[3..4].to_vec(), capacity: 1 },
+        ];
+        persistence.save_lobby(&lobby).await.unwrap();
+
+        lobby_manager.rebalance_teams(lobby.id).await.unwrap();
+
+        let rebalanced = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        let sizes: Vec<usize> = rebalanced.teams.iter().map(|t| t.size()).collect();
+        assert_eq!(sizes, vec![3, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_matches_per_tick_capped_and_remainder_carried_over() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let queue_manager = Arc::new(QueueManager::new(persistence.clone()));
+        queue_manager
+            .register_queue(QueueConfig::new("duel", MatchFormat::one_v_one(), MatchConstraints::permissive()))
+            .await
+            .unwrap();
+        for _ in 0..20 {
+            queue_manager
+                .join_queue_solo("duel".to_string(), Uuid::new_v4(), Rating::default_beginner(), Default::default(), None)
+                .await
+                .unwrap();
+        }
+
+        let config = RunnerConfig::builder()
+            .max_matches_per_tick(3)
+            .auto_dispatch(false)
+            .queue("duel")
+            .build();
+        let runner = MatchmakingRunner::new(config, queue_manager.clone(), persistence);
+
+        let mut per_tick = Vec::new();
+        let mut queued = queue_manager.get_queue_size("duel").await.unwrap();
+        while queued > 0 && per_tick.len() < 10 {
+            runner.process_tick().await.unwrap();
+            let now_queued = queue_manager.get_queue_size("duel").await.unwrap();
+            per_tick.push((queued - now_queued) / 2);
+            queued = now_queued;
+        }
+        assert_eq!(per_tick, vec![3, 3, 3, 1]);
+
+        let unlimited = RunnerConfig::builder().max_matches_per_tick(None).build();
+        assert_eq!(unlimited.max_matches_per_tick, None);
     }
 
-    fn determine_team_outcome(&self, outcomes: &[(Uuid, crate::mmr::Outcome)], team_players: &[(Uuid, Rating)]) -> crate::mmr::Outcome {
-        // For simplicity, use the first player's outcome as team outcome
-        // In a real implementation, you'd aggregate team performance
-        for (player_id, outcome) in outcomes {
-            if team_players.iter().any(|(id, _)| id == player_id) {
-                return *outcome;
+    #[tokio::test]
+    async fn test_match_stream_yields_formed_matches() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let queue_manager = Arc::new(QueueManager::new(persistence.clone()));
+        queue_manager
+            .register_queue(QueueConfig::new("duel", MatchFormat::one_v_one(), MatchConstraints::permissive()))
+            .await
+            .unwrap();
+        let players: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        for player_id in &players {
+            queue_manager
+                .join_queue_solo("duel".to_string(), *player_id, Rating::default_beginner(), Default::default(), None)
+                .await
+                .unwrap();
+        }
+
+        let config = RunnerConfig::builder().tick_interval_ms(10).queue("duel").build();
+        let runner = Arc::new(MatchmakingRunner::new(config, queue_manager, persistence));
+        let stream = runner.match_stream();
+        let handle = tokio::spawn({
+            let runner = runner.clone();
+            async move { runner.start().await }
+        });
+
+        let matches: Vec<MatchResult> = tokio::time::timeout(Duration::from_secs(5), stream.take(2).collect())
+            .await
+            .unwrap();
+        runner.stop();
+        handle.await.unwrap().unwrap();
+
+        let mut matched: Vec<Uuid> = matches.iter().flat_map(|m| m.player_ids()).collect();
+        matched.sort();
+        let mut expected = players;
+        expected.sort();
+        assert_eq!(matched, expected);
+        assert!(matches.iter().all(|m| m.player_ids().len() == 2));
+    }
+
+    /// A forming 3v3 lobby with two players on team 0 and one on team 1
+    async fn partial_lobby(persistence: &Arc<InMemoryAdapter>) -> Lobby {
+        let mut lobby = Lobby::from_match_result(
+            crate::queue::MatchResult {
+                match_id: Uuid::new_v4(),
+                entries: Vec::new(),
+                team_assignments: Vec::new(),
+                split_party_ids: Vec::new(),
+                quality_score: 1.0,
+                game_mode: None,
+                team_roles: Vec::new(),
+            },
+            vec![3, 3],
+            LobbyMetadata::default(),
+        );
+        // Filled alternately, starting with team 0
+        assert_eq!(lobby.add_players(&[Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()]).unwrap(), vec![0, 1, 0]);
+        persistence.save_lobby(&lobby).await.unwrap();
+        lobby
+    }
+
+    #[tokio::test]
+    async fn test_add_players_fills_under_filled_teams_up_to_capacity() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let lobby_manager = LobbyManager::new(persistence.clone());
+        let lobby = partial_lobby(&persistence).await;
+        assert_eq!(lobby.remaining_slots(), 3);
+
+        let first = Uuid::new_v4();
+        let lobby = lobby_manager.add_players(lobby.id, &[first]).await.unwrap();
+        assert_eq!(lobby.get_player_team(first), Some(1));
+
+        let (second, third) = (Uuid::new_v4(), Uuid::new_v4());
+        let lobby = lobby_manager.add_players(lobby.id, &[second, third]).await.unwrap();
+        assert_eq!(lobby.get_player_team(second), Some(0));
+        assert_eq!(lobby.get_player_team(third), Some(1));
+        assert_eq!(lobby.remaining_slots(), 0);
+        assert!(lobby.teams.iter().all(|team| team.size() == 3));
+
+        let err = lobby_manager.add_players(lobby.id, &[Uuid::new_v4()]).await.unwrap_err();
+        assert!(matches!(err, MatchForgeError::LobbyFull(id, 0) if id == lobby.id));
+        let stored = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        assert_eq!(stored.player_ids.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_within_grace_keeps_slot() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let clock = Arc::new(MockClock::default());
+        let lobby_manager = LobbyManager::new(persistence.clone())
+            .with_clock(clock.clone())
+            .with_reconnect_grace(chrono::Duration::seconds(30));
+        let lobby = partial_lobby(&persistence).await;
+        let player_id = lobby.player_ids[1];
+
+        let deadline = lobby_manager.disconnect_player(lobby.id, player_id).await.unwrap();
+        assert_eq!(deadline, clock.now() + chrono::Duration::seconds(30));
+        clock.advance(chrono::Duration::seconds(20));
+        assert!(lobby_manager.expire_reconnections(lobby.id).await.unwrap().is_empty());
+        let held = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        assert!(held.is_reconnecting(player_id));
+        assert_eq!(held.remaining_slots(), 3);
+
+        lobby_manager.reconnect_player(lobby.id, player_id).await.unwrap();
+        clock.advance(chrono::Duration::seconds(20));
+        assert!(lobby_manager.expire_reconnections(lobby.id).await.unwrap().is_empty());
+        let restored = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        assert!(!restored.is_reconnecting(player_id));
+        assert_eq!(restored.get_player_team(player_id), Some(1));
+        assert!(lobby_manager.reconnect_player(lobby.id, player_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expired_reconnection_opens_slot_for_backfill() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let clock = Arc::new(MockClock::default());
+        let dodges = Arc::new(DodgeTracker::new());
+        let lobby_manager = LobbyManager::new(persistence.clone())
+            .with_clock(clock.clone())
+            .with_dodge_tracker(dodges.clone())
+            .with_reconnect_grace(chrono::Duration::seconds(30));
+        let lobby = partial_lobby(&persistence).await;
+        let player_id = lobby.player_ids[1];
+
+        lobby_manager.disconnect_player(lobby.id, player_id).await.unwrap();
+        clock.advance(chrono::Duration::seconds(30));
+        assert!(lobby_manager.reconnect_player(lobby.id, player_id).await.is_err());
+        assert_eq!(dodges.dodge_count(player_id).await, 0);
+
+        assert_eq!(lobby_manager.expire_reconnections(lobby.id).await.unwrap(), vec![player_id]);
+        assert_eq!(dodges.dodge_count(player_id).await, 1);
+        let stored = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        assert!(!stored.player_ids.contains(&player_id));
+        assert_eq!(stored.get_player_team(player_id), None);
+        assert_eq!(stored.remaining_slots(), 4);
+
+        // The freed slot on team 1 is backfilled first
+        let replacement = Uuid::new_v4();
+        let backfilled = lobby_manager.add_players(lobby.id, &[replacement]).await.unwrap();
+        assert_eq!(backfilled.get_player_team(replacement), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_racing_additions_cannot_overfill() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let lobby_manager = LobbyManager::new(persistence.clone());
+        let lobby = partial_lobby(&persistence).await;
+
+        let first = [Uuid::new_v4(), Uuid::new_v4()];
+        let second = [Uuid::new_v4(), Uuid::new_v4()];
+        let (a, b) = tokio::join!(
+            lobby_manager.add_players(lobby.id, &first),
+            lobby_manager.add_players(lobby.id, &second),
+        );
+        assert_eq!(a.is_ok() as usize + b.is_ok() as usize, 1);
+
+        let stored = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        assert_eq!(stored.player_ids.len(), 5);
+        assert_eq!(stored.remaining_slots(), 1);
+        assert!(lobby_manager.add_players(lobby.id, &[stored.player_ids[0]]).await.is_err());
+    }
+
+    fn counting_callback() -> (DispatchCallback, Arc<std::sync::atomic::AtomicUsize>) {
+        let count = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = count.clone();
+        let callback: DispatchCallback = Arc::new(move |lobby: &Lobby| {
+            assert_eq!(lobby.state, LobbyState::Ready);
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        });
+        (callback, count)
+    }
+
+    #[tokio::test]
+    async fn test_last_ready_advances_lobby_and_dispatches() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let (callback, dispatched) = counting_callback();
+        let lobby_manager = LobbyManager::new(persistence.clone()).with_dispatch_callback(callback);
+        let (mut lobby, _) = stacked_lobby(&persistence).await;
+        lobby.transition_to(LobbyState::WaitingForReady).unwrap();
+        persistence.save_lobby(&lobby).await.unwrap();
+
+        let (last, others) = lobby.player_ids.split_last().unwrap();
+        for player_id in others {
+            lobby_manager.mark_player_ready(lobby.id, *player_id).await.unwrap();
+        }
+        let waiting = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        assert!(!waiting.is_fully_ready());
+        assert_eq!(waiting.state, LobbyState::WaitingForReady);
+        assert_eq!(dispatched.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        lobby_manager.mark_player_ready(lobby.id, *last).await.unwrap();
+        let ready = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        assert!(ready.is_fully_ready());
+        assert_eq!(ready.state, LobbyState::Ready);
+        assert_eq!(dispatched.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    async fn ready_lobby(persistence: &Arc<InMemoryAdapter>) -> Lobby {
+        let (mut lobby, _) = stacked_lobby(persistence).await;
+        lobby.transition_to(LobbyState::WaitingForReady).unwrap();
+        lobby.transition_to(LobbyState::Ready).unwrap();
+        persistence.save_lobby(&lobby).await.unwrap();
+        lobby
+    }
+
+    #[tokio::test]
+    async fn test_allocated_server_stored_and_released_on_close() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let allocator = Arc::new(StaticPoolAllocator::new(vec![ServerHandle::new("eu-1", "10.0.0.1:7777")]));
+        let lobby_manager = LobbyManager::new(persistence.clone()).with_server_allocator(allocator.clone());
+        let lobby = ready_lobby(&persistence).await;
+
+        let server = lobby_manager.dispatch_to_allocated_server(lobby.id).await.unwrap();
+        assert_eq!(server, ServerHandle::new("eu-1", "10.0.0.1:7777"));
+        let dispatched = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        assert_eq!(dispatched.state, LobbyState::Dispatched);
+        assert_eq!(dispatched.metadata.server, Some(server));
+        assert_eq!(dispatched.metadata.server_id.as_deref(), Some("eu-1"));
+        assert_eq!(allocator.available_count(), 0);
+
+        lobby_manager.close_lobby(lobby.id).await.unwrap();
+        assert_eq!(allocator.available_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_allocator_leaves_lobby_ready_for_retry() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let allocator = Arc::new(StaticPoolAllocator::new(vec![ServerHandle::new("eu-1", "10.0.0.1:7777")]));
+        let lobby_manager = LobbyManager::new(persistence.clone()).with_server_allocator(allocator.clone());
+        let first = ready_lobby(&persistence).await;
+        let second = ready_lobby(&persistence).await;
+
+        lobby_manager.dispatch_to_allocated_server(first.id).await.unwrap();
+        assert!(lobby_manager.dispatch_to_allocated_server(second.id).await.is_err());
+        let waiting = lobby_manager.get_lobby(second.id).await.unwrap().unwrap();
+        assert_eq!(waiting.state, LobbyState::Ready);
+        assert_eq!(waiting.metadata.server, None);
+
+        // Once the first match ends its server goes to the waiting lobby
+        lobby_manager.close_lobby(first.id).await.unwrap();
+        let server = lobby_manager.dispatch_to_allocated_server(second.id).await.unwrap();
+        assert_eq!(server.server_id, "eu-1");
+        assert_eq!(lobby_manager.get_lobby(second.id).await.unwrap().unwrap().state, LobbyState::Dispatched);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_ready_check_requeues_ready_and_penalizes_others() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let clock = Arc::new(MockClock::default());
+        let queue_manager = QueueManager::new(persistence.clone()).with_clock(clock.clone());
+        queue_manager
+            .register_queue(QueueConfig::new("duo", MatchFormat::two_v_two(), MatchConstraints::permissive()))
+            .await
+            .unwrap();
+        let party_id = Uuid::new_v4();
+        let party = queue_manager
+            .join_queue_party("duo".to_string(), party_id, vec![Uuid::new_v4(), Uuid::new_v4()], Rating::default_beginner(), Default::default())
+            .await
+            .unwrap();
+        clock.advance(chrono::Duration::seconds(20));
+        let mut solos = Vec::new();
+        for _ in 0..2 {
+            solos.push(
+                queue_manager
+                    .join_queue_solo("duo".to_string(), Uuid::new_v4(), Rating::default_beginner(), Default::default(), None)
+                    .await
+                    .unwrap(),
+            );
+        }
+        let match_result = queue_manager.find_matches("duo").await.unwrap().remove(0);
+
+        let dodges = Arc::new(DodgeTracker::new());
+        let lobby_manager = LobbyManager::new(persistence.clone()).with_dodge_tracker(dodges.clone());
+        let mut lobby = Lobby::from_match_result(match_result, vec![2, 2], LobbyMetadata::default());
+        lobby.transition_to(LobbyState::WaitingForReady).unwrap();
+        persistence.save_lobby(&lobby).await.unwrap();
+        let (ready_solo, dodger) = (solos[0].player_ids[0], solos[1].player_ids[0]);
+        for player_id in party.player_ids.iter().chain([&ready_solo]) {
+            lobby_manager.mark_player_ready(lobby.id, *player_id).await.unwrap();
+        }
+
+        let requeued = lobby_manager.cancel_ready_check(lobby.id).await.unwrap();
+        assert_eq!(requeued.len(), 2);
+        let returned_party = requeued.iter().find(|e| e.party_id == Some(party_id)).unwrap();
+        assert_eq!(returned_party.player_ids, party.player_ids);
+        assert_eq!(returned_party.joined_at, party.joined_at);
+        let returned_solo = requeued.iter().find(|e| e.player_ids == vec![ready_solo]).unwrap();
+        assert_eq!(returned_solo.joined_at, solos[0].joined_at);
+        assert!(requeued.iter().all(|e| !e.player_ids.contains(&dodger)));
+
+        assert_eq!(dodges.dodge_count(dodger).await, 1);
+        assert_eq!(dodges.dodge_count(ready_solo).await, 0);
+        assert_eq!(lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap().state, LobbyState::Closed);
+
+        // A second cancel finds no ready check and penalizes nobody again
+        assert!(lobby_manager.cancel_ready_check(lobby.id).await.is_err());
+        assert_eq!(dodges.dodge_count(dodger).await, 1);
+
+        for entry in requeued {
+            queue_manager.requeue(entry).await.unwrap();
+        }
+        assert_eq!(queue_manager.get_queue_size("duo").await.unwrap(), 2);
+    }
+
+    /// In-memory storage that yields after each lobby load, widening load-modify-save races
+    #[derive(Default)]
+    struct YieldingAdapter {
+        inner: InMemoryAdapter,
+    }
+
+    #[async_trait::async_trait]
+    impl PersistenceAdapter for YieldingAdapter {
+        async fn save_player_rating(&self, a0: Uuid, a1: Rating) -> Result<()> { self.inner.save_player_rating(a0, a1).await }
+        async fn load_player_rating(&self, a0: Uuid) -> Result<Option<Rating>> { self.inner.load_player_rating(a0).await }
+        async fn save_shadow_rating(&self, a0: Uuid, a1: Rating) -> Result<()> { self.inner.save_shadow_rating(a0, a1).await }
+        async fn load_shadow_rating(&self, a0: Uuid) -> Result<Option<Rating>> { self.inner.load_shadow_rating(a0).await }
+        async fn load_all_ratings(&self) -> Result<Vec<(Uuid, Rating)>> { self.inner.load_all_ratings().await }
+        async fn save_queue_entry(&self, a0: &QueueEntry) -> Result<()> { self.inner.save_queue_entry(a0).await }
+        async fn load_queue_entries(&self, a0: &str) -> Result<Vec<QueueEntry>> { self.inner.load_queue_entries(a0).await }
+        async fn delete_queue_entry(&self, a0: Uuid) -> Result<()> { self.inner.delete_queue_entry(a0).await }
+        async fn load_all_queue_entries(&self) -> Result<Vec<QueueEntry>> { self.inner.load_all_queue_entries().await }
+        async fn save_party(&self, a0: &crate::party::Party) -> Result<()> { self.inner.save_party(a0).await }
+        async fn load_party(&self, a0: Uuid) -> Result<Option<crate::party::Party>> { self.inner.load_party(a0).await }
+        async fn delete_party(&self, a0: Uuid) -> Result<()> { self.inner.delete_party(a0).await }
+        async fn load_all_parties(&self) -> Result<Vec<crate::party::Party>> { self.inner.load_all_parties().await }
+        async fn save_lobby(&self, a0: &Lobby) -> Result<()> { self.inner.save_lobby(a0).await }
+        async fn load_lobby(&self, a0: Uuid) -> Result<Option<Lobby>> {
+            let lobby = self.inner.load_lobby(a0).await;
+            tokio::task::yield_now().await;
+            lobby
+        }
+        async fn delete_lobby(&self, a0: Uuid) -> Result<()> { self.inner.delete_lobby(a0).await }
+        async fn load_all_lobbies(&self) -> Result<Vec<Lobby>> { self.inner.load_all_lobbies().await }
+        async fn player_in_active_lobby(&self, a0: Uuid) -> Result<bool> { self.inner.player_in_active_lobby(a0).await }
+        async fn save_match_result(&self, a0: &Lobby) -> Result<()> { self.inner.save_match_result(a0).await }
+        async fn load_player_match_history(&self, a0: Uuid, a1: usize) -> Result<Vec<Lobby>> { self.inner.load_player_match_history(a0, a1).await }
+        async fn save_season_rating(&self, a0: &str, a1: &str, a2: Uuid, a3: Rating) -> Result<()> { self.inner.save_season_rating(a0, a1, a2, a3).await }
+        async fn load_season_rating(&self, a0: &str, a1: &str, a2: Uuid) -> Result<Option<Rating>> { self.inner.load_season_rating(a0, a1, a2).await }
+        async fn load_season_ratings(&self, a0: &str, a1: &str) -> Result<Vec<(Uuid, Rating)>> { self.inner.load_season_ratings(a0, a1).await }
+        async fn top_players(&self, a0: &str, a1: &str, a2: usize) -> Result<Vec<(Uuid, Rating)>> { self.inner.top_players(a0, a1, a2).await }
+        async fn save_insight(&self, a0: &crate::analytics::insights::InsightRecord) -> Result<()> { self.inner.save_insight(a0).await }
+        async fn load_insight(&self, a0: Uuid) -> Result<Option<crate::analytics::insights::InsightRecord>> { self.inner.load_insight(a0).await }
+        async fn save_rating_audit(&self, a0: &RatingAuditEntry) -> Result<()> { self.inner.save_rating_audit(a0).await }
+        async fn load_rating_audit(&self, a0: Uuid) -> Result<Vec<RatingAuditEntry>> { self.inner.load_rating_audit(a0).await }
+        async fn save_bracket(&self, a0: &crate::queue::TournamentBracket) -> Result<()> { self.inner.save_bracket(a0).await }
+        async fn load_bracket(&self, a0: Uuid) -> Result<Option<crate::queue::TournamentBracket>> { self.inner.load_bracket(a0).await }
+        async fn delete_bracket(&self, a0: Uuid) -> Result<()> { self.inner.delete_bracket(a0).await }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_ready_calls_all_recorded() {
+        let persistence = Arc::new(YieldingAdapter::default());
+        let (callback, dispatched) = counting_callback();
+        let lobby_manager = Arc::new(LobbyManager::new(persistence.clone()).with_dispatch_callback(callback));
+        for _ in 0..20 {
+            let (mut lobby, _) = stacked_lobby(&persistence).await;
+            lobby.transition_to(LobbyState::WaitingForReady).unwrap();
+            persistence.save_lobby(&lobby).await.unwrap();
+
+            let readies: Vec<_> = lobby
+                .player_ids
+                .iter()
+                .map(|player_id| {
+                    let (lobby_manager, lobby_id, player_id) = (lobby_manager.clone(), lobby.id, *player_id);
+                    tokio::spawn(async move { lobby_manager.mark_player_ready(lobby_id, player_id).await })
+                })
+                .collect();
+            for ready in readies {
+                ready.await.unwrap().unwrap();
+            }
+
+            let stored = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+            assert_eq!(stored.ready_players.len(), 4);
+            assert_eq!(stored.state, LobbyState::Ready);
+        }
+        assert_eq!(dispatched.load(std::sync::atomic::Ordering::SeqCst), 20);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_ready_is_idempotent() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let (callback, dispatched) = counting_callback();
+        let lobby_manager = LobbyManager::new(persistence.clone()).with_dispatch_callback(callback);
+        let (mut lobby, _) = stacked_lobby(&persistence).await;
+        lobby.transition_to(LobbyState::WaitingForReady).unwrap();
+        persistence.save_lobby(&lobby).await.unwrap();
+
+        let first = lobby.player_ids[0];
+        lobby_manager.mark_player_ready(lobby.id, first).await.unwrap();
+        lobby_manager.mark_player_ready(lobby.id, first).await.unwrap();
+        let partial = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        assert_eq!(partial.ready_players.len(), 1);
+        assert_eq!(partial.state, LobbyState::WaitingForReady);
+
+        for player_id in &lobby.player_ids {
+            lobby_manager.mark_player_ready(lobby.id, *player_id).await.unwrap();
+        }
+        lobby_manager.mark_player_ready(lobby.id, first).await.unwrap();
+
+        let ready = lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap();
+        assert_eq!(ready.state, LobbyState::Ready);
+        assert_eq!(dispatched.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(lobby_manager.mark_player_ready(lobby.id, Uuid::new_v4()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_match_update_audited_once_per_player() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let audit_log = Arc::new(crate::mmr::InMemoryAuditLog::new());
+        let lobby_manager = LobbyManager::new(persistence.clone()).with_audit_log(audit_log.clone());
+        let (lobby, _) = stacked_lobby(&persistence).await;
+
+        let outcomes: Vec<(Uuid, crate::mmr::Outcome)> = lobby
+            .teams
+            .iter()
+            .flat_map(|t| {
+                let outcome = if t.team_id == 0 { crate::mmr::Outcome::Win } else { crate::mmr::Outcome::Loss };
+                t.player_ids.iter().map(move |id| (*id, outcome))
+            })
+            .collect();
+        lobby_manager
+            .update_ratings(lobby.id, &outcomes, Arc::new(crate::mmr::EloAlgorithm::default()))
+            .await
+            .unwrap();
+
+        for player_id in &lobby.player_ids {
+            let entries = audit_log.entries_for(*player_id).await.unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].reason, RatingChangeReason::Match { match_id: lobby.match_id });
+            let saved = persistence.load_player_rating(*player_id).await.unwrap().unwrap();
+            assert_eq!(entries[0].after.rating, saved.rating);
+            assert_ne!(entries[0].before.rating, saved.rating);
+        }
+    }
+
+    /// Outcomes with team 0 winning
+    fn team_zero_wins(lobby: &Lobby) -> Vec<(Uuid, Outcome)> {
+        lobby
+            .teams
+            .iter()
+            .flat_map(|t| {
+                let outcome = if t.team_id == 0 { Outcome::Win } else { Outcome::Loss };
+                t.player_ids.iter().map(move |id| (*id, outcome))
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_reported_result_rates_players_and_closes_lobby() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let lobby_manager = LobbyManager::new(persistence.clone());
+        let (mut lobby, ratings) = stacked_lobby(&persistence).await;
+
+        // Not dispatched yet
+        assert!(lobby_manager.report_result(lobby.id, team_zero_wins(&lobby)).await.is_err());
+
+        lobby.state = LobbyState::Dispatched;
+        persistence.save_lobby(&lobby).await.unwrap();
+        lobby_manager.report_result(lobby.id, team_zero_wins(&lobby)).await.unwrap();
+
+        assert!(lobby_manager.get_lobby(lobby.id).await.unwrap().is_none());
+        for team in &lobby.teams {
+            for player_id in &team.player_ids {
+                let after = persistence.load_player_rating(*player_id).await.unwrap().unwrap().rating;
+                assert_eq!(after > ratings[player_id], team.team_id == 0);
+            }
+        }
+        let history = persistence.load_player_match_history(lobby.player_ids[0], 1).await.unwrap();
+        assert_eq!(history[0].match_id, lobby.match_id);
+    }
+
+    #[tokio::test]
+    async fn test_reported_result_for_runner_two_v_two() {
+        let (queue_manager, persistence, players) = queued_two_v_two().await;
+        for player_id in &players {
+            persistence.save_player_rating(*player_id, Rating::default_beginner()).await.unwrap();
+        }
+        let config = RunnerConfig::builder().auto_dispatch(false).queue("squads").build();
+        let runner = MatchmakingRunner::new(config, queue_manager, persistence.clone());
+        assert_eq!(runner.process_queue("squads", 10).await.unwrap(), 1);
+        let mut lobby = persistence.load_all_lobbies().await.unwrap().remove(0);
+        lobby.state = LobbyState::Dispatched;
+        persistence.save_lobby(&lobby).await.unwrap();
+
+        let protected = lobby.teams[1].player_ids[0];
+        let protections = [(protected, RatingProtection::no_loss())].into();
+        LobbyManager::new(persistence.clone())
+            .report_result_with_protection(lobby.id, team_zero_wins(&lobby), &protections)
+            .await
+            .unwrap();
+
+        assert!(persistence.load_lobby(lobby.id).await.unwrap().is_none());
+        let beginner = Rating::default_beginner().rating;
+        for team in &lobby.teams {
+            assert_eq!(team.size(), 2);
+            for player_id in &team.player_ids {
+                let after = persistence.load_player_rating(*player_id).await.unwrap().unwrap().rating;
+                match (team.team_id, *player_id == protected) {
+                    (0, _) => assert!(after > beginner),
+                    (_, true) => assert_eq!(after, beginner),
+                    _ => assert!(after < beginner),
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_result_reports_rejected() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let lobby_manager = LobbyManager::new(persistence.clone());
+        let (mut lobby, ratings) = stacked_lobby(&persistence).await;
+        lobby.state = LobbyState::Dispatched;
+        persistence.save_lobby(&lobby).await.unwrap();
+        let valid = team_zero_wins(&lobby);
+
+        let stranger = Uuid::new_v4();
+        let mut unknown = valid.clone();
+        unknown[3] = (stranger, Outcome::Loss);
+        assert!(matches!(
+            lobby_manager.report_result(lobby.id, unknown).await,
+            Err(MatchForgeError::PlayerNotFound(id)) if id == stranger
+        ));
+
+        let missing = valid[..3].to_vec();
+        assert!(lobby_manager.report_result(lobby.id, missing).await.is_err());
+        let mut duplicated = valid.clone();
+        duplicated[3] = duplicated[2];
+        assert!(lobby_manager.report_result(lobby.id, duplicated).await.is_err());
+        let mut mixed = valid.clone();
+        mixed[1].1 = Outcome::Loss;
+        assert!(lobby_manager.report_result(lobby.id, mixed).await.is_err());
+
+        // Nothing was applied
+        assert_eq!(lobby_manager.get_lobby(lobby.id).await.unwrap().unwrap().state, LobbyState::Dispatched);
+        for (player_id, rating) in &ratings {
+            assert_eq!(persistence.load_player_rating(*player_id).await.unwrap().unwrap().rating, *rating);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decay_audited_once_and_skips_players_without_history() {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let clock = Arc::new(MockClock::default());
+        let audit_log = Arc::new(crate::mmr::InMemoryAuditLog::new());
+        let lobby_manager = LobbyManager::new(persistence.clone())
+            .with_audit_log(audit_log.clone())
+            .with_clock(clock.clone());
+
+        let (mut lobby, _) = stacked_lobby(&persistence).await;
+        lobby.created_at = clock.now();
+        persistence.save_match_result(&lobby).await.unwrap();
+        clock.advance(chrono::Duration::days(10));
+
+        let inactive = lobby.player_ids[0];
+        let newcomer = Uuid::new_v4();
+        persistence.save_player_rating(newcomer, Rating::default_beginner()).await.unwrap();
+
+        let decay = crate::mmr::LinearDecay::new(2.0, 100.0).with_clock(clock.clone());
+        let decayed = lobby_manager.decay_ratings(&[inactive, newcomer], &decay).await.unwrap();
+        assert_eq!(decayed, 1);
+
+        let entries = audit_log.entries_for(inactive).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].reason, RatingChangeReason::Decay);
+        assert_eq!(entries[0].before.rating - entries[0].after.rating, 20.0);
+        assert_eq!(entries[0].at, clock.now());
+        assert!(audit_log.entries_for(newcomer).await.unwrap().is_empty());
+    }
+
+    /// Storage that is down: every call fails
+    struct UnavailableAdapter;
+
+    fn unavailable<T>() -> Result<T> {
+        Err(MatchForgeError::OperationFailed("storage unavailable".to_string()))
+    }
+
+    #[async_trait::async_trait]
+    impl PersistenceAdapter for UnavailableAdapter {
+        async fn save_player_rating(&self, _: Uuid, _: Rating) -> Result<()> { unavailable() }
+        async fn load_player_rating(&self, _: Uuid) -> Result<Option<Rating>> { unavailable() }
+        async fn save_shadow_rating(&self, _: Uuid, _: Rating) -> Result<()> { unavailable() }
+        async fn load_shadow_rating(&self, _: Uuid) -> Result<Option<Rating>> { unavailable() }
+        async fn load_all_ratings(&self) -> Result<Vec<(Uuid, Rating)>> { unavailable() }
+        async fn save_queue_entry(&self, _: &QueueEntry) -> Result<()> { unavailable() }
+        async fn load_queue_entries(&self, _: &str) -> Result<Vec<QueueEntry>> { unavailable() }
+        async fn delete_queue_entry(&self, _: Uuid) -> Result<()> { unavailable() }
+        async fn load_all_queue_entries(&self) -> Result<Vec<QueueEntry>> { unavailable() }
+        async fn save_party(&self, _: &crate::party::Party) -> Result<()> { unavailable() }
+        async fn load_party(&self, _: Uuid) -> Result<Option<crate::party::Party>> { unavailable() }
+        async fn delete_party(&self, _: Uuid) -> Result<()> { unavailable() }
+        async fn load_all_parties(&self) -> Result<Vec<crate::party::Party>> { unavailable() }
+        async fn save_lobby(&self, _: &Lobby) -> Result<()> { unavailable() }
+        async fn load_lobby(&self, _: Uuid) -> Result<Option<Lobby>> { unavailable() }
+        async fn delete_lobby(&self, _: Uuid) -> Result<()> { unavailable() }
+        async fn load_all_lobbies(&self) -> Result<Vec<Lobby>> { unavailable() }
+        async fn player_in_active_lobby(&self, _: Uuid) -> Result<bool> { unavailable() }
+        async fn save_match_result(&self, _: &Lobby) -> Result<()> { unavailable() }
+        async fn load_player_match_history(&self, _: Uuid, _: usize) -> Result<Vec<Lobby>> { unavailable() }
+        async fn save_season_rating(&self, _: &str, _: &str, _: Uuid, _: Rating) -> Result<()> { unavailable() }
+        async fn load_season_rating(&self, _: &str, _: &str, _: Uuid) -> Result<Option<Rating>> { unavailable() }
+        async fn load_season_ratings(&self, _: &str, _: &str) -> Result<Vec<(Uuid, Rating)>> { unavailable() }
+        async fn top_players(&self, _: &str, _: &str, _: usize) -> Result<Vec<(Uuid, Rating)>> { unavailable() }
+        async fn save_insight(&self, _: &crate::analytics::insights::InsightRecord) -> Result<()> { unavailable() }
+        async fn load_insight(&self, _: Uuid) -> Result<Option<crate::analytics::insights::InsightRecord>> { unavailable() }
+        async fn save_rating_audit(&self, _: &RatingAuditEntry) -> Result<()> { unavailable() }
+        async fn load_rating_audit(&self, _: Uuid) -> Result<Vec<RatingAuditEntry>> { unavailable() }
+        async fn save_bracket(&self, _: &crate::queue::TournamentBracket) -> Result<()> { unavailable() }
+        async fn load_bracket(&self, _: Uuid) -> Result<Option<crate::queue::TournamentBracket>> { unavailable() }
+        async fn delete_bracket(&self, _: Uuid) -> Result<()> { unavailable() }
+    }
+
+    #[tokio::test]
+    async fn test_failed_lobby_creation_returns_players_to_queue() {
+        let queue_manager = Arc::new(QueueManager::new(Arc::new(InMemoryAdapter::new())));
+        queue_manager
+            .register_queue(QueueConfig::new("duel", MatchFormat::one_v_one(), MatchConstraints::permissive()))
+            .await
+            .unwrap();
+        let players = [Uuid::new_v4(), Uuid::new_v4()];
+        for player_id in players {
+            queue_manager
+                .join_queue_solo("duel".to_string(), player_id, Rating::default_beginner(), Default::default(), None)
+                .await
+                .unwrap();
+        }
+
+        let config = RunnerConfig::builder().auto_dispatch(false).queue("duel").build();
+        let runner = MatchmakingRunner::new(config, queue_manager.clone(), Arc::new(UnavailableAdapter));
+        let mut formed = runner.subscribe_matches();
+
+        // The queue's error is logged, not returned from the tick
+        runner.tick().await.unwrap();
+        assert!(formed.try_recv().is_err());
+        assert_eq!(queue_manager.get_queue_size("duel").await.unwrap(), 2);
+        for player_id in players {
+            assert_eq!(queue_manager.entry_status("duel", player_id).await.unwrap(), EntryStatus::Active);
+        }
+
+        // Once storage is back the same players match
+        let matches = queue_manager.find_matches("duel").await.unwrap();
+        assert_eq!(matches.len(), 1);
+    }
+
+    /// Validator returning the same outcome for every match, counting calls
+    struct FixedValidator(ValidationOutcome, std::sync::atomic::AtomicUsize);
+
+    impl MatchValidator for FixedValidator {
+        fn validate(&self, _result: &MatchResult) -> ValidationOutcome {
+            self.1.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.0.clone()
+        }
+    }
+
+    async fn queued_two_v_two() -> (Arc<QueueManager>, Arc<InMemoryAdapter>, Vec<Uuid>) {
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let queue_manager = Arc::new(QueueManager::new(persistence.clone()));
+        queue_manager
+            .register_queue(QueueConfig::new("squads", MatchFormat::two_v_two(), MatchConstraints::permissive()))
+            .await
+            .unwrap();
+        let players: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        for player_id in &players {
+            queue_manager
+                .join_queue_solo("squads".to_string(), *player_id, Rating::default_beginner(), Default::default(), None)
+                .await
+                .unwrap();
+        }
+        (queue_manager, persistence, players)
+    }
+
+    #[tokio::test]
+    async fn test_runner_lobby_teams_follow_queue_format() {
+        let (queue_manager, persistence, players) = queued_two_v_two().await;
+        let config = RunnerConfig::builder().auto_dispatch(false).queue("squads").build();
+        let runner = MatchmakingRunner::new(config, queue_manager, persistence.clone());
+        let mut formed = runner.subscribe_matches();
+
+        assert_eq!(runner.process_queue("squads", 10).await.unwrap(), 1);
+        let match_result = formed.try_recv().unwrap();
+        let lobby = persistence.load_all_lobbies().await.unwrap().remove(0);
+        assert_eq!(lobby.teams.iter().map(|t| t.capacity).collect::<Vec<_>>(), vec![2, 2]);
+        for (entry, team) in match_result.entries.iter().zip(&match_result.team_assignments) {
+            assert_eq!(lobby.get_player_team(entry.player_ids[0]), Some(*team));
+        }
+        assert!(players.iter().all(|p| lobby.get_player_team(*p).is_some()));
+        assert_eq!(lobby.remaining_slots(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_match_leaves_entries_queued() {
+        let (queue_manager, persistence, players) = queued_two_v_two().await;
+        let validator = Arc::new(FixedValidator(
+            ValidationOutcome::Reject { reason: "too many premades".to_string() },
+            Default::default(),
+        ));
+        let config = RunnerConfig::builder().auto_dispatch(false).queue("squads").build();
+        let runner = MatchmakingRunner::new(config, queue_manager.clone(), persistence).with_match_validator(validator.clone());
+        let mut formed = runner.subscribe_matches();
+
+        assert_eq!(runner.process_queue("squads", 10).await.unwrap(), 0);
+        assert_eq!(validator.1.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(formed.try_recv().is_err());
+        for player_id in players {
+            assert_eq!(queue_manager.entry_status("squads", player_id).await.unwrap(), EntryStatus::Active);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_adjusted_match_uses_validator_teams() {
+        let (queue_manager, persistence, _) = queued_two_v_two().await;
+        let adjusted = ValidationOutcome::Adjusted { team_assignments: vec![1, 0, 0, 1] };
+        let config = RunnerConfig::builder().auto_dispatch(false).queue("squads").build();
+        let runner = MatchmakingRunner::new(config, queue_manager.clone(), persistence.clone())
+            .with_match_validator(Arc::new(FixedValidator(adjusted, Default::default())));
+        let mut formed = runner.subscribe_matches();
+
+        assert_eq!(runner.process_queue("squads", 10).await.unwrap(), 1);
+        let match_result = formed.try_recv().unwrap();
+        assert_eq!(match_result.team_assignments, vec![1, 0, 0, 1]);
+        assert_eq!(queue_manager.get_queue_size("squads").await.unwrap(), 0);
+
+        let lobby = persistence.load_all_lobbies().await.unwrap().remove(0);
+        let team_of = |i: usize| match_result.entries[i].player_ids[0];
+        let mut teams: Vec<Vec<Uuid>> = lobby.teams.iter().map(|t| t.player_ids.clone()).collect();
+        for team in &mut teams {
+            team.sort();
+        }
+        let mut expected = vec![vec![team_of(1), team_of(2)], vec![team_of(0), team_of(3)]];
+        for team in &mut expected {
+            team.sort();
+        }
+        assert_eq!(teams, expected);
+    }
+
+    #[tokio::test]
+    async fn test_unbalanced_adjustment_skips_only_that_match() {
+        /// Returns a lopsided adjustment for the first match and accepts the rest
+        struct LopsidedFirst(std::sync::atomic::AtomicUsize);
+
+        impl MatchValidator for LopsidedFirst {
+            fn validate(&self, _result: &MatchResult) -> ValidationOutcome {
+                match self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+                    0 => ValidationOutcome::Adjusted { team_assignments: vec![0, 0, 0, 1] },
+                    _ => ValidationOutcome::Accept,
+                }
             }
         }
-        crate::mmr::Outcome::Loss // Default fallback
+
+        let (queue_manager, persistence, mut players) = queued_two_v_two().await;
+        for _ in 0..4 {
+            let player_id = Uuid::new_v4();
+            queue_manager
+                .join_queue_solo("squads".to_string(), player_id, Rating::default_beginner(), Default::default(), None)
+                .await
+                .unwrap();
+            players.push(player_id);
+        }
+        let config = RunnerConfig::builder().auto_dispatch(false).queue("squads").build();
+        let runner = MatchmakingRunner::new(config, queue_manager.clone(), persistence)
+            .with_match_validator(Arc::new(LopsidedFirst(Default::default())));
+        let mut formed = runner.subscribe_matches();
+
+        assert_eq!(runner.process_queue("squads", 10).await.unwrap(), 1);
+        let committed = formed.try_recv().unwrap().player_ids();
+        assert_eq!(queue_manager.get_queue_size("squads").await.unwrap(), 4);
+        for player_id in players.iter().filter(|p| !committed.contains(p)) {
+            assert_eq!(queue_manager.entry_status("squads", *player_id).await.unwrap(), EntryStatus::Active);
+        }
     }
 }
@@ -0,0 +1,75 @@
+//! Final say over proposed matches before the runner commits them
+//!
+//! [`MatchmakingRunner::with_match_validator`](super::MatchmakingRunner::with_match_validator)
+//! consults a [`MatchValidator`] for every match a queue proposes, before
+//! its players are reserved and a lobby is created.
+
+use crate::{
+    error::*,
+    queue::{MatchFormat, MatchResult},
+};
+use std::collections::HashMap;
+
+/// What a [`MatchValidator`] decided about a proposed match
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationOutcome {
+    /// Commit the match as proposed
+    Accept,
+    /// Drop the match; its entries stay queued for the next pass
+    Reject { reason: String },
+    /// Commit the match with these team assignments, one per entry
+    Adjusted { team_assignments: Vec<usize> },
+}
+
+/// Studio hook to veto or rebalance a match, e.g. rejecting too many premades
+pub trait MatchValidator: Send + Sync {
+    fn validate(&self, result: &MatchResult) -> ValidationOutcome;
+}
+
+/// Replace a match's team assignments after checking they fit `format`
+///
+/// Fails if there isn't one assignment per entry, an assignment names a
+/// team the format doesn't have, or (unless a party was split) a team ends
+/// up with a different number of players than the format's team size.
+pub(crate) fn apply_team_assignments(result: &mut MatchResult, team_assignments: Vec<usize>, format: &MatchFormat) -> Result<()> {
+    if team_assignments.len() != result.entries.len() {
+        return Err(MatchForgeError::OperationFailed(format!(
+            "Adjusted match {} has {} team assignments for {} entries",
+            result.match_id,
+            team_assignments.len(),
+            result.entries.len()
+        )));
+    }
+    if let Some(team) = team_assignments.iter().find(|&&team| team >= format.team_count()) {
+        return Err(MatchForgeError::OperationFailed(format!(
+            "Adjusted match {} assigns an entry to team {} of {}",
+            result.match_id,
+            team,
+            format.team_count()
+        )));
+    }
+    if result.split_party_ids.is_empty() {
+        let mut team_players = vec![0; format.team_count()];
+        for (entry, &team) in result.entries.iter().zip(&team_assignments) {
+            team_players[team] += entry.player_count();
+        }
+        if team_players != format.team_sizes {
+            return Err(MatchForgeError::OperationFailed(format!(
+                "Adjusted match {} has team sizes {:?}, expected {:?}",
+                result.match_id, team_players, format.team_sizes
+            )));
+        }
+    }
+
+    if !result.team_roles.is_empty() {
+        let mut team_roles = vec![HashMap::new(); format.team_count()];
+        for (entry, &team) in result.entries.iter().zip(&team_assignments) {
+            for (role, count) in entry.role_counts() {
+                *team_roles[team].entry(role).or_insert(0) += count;
+            }
+        }
+        result.team_roles = team_roles;
+    }
+    result.team_assignments = team_assignments;
+    Ok(())
+}
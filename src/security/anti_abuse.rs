@@ -2,7 +2,7 @@
 //! 
 //! Provides comprehensive abuse detection and prevention mechanisms.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use std::time::Duration;
@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::rate_limiter::RateLimiter;
+use crate::mmr::Rating;
 
 /// Abuse detection and prevention system
 pub struct AntiAbuseSystem {
@@ -19,6 +20,8 @@ pub struct AntiAbuseSystem {
     player_behavior: Arc<RwLock<HashMap<Uuid, PlayerBehavior>>>,
     abuse_reports: Arc<RwLock<Vec<AbuseReport>>>,
     reputation_scores: Arc<RwLock<HashMap<Uuid, ReputationScore>>>,
+    /// Players matched only among themselves, see [`AbuseAction::ShadowBan`]
+    shadow_banned: Arc<RwLock<HashSet<Uuid>>>,
 }
 
 /// Anti-abuse configuration
@@ -63,6 +66,9 @@ pub struct AbuseThresholds {
     
     /// Suspicious rating manipulation threshold
     pub rating_manipulation_threshold: f64,
+    
+    /// Largest rating gap between party members before the party is flagged as possible boosting
+    pub max_party_rating_spread: f64,
 }
 
 impl Default for AbuseThresholds {
@@ -74,6 +80,7 @@ impl Default for AbuseThresholds {
             min_reputation_score: -50.0,
             max_reports_per_hour: 10,
             rating_manipulation_threshold: 0.8,
+            max_party_rating_spread: 1000.0,
         }
     }
 }
@@ -95,6 +102,9 @@ pub struct AbuseActions {
     
     /// Reputation reward for good behavior
     pub reputation_reward: f64,
+    
+    /// Recommend shadow bans in place of temporary and permanent bans
+    pub shadow_ban: bool,
 }
 
 impl Default for AbuseActions {
@@ -105,6 +115,7 @@ impl Default for AbuseActions {
             perm_ban_threshold: 5,
             reputation_penalty: 10.0,
             reputation_reward: 1.0,
+            shadow_ban: false,
         }
     }
 }
@@ -183,6 +194,8 @@ pub enum AbuseReportType {
     InappropriateName,
     IntentionalFeeding,
     QueueManipulation,
+    /// A high-rated player queuing with a much lower-rated one to carry them
+    PossibleBoosting,
     Other(String),
 }
 
@@ -222,6 +235,9 @@ pub enum AbuseAction {
     TemporaryBan(Duration),
     ReputationPenalty(f64),
     PermanentBan,
+    /// Let the player keep queueing, but only match them with other
+    /// shadow-banned players (see `QueueManager::with_anti_abuse`)
+    ShadowBan,
     Monitor,
     NoAction,
 }
@@ -235,6 +251,7 @@ impl AntiAbuseSystem {
             player_behavior: Arc::new(RwLock::new(HashMap::new())),
             abuse_reports: Arc::new(RwLock::new(Vec::new())),
             reputation_scores: Arc::new(RwLock::new(HashMap::new())),
+            shadow_banned: Arc::new(RwLock::new(HashSet::new())),
         }
     }
     
@@ -398,6 +415,47 @@ impl AntiAbuseSystem {
         Ok(())
     }
     
+    /// Flag a party whose members' ratings are further apart than
+    /// `max_party_rating_spread`, the usual sign of a high-rated player
+    /// carrying a low-rated one
+    ///
+    /// Files a system report against the highest-rated member, with the
+    /// lowest-rated member and the spread as evidence, and returns it.
+    /// `None` if the party is within the threshold.
+    pub async fn check_party_rating_spread(&self, party_id: Uuid, members: &[(Uuid, Rating)]) -> Option<AbuseReport> {
+        let by_rating = |a: &&(Uuid, Rating), b: &&(Uuid, Rating)| a.1.rating.total_cmp(&b.1.rating);
+        let (booster_id, highest) = members.iter().max_by(by_rating)?;
+        let (carried_id, lowest) = members.iter().min_by(by_rating)?;
+        let spread = highest.rating - lowest.rating;
+        if spread <= self.config.thresholds.max_party_rating_spread {
+            return None;
+        }
+
+        let mut evidence = HashMap::new();
+        evidence.insert("party_id".to_string(), party_id.to_string());
+        evidence.insert("carried_player_id".to_string(), carried_id.to_string());
+        evidence.insert("rating_spread".to_string(), format!("{:.0}", spread));
+        let report = AbuseReport {
+            id: Uuid::new_v4(),
+            // Raised by the system rather than a player
+            reporter_id: Uuid::nil(),
+            reported_player_id: *booster_id,
+            report_type: AbuseReportType::PossibleBoosting,
+            reason: format!(
+                "Party rating spread of {:.0} exceeds {:.0}",
+                spread, self.config.thresholds.max_party_rating_spread
+            ),
+            evidence,
+            timestamp: Utc::now(),
+            status: ReportStatus::Pending,
+            reviewed_by: None,
+            review_notes: None,
+        };
+
+        self.abuse_reports.write().await.push(report.clone());
+        Some(report)
+    }
+    
     /// Get abuse reports for a player
     pub async fn get_reports_for_player(&self, player_id: Uuid) -> Vec<AbuseReport> {
         let reports = self.abuse_reports.read().await;
@@ -431,6 +489,9 @@ impl AntiAbuseSystem {
                 // In a real implementation, this would send a warning to the player
                 eprintln!("Warning sent to player {}: {}", player_id, message);
             }
+            AbuseAction::ShadowBan => {
+                self.shadow_banned.write().await.insert(player_id);
+            }
             AbuseAction::Monitor => {
                 // Add to monitoring list
                 eprintln!("Player {} added to monitoring list", player_id);
@@ -441,16 +502,31 @@ impl AntiAbuseSystem {
         Ok(())
     }
     
+    /// Whether a player is shadow-banned
+    pub async fn is_shadow_banned(&self, player_id: Uuid) -> bool {
+        self.shadow_banned.read().await.contains(&player_id)
+    }
+    
+    /// Every shadow-banned player
+    pub async fn shadow_banned_players(&self) -> HashSet<Uuid> {
+        self.shadow_banned.read().await.clone()
+    }
+    
+    /// Return a shadow-banned player to normal matching; `false` if they weren't banned
+    pub async fn lift_shadow_ban(&self, player_id: Uuid) -> bool {
+        self.shadow_banned.write().await.remove(&player_id)
+    }
+    
     /// Clean up old data
     pub async fn cleanup(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let cutoff = Utc::now() - chrono::Duration::from_std(self.config.behavior_retention).unwrap();
+        let cutoff = Utc::now() - crate::time::to_chrono(self.config.behavior_retention);
         
         // Clean up old behavior data
         let mut behavior = self.player_behavior.write().await;
         behavior.retain(|_, player_behavior| player_behavior.last_activity > cutoff);
         
         // Clean up old reports
-        let report_cutoff = Utc::now() - chrono::Duration::from_std(self.config.report_retention).unwrap();
+        let report_cutoff = Utc::now() - crate::time::to_chrono(self.config.report_retention);
         let mut reports = self.abuse_reports.write().await;
         reports.retain(|report| report.timestamp > report_cutoff);
         
@@ -496,12 +572,21 @@ impl AntiAbuseSystem {
             }
             AbuseLevel::High => {
                 if confidence > 0.8 {
-                    Some(AbuseAction::TemporaryBan(self.config.actions.temp_ban_duration))
+                    Some(self.ban(AbuseAction::TemporaryBan(self.config.actions.temp_ban_duration)))
                 } else {
                     Some(AbuseAction::ReputationPenalty(self.config.actions.reputation_penalty))
                 }
             }
-            AbuseLevel::Critical => Some(AbuseAction::PermanentBan),
+            AbuseLevel::Critical => Some(self.ban(AbuseAction::PermanentBan)),
+        }
+    }
+    
+    /// `ban`, or a shadow ban when configured to shadow-ban instead
+    fn ban(&self, ban: AbuseAction) -> AbuseAction {
+        if self.config.actions.shadow_ban {
+            AbuseAction::ShadowBan
+        } else {
+            ban
         }
     }
     
@@ -598,4 +683,57 @@ mod tests {
         // Score should be lower now
         assert!(reputation.unwrap().score < 10.0);
     }
-}
+    
+    #[tokio::test]
+    async fn test_shadow_ban_replaces_bans_when_configured() {
+        let mut config = AntiAbuseConfig::default();
+        config.actions.shadow_ban = true;
+        let system = AntiAbuseSystem::new(config);
+        assert!(matches!(system.determine_action(&AbuseLevel::Critical, 1.0), Some(AbuseAction::ShadowBan)));
+        assert!(matches!(system.determine_action(&AbuseLevel::High, 0.9), Some(AbuseAction::ShadowBan)));
+        
+        let player_id = Uuid::new_v4();
+        system.apply_action(player_id, AbuseAction::ShadowBan).await.unwrap();
+        assert!(system.is_shadow_banned(player_id).await);
+        assert!(system.lift_shadow_ban(player_id).await);
+        assert!(!system.is_shadow_banned(player_id).await);
+    }
+    
+    #[tokio::test]
+    async fn test_high_party_rating_spread_flagged_as_boosting() {
+        let system = AntiAbuseSystem::new(AntiAbuseConfig::default());
+        let booster = Uuid::new_v4();
+        let carried = Uuid::new_v4();
+        let party_id = Uuid::new_v4();
+        
+        let report = system
+            .check_party_rating_spread(party_id, &[
+                (carried, Rating::new(900.0, 80.0, 0.06)),
+                (booster, Rating::new(2400.0, 60.0, 0.06)),
+            ])
+            .await
+            .unwrap();
+        
+        assert!(matches!(report.report_type, AbuseReportType::PossibleBoosting));
+        assert_eq!(report.reported_player_id, booster);
+        assert_eq!(report.evidence["carried_player_id"], carried.to_string());
+        assert_eq!(report.evidence["party_id"], party_id.to_string());
+        assert_eq!(report.evidence["rating_spread"], "1500");
+        assert_eq!(system.get_reports_for_player(booster).await.len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_normal_party_not_flagged() {
+        let system = AntiAbuseSystem::new(AntiAbuseConfig::default());
+        let members: Vec<(Uuid, Rating)> = [1450.0, 1620.0, 1800.0]
+            .into_iter()
+            .map(|r| (Uuid::new_v4(), Rating::new(r, 80.0, 0.06)))
+            .collect();
+        
+        assert!(system.check_party_rating_spread(Uuid::new_v4(), &members).await.is_none());
+        assert!(system.check_party_rating_spread(Uuid::new_v4(), &[]).await.is_none());
+        for (player_id, _) in &members {
+            assert!(system.get_reports_for_player(*player_id).await.is_empty());
+        }
+    }
+}
\ No newline at end of file
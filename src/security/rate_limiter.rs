@@ -147,7 +147,7 @@ impl RateLimiter {
         let mut penalties = self.penalties.write().await;
         penalties.insert(client_id, Penalty {
             reason,
-            expires_at: Utc::now() + chrono::Duration::from_std(duration).unwrap(),
+            expires_at: Utc::now() + crate::time::to_chrono(duration),
         });
     }
     
@@ -59,15 +59,15 @@ impl Default for SecurityConfig {
 pub struct SecurityManager {
     config: SecurityConfig,
     sessions: Arc<RwLock<HashMap<String, Session>>>,
-    rate_limiter: Option<RateLimiter>,
-    anti_abuse_system: Option<AntiAbuseSystem>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    anti_abuse_system: Option<Arc<AntiAbuseSystem>>,
 }
 
 impl SecurityManager {
     /// Create a new security manager
     pub fn new(config: SecurityConfig) -> Self {
-        let rate_limiter = config.rate_limit_config.clone().map(RateLimiter::new);
-        let anti_abuse_system = config.anti_abuse_config.clone().map(AntiAbuseSystem::new);
+        let rate_limiter = config.rate_limit_config.clone().map(|c| Arc::new(RateLimiter::new(c)));
+        let anti_abuse_system = config.anti_abuse_config.clone().map(|c| Arc::new(AntiAbuseSystem::new(c)));
         
         Self {
             config,
@@ -77,6 +77,16 @@ impl SecurityManager {
         }
     }
     
+    /// The rate limiter, if `rate_limit_config` was set
+    pub fn rate_limiter(&self) -> Option<&Arc<RateLimiter>> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// The anti-abuse system, if `anti_abuse_config` was set
+    pub fn anti_abuse(&self) -> Option<&Arc<AntiAbuseSystem>> {
+        self.anti_abuse_system.as_ref()
+    }
+
     /// Create a security context for a request
     pub async fn create_context(&self, request: &SecurityRequest) -> Result<SecurityContext, SecurityError> {
         // Check rate limiting
@@ -299,7 +309,7 @@ impl SecurityManager {
             id: session_id.clone(),
             user_id,
             created_at: Utc::now(),
-            expires_at: Utc::now() + chrono::Duration::from_std(self.config.session_timeout).unwrap(),
+            expires_at: Utc::now() + crate::time::to_chrono(self.config.session_timeout),
             last_activity: Utc::now(),
         };
         
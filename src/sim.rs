@@ -0,0 +1,280 @@
+//! Synthetic load for judging matchmaking quality before launch
+//!
+//! A [`Simulation`] feeds synthetic players into a real [`QueueManager`]
+//! and [`MatchmakingRunner`] on a simulated clock, so thousands of players
+//! arriving over an hour run in well under a second. Runs with the same
+//! config and seed produce the same report.
+
+use crate::{
+    clock::{Clock, MockClock},
+    error::*,
+    mmr::Rating,
+    persistence::InMemoryAdapter,
+    queue::{EntryMetadata, MatchResult, QueueConfig, QueueManager, WaitPercentiles},
+    runner::{MatchmakingRunner, RunnerConfig},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+/// Population and pacing of a [`Simulation`]
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// Queue the synthetic players join
+    pub queue: QueueConfig,
+    /// Number of synthetic players
+    pub players: usize,
+    /// Mean arrivals per second; gaps between arrivals are exponentially distributed
+    pub arrivals_per_second: f64,
+    /// Mean of the normally distributed player ratings
+    pub rating_mean: f64,
+    /// Standard deviation of player ratings
+    pub rating_std_dev: f64,
+    /// Simulated time between runner ticks
+    pub tick_interval: chrono::Duration,
+    /// Stop after this much simulated time even if players are still waiting
+    pub max_duration: chrono::Duration,
+    /// Seed for ratings and arrival times
+    pub seed: u64,
+}
+
+impl SimulationConfig {
+    /// 1,000 solo players arriving at 5 per second, rated around 1500
+    pub fn new(queue: QueueConfig) -> Self {
+        Self {
+            queue,
+            players: 1000,
+            arrivals_per_second: 5.0,
+            rating_mean: 1500.0,
+            rating_std_dev: 300.0,
+            tick_interval: chrono::Duration::seconds(1),
+            max_duration: chrono::Duration::hours(1),
+            seed: 0,
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.arrivals_per_second.is_nan() || self.arrivals_per_second <= 0.0 {
+            return Err(MatchForgeError::InvalidConfiguration(
+                "Simulation arrival rate must be positive".to_string(),
+            ));
+        }
+        if self.tick_interval <= chrono::Duration::zero() {
+            return Err(MatchForgeError::InvalidConfiguration(
+                "Simulation tick interval must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Aggregate outcome of a [`Simulation`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport {
+    pub players_joined: usize,
+    pub players_matched: usize,
+    /// Players still queued when the simulation stopped
+    pub players_waiting: usize,
+    pub matches: usize,
+    /// Mean [`MatchResult::quality_score`], 0.0 without matches
+    pub mean_quality: f64,
+    /// Mean wait of matched players
+    pub mean_wait: Duration,
+    /// Wait percentiles of matched players
+    pub wait_percentiles: WaitPercentiles,
+    /// Mean gap between the highest and lowest team average rating per match
+    pub mean_team_rating_gap: f64,
+    /// Largest team average rating gap in any match
+    pub max_team_rating_gap: f64,
+    /// Simulated time until every player was matched or `max_duration` ran out
+    pub simulated_duration: chrono::Duration,
+}
+
+/// Deterministic matchmaking simulation, see the [module docs](self)
+pub struct Simulation {
+    config: SimulationConfig,
+}
+
+impl Simulation {
+    pub fn new(config: SimulationConfig) -> Self {
+        Self { config }
+    }
+
+    /// Run the simulation to completion
+    pub async fn run(&self) -> Result<SimulationReport> {
+        self.config.validate()?;
+        let config = &self.config;
+        let queue_name = config.queue.name.clone();
+
+        let clock = Arc::new(MockClock::default());
+        let persistence = Arc::new(InMemoryAdapter::new());
+        let queue_manager = Arc::new(QueueManager::new(persistence.clone()).with_clock(clock.clone()));
+        queue_manager.register_queue(config.queue.clone()).await?;
+        let runner_config = RunnerConfig::builder()
+            .tick_interval_ms(config.tick_interval.num_milliseconds() as u64)
+            .auto_dispatch(false)
+            .queue(queue_name.clone())
+            .build();
+        let runner = MatchmakingRunner::new(runner_config, queue_manager.clone(), persistence).with_clock(clock.clone());
+        let mut formed = runner.subscribe_matches();
+
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let start = clock.now();
+        let mut next_arrival = start + Self::arrival_gap(&mut rng, config.arrivals_per_second);
+        let mut now = start;
+        let mut joined = 0;
+        let mut waits = Vec::new();
+        let mut qualities = Vec::new();
+        let mut rating_gaps = Vec::new();
+
+        loop {
+            while joined < config.players && next_arrival <= now {
+                clock.set(next_arrival);
+                let rating = Rating {
+                    rating: Self::sample_rating(&mut rng, config.rating_mean, config.rating_std_dev),
+                    ..Rating::default_beginner()
+                };
+                let player_id = Uuid::from_u128(rng.gen());
+                queue_manager
+                    .join_queue_solo(queue_name.clone(), player_id, rating, EntryMetadata::default(), None)
+                    .await?;
+                joined += 1;
+                next_arrival += Self::arrival_gap(&mut rng, config.arrivals_per_second);
+            }
+
+            clock.set(now);
+            runner.tick().await?;
+            while let Ok(match_result) = formed.try_recv() {
+                waits.extend(
+                    match_result
+                        .entries
+                        .iter()
+                        .flat_map(|e| std::iter::repeat_n(e.wait_time(now), e.player_count()))
+                        .map(crate::time::to_std),
+                );
+                qualities.push(match_result.quality_score);
+                rating_gaps.push(Self::team_rating_gap(&match_result));
+            }
+
+            let waiting = queue_manager.get_queue_size(&queue_name).await?;
+            if (joined == config.players && waiting == 0) || now - start >= config.max_duration {
+                break;
+            }
+            now += config.tick_interval;
+        }
+
+        let mean = |values: &[f64]| {
+            if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 }
+        };
+        Ok(SimulationReport {
+            players_joined: joined,
+            players_matched: waits.len(),
+            players_waiting: queue_manager.get_queue_size(&queue_name).await?,
+            matches: qualities.len(),
+            mean_quality: mean(&qualities),
+            mean_wait: if waits.is_empty() {
+                Duration::ZERO
+            } else {
+                waits.iter().sum::<Duration>() / waits.len() as u32
+            },
+            wait_percentiles: WaitPercentiles::from_durations(&waits),
+            mean_team_rating_gap: mean(&rating_gaps),
+            max_team_rating_gap: rating_gaps.iter().copied().fold(0.0, f64::max),
+            simulated_duration: now - start,
+        })
+    }
+
+    /// Exponentially distributed gap until the next arrival
+    fn arrival_gap(rng: &mut StdRng, arrivals_per_second: f64) -> chrono::Duration {
+        let seconds = -(1.0 - rng.gen::<f64>()).ln() / arrivals_per_second;
+        chrono::Duration::milliseconds((seconds * 1000.0).round() as i64)
+    }
+
+    /// Normally distributed rating (Box-Muller)
+    fn sample_rating(rng: &mut StdRng, mean: f64, std_dev: f64) -> f64 {
+        let u1 = 1.0 - rng.gen::<f64>();
+        let u2 = rng.gen::<f64>();
+        mean + std_dev * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    /// Highest minus lowest player-weighted team average rating
+    fn team_rating_gap(match_result: &MatchResult) -> f64 {
+        let team_count = match_result.team_assignments.iter().copied().max().map_or(0, |t| t + 1);
+        let averages: Vec<f64> = (0..team_count)
+            .filter_map(|team| {
+                let (total, players) = match_result
+                    .entries
+                    .iter()
+                    .zip(&match_result.team_assignments)
+                    .filter(|(_, assigned)| **assigned == team)
+                    .fold((0.0, 0), |(total, players), (entry, _)| {
+                        let count = entry.player_count();
+                        (total + entry.average_rating.rating * count as f64, players + count)
+                    });
+                (players > 0).then(|| total / players as f64)
+            })
+            .collect();
+        let highest = averages.iter().copied().fold(f64::MIN, f64::max);
+        let lowest = averages.iter().copied().fold(f64::MAX, f64::min);
+        if averages.is_empty() { 0.0 } else { highest - lowest }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::{MatchConstraints, MatchFormat};
+
+    fn small_config(seed: u64) -> SimulationConfig {
+        SimulationConfig {
+            players: 200,
+            arrivals_per_second: 10.0,
+            max_duration: chrono::Duration::minutes(10),
+            seed,
+            ..SimulationConfig::new(QueueConfig::new(
+                "sim_1v1",
+                MatchFormat::one_v_one(),
+                MatchConstraints::permissive(),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_small_simulation_report_is_sane() {
+        let report = Simulation::new(small_config(7)).run().await.unwrap();
+
+        assert_eq!(report.players_joined, 200);
+        assert_eq!(report.players_matched + report.players_waiting, 200);
+        assert!(report.players_matched >= 190);
+        assert_eq!(report.players_matched, report.matches * 2);
+        assert!((0.0..=1.0).contains(&report.mean_quality));
+        assert_eq!(report.wait_percentiles.sample_count, report.players_matched);
+        assert!(report.wait_percentiles.p50 <= report.wait_percentiles.p99);
+        assert!(report.mean_wait <= report.wait_percentiles.p99);
+        assert!(report.mean_wait < Duration::from_secs(60));
+        // Permissive constraints allow gaps up to 500 plus wait-based expansion
+        assert!(report.mean_team_rating_gap > 0.0);
+        assert!(report.mean_team_rating_gap <= report.max_team_rating_gap);
+        assert!(report.simulated_duration > chrono::Duration::zero());
+        assert!(report.simulated_duration <= chrono::Duration::minutes(10));
+    }
+
+    #[tokio::test]
+    async fn test_same_seed_same_report() {
+        let first = Simulation::new(small_config(42)).run().await.unwrap();
+        let second = Simulation::new(small_config(42)).run().await.unwrap();
+        assert_eq!(first, second);
+
+        let other = Simulation::new(small_config(43)).run().await.unwrap();
+        assert_ne!(first, other);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config_rejected() {
+        let config = SimulationConfig {
+            arrivals_per_second: 0.0,
+            ..small_config(1)
+        };
+        assert!(Simulation::new(config).run().await.is_err());
+    }
+}
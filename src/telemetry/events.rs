@@ -2,9 +2,15 @@
 //! 
 //! Provides comprehensive event tracking and logging for all matchmaking operations.
 
+use super::sink::EventSink;
 use std::collections::HashMap;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
 /// MatchForge events
@@ -30,6 +36,8 @@ pub enum EventType {
     MatchmakingCompleted,
     MatchFound,
     MatchQualityCalculated,
+    /// The adaptive matcher widened the rating delta to form a match
+    ConstraintsRelaxed,
     
     // Lobby events
     LobbyCreated,
@@ -88,6 +96,16 @@ pub enum EventData {
         quality_score: f64,
         wait_time_ms: u64,
     },
+    ConstraintsRelaxed {
+        queue_name: String,
+        /// Queue entry whose wait widened the constraints
+        entry_id: Uuid,
+        player_ids: Vec<Uuid>,
+        wait_time_ms: u64,
+        base_max_rating_delta: f64,
+        /// Factor applied to the base delta, see [`ExpansionCurve::multiplier`](crate::queue::ExpansionCurve::multiplier)
+        multiplier: f64,
+    },
     LobbyCreated {
         lobby_id: Uuid,
         match_id: Uuid,
@@ -210,6 +228,7 @@ impl Event {
             EventData::PartyMemberAdded { player_id: pid, .. } => *pid == player_id,
             EventData::PartyMemberRemoved { player_id: pid, .. } => *pid == player_id,
             EventData::MatchFound { player_ids, .. } => player_ids.contains(&player_id),
+            EventData::ConstraintsRelaxed { player_ids, .. } => player_ids.contains(&player_id),
             _ => false,
         }
     }
@@ -222,6 +241,7 @@ impl Event {
             EventData::QueueSizeChange { queue_name: q, .. } => q == queue_name,
             EventData::MatchmakingStart { queue_name: q, .. } => q == queue_name,
             EventData::MatchmakingComplete { queue_name: q, .. } => q == queue_name,
+            EventData::ConstraintsRelaxed { queue_name: q, .. } => q == queue_name,
             _ => false,
         }
     }
@@ -261,10 +281,24 @@ pub trait EventCollector: Send + Sync {
     fn clear_old_events(&self, older_than: DateTime<Utc>);
 }
 
+/// Which events a full [`MemoryEventCollector`] discards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Evict the oldest retained event to make room for the new one
+    #[default]
+    DropOldest,
+    /// Keep the retained events and discard the new one
+    DropNewest,
+}
+
 /// In-memory event collector implementation
 pub struct MemoryEventCollector {
     events: std::sync::Mutex<Vec<Event>>,
     max_events: usize,
+    overflow_policy: OverflowPolicy,
+    dropped_events: AtomicUsize,
+    sinks: Vec<mpsc::UnboundedSender<Event>>,
+    sink_errors: Arc<AtomicUsize>,
 }
 
 impl MemoryEventCollector {
@@ -273,19 +307,68 @@ impl MemoryEventCollector {
         Self {
             events: std::sync::Mutex::new(Vec::with_capacity(max_events)),
             max_events,
+            overflow_policy: OverflowPolicy::default(),
+            dropped_events: AtomicUsize::new(0),
+            sinks: Vec::new(),
+            sink_errors: Arc::new(AtomicUsize::new(0)),
         }
     }
     
-    /// Add event to the collector
+    /// Choose which events are discarded once `max_events` are retained
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+        self
+    }
+    
+    /// Forward every recorded event to a sink
+    /// 
+    /// Events are published in order from a background task, so this must be
+    /// called within a Tokio runtime. Publish errors are logged and counted
+    /// (see [`sink_error_count`](Self::sink_error_count)) but never stop the collector.
+    pub fn with_sink(mut self, sink: Arc<dyn EventSink>) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Event>();
+        let sink_errors = self.sink_errors.clone();
+        
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                if let Err(e) = sink.publish(&event).await {
+                    sink_errors.fetch_add(1, Ordering::Relaxed);
+                    eprintln!("Event sink error for event {}: {}", event.id, e);
+                }
+            }
+        });
+        
+        self.sinks.push(sender);
+        self
+    }
+    
+    /// Number of events a sink failed to publish
+    pub fn sink_error_count(&self) -> usize {
+        self.sink_errors.load(Ordering::Relaxed)
+    }
+    
+    /// Number of events discarded because the collector was full
+    /// 
+    /// Sinks still receive dropped events; only the in-memory copy is lost.
+    pub fn dropped_event_count(&self) -> usize {
+        self.dropped_events.load(Ordering::Relaxed)
+    }
+    
+    /// Add event to the collector, applying the overflow policy when full
     fn add_event(&self, event: Event) {
         let mut events = self.events.lock().unwrap();
-        events.push(event);
-        
-        // Remove oldest events if we exceed the limit
-        if events.len() > self.max_events {
-            let remove_count = events.len() - self.max_events;
-            events.drain(0..remove_count);
+        if events.len() >= self.max_events {
+            self.dropped_events.fetch_add(1, Ordering::Relaxed);
+            match self.overflow_policy {
+                OverflowPolicy::DropNewest => return,
+                OverflowPolicy::DropOldest if self.max_events == 0 => return,
+                OverflowPolicy::DropOldest => {
+                    let remove_count = events.len() + 1 - self.max_events;
+                    events.drain(0..remove_count);
+                }
+            }
         }
+        events.push(event);
     }
     
     /// Drain events up to a certain count
@@ -315,6 +398,9 @@ impl MemoryEventCollector {
 
 impl EventCollector for MemoryEventCollector {
     fn record_event(&self, event: Event) {
+        for sink in &self.sinks {
+            let _ = sink.send(event.clone());
+        }
         self.add_event(event);
     }
     
@@ -404,6 +490,28 @@ impl EventBuilder {
         )
     }
     
+    /// Build a constraints relaxed event
+    pub fn constraints_relaxed(
+        queue_name: String,
+        entry_id: Uuid,
+        player_ids: Vec<Uuid>,
+        wait_time_ms: u64,
+        base_max_rating_delta: f64,
+        multiplier: f64,
+    ) -> Event {
+        Event::new(
+            EventType::ConstraintsRelaxed,
+            EventData::ConstraintsRelaxed {
+                queue_name,
+                entry_id,
+                player_ids,
+                wait_time_ms,
+                base_max_rating_delta,
+                multiplier,
+            },
+        )
+    }
+    
     /// Build a lobby created event
     pub fn lobby_created(lobby_id: Uuid, match_id: Uuid, player_count: usize) -> Event {
         Event::new(
@@ -554,3 +662,46 @@ pub struct QueuePerformance {
     pub avg_wait_time_ms: u64,
     pub success_rate: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(collector: &MemoryEventCollector, count: usize) -> Vec<Uuid> {
+        (0..count)
+            .map(|_| {
+                let event = EventBuilder::queue_join("ranked".to_string(), Uuid::new_v4(), 1500.0);
+                let id = event.id;
+                collector.record_event(event);
+                id
+            })
+            .collect()
+    }
+
+    fn retained(collector: &MemoryEventCollector) -> Vec<Uuid> {
+        collector.events.lock().unwrap().iter().map(|e| e.id).collect()
+    }
+
+    #[test]
+    fn test_drop_oldest_keeps_latest_events() {
+        let collector = MemoryEventCollector::new(3);
+        let ids = fill(&collector, 5);
+
+        assert_eq!(collector.dropped_event_count(), 2);
+        assert_eq!(retained(&collector), ids[2..]);
+    }
+
+    #[test]
+    fn test_drop_newest_keeps_earliest_events() {
+        let collector = MemoryEventCollector::new(3).with_overflow_policy(OverflowPolicy::DropNewest);
+        let ids = fill(&collector, 5);
+
+        assert_eq!(collector.dropped_event_count(), 2);
+        assert_eq!(retained(&collector), ids[..3]);
+
+        // Nothing is dropped while there is room
+        let collector = MemoryEventCollector::new(3).with_overflow_policy(OverflowPolicy::DropNewest);
+        fill(&collector, 3);
+        assert_eq!(collector.dropped_event_count(), 0);
+    }
+}
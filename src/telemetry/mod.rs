@@ -6,7 +6,9 @@
 pub mod metrics;
 pub mod events;
 pub mod monitoring;
+pub mod sink;
 
 pub use metrics::{MatchmakingMetrics, MetricsCollector};
-pub use events::{Event, EventCollector, EventType};
-pub use monitoring::{MonitoringConfig, MonitoringService};
+pub use events::{Event, EventCollector, EventType, MemoryEventCollector, OverflowPolicy};
+pub use monitoring::{AlertThresholds, MonitoringConfig, MonitoringService};
+pub use sink::{ChannelSink, EventSink, LoggingSink};
@@ -47,6 +47,12 @@ pub struct AlertThresholds {
     
     /// Minimum health score (0-100)
     pub min_health_score: f64,
+    
+    /// Maximum process memory usage (MB)
+    pub max_memory_usage_mb: u64,
+    
+    /// Maximum CPU usage (0-100)
+    pub max_cpu_usage_percent: f64,
 }
 
 impl Default for AlertThresholds {
@@ -57,6 +63,8 @@ impl Default for AlertThresholds {
             max_error_rate: 0.05,         // 5%
             max_queue_size: 1000,
             min_health_score: 70.0,       // 70/100
+            max_memory_usage_mb: 2048,
+            max_cpu_usage_percent: 80.0,
         }
     }
 }
@@ -0,0 +1,144 @@
+//! Event sinks for forwarding matchmaking events to external systems
+//!
+//! A sink receives every event recorded by a collector it is attached to,
+//! e.g. to publish match-found events on a message bus.
+
+use super::events::Event;
+use crate::error::{MatchForgeError, Result};
+use std::{future::Future, pin::Pin};
+use tokio::sync::mpsc;
+
+/// Boxed future returned by [`EventSink::publish`]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Destination for recorded events
+pub trait EventSink: Send + Sync {
+    /// Publish a single event
+    fn publish<'a>(&'a self, event: &'a Event) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Sink that prints events to stdout
+pub struct LoggingSink;
+
+impl EventSink for LoggingSink {
+    fn publish<'a>(&'a self, event: &'a Event) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            println!("[{}] {:?} {}", event.timestamp.to_rfc3339(), event.event_type, event.id);
+            Ok(())
+        })
+    }
+}
+
+/// Sink that forwards events into a Tokio channel
+pub struct ChannelSink {
+    sender: mpsc::UnboundedSender<Event>,
+}
+
+impl ChannelSink {
+    /// Create a sink and the receiver its events are delivered to
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<Event>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (Self { sender }, receiver)
+    }
+}
+
+impl EventSink for ChannelSink {
+    fn publish<'a>(&'a self, event: &'a Event) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.sender
+                .send(event.clone())
+                .map_err(|_| MatchForgeError::OperationFailed("Event channel closed".to_string()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::events::{EventBuilder, EventCollector, MemoryEventCollector};
+    use std::sync::{Arc, Mutex};
+    use uuid::Uuid;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        events: Mutex<Vec<Uuid>>,
+    }
+
+    impl EventSink for RecordingSink {
+        fn publish<'a>(&'a self, event: &'a Event) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async move {
+                self.events.lock().unwrap().push(event.id);
+                Ok(())
+            })
+        }
+    }
+
+    struct FailingSink;
+
+    impl EventSink for FailingSink {
+        fn publish<'a>(&'a self, _event: &'a Event) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async { Err(MatchForgeError::OperationFailed("broker unavailable".to_string())) })
+        }
+    }
+
+    fn sample_events() -> Vec<Event> {
+        vec![
+            EventBuilder::queue_join("ranked".to_string(), Uuid::new_v4(), 1500.0),
+            EventBuilder::match_found(Uuid::new_v4(), vec![Uuid::new_v4(), Uuid::new_v4()], 0.9, 1200),
+            EventBuilder::lobby_created(Uuid::new_v4(), Uuid::new_v4(), 2),
+        ]
+    }
+
+    async fn wait_for(condition: impl Fn() -> bool) {
+        for _ in 0..100 {
+            if condition() {
+                return;
+            }
+            tokio::task::yield_now().await;
+        }
+        panic!("condition not reached");
+    }
+
+    #[tokio::test]
+    async fn test_every_event_reaches_sink() {
+        let sink = Arc::new(RecordingSink::default());
+        let collector = MemoryEventCollector::new(100).with_sink(sink.clone());
+
+        let events = sample_events();
+        for event in &events {
+            collector.record_event(event.clone());
+        }
+
+        let expected: Vec<Uuid> = events.iter().map(|e| e.id).collect();
+        wait_for(|| sink.events.lock().unwrap().len() == expected.len()).await;
+        assert_eq!(*sink.events.lock().unwrap(), expected);
+        assert_eq!(collector.get_recent_events(10).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_sink_error_does_not_break_collector() {
+        let recording = Arc::new(RecordingSink::default());
+        let collector = MemoryEventCollector::new(100)
+            .with_sink(Arc::new(FailingSink))
+            .with_sink(recording.clone());
+
+        for event in sample_events() {
+            collector.record_event(event);
+        }
+
+        wait_for(|| collector.sink_error_count() == 3).await;
+        wait_for(|| recording.events.lock().unwrap().len() == 3).await;
+        assert_eq!(collector.get_recent_events(10).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_channel_sink_delivers_events() {
+        let (sink, mut receiver) = ChannelSink::new();
+        let collector = MemoryEventCollector::new(100).with_sink(Arc::new(sink));
+
+        let event = EventBuilder::queue_join("ranked".to_string(), Uuid::new_v4(), 1500.0);
+        collector.record_event(event.clone());
+
+        assert_eq!(receiver.recv().await.unwrap().id, event.id);
+    }
+}
@@ -0,0 +1,55 @@
+//! Conversions between `std::time::Duration` and `chrono::Duration`
+//!
+//! Wait times are measured with `chrono` (differences of timestamps) while
+//! configs and stats use `std`. These conversions never fail: values past
+//! the target's range saturate at its maximum and negative durations become
+//! zero, instead of silently turning into zero on overflow.
+
+/// `std` duration as a `chrono` duration, saturating at `chrono::Duration::MAX`
+pub fn to_chrono(duration: std::time::Duration) -> chrono::Duration {
+    chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::MAX)
+}
+
+/// `chrono` duration as a `std` duration; negative durations become zero
+///
+/// Every non-negative `chrono::Duration` fits in a `std::time::Duration`.
+pub fn to_std(duration: chrono::Duration) -> std::time::Duration {
+    duration.to_std().unwrap_or_default()
+}
+
+/// Fractional seconds as a `chrono` duration
+///
+/// Negative and NaN inputs become zero; values past the range of
+/// `chrono::Duration` (including infinity) saturate at its maximum.
+pub fn seconds_f64(seconds: f64) -> chrono::Duration {
+    if seconds.is_nan() || seconds <= 0.0 {
+        return chrono::Duration::zero();
+    }
+    std::time::Duration::try_from_secs_f64(seconds).map_or(chrono::Duration::MAX, to_chrono)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_within_range() {
+        let std_duration = std::time::Duration::from_millis(90_500);
+        assert_eq!(to_chrono(std_duration), chrono::Duration::milliseconds(90_500));
+        assert_eq!(to_std(chrono::Duration::milliseconds(90_500)), std_duration);
+        assert_eq!(to_std(chrono::Duration::MAX), chrono::Duration::MAX.to_std().unwrap());
+        assert_eq!(seconds_f64(1.5), chrono::Duration::milliseconds(1500));
+    }
+
+    #[test]
+    fn test_out_of_range_saturates_instead_of_zeroing() {
+        assert_eq!(to_chrono(std::time::Duration::MAX), chrono::Duration::MAX);
+        assert_eq!(to_chrono(std::time::Duration::from_secs(u64::MAX / 2)), chrono::Duration::MAX);
+        assert_eq!(to_std(chrono::Duration::seconds(-5)), std::time::Duration::ZERO);
+
+        assert_eq!(seconds_f64(f64::INFINITY), chrono::Duration::MAX);
+        assert_eq!(seconds_f64(1e300), chrono::Duration::MAX);
+        assert_eq!(seconds_f64(-3.0), chrono::Duration::zero());
+        assert_eq!(seconds_f64(f64::NAN), chrono::Duration::zero());
+    }
+}
@@ -21,16 +21,8 @@ async fn test_complete_matchmaking_flow() -> Result<()> {
 
     // Register multiple queues
     let queue_configs = vec![
-        QueueConfig {
-            name: "ranked_1v1".to_string(),
-            format: MatchFormat::one_v_one(),
-            constraints: MatchConstraints::strict(),
-        },
-        QueueConfig {
-            name: "casual_5v5".to_string(),
-            format: MatchFormat::five_v_five(),
-            constraints: MatchConstraints::permissive(),
-        },
+        QueueConfig::new("ranked_1v1", MatchFormat::one_v_one(), MatchConstraints::strict()),
+        QueueConfig::new("casual_5v5", MatchFormat::five_v_five(), MatchConstraints::permissive()),
     ];
 
     for config in queue_configs {
@@ -47,6 +39,7 @@ async fn test_complete_matchmaking_flow() -> Result<()> {
             *player_id,
             *rating,
             EntryMetadata::default(),
+            None,
         ).await?;
     }
 
@@ -109,11 +102,11 @@ async fn test_party_matchmaking() -> Result<()> {
     ));
 
     // Register queue
-    queue_manager.register_queue(QueueConfig {
-        name: "team_5v5".to_string(),
-        format: MatchFormat::five_v_five(),
-        constraints: MatchConstraints::permissive(),
-    }).await?;
+    queue_manager.register_queue(QueueConfig::new(
+        "team_5v5",
+        MatchFormat::five_v_five(),
+        MatchConstraints::permissive(),
+    )).await?;
 
     // Create parties
     let party1 = party_manager.create_party(Uuid::new_v4(), 5).await?;
@@ -162,6 +155,7 @@ async fn test_party_matchmaking() -> Result<()> {
             *player_id,
             Rating::default_beginner(),
             EntryMetadata::default(),
+            None,
         ).await?;
     }
 
@@ -182,11 +176,11 @@ async fn test_matchmaking_runner() -> Result<()> {
     let queue_manager = Arc::new(QueueManager::new(persistence.clone()));
 
     // Register queue
-    queue_manager.register_queue(QueueConfig {
-        name: "test_queue".to_string(),
-        format: MatchFormat::two_v_two(),
-        constraints: MatchConstraints::permissive(),
-    }).await?;
+    queue_manager.register_queue(QueueConfig::new(
+        "test_queue",
+        MatchFormat::two_v_two(),
+        MatchConstraints::permissive(),
+    )).await?;
 
     // Add players to queue
     let players: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
@@ -196,13 +190,15 @@ async fn test_matchmaking_runner() -> Result<()> {
             *player_id,
             Rating::default_beginner(),
             EntryMetadata::default(),
+            None,
         ).await?;
     }
 
     // Create runner with fast tick interval
     let runner_config = RunnerConfig {
         tick_interval_ms: 100,
-        max_matches_per_tick: 10,
+        tick_jitter_ms: None,
+        max_matches_per_tick: Some(10),
         auto_dispatch: false,
         queue_configs: {
             let mut configs = std::collections::HashMap::new();
@@ -210,6 +206,7 @@ async fn test_matchmaking_runner() -> Result<()> {
                 enabled: true,
                 priority: 1,
                 max_concurrent_matches: 10,
+                tick_interval_ms: None,
             });
             configs
         },
@@ -270,7 +267,7 @@ async fn test_season_reset() -> Result<()> {
     let original_rating = Rating::new(2000.0, 150.0, 0.03);
 
     // Test soft reset
-    let soft_reset = SoftReset::new(1500.0, 0.5);
+    let soft_reset = SoftReset::new(0.5, 1500.0, 1.5);
     let soft_reset_rating = soft_reset.reset_rating(original_rating);
 
     assert!(soft_reset_rating.rating < original_rating.rating, "Soft reset should lower rating");
@@ -295,6 +292,7 @@ async fn test_lifecycle_management() -> Result<()> {
     // Create a test lobby
     let player_ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
     let mut lobby = Lobby {
+        schema_version: Lobby::SCHEMA_VERSION,
         id: Uuid::new_v4(),
         match_id: Uuid::new_v4(),
         state: LobbyState::Forming,
@@ -302,16 +300,19 @@ async fn test_lifecycle_management() -> Result<()> {
             Team {
                 team_id: 0,
                 player_ids: player_ids[0..2].to_vec(),
+                capacity: 2,
             },
             Team {
                 team_id: 1,
                 player_ids: player_ids[2..4].to_vec(),
+                capacity: 2,
             },
         ],
         player_ids: player_ids.clone(),
         ready_players: std::collections::HashSet::new(),
         created_at: Utc::now(),
         metadata: LobbyMetadata::default(),
+        queue_entries: Vec::new(),
     };
 
     persistence.save_lobby(&lobby).await?;
@@ -361,6 +362,7 @@ async fn test_error_handling() -> Result<()> {
         player_id,
         Rating::default_beginner(),
         EntryMetadata::default(),
+        None,
     ).await;
     assert!(result.is_err(), "Should fail for non-existent queue");
 
@@ -392,11 +394,11 @@ async fn test_concurrent_operations() -> Result<()> {
     let queue_manager = Arc::new(QueueManager::new(persistence.clone()));
 
     // Register queue
-    queue_manager.register_queue(QueueConfig {
-        name: "concurrent_test".to_string(),
-        format: MatchFormat::one_v_one(),
-        constraints: MatchConstraints::permissive(),
-    }).await?;
+    queue_manager.register_queue(QueueConfig::new(
+        "concurrent_test",
+        MatchFormat::one_v_one(),
+        MatchConstraints::permissive(),
+    )).await?;
 
     // Add many players concurrently
     let mut handles = Vec::new();
@@ -411,6 +413,7 @@ async fn test_concurrent_operations() -> Result<()> {
                 player_id,
                 rating,
                 EntryMetadata::default(),
+                None,
             ).await
         });
         handles.push(handle);